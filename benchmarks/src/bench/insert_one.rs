@@ -3,6 +3,7 @@ use std::{convert::TryInto, fs::File, path::PathBuf};
 use anyhow::{bail, Result};
 use mongodb::{
     bson::{Bson, Document},
+    options::{InsertOneOptions, WriteConcern},
     Client,
     Collection,
     Database,
@@ -16,6 +17,7 @@ pub struct InsertOneBenchmark {
     num_iter: usize,
     coll: Collection<Document>,
     doc: Document,
+    insert_options: Option<InsertOneOptions>,
 }
 
 // Specifies the options to a `InsertOneBenchmark::setup` operation.
@@ -23,6 +25,7 @@ pub struct Options {
     pub num_iter: usize,
     pub path: PathBuf,
     pub uri: String,
+    pub write_concern: Option<WriteConcern>,
 }
 
 #[async_trait::async_trait]
@@ -46,6 +49,11 @@ impl Benchmark for InsertOneBenchmark {
         let json: Value = spawn_blocking_and_await!(serde_json::from_reader(&mut file))?;
 
         let coll = db.collection(&COLL_NAME);
+        let insert_options = options.write_concern.map(|write_concern| {
+            InsertOneOptions::builder()
+                .write_concern(write_concern)
+                .build()
+        });
 
         Ok(InsertOneBenchmark {
             db,
@@ -55,6 +63,7 @@ impl Benchmark for InsertOneBenchmark {
                 Bson::Document(doc) => doc,
                 _ => bail!("invalid json test file"),
             },
+            insert_options,
         })
     }
 
@@ -67,7 +76,9 @@ impl Benchmark for InsertOneBenchmark {
 
     async fn do_task(&self) -> Result<()> {
         for _ in 0..self.num_iter {
-            self.coll.insert_one(&self.doc, None).await?;
+            self.coll
+                .insert_one(&self.doc, self.insert_options.clone())
+                .await?;
         }
 
         Ok(())
@@ -26,6 +26,7 @@ use std::{
 use anyhow::Result;
 use clap::{App, Arg, ArgMatches};
 use lazy_static::lazy_static;
+use mongodb::options::{Acknowledgment, WriteConcern};
 
 use crate::bench::{
     bson_decode::BsonDecodeBenchmark,
@@ -141,6 +142,7 @@ async fn single_doc_benchmarks(uri: &str, more_info: bool, ids: &[bool]) -> Resu
                 .join("single_and_multi_document")
                 .join("small_doc.json"),
             uri: uri.to_string(),
+            write_concern: None,
         };
         println!("Running Small doc insertOne...");
         let small_insert_one =
@@ -158,6 +160,7 @@ async fn single_doc_benchmarks(uri: &str, more_info: bool, ids: &[bool]) -> Resu
                 .join("single_and_multi_document")
                 .join("large_doc.json"),
             uri: uri.to_string(),
+            write_concern: None,
         };
         println!("Running Large doc insertOne...");
         let large_insert_one =
@@ -174,6 +177,35 @@ async fn single_doc_benchmarks(uri: &str, more_info: bool, ids: &[bool]) -> Resu
     Ok(comp_score)
 }
 
+/// Runs the unacknowledged write benchmarks. These aren't part of the standard benchmark suite, so
+/// their scores are reported but not included in the driver's composite score, which keeps it
+/// comparable with the scores of other drivers.
+async fn unacknowledged_benchmarks(uri: &str, more_info: bool, ids: &[bool]) -> Result<()> {
+    // Small doc insertOne (w: 0)
+    if ids[15] {
+        let small_insert_one_options = bench::insert_one::Options {
+            num_iter: 10000,
+            path: DATA_PATH
+                .join("single_and_multi_document")
+                .join("small_doc.json"),
+            uri: uri.to_string(),
+            write_concern: Some(WriteConcern::builder().w(Acknowledgment::Nodes(0)).build()),
+        };
+        println!("Running Small doc insertOne (w: 0)...");
+        let small_insert_one =
+            bench::run_benchmark::<InsertOneBenchmark>(small_insert_one_options).await?;
+
+        score_test(
+            small_insert_one,
+            "Small doc insertOne (w: 0)",
+            2.75,
+            more_info,
+        );
+    }
+
+    Ok(())
+}
+
 async fn multi_doc_benchmarks(uri: &str, more_info: bool, ids: &[bool]) -> Result<f64> {
     println!("----------------------------");
     println!("Multi-Doc Benchmarks:");
@@ -398,7 +430,7 @@ async fn bson_benchmarks(more_info: bool, ids: &[bool]) -> Result<f64> {
 
 fn parse_ids(matches: ArgMatches) -> Vec<bool> {
     let id_list: Vec<usize> = match matches.value_of("ids") {
-        Some("all") | None => vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        Some("all") | None => vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
         Some(id_list) => id_list
             .split(',')
             .map(|str| {
@@ -408,9 +440,9 @@ fn parse_ids(matches: ArgMatches) -> Vec<bool> {
             .collect(),
     };
 
-    let mut ids = vec![false; 15];
+    let mut ids = vec![false; 16];
     for id in id_list {
-        if id < 1 || id > 15 {
+        if id < 1 || id > 16 {
             panic!("invalid test IDs provided, see README");
         }
         ids[id - 1] = true;
@@ -421,6 +453,7 @@ fn parse_ids(matches: ArgMatches) -> Vec<bool> {
         ids[1] = true;
         ids[2] = true;
         ids[3] = true;
+        ids[15] = true;
     }
     if matches.is_present("multi") {
         ids[4] = true;
@@ -438,7 +471,6 @@ fn parse_ids(matches: ArgMatches) -> Vec<bool> {
         ids[12] = true;
         ids[13] = true;
         ids[14] = true;
-        ids[15] = true;
     }
 
     ids
@@ -505,6 +537,7 @@ Run benchmarks by id number (comma-separated):
     13: BSON deeply nested document encode
     14: BSON full document decode
     15: BSON full document encode
+    16: Small doc insertOne (w: 0)
     all: All benchmarks
                     ",
                 ),
@@ -548,6 +581,13 @@ Run benchmarks by id number (comma-separated):
         bson_benchmarks(verbose, &ids).await.unwrap();
     }
 
+    // Unacknowledged writes
+    if ids[15] {
+        // Unacknowledged write benchmarks aren't computed as part of the composite score either,
+        // since the other drivers' scores don't include them.
+        unacknowledged_benchmarks(uri, verbose, &ids).await.unwrap();
+    }
+
     println!("----------------------------");
     println!("Driver benchmark composite score = {}", comp_score);
 }
@@ -7,9 +7,21 @@ use crate::{
     runtime::{AsyncLittleEndianRead, AsyncLittleEndianWrite},
 };
 
+/// Reads a document from `reader`. If `utf8_lossy` is true, invalid UTF-8 sequences in strings
+/// will be replaced with the Unicode replacement character rather than causing an error.
 pub(crate) async fn decode_document<R: AsyncRead + Unpin + Send>(
     reader: &mut R,
+    utf8_lossy: bool,
 ) -> Result<Document> {
+    let bytes = read_document_bytes(reader).await?;
+    parse_document(&bytes, utf8_lossy)
+}
+
+/// Reads the bytes of a single document, including its length prefix, from `reader` without
+/// parsing them.
+pub(crate) async fn read_document_bytes<R: AsyncRead + Unpin + Send>(
+    reader: &mut R,
+) -> Result<Vec<u8>> {
     let length = reader.read_i32().await?;
 
     let mut bytes = Vec::new();
@@ -20,7 +32,18 @@ pub(crate) async fn decode_document<R: AsyncRead + Unpin + Send>(
         .read_to_end(&mut bytes)
         .await?;
 
-    let document = Document::from_reader(&mut bytes.as_slice())?;
+    Ok(bytes)
+}
+
+/// Parses a document from bytes read by `read_document_bytes`. If `utf8_lossy` is true, invalid
+/// UTF-8 sequences in strings will be replaced with the Unicode replacement character rather than
+/// causing an error.
+pub(crate) fn parse_document(mut bytes: &[u8], utf8_lossy: bool) -> Result<Document> {
+    let document = if utf8_lossy {
+        Document::from_reader_utf8_lossy(&mut bytes)?
+    } else {
+        Document::from_reader(&mut bytes)?
+    };
     Ok(document)
 }
 
@@ -153,6 +153,18 @@ pub(crate) fn serialize_u64_option_as_i64<S: Serializer>(
     }
 }
 
+/// Deserialize an i64 from any BSON number type if it could be done losslessly.
+pub(crate) fn deserialize_i64_from_bson_number<'de, D>(
+    deserializer: D,
+) -> std::result::Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let bson = Bson::deserialize(deserializer)?;
+    get_int(&bson)
+        .ok_or_else(|| D::Error::custom(format!("could not deserialize i64 from {:?}", bson)))
+}
+
 /// Deserialize an u64 from any BSON number type if it could be done losslessly.
 pub(crate) fn deserialize_u64_from_bson_number<'de, D>(
     deserializer: D,
@@ -181,6 +193,87 @@ pub fn doc_size_bytes(doc: &Document) -> u64 {
         + 1
 }
 
+/// Reads the lengths of the documents in the `firstBatch` or `nextBatch` array of a cursor reply
+/// from the reply's raw bytes. These can differ from the sizes of the parsed documents when the
+/// reply was parsed with invalid UTF-8 replaced. Returns `None` if `bytes` is not a well-formed
+/// cursor reply.
+pub(crate) fn raw_batch_lengths(bytes: &[u8]) -> Option<Vec<usize>> {
+    let cursor = find_raw_element(bytes, |key| key == b"cursor")?;
+    let batch = find_raw_element(cursor, |key| key == b"firstBatch" || key == b"nextBatch")?;
+
+    let mut lengths = Vec::new();
+    let mut rest = batch.get(4..batch.len().checked_sub(1)?)?;
+    while !rest.is_empty() {
+        if rest[0] != 0x03 {
+            return None;
+        }
+        let value_start = 1 + rest[1..].iter().position(|b| *b == 0)? + 1;
+        let length = raw_value_length(0x03, rest.get(value_start..)?)?;
+        lengths.push(length);
+        rest = rest.get(value_start + length..)?;
+    }
+    Some(lengths)
+}
+
+/// Finds the first element of the raw document `bytes` whose key matches `matches_key` and returns
+/// the raw bytes of its value, if that value is a document or an array.
+fn find_raw_element(bytes: &[u8], matches_key: impl Fn(&[u8]) -> bool) -> Option<&[u8]> {
+    let mut rest = bytes.get(4..)?;
+    while *rest.first()? != 0 {
+        let element_type = rest[0];
+        let key_length = rest[1..].iter().position(|b| *b == 0)?;
+        let key = &rest[1..1 + key_length];
+        let value = rest.get(1 + key_length + 1..)?;
+        let length = raw_value_length(element_type, value)?;
+        if matches_key(key) && (element_type == 0x03 || element_type == 0x04) {
+            return value.get(..length);
+        }
+        rest = value.get(length..)?;
+    }
+    None
+}
+
+/// The number of bytes taken up by a raw value of the given element type at the start of `value`.
+fn raw_value_length(element_type: u8, value: &[u8]) -> Option<usize> {
+    let prefix = || -> Option<usize> {
+        let mut length = [0u8; 4];
+        length.copy_from_slice(value.get(..4)?);
+        usize::try_from(i32::from_le_bytes(length)).ok()
+    };
+    let cstring = |start: usize| -> Option<usize> {
+        Some(value.get(start..)?.iter().position(|b| *b == 0)? + 1)
+    };
+
+    let length = match element_type {
+        // double, datetime, timestamp, int64
+        0x01 | 0x09 | 0x11 | 0x12 => 8,
+        // string, JavaScript code, symbol
+        0x02 | 0x0D | 0x0E => 4 + prefix()?,
+        // document, array, JavaScript code with scope
+        0x03 | 0x04 | 0x0F => prefix()?,
+        // binary: length prefix, subtype, bytes
+        0x05 => 4 + 1 + prefix()?,
+        // undefined, null, min key, max key
+        0x06 | 0x0A | 0x7F | 0xFF => 0,
+        0x07 => 12,
+        0x08 => 1,
+        // regular expression: pattern and options
+        0x0B => {
+            let pattern = cstring(0)?;
+            pattern + cstring(pattern)?
+        }
+        // DB pointer: namespace and ObjectId
+        0x0C => 4 + prefix()? + 12,
+        0x10 => 4,
+        0x13 => 16,
+        _ => return None,
+    };
+    if length > value.len() {
+        return None;
+    }
+    Some(length)
+}
+
 pub fn size_bytes(val: &Bson) -> u64 {
     match val {
         Bson::Double(_) => 8,
@@ -303,7 +396,7 @@ mod test {
         Timestamp,
     };
 
-    use super::doc_size_bytes;
+    use super::{async_encoding, doc_size_bytes, raw_batch_lengths};
 
     #[cfg_attr(feature = "tokio-runtime", tokio::test)]
     #[cfg_attr(feature = "async-std-runtime", async_std::test)]
@@ -340,4 +433,57 @@ mod test {
 
         assert_eq!(size_bytes, serialized_bytes.len() as u64);
     }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn decode_document_utf8_lossy() {
+        let mut bytes = Vec::new();
+        doc! { "s": "a__b" }.to_writer(&mut bytes).unwrap();
+
+        // Replace the placeholder with a sequence that isn't valid UTF-8.
+        let start = bytes.windows(2).position(|w| w == b"__").unwrap();
+        bytes[start..start + 2].copy_from_slice(&[0xff, 0xfe]);
+
+        async_encoding::decode_document(&mut bytes.as_slice(), false)
+            .await
+            .expect_err("strict decoding should fail");
+
+        let doc = async_encoding::decode_document(&mut bytes.as_slice(), true)
+            .await
+            .expect("lossy decoding should succeed");
+        assert_eq!(doc, doc! { "s": "a\u{FFFD}\u{FFFD}b" });
+    }
+
+    #[test]
+    fn raw_batch_lengths_of_cursor_reply() {
+        let batch = vec![
+            doc! { "s": "a__b" },
+            doc! { "x": 1, "r": Bson::RegularExpression(Regex { pattern: "a".into(), options: "i".into() }) },
+        ];
+        let reply = doc! {
+            "ok": 1.0,
+            "cursor": {
+                "id": 0i64,
+                "ns": "db.coll",
+                "firstBatch": batch.clone(),
+            },
+        };
+        let mut bytes = Vec::new();
+        reply.to_writer(&mut bytes).unwrap();
+
+        // The lengths are read without parsing the documents, so invalid UTF-8 doesn't affect them.
+        let start = bytes.windows(2).position(|w| w == b"__").unwrap();
+        bytes[start..start + 2].copy_from_slice(&[0xff, 0xfe]);
+
+        let expected: Vec<usize> = batch
+            .iter()
+            .map(|doc| doc_size_bytes(doc) as usize)
+            .collect();
+        assert_eq!(raw_batch_lengths(&bytes), Some(expected));
+
+        assert_eq!(raw_batch_lengths(&bytes[..bytes.len() - 10]), None);
+        let mut not_a_cursor = Vec::new();
+        doc! { "ok": 1.0 }.to_writer(&mut not_a_cursor).unwrap();
+        assert_eq!(raw_batch_lengths(&not_a_cursor), None);
+    }
 }
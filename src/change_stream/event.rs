@@ -0,0 +1,173 @@
+//! Contains the types of the events returned by change streams.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::bson::{Bson, Document, Timestamp};
+
+/// An opaque token used for resuming an interrupted
+/// [`ChangeStream`](../struct.ChangeStream.html).
+///
+/// When starting a new change stream,
+/// [`ChangeStreamOptions::start_after`](crate::options::ChangeStreamOptions::start_after) and
+/// [`ChangeStreamOptions::resume_after`](crate::options::ChangeStreamOptions::resume_after) can be
+/// specified with instances of `ResumeToken` to start the stream after the event that the token
+/// belongs to.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(transparent)]
+pub struct ResumeToken(pub(crate) Bson);
+
+/// A `ChangeStreamEvent` represents a
+/// [change event](https://docs.mongodb.com/manual/reference/change-events/) in the associated change
+/// stream.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ChangeStreamEvent<T> {
+    /// The token that can be used to resume a change stream after this event.
+    #[serde(rename = "_id")]
+    pub id: ResumeToken,
+
+    /// The type of operation that caused this event.
+    pub operation_type: OperationType,
+
+    /// The namespace affected by the operation. This isn't present for invalidate events.
+    pub ns: Option<ChangeNamespace>,
+
+    /// The new name of the collection, for rename events.
+    pub to: Option<ChangeNamespace>,
+
+    /// The `_id` of the document affected by the operation, and its shard key fields if the
+    /// collection is sharded. This is only present for events that affect a single document.
+    pub document_key: Option<Document>,
+
+    /// The document affected by the operation. This is always present for insert and replace
+    /// events, holding the new version of the document. For update events, it's only present if
+    /// [`ChangeStreamOptions::full_document`](crate::options::ChangeStreamOptions::full_document)
+    /// was set to `UpdateLookup`.
+    pub full_document: Option<T>,
+
+    /// The version of the document from before the operation, for update, replace and delete
+    /// events. This is only present if
+    /// [`full_document_before_change`](crate::options::ChangeStreamOptions::full_document_before_change)
+    /// was set and the collection records pre-images.
+    pub full_document_before_change: Option<T>,
+
+    /// The fields that were changed by the operation, for update events.
+    pub update_description: Option<UpdateDescription>,
+
+    /// The cluster time at which the operation occurred.
+    pub cluster_time: Option<Timestamp>,
+}
+
+/// Describes the changes that an update operation made to a document.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct UpdateDescription {
+    /// The fields that were added or changed, mapped to their new values.
+    pub updated_fields: Document,
+
+    /// The names of the fields that were removed.
+    pub removed_fields: Vec<String>,
+
+    /// The arrays that were shortened by a pipeline-style update. This is only reported by server
+    /// versions 5.0 and above.
+    pub truncated_arrays: Option<Vec<TruncatedArray>>,
+}
+
+/// An array that was shortened by an update operation.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct TruncatedArray {
+    /// The name of the field holding the array.
+    pub field: String,
+
+    /// The length of the array after the update.
+    pub new_size: i32,
+}
+
+/// The database and collection affected by a change stream event.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct ChangeNamespace {
+    /// The name of the database.
+    pub db: String,
+
+    /// The name of the collection. This isn't present for events that affect a whole database.
+    pub coll: Option<String>,
+}
+
+/// The types of operations that can be reported by a change stream.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum OperationType {
+    /// A document was inserted.
+    Insert,
+
+    /// A document was updated.
+    Update,
+
+    /// A document was replaced.
+    Replace,
+
+    /// A document was deleted.
+    Delete,
+
+    /// A collection was dropped.
+    Drop,
+
+    /// A collection was renamed.
+    Rename,
+
+    /// A database was dropped.
+    DropDatabase,
+
+    /// The change stream was invalidated, e.g. because the collection it was watching was
+    /// dropped or renamed. No further events will be returned by the stream.
+    Invalidate,
+
+    /// An operation type that isn't covered by the other variants, such as the ones that are only
+    /// reported when
+    /// [`show_expanded_events`](crate::options::ChangeStreamOptions::show_expanded_events) is set.
+    Other(String),
+}
+
+impl OperationType {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Insert => "insert",
+            Self::Update => "update",
+            Self::Replace => "replace",
+            Self::Delete => "delete",
+            Self::Drop => "drop",
+            Self::Rename => "rename",
+            Self::DropDatabase => "dropDatabase",
+            Self::Invalidate => "invalidate",
+            Self::Other(ref name) => name.as_str(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for OperationType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(match name.as_str() {
+            "insert" => Self::Insert,
+            "update" => Self::Update,
+            "replace" => Self::Replace,
+            "delete" => Self::Delete,
+            "drop" => Self::Drop,
+            "rename" => Self::Rename,
+            "dropDatabase" => Self::DropDatabase,
+            "invalidate" => Self::Invalidate,
+            _ => Self::Other(name),
+        })
+    }
+}
+
+impl Serialize for OperationType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
@@ -0,0 +1,340 @@
+//! Contains the functionality for change streams.
+
+pub mod event;
+pub(crate) mod options;
+#[cfg(test)]
+mod test;
+
+use std::{
+    collections::VecDeque,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use derivative::Derivative;
+use futures_core::{future::BoxFuture, Future, Stream};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    bson::{doc, from_document, Bson, Document, Timestamp},
+    change_stream::{event::ResumeToken, options::ChangeStreamOptions},
+    cursor::Cursor,
+    error::{ErrorKind, Result},
+    operation::{Aggregate, ChangeStreamAggregate},
+    Client,
+    Namespace,
+};
+
+/// A `ChangeStream` streams the ongoing changes of its associated collection. A `ChangeStream` can
+/// be obtained by calling [`Collection::watch`](../struct.Collection.html#method.watch).
+///
+/// `ChangeStream` implements
+/// [`Stream`](https://docs.rs/futures/latest/futures/stream/trait.Stream.html), so it can be
+/// iterated over in the same way as a [`Cursor`](../struct.Cursor.html). Unlike a cursor, a change
+/// stream doesn't end when there are no more events; it waits for new ones instead, and only ends
+/// once it's invalidated.
+///
+/// If fetching the next batch of events fails with an error after which the stream can be
+/// resumed, such as a network error, the change stream reopens itself once, picking up after the
+/// last event it returned.
+///
+/// ```rust
+/// # use futures::stream::StreamExt;
+/// # #[cfg(not(feature = "sync"))]
+/// # use mongodb::{bson::Document, error::Result, Client};
+/// #
+/// # #[cfg(not(feature = "sync"))]
+/// # async fn do_stuff() -> Result<()> {
+/// # let client = Client::with_uri_str("mongodb://example.com").await?;
+/// # let coll = client.database("foo").collection::<Document>("bar");
+/// let mut change_stream = coll.watch(None, None).await?;
+/// while let Some(event) = change_stream.next().await.transpose()? {
+///     println!("operation performed: {:?}", event.operation_type);
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct ChangeStream<T>
+where
+    T: DeserializeOwned,
+{
+    cursor: Cursor<Document>,
+
+    /// The events from the most recent batch that haven't been returned yet.
+    buffer: VecDeque<Document>,
+
+    /// The arguments that the stream was opened with, which are reused when resuming.
+    args: WatchArgs,
+
+    data: ChangeStreamData,
+
+    /// Whether the most recent batch from the server was empty and no request has been made since,
+    /// in which case [`ChangeStream::next_if_any`] returns without making one.
+    last_batch_empty: bool,
+
+    /// The `aggregate` reopening the stream after a resumable error, if one is in progress.
+    #[derivative(Debug = "ignore")]
+    pending_resume: Option<BoxFuture<'static, Result<Cursor<Document>>>>,
+
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> ChangeStream<T>
+where
+    T: DeserializeOwned,
+{
+    pub(crate) async fn open(args: WatchArgs) -> Result<Self> {
+        let (mut cursor, operation_time) = execute_watch(&args, args.options.as_ref()).await?;
+        let mut data = ChangeStreamData::new(args.options.as_ref());
+        data.initial_operation_time = operation_time;
+
+        let buffer = cursor.take_buffer();
+        let mut stream = Self {
+            cursor,
+            buffer: VecDeque::new(),
+            args,
+            data,
+            last_batch_empty: false,
+            pending_resume: None,
+            _phantom: PhantomData,
+        };
+        stream.handle_batch(buffer);
+        Ok(stream)
+    }
+
+    /// The token that can be used to start a new change stream that picks up after the last event
+    /// returned by this one, or `None` if the server hasn't provided one yet.
+    ///
+    /// Once the stream has returned an invalidate event, the token can only be used with
+    /// [`ChangeStreamOptions::start_after`](crate::options::ChangeStreamOptions::start_after).
+    pub fn resume_token(&self) -> Option<ResumeToken> {
+        self.data.resume_token.clone()
+    }
+
+    /// Retrieves the next event if one is available without waiting for new ones, returning `None`
+    /// otherwise. This can be used to process changes in between other work on the same task.
+    ///
+    /// If no events are buffered and the server's most recent batch was empty, this returns
+    /// `None` immediately. Otherwise, it makes at most one request to the server, which waits for
+    /// up to
+    /// [`max_await_time`](crate::options::ChangeStreamOptions::max_await_time) for new events.
+    /// `None` is also returned once the stream has been invalidated.
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "sync"))]
+    /// # use mongodb::{bson::Document, error::Result, Client};
+    /// #
+    /// # #[cfg(not(feature = "sync"))]
+    /// # async fn do_stuff() -> Result<()> {
+    /// # let client = Client::with_uri_str("mongodb://example.com").await?;
+    /// # let coll = client.database("foo").collection::<Document>("bar");
+    /// let mut change_stream = coll.watch(None, None).await?;
+    /// loop {
+    ///     while let Some(event) = change_stream.next_if_any().await? {
+    ///         println!("operation performed: {:?}", event.operation_type);
+    ///     }
+    ///     // do other work
+    /// #   break;
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn next_if_any(&mut self) -> Result<Option<T>> {
+        futures_util::future::poll_fn(|cx| self.poll_next_event(cx, false)).await
+    }
+
+    /// Polls for the next event. If `wait` is true, this keeps fetching batches until an event
+    /// arrives; otherwise, it gives up once a batch comes back empty.
+    fn poll_next_event(&mut self, cx: &mut Context<'_>, wait: bool) -> Poll<Result<Option<T>>> {
+        loop {
+            if let Some(ref mut resume) = self.pending_resume {
+                let result = match Pin::new(resume).poll(cx) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => return Poll::Pending,
+                };
+                self.pending_resume = None;
+                let mut cursor = result?;
+                let buffer = cursor.take_buffer();
+                self.cursor = cursor;
+                self.handle_batch(buffer);
+            }
+
+            if let Some(event) = self.buffer.pop_front() {
+                return Poll::Ready(self.process_event(event).map(Some));
+            }
+            if !wait && self.last_batch_empty {
+                self.last_batch_empty = false;
+                return Poll::Ready(Ok(None));
+            }
+
+            match self.cursor.poll_next_raw_batch(cx) {
+                Poll::Ready(Some(Ok(batch))) => {
+                    self.handle_batch(batch);
+                    if !wait && self.buffer.is_empty() {
+                        return Poll::Ready(Ok(None));
+                    }
+                }
+                Poll::Ready(Some(Err(error))) if error.is_resumable() => self.start_resume(),
+                Poll::Ready(Some(Err(error))) => return Poll::Ready(Err(error)),
+                Poll::Ready(None) => return Poll::Ready(Ok(None)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    /// Updates the resume token for a new batch of events and buffers the events.
+    fn handle_batch(&mut self, batch: VecDeque<Document>) {
+        if batch.is_empty() {
+            if let Some(token) = self.cursor.post_batch_resume_token() {
+                self.data.resume_token = Some(ResumeToken(Bson::Document(token.clone())));
+            }
+        }
+        self.last_batch_empty = batch.is_empty();
+        self.buffer = batch;
+    }
+
+    /// Records the resume token of an event that is about to be returned and deserializes it.
+    fn process_event(&mut self, event: Document) -> Result<T> {
+        let id = match event.get("_id") {
+            Some(id) => id.clone(),
+            None => return Err(ErrorKind::MissingResumeToken.into()),
+        };
+        let invalidate = event.get_str("operationType") == Ok("invalidate");
+
+        // The token reported for the batch is at least as recent as the last event in it, so it's
+        // used once the batch has been returned. The server only accepts an invalidate's own token
+        // for starting after it, though.
+        let token = match self.cursor.post_batch_resume_token() {
+            Some(token) if self.buffer.is_empty() && !invalidate => Bson::Document(token.clone()),
+            _ => id,
+        };
+        self.data.resume_token = Some(ResumeToken(token));
+        self.data.resume_with_start_after = invalidate;
+
+        from_document(event).map_err(Into::into)
+    }
+
+    /// Starts reopening the stream with options that pick up where it left off.
+    fn start_resume(&mut self) {
+        let options = self.data.resume_options(self.args.options.as_ref());
+        let args = self.args.clone();
+        self.pending_resume = Some(Box::pin(async move {
+            execute_watch(&args, Some(&options))
+                .await
+                .map(|(cursor, _)| cursor)
+        }));
+    }
+}
+
+impl<T> Stream for ChangeStream<T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_event(cx, true).map(Result::transpose)
+    }
+}
+
+/// The arguments that a change stream was opened with.
+#[derive(Clone, Debug)]
+pub(crate) struct WatchArgs {
+    pub(crate) client: Client,
+    pub(crate) target: Namespace,
+    pub(crate) pipeline: Vec<Document>,
+    pub(crate) options: Option<ChangeStreamOptions>,
+}
+
+/// The state that a change stream needs in order to resume.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ChangeStreamData {
+    /// The token of the last event returned, or of the most recent batch if it was reported after
+    /// that event.
+    resume_token: Option<ResumeToken>,
+
+    /// Whether the stream has to resume with `startAfter` rather than `resumeAfter`. This is the
+    /// case if it was opened with `start_after` and hasn't returned any events yet, or if the last
+    /// event it returned was an invalidate, since the server rejects `resumeAfter` for those.
+    resume_with_start_after: bool,
+
+    /// The `operationTime` of the reply that opened the stream, which is where the stream resumes
+    /// from if it's interrupted before the server provides a resume token. This is only recorded
+    /// if the stream wasn't opened with an explicit starting point.
+    initial_operation_time: Option<Timestamp>,
+}
+
+impl ChangeStreamData {
+    fn new(options: Option<&ChangeStreamOptions>) -> Self {
+        let start_after = options.and_then(|opts| opts.start_after.clone());
+        let resume_with_start_after = start_after.is_some();
+        let resume_token =
+            start_after.or_else(|| options.and_then(|opts| opts.resume_after.clone()));
+        Self {
+            resume_token,
+            resume_with_start_after,
+            initial_operation_time: None,
+        }
+    }
+
+    /// The options to reopen the stream with after a resumable error. Once there's a resume
+    /// token, the stream resumes from it, so the original starting point, including any
+    /// `start_at_operation_time`, is dropped. Until then, the stream resumes from the time it was
+    /// opened at.
+    fn resume_options(&self, options: Option<&ChangeStreamOptions>) -> ChangeStreamOptions {
+        let mut options = options.cloned().unwrap_or_default();
+        if let Some(ref token) = self.resume_token {
+            options.start_at_operation_time = None;
+            if self.resume_with_start_after {
+                options.start_after = Some(token.clone());
+                options.resume_after = None;
+            } else {
+                options.resume_after = Some(token.clone());
+                options.start_after = None;
+            }
+        } else if options.start_at_operation_time.is_none() {
+            options.start_at_operation_time = self.initial_operation_time;
+        }
+        options
+    }
+}
+
+/// Runs the `aggregate` that opens a change stream with the given options. If the options don't
+/// specify where the stream starts, the `operationTime` of the reply is also returned.
+async fn execute_watch(
+    args: &WatchArgs,
+    options: Option<&ChangeStreamOptions>,
+) -> Result<(Cursor<Document>, Option<Timestamp>)> {
+    let stage = match options {
+        Some(options) => options.stage()?,
+        None => Document::new(),
+    };
+    let mut pipeline = vec![doc! { "$changeStream": stage }];
+    pipeline.extend(args.pipeline.iter().cloned());
+
+    let capture_operation_time = options
+        .map(|opts| {
+            opts.resume_after.is_none()
+                && opts.start_after.is_none()
+                && opts.start_at_operation_time.is_none()
+        })
+        .unwrap_or(true);
+    let aggregate = ChangeStreamAggregate::new(
+        Aggregate::new(
+            args.target.clone(),
+            pipeline,
+            options.map(ChangeStreamOptions::aggregate_options),
+        ),
+        capture_operation_time,
+    );
+    let ((spec, operation_time), session) = args.client.execute_cursor_operation(aggregate).await?;
+    Ok((
+        Cursor::new(args.client.clone(), spec, session),
+        operation_time,
+    ))
+}
@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    bson::{to_bson, Bson, Document, Timestamp},
+    bson_util::deserialize_duration_from_u64_millis,
+    change_stream::event::ResumeToken,
+    collation::Collation,
+    concern::ReadConcern,
+    error::{ErrorKind, Result},
+    options::AggregateOptions,
+    selection_criteria::SelectionCriteria,
+};
+
+/// These are the valid options that can be passed to the `watch` method for creating a
+/// [`ChangeStream`](../change_stream/struct.ChangeStream.html).
+#[derive(Clone, Debug, Default, Deserialize, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ChangeStreamOptions {
+    /// Configures how the [`full_document`](super::event::ChangeStreamEvent::full_document)
+    /// field of change stream events is populated for update events. By default, it is only
+    /// populated for insert and replace events.
+    pub full_document: Option<FullDocumentType>,
+
+    /// Configures whether the
+    /// [`full_document_before_change`](super::event::ChangeStreamEvent::full_document_before_change)
+    /// field of change stream events is populated. By default, it isn't.
+    ///
+    /// Pre-images are only available for collections that have `changeStreamPreAndPostImages`
+    /// enabled. This option is only supported on server versions 6.0 and above.
+    pub full_document_before_change: Option<FullDocumentBeforeChangeType>,
+
+    /// Specifies the logical starting point for the new change stream. The stream will return the
+    /// events that occurred after the event that the token belongs to.
+    ///
+    /// This can't be used to resume after an invalidate event; use `start_after` for that.
+    pub resume_after: Option<ResumeToken>,
+
+    /// Like `resume_after`, but also allows the new change stream to start after an invalidate
+    /// event, in which case it returns the events for the collection with the same name that are
+    /// newer than the invalidate.
+    pub start_after: Option<ResumeToken>,
+
+    /// The change stream will only return the events that occurred at or after this cluster time.
+    ///
+    /// Only one of `resume_after`, `start_after` and `start_at_operation_time` can be specified.
+    pub start_at_operation_time: Option<Timestamp>,
+
+    /// Whether the change stream should also return events that are only reported on request,
+    /// such as the `create` events for collections and indexes.
+    ///
+    /// This option is only supported on server versions 6.0 and above.
+    pub show_expanded_events: Option<bool>,
+
+    /// The maximum number of events the server should return per batch.
+    pub batch_size: Option<u32>,
+
+    /// The maximum amount of time for the server to wait for new events before returning an
+    /// empty batch.
+    #[serde(deserialize_with = "deserialize_duration_from_u64_millis", default)]
+    pub max_await_time: Option<Duration>,
+
+    /// The collation to use when evaluating the pipeline passed to `watch`.
+    pub collation: Option<Collation>,
+
+    /// The read concern to use for the change stream.
+    ///
+    /// If none is specified, the read concern defined on the object being watched will be used.
+    pub read_concern: Option<ReadConcern>,
+
+    /// The criteria used to select a server for the change stream.
+    ///
+    /// If none is specified, the selection criteria defined on the object being watched will be
+    /// used.
+    #[serde(skip)]
+    pub selection_criteria: Option<SelectionCriteria>,
+}
+
+impl ChangeStreamOptions {
+    /// Builds the body of the `$changeStream` stage that opens a change stream with these options.
+    /// Returns an error if more than one starting point for the stream is specified.
+    pub(crate) fn stage(&self) -> Result<Document> {
+        let starting_points = [
+            self.resume_after.is_some(),
+            self.start_after.is_some(),
+            self.start_at_operation_time.is_some(),
+        ];
+        if starting_points.iter().filter(|set| **set).count() > 1 {
+            return Err(ErrorKind::InvalidArgument {
+                message: "only one of resume_after, start_after and start_at_operation_time can \
+                          be specified"
+                    .to_string(),
+            }
+            .into());
+        }
+
+        let mut stage = Document::new();
+        if let Some(ref full_document) = self.full_document {
+            stage.insert("fullDocument", to_bson(full_document)?);
+        }
+        if let Some(ref full_document_before_change) = self.full_document_before_change {
+            stage.insert(
+                "fullDocumentBeforeChange",
+                to_bson(full_document_before_change)?,
+            );
+        }
+        if let Some(ref token) = self.resume_after {
+            stage.insert("resumeAfter", token.0.clone());
+        }
+        if let Some(ref token) = self.start_after {
+            stage.insert("startAfter", token.0.clone());
+        }
+        if let Some(time) = self.start_at_operation_time {
+            stage.insert("startAtOperationTime", Bson::Timestamp(time));
+        }
+        if let Some(show_expanded_events) = self.show_expanded_events {
+            stage.insert("showExpandedEvents", show_expanded_events);
+        }
+        Ok(stage)
+    }
+
+    /// The options for the `aggregate` command that opens a change stream with these options.
+    pub(crate) fn aggregate_options(&self) -> AggregateOptions {
+        AggregateOptions::builder()
+            .batch_size(self.batch_size)
+            .max_await_time(self.max_await_time)
+            .collation(self.collation.clone())
+            .read_concern(self.read_concern.clone())
+            .selection_criteria(self.selection_criteria.clone())
+            .build()
+    }
+}
+
+/// Describes the modes for configuring the
+/// [`full_document`](super::event::ChangeStreamEvent::full_document) field of change stream
+/// events.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum FullDocumentType {
+    /// The field is only populated for insert and replace events.
+    Default,
+
+    /// The field is also populated for update events, with the most current majority-committed
+    /// version of the updated document.
+    UpdateLookup,
+
+    /// The field is also populated for update events if the post-image of the update is available.
+    /// This is only supported on server versions 6.0 and above.
+    WhenAvailable,
+
+    /// Like `WhenAvailable`, but the server returns an error if the post-image isn't available.
+    /// This is only supported on server versions 6.0 and above.
+    Required,
+}
+
+/// Describes the modes for configuring the
+/// [`full_document_before_change`](super::event::ChangeStreamEvent::full_document_before_change)
+/// field of change stream events.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum FullDocumentBeforeChangeType {
+    /// The field is populated with the pre-image of the document if it's available.
+    WhenAvailable,
+
+    /// The field is populated with the pre-image of the document, and the server returns an error
+    /// if it isn't available.
+    Required,
+
+    /// The field isn't populated. This is the default.
+    Off,
+}
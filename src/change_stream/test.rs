@@ -0,0 +1,163 @@
+use crate::{
+    bson::{doc, from_document, Bson, Document, Timestamp},
+    change_stream::{
+        event::{ChangeStreamEvent, OperationType, ResumeToken},
+        options::ChangeStreamOptions,
+        ChangeStreamData,
+    },
+    error::ErrorKind,
+};
+
+fn token(n: i32) -> ResumeToken {
+    ResumeToken(Bson::Document(doc! { "_data": n }))
+}
+
+#[test]
+fn resume_without_token_keeps_starting_point() {
+    let time = Timestamp {
+        time: 1,
+        increment: 2,
+    };
+    let options = ChangeStreamOptions::builder()
+        .start_at_operation_time(time)
+        .build();
+    let data = ChangeStreamData::new(Some(&options));
+
+    let resume = data.resume_options(Some(&options));
+    assert_eq!(resume.start_at_operation_time, Some(time));
+    assert_eq!(resume.resume_after, None);
+    assert_eq!(resume.start_after, None);
+}
+
+#[test]
+fn resume_after_event_uses_resume_after() {
+    let options = ChangeStreamOptions::builder()
+        .start_at_operation_time(Timestamp {
+            time: 1,
+            increment: 2,
+        })
+        .build();
+    let mut data = ChangeStreamData::new(Some(&options));
+    data.resume_token = Some(token(1));
+
+    let resume = data.resume_options(Some(&options));
+    assert_eq!(resume.resume_after, Some(token(1)));
+    assert_eq!(resume.start_after, None);
+    assert_eq!(resume.start_at_operation_time, None);
+}
+
+#[test]
+fn resume_before_first_event_uses_start_after() {
+    let options = ChangeStreamOptions::builder().start_after(token(1)).build();
+    let data = ChangeStreamData::new(Some(&options));
+
+    let resume = data.resume_options(Some(&options));
+    assert_eq!(resume.start_after, Some(token(1)));
+    assert_eq!(resume.resume_after, None);
+
+    // Once an event has been returned, the stream resumes after it instead.
+    let mut data = data;
+    data.resume_token = Some(token(2));
+    data.resume_with_start_after = false;
+    let resume = data.resume_options(Some(&options));
+    assert_eq!(resume.resume_after, Some(token(2)));
+    assert_eq!(resume.start_after, None);
+}
+
+#[test]
+fn resume_after_invalidate_uses_start_after() {
+    let options = ChangeStreamOptions::builder()
+        .resume_after(token(1))
+        .build();
+    let mut data = ChangeStreamData::new(Some(&options));
+    data.resume_token = Some(token(2));
+    data.resume_with_start_after = true;
+
+    let resume = data.resume_options(Some(&options));
+    assert_eq!(resume.start_after, Some(token(2)));
+    assert_eq!(resume.resume_after, None);
+}
+
+#[test]
+fn resume_before_token_uses_initial_operation_time() {
+    let time = Timestamp {
+        time: 3,
+        increment: 4,
+    };
+    let mut data = ChangeStreamData::new(None);
+    data.initial_operation_time = Some(time);
+
+    let resume = data.resume_options(None);
+    assert_eq!(resume.start_at_operation_time, Some(time));
+    assert_eq!(resume.resume_after, None);
+    assert_eq!(resume.start_after, None);
+
+    // A resume token takes precedence over the time the stream was opened at.
+    data.resume_token = Some(token(1));
+    let resume = data.resume_options(None);
+    assert_eq!(resume.resume_after, Some(token(1)));
+    assert_eq!(resume.start_at_operation_time, None);
+}
+
+#[test]
+fn multiple_starting_points_rejected() {
+    let time = Timestamp {
+        time: 1,
+        increment: 2,
+    };
+    let invalid = vec![
+        ChangeStreamOptions::builder()
+            .resume_after(token(1))
+            .start_after(token(1))
+            .build(),
+        ChangeStreamOptions::builder()
+            .resume_after(token(1))
+            .start_at_operation_time(time)
+            .build(),
+        ChangeStreamOptions::builder()
+            .start_after(token(1))
+            .start_at_operation_time(time)
+            .build(),
+    ];
+    for options in invalid {
+        let error = options.stage().unwrap_err();
+        assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+    }
+
+    let stage = ChangeStreamOptions::builder()
+        .start_at_operation_time(time)
+        .build()
+        .stage()
+        .unwrap();
+    assert_eq!(stage, doc! { "startAtOperationTime": time });
+}
+
+#[test]
+fn deserialize_update_event() {
+    let event = doc! {
+        "_id": { "_data": "token" },
+        "operationType": "update",
+        "ns": { "db": "db", "coll": "coll" },
+        "documentKey": { "_id": 1 },
+        "updateDescription": {
+            "updatedFields": { "x": 2 },
+            "removedFields": ["y"],
+            "truncatedArrays": [{ "field": "z", "newSize": 1 }],
+        },
+        "fullDocumentBeforeChange": { "_id": 1, "x": 1, "y": 1, "z": [1, 2] },
+    };
+    let event: ChangeStreamEvent<Document> = from_document(event).unwrap();
+
+    assert_eq!(event.operation_type, OperationType::Update);
+    let description = event.update_description.unwrap();
+    assert_eq!(description.updated_fields, doc! { "x": 2 });
+    assert_eq!(description.removed_fields, vec!["y".to_string()]);
+    let truncated = description.truncated_arrays.unwrap();
+    assert_eq!(truncated[0].field, "z");
+    assert_eq!(truncated[0].new_size, 1);
+    assert_eq!(
+        event.full_document_before_change,
+        Some(doc! { "_id": 1, "x": 1, "y": 1, "z": [1, 2] })
+    );
+    assert_eq!(event.full_document, None);
+}
@@ -3,10 +3,10 @@ use super::{session::TransactionState, Client, ClientSession};
 use std::{collections::HashSet, sync::Arc};
 
 use lazy_static::lazy_static;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::{
-    bson::Document,
+    bson::{Bson, Document},
     cmap::Connection,
     error::{
         Error,
@@ -19,7 +19,14 @@ use crate::{
     event::command::{CommandFailedEvent, CommandStartedEvent, CommandSucceededEvent},
     operation::{AbortTransaction, CommitTransaction, Operation, Retryability},
     options::SelectionCriteria,
-    sdam::{HandshakePhase, SelectedServer, SessionSupportStatus, TransactionSupportStatus},
+    results::OperationOutcome,
+    sdam::{
+        HandshakePhase,
+        SelectedServer,
+        SessionSupportStatus,
+        TopologyType,
+        TransactionSupportStatus,
+    },
     selection_criteria::ReadPreference,
 };
 
@@ -45,6 +52,13 @@ lazy_static! {
     };
 }
 
+/// The minimum wire version required for snapshot reads outside of transactions (MongoDB 5.0).
+const SNAPSHOT_READS_MIN_WIRE_VERSION: i32 = 13;
+
+/// How long past an operation's `max_time` the client waits for a reply before giving up on it.
+/// This leaves the server room to report MaxTimeMSExpired itself in the common case.
+const MAX_TIME_GRACE_PERIOD: Duration = Duration::from_secs(1);
+
 impl Client {
     /// Execute the given operation.
     ///
@@ -56,8 +70,22 @@ impl Client {
         op: T,
         session: impl Into<Option<&mut ClientSession>>,
     ) -> Result<T::O> {
-        // TODO RUST-9: allow unacknowledged write concerns
-        if !op.is_acknowledged() {
+        self.execute_operation_with_outcome(op, session)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Execute the given operation, also returning an `OperationOutcome` describing how it was
+    /// executed.
+    ///
+    /// Server selection and session handling are the same as for `execute_operation`.
+    pub(crate) async fn execute_operation_with_outcome<T: Operation>(
+        &self,
+        op: T,
+        session: impl Into<Option<&mut ClientSession>>,
+    ) -> Result<(T::O, OperationOutcome)> {
+        // TODO RUST-9: allow unacknowledged write concerns for the remaining write operations
+        if !op.is_acknowledged() && !op.supports_unacknowledged_writes() {
             return Err(ErrorKind::InvalidArgument {
                 message: "Unacknowledged write concerns are not supported".to_string(),
             }
@@ -65,6 +93,15 @@ impl Client {
         }
         match session.into() {
             Some(session) => {
+                if session.is_snapshot() && op.is_write() {
+                    return Err(ErrorKind::InvalidArgument {
+                        message: format!(
+                            "{} cannot be executed with a snapshot session",
+                            op.name()
+                        ),
+                    }
+                    .into());
+                }
                 if let Some(SelectionCriteria::ReadPreference(read_preference)) =
                     op.selection_criteria()
                 {
@@ -92,10 +129,21 @@ impl Client {
         &self,
         op: T,
     ) -> Result<(T::O, Option<ClientSession>)> {
+        self.execute_cursor_operation_with_outcome(op)
+            .await
+            .map(|(result, session, _)| (result, session))
+    }
+
+    /// Execute the given operation, returning the implicit session created for it if one was and
+    /// an `OperationOutcome` describing how it was executed.
+    pub(crate) async fn execute_cursor_operation_with_outcome<T: Operation>(
+        &self,
+        op: T,
+    ) -> Result<(T::O, Option<ClientSession>, OperationOutcome)> {
         let mut implicit_session = self.start_implicit_session(&op).await?;
         self.execute_operation_with_retry(op, implicit_session.as_mut())
             .await
-            .map(|result| (result, implicit_session))
+            .map(|(result, outcome)| (result, implicit_session, outcome))
     }
 
     /// Selects a server and executes the given operation on it, optionally using a provided
@@ -104,7 +152,9 @@ impl Client {
         &self,
         mut op: T,
         mut session: Option<&mut ClientSession>,
-    ) -> Result<T::O> {
+    ) -> Result<(T::O, OperationOutcome)> {
+        let start_time = Instant::now();
+
         // If the current transaction has been committed/aborted and it is not being
         // re-committed/re-aborted, reset the transaction's state to TransactionState::None.
         if let Some(ref mut session) = session {
@@ -119,21 +169,35 @@ impl Client {
             }
         }
 
-        let server = match self.select_server(op.selection_criteria()).await {
+        let selection_criteria = session
+            .as_ref()
+            .and_then(|session| session.transaction.pinned_mongos())
+            .or_else(|| op.selection_criteria());
+        let server = match self.select_server(selection_criteria).await {
             Ok(server) => server,
             Err(mut err) => {
-                err.add_labels(None, &session, None)?;
+                err.add_labels_and_update_pin(None, &mut session, None)?;
                 return Err(err);
             }
         };
 
-        let mut conn = match server.pool.check_out().await {
+        if let Some(ref mut session) = session {
+            if session.transaction.state == TransactionState::Starting
+                && self.inner.topology.topology_type() == TopologyType::Sharded
+            {
+                session.transaction.pin_mongos(server.address.clone());
+            }
+        }
+
+        let mut conn = match self.get_connection(&session, &op, &server).await {
             Ok(conn) => conn,
             Err(mut err) => {
-                err.add_labels(None, &session, None)?;
+                err.add_labels_and_update_pin(None, &mut session, None)?;
 
                 if err.is_pool_cleared() {
-                    return self.execute_retry(&mut op, &mut session, None, err).await;
+                    return self
+                        .execute_retry(&mut op, &mut session, None, err, start_time)
+                        .await;
                 } else {
                     return Err(err);
                 }
@@ -166,7 +230,7 @@ impl Client {
             )
             .await
         {
-            Ok(result) => Ok(result),
+            Ok(result) => Ok((result, OperationOutcome::new(&conn, 1, start_time))),
             Err(mut err) => {
                 // Retryable writes are only supported by storage engines with document-level
                 // locking, so users need to disable retryable writes if using mmapv1.
@@ -194,10 +258,8 @@ impl Client {
                 // release the selected server to decrement its operation count
                 drop(server);
 
-                if retryability == Retryability::Read && err.is_read_retryable()
-                    || retryability == Retryability::Write && err.is_write_retryable()
-                {
-                    self.execute_retry(&mut op, &mut session, txn_number, err)
+                if err.is_retryable(&retryability) {
+                    self.execute_retry(&mut op, &mut session, txn_number, err, start_time)
                         .await
                 } else {
                     Err(err)
@@ -212,15 +274,20 @@ impl Client {
         session: &mut Option<&mut ClientSession>,
         txn_number: Option<u64>,
         first_error: Error,
-    ) -> Result<T::O> {
-        let server = match self.select_server(op.selection_criteria()).await {
+        start_time: Instant,
+    ) -> Result<(T::O, OperationOutcome)> {
+        let selection_criteria = session
+            .as_ref()
+            .and_then(|session| session.transaction.pinned_mongos())
+            .or_else(|| op.selection_criteria());
+        let server = match self.select_server(selection_criteria).await {
             Ok(server) => server,
             Err(_) => {
                 return Err(first_error);
             }
         };
 
-        let mut conn = match server.pool.check_out().await {
+        let mut conn = match self.get_connection(session, op, &server).await {
             Ok(c) => c,
             Err(_) => return Err(first_error),
         };
@@ -236,7 +303,7 @@ impl Client {
             .execute_operation_on_connection(op, &mut conn, session, txn_number, &retryability)
             .await
         {
-            Ok(result) => Ok(result),
+            Ok(result) => Ok((result, OperationOutcome::new(&conn, 2, start_time))),
             Err(err) => {
                 self.inner
                     .topology
@@ -271,6 +338,9 @@ impl Client {
         }
 
         let mut cmd = op.build(connection.stream_description()?)?;
+        if self.inner.options.disable_exhaust == Some(true) {
+            cmd.exhaust_allowed = false;
+        }
         self.inner
             .topology
             .update_command_with_read_pref(connection.address(), &mut cmd, op.selection_criteria())
@@ -282,16 +352,41 @@ impl Client {
                 if let Some(txn_number) = txn_number {
                     cmd.set_txn_number(txn_number);
                 }
+                if !session.in_transaction()
+                    && op.supports_read_concern()
+                    && session.is_snapshot()
+                    && connection
+                        .stream_description()?
+                        .max_wire_version
+                        .unwrap_or(0)
+                        < SNAPSHOT_READS_MIN_WIRE_VERSION
+                {
+                    return Err(ErrorKind::InvalidArgument {
+                        message: "snapshot reads require MongoDB 5.0 or later".to_string(),
+                    }
+                    .into());
+                }
+                cmd.set_read_concern(session, op.supports_read_concern());
+                if let Some(ref recovery_token) = session.transaction.recovery_token {
+                    if cmd.name == CommitTransaction::NAME || cmd.name == AbortTransaction::NAME {
+                        cmd.set_recovery_token(recovery_token);
+                    }
+                }
                 match session.transaction.state {
                     TransactionState::Starting => {
+                        if self.is_load_balanced() {
+                            session.transaction.pin_connection(connection.pin()?);
+                        }
                         cmd.set_start_transaction();
                         cmd.set_autocommit();
-                        cmd.set_txn_read_concern(*session)?;
+                        cmd.clear_write_concern();
                         session.transaction.state = TransactionState::InProgress;
                     }
-                    TransactionState::InProgress
-                    | TransactionState::Committed { .. }
-                    | TransactionState::Aborted => {
+                    TransactionState::InProgress => {
+                        cmd.set_autocommit();
+                        cmd.clear_write_concern();
+                    }
+                    TransactionState::Committed { .. } | TransactionState::Aborted => {
                         cmd.set_autocommit();
                     }
                     _ => {}
@@ -321,10 +416,15 @@ impl Client {
             cmd.set_cluster_time(cluster_time);
         }
 
+        // Unacknowledged writes are sent without waiting for a reply, since the server has
+        // nothing to report about them.
+        cmd.more_to_come = !op.is_acknowledged();
+
         let connection_info = connection.info();
         let request_id = crate::cmap::conn::next_request_id();
 
         if let Some(ref server_api) = self.inner.options.server_api {
+            server_api.check_command(&cmd)?;
             cmd.set_server_api(server_api);
         }
 
@@ -339,12 +439,23 @@ impl Client {
             } else {
                 cmd.body.clone()
             };
+            let cursor_id = match cmd.name.as_str() {
+                "getMore" => cmd.body.get_i64("getMore").ok(),
+                "killCursors" => cmd
+                    .body
+                    .get_array("cursors")
+                    .ok()
+                    .and_then(|cursors| cursors.first())
+                    .and_then(Bson::as_i64),
+                _ => None,
+            };
             let command_started_event = CommandStartedEvent {
                 command: command_body,
                 db: cmd.target_db.clone(),
                 command_name: cmd.name.clone(),
                 request_id,
                 connection: connection_info.clone(),
+                cursor_id,
             };
 
             handler.handle_command_started_event(command_started_event);
@@ -353,7 +464,13 @@ impl Client {
         let start_time = Instant::now();
         let cmd_name = cmd.name.clone();
 
-        let response_result = match connection.send_command(cmd, request_id).await {
+        let operation_timeout = op
+            .max_time()
+            .map(|max_time| max_time + MAX_TIME_GRACE_PERIOD);
+        let response_result = match connection
+            .send_command_with_timeout(cmd, request_id, operation_timeout)
+            .await
+        {
             Ok(response) => {
                 if let Some(cluster_time) = response.cluster_time() {
                     self.inner.topology.advance_cluster_time(cluster_time).await;
@@ -361,6 +478,19 @@ impl Client {
                         session.advance_cluster_time(cluster_time)
                     }
                 }
+                if let Some(ref mut session) = session {
+                    if let Some(operation_time) = response.operation_time() {
+                        session.advance_operation_time(operation_time);
+                    }
+                    if session.is_snapshot() && session.snapshot_time().is_none() {
+                        if let Some(snapshot_time) = response.snapshot_time() {
+                            session.set_snapshot_time(snapshot_time);
+                        }
+                    }
+                    if let Some(recovery_token) = response.recovery_token() {
+                        session.transaction.recovery_token = Some(recovery_token.clone());
+                    }
+                }
                 response.validate().map(|_| response)
             }
             err => err,
@@ -383,15 +513,17 @@ impl Client {
                 });
 
                 if let Some(session) = session {
-                    if err.is_network_error() {
+                    if err.is_network_error() || matches!(*err.kind, ErrorKind::Timeout { .. }) {
                         session.mark_dirty();
                     }
                 }
 
-                err.add_labels(Some(connection), session, Some(retryability))?;
+                err.add_labels_and_update_pin(Some(connection), session, Some(retryability))?;
                 op.handle_error(err)
             }
             Ok(response) => {
+                // Unacknowledged writes have no reply, so the success is reported with the
+                // `{ ok: 1 }` placeholder response, as the command monitoring spec requires.
                 self.emit_command_event(|handler| {
                     let should_redact =
                         REDACTED_COMMANDS.contains(cmd_name.to_lowercase().as_str());
@@ -412,9 +544,19 @@ impl Client {
                 });
 
                 match op.handle_response(response, connection.stream_description()?) {
-                    Ok(response) => Ok(response),
+                    Ok(mut response) => {
+                        self.pin_connection_for_cursor(op, &mut response, connection, session)?;
+                        if connection.is_streaming() {
+                            op.take_streaming_connection(&mut response, connection.detach());
+                        }
+                        Ok(response)
+                    }
                     Err(mut err) => {
-                        err.add_labels(Some(connection), session, Some(retryability))?;
+                        err.add_labels_and_update_pin(
+                            Some(connection),
+                            session,
+                            Some(retryability),
+                        )?;
                         Err(err)
                     }
                 }
@@ -422,6 +564,64 @@ impl Client {
         }
     }
 
+    /// Checks out a connection to `server` for `op`, unless the session's transaction or the
+    /// cursor that `op` continues is pinned to a connection, in which case that connection is
+    /// used instead.
+    async fn get_connection<T: Operation>(
+        &self,
+        session: &Option<&mut ClientSession>,
+        op: &T,
+        server: &SelectedServer,
+    ) -> Result<Connection> {
+        let session_pin = session
+            .as_ref()
+            .and_then(|session| session.transaction.pinned_connection());
+        match (session_pin, op.pinned_connection()) {
+            (Some(handle), None) | (None, Some(handle)) => handle.take_connection().await,
+            // A cursor created within a transaction shares the transaction's connection.
+            (Some(session_handle), Some(op_handle)) if session_handle.id() == op_handle.id() => {
+                op_handle.take_connection().await
+            }
+            (Some(_), Some(_)) => Err(ErrorKind::Internal {
+                message: "cursor and transaction are pinned to different connections".to_string(),
+            }
+            .into()),
+            (None, None) => server.pool.check_out().await,
+        }
+    }
+
+    /// Pins the cursor created by `op`, if any, to `connection` when connected through a load
+    /// balancer, so that its getMore and killCursors commands reach the same backend server. A
+    /// cursor created within a transaction shares the transaction's pinned connection.
+    fn pin_connection_for_cursor<T: Operation>(
+        &self,
+        op: &T,
+        output: &mut T::O,
+        connection: &mut Connection,
+        session: &Option<&mut ClientSession>,
+    ) -> Result<()> {
+        if !self.is_load_balanced() {
+            return Ok(());
+        }
+        let spec = match op.cursor_specification(output) {
+            Some(spec) if spec.id() != 0 => spec,
+            _ => return Ok(()),
+        };
+        let session_pin = session
+            .as_ref()
+            .and_then(|session| session.transaction.pinned_connection());
+        spec.info.pinned_connection = Some(match session_pin {
+            Some(handle) => handle.clone(),
+            None => connection.pin()?,
+        });
+        Ok(())
+    }
+
+    /// Whether the client is connected to the deployment through a load balancer.
+    fn is_load_balanced(&self) -> bool {
+        self.inner.options.load_balanced == Some(true)
+    }
+
     /// Start an implicit session if the operation and write concern are compatible with sessions.
     async fn start_implicit_session<T: Operation>(&self, op: &T) -> Result<Option<ClientSession>> {
         match self.get_session_support_status().await? {
@@ -491,7 +691,11 @@ impl Client {
                 Retryability::Read if self.inner.options.retry_reads != Some(false) => {
                     return Ok(Retryability::Read);
                 }
-                Retryability::Write if conn.stream_description()?.supports_retryable_writes() => {
+                // unacknowledged writes can't be retried, since their outcome is never known
+                Retryability::Write
+                    if op.is_acknowledged()
+                        && conn.stream_description()?.supports_retryable_writes() =>
+                {
                     // commitTransaction and abortTransaction should be retried regardless of the
                     // value for retry_writes set on the Client
                     if op.name() == CommitTransaction::NAME
@@ -509,7 +713,8 @@ impl Client {
 }
 
 impl Error {
-    /// Adds the necessary labels to this Error.
+    /// Adds the necessary labels to this Error, and unpins the session from its mongos if the
+    /// labels indicate that the transaction can't continue on it.
     ///
     /// A TransientTransactionError label should be added if a transaction is in progress and the
     /// error is a network or server selection error.
@@ -519,10 +724,14 @@ impl Error {
     /// server version, a label should only be added if the `retry_writes` client option is not set
     /// to `false`, the operation during which the error occured is write-retryable, and a
     /// TransientTransactionError label has not already been added.
-    fn add_labels(
+    ///
+    /// Errors with a TransientTransactionError or UnknownTransactionCommitResult label unpin the
+    /// session, since the transaction will either be retried from the start or committed through
+    /// any mongos using its recovery token.
+    fn add_labels_and_update_pin(
         &mut self,
         conn: Option<&Connection>,
-        session: &Option<&mut ClientSession>,
+        session: &mut Option<&mut ClientSession>,
         retryability: Option<&Retryability>,
     ) -> Result<()> {
         let transaction_state = session.as_ref().map_or(&TransactionState::None, |session| {
@@ -566,6 +775,14 @@ impl Error {
                 }
             }
         }
+
+        if let Some(ref mut session) = session {
+            if self.contains_label(TRANSIENT_TRANSACTION_ERROR)
+                || self.contains_label(UNKNOWN_TRANSACTION_COMMIT_RESULT)
+            {
+                session.transaction.unpin();
+            }
+        }
         Ok(())
     }
 }
@@ -7,12 +7,14 @@ use std::{sync::Arc, time::Duration};
 
 use bson::Bson;
 use derivative::Derivative;
-use std::time::Instant;
 
 #[cfg(test)]
 use crate::options::ServerAddress;
+use futures_util::future;
+
 use crate::{
     bson::Document,
+    coll::FindOneCoalescer,
     concern::{ReadConcern, WriteConcern},
     db::Database,
     error::{ErrorKind, Result},
@@ -25,10 +27,20 @@ use crate::{
         ReadPreference,
         SelectionCriteria,
         SessionOptions,
+        WarmUpOptions,
     },
     results::DatabaseSpecification,
-    sdam::{SelectedServer, SessionSupportStatus, Topology},
+    runtime::Clock,
+    sdam::{
+        SelectedServer,
+        ServerCapabilities,
+        ServerInfo,
+        SessionSupportStatus,
+        Topology,
+        TopologyType,
+    },
     ClientSession,
+    RUNTIME,
 };
 pub(crate) use session::{ClusterTime, SESSIONS_UNSUPPORTED_COMMANDS};
 use session::{ServerSession, ServerSessionPool};
@@ -84,6 +96,8 @@ struct ClientInner {
     topology: Topology,
     options: ClientOptions,
     session_pool: ServerSessionPool,
+    clock: Arc<dyn Clock>,
+    find_one_coalescer: FindOneCoalescer,
 }
 
 impl Drop for ClientInner {
@@ -111,12 +125,23 @@ impl Client {
         let inner = Arc::new(ClientInner {
             topology: Topology::new(options.clone())?,
             session_pool: ServerSessionPool::new(),
+            clock: options.clock(),
+            find_one_coalescer: Default::default(),
             options,
         });
 
         Ok(Self { inner })
     }
 
+    pub(crate) fn find_one_coalescer(&self) -> &FindOneCoalescer {
+        &self.inner.find_one_coalescer
+    }
+
+    #[cfg(feature = "sync")]
+    pub(crate) fn topology(&self) -> &Topology {
+        &self.inner.topology
+    }
+
     pub(crate) fn emit_command_event(&self, emit: impl FnOnce(&Arc<dyn CommandEventHandler>)) {
         if let Some(ref handler) = self.inner.options.command_event_handler {
             emit(handler);
@@ -172,6 +197,18 @@ impl Client {
         })
     }
 
+    /// Gets information about each database present in the cluster the Client is connected to,
+    /// returning the documents reported by the server as-is rather than deserializing them into
+    /// [`DatabaseSpecification`](results/struct.DatabaseSpecification.html)s.
+    pub async fn list_databases_raw(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<ListDatabasesOptions>>,
+    ) -> Result<Vec<Document>> {
+        let op = ListDatabases::new(filter.into(), false, options.into());
+        self.execute_operation(op, None).await
+    }
+
     /// Gets the names of the databases present in the cluster the Client is connected to.
     pub async fn list_database_names(
         &self,
@@ -197,8 +234,75 @@ impl Client {
         }
     }
 
+    /// Gets the type of the cluster the `Client` is connected to, based on the driver's most recent
+    /// knowledge of the cluster.
+    ///
+    /// This method does not send or receive anything across the wire, so the returned value may be
+    /// `TopologyType::Unknown` if the driver has not yet discovered the cluster.
+    pub async fn topology_type(&self) -> TopologyType {
+        self.inner.topology.topology_type()
+    }
+
+    /// Gets information about each of the servers known to be in the cluster the `Client` is
+    /// connected to, based on the driver's most recent knowledge of the cluster.
+    ///
+    /// This method does not send or receive anything across the wire.
+    pub async fn servers(&self) -> Vec<ServerInfo<'static>> {
+        self.inner
+            .topology
+            .server_descriptions()
+            .into_iter()
+            .map(ServerInfo::new_owned)
+            .collect()
+    }
+
+    /// Gets the features supported by the cluster the `Client` is connected to, based on the
+    /// driver's most recent knowledge of the cluster.
+    ///
+    /// This method does not send or receive anything across the wire, so the returned value may
+    /// not be known yet if the driver has not yet discovered the cluster. See
+    /// [`ServerCapabilities::is_known`](struct.ServerCapabilities.html#method.is_known).
+    pub async fn server_capabilities(&self) -> ServerCapabilities {
+        self.inner.topology.server_capabilities()
+    }
+
+    /// Establishes `min_pool_size` connections (or a single connection if `min_pool_size` is not
+    /// set) to the server selected by this `Client`'s selection criteria, so that the connection
+    /// pool is ready to serve traffic without incurring the cost of connection establishment on the
+    /// first operations.
+    ///
+    /// This returns once every connection has completed its handshake, or with an error if any of
+    /// them failed or if the `timeout` in the provided options elapsed first. No commands are sent
+    /// on the connections beyond those needed to establish them.
+    pub async fn warm_up(&self, options: impl Into<Option<WarmUpOptions>>) -> Result<()> {
+        let options = options.into();
+
+        let warm_up = async {
+            let server = self.select_server(self.selection_criteria()).await?;
+            let count = self.inner.options.min_pool_size.unwrap_or(0).max(1);
+
+            // Hold on to every connection until all of them have been checked out so that the
+            // pool can't satisfy the check-outs by reusing a single connection. They're checked
+            // back in when they're dropped.
+            future::try_join_all((0..count).map(|_| server.pool.check_out())).await?;
+
+            Ok(())
+        };
+
+        match options.and_then(|options| options.timeout) {
+            Some(timeout) => RUNTIME.timeout(timeout, warm_up).await?,
+            None => warm_up.await,
+        }
+    }
+
     /// Starts a new `ClientSession`.
+    ///
+    /// Returns an error if both `causal_consistency` and `snapshot` are set to true in `options`.
     pub async fn start_session(&self, options: Option<SessionOptions>) -> Result<ClientSession> {
+        if let Some(ref options) = options {
+            options.validate()?;
+        }
+
         match self.get_session_support_status().await? {
             SessionSupportStatus::Supported {
                 logical_session_timeout,
@@ -252,6 +356,11 @@ impl Client {
         self.inner.session_pool.clear().await;
     }
 
+    #[cfg(test)]
+    pub(crate) async fn session_pool_size(&self) -> usize {
+        self.inner.session_pool.len().await
+    }
+
     #[cfg(test)]
     pub(crate) async fn is_session_checked_in(&self, id: &Document) -> bool {
         self.inner.session_pool.contains(id).await
@@ -274,7 +383,7 @@ impl Client {
         let criteria =
             criteria.unwrap_or(&SelectionCriteria::ReadPreference(ReadPreference::Primary));
 
-        let start_time = Instant::now();
+        let start_time = self.inner.clock.now();
         let timeout = self
             .inner
             .options
@@ -299,7 +408,7 @@ impl Client {
 
             // If the time that has passed since the start of the loop is greater than the timeout,
             // then `time_remaining` will be 0, so no change will be found.
-            let time_passed = start_time.elapsed();
+            let time_passed = self.inner.clock.now().saturating_duration_since(start_time);
             let time_remaining = timeout
                 .checked_sub(time_passed)
                 .unwrap_or_else(|| Duration::from_millis(0));
@@ -41,10 +41,12 @@ use crate::{
     bson::{doc, Bson, Document},
     bson_util::{deserialize_duration_from_u64_millis, serialize_duration_as_int_millis},
     client::auth::{AuthMechanism, Credential},
+    cmap::Command,
     concern::{Acknowledgment, ReadConcern, WriteConcern},
     error::{ErrorKind, Result},
-    event::{cmap::CmapEventHandler, command::CommandEventHandler},
+    event::{cmap::CmapEventHandler, command::CommandEventHandler, sdam::SdamEventHandler},
     options::ReadConcernLevel,
+    runtime::{Clock, SystemClock},
     sdam::MIN_HEARTBEAT_FREQUENCY,
     selection_criteria::{ReadPreference, SelectionCriteria, TagSet},
     srv::{OriginalSrvInfo, SrvResolver},
@@ -64,8 +66,10 @@ const URI_OPTIONS: &[&str] = &[
     "directconnection",
     "heartbeatfrequencyms",
     "journal",
+    "loadbalanced",
     "localthresholdms",
     "maxidletimems",
+    "maxconnecting",
     "maxstalenessseconds",
     "maxpoolsize",
     "minpoolsize",
@@ -291,10 +295,11 @@ impl fmt::Display for ServerAddress {
     }
 }
 
-/// Specifies the server API version to declare
+/// Specifies the server API version to declare.
 #[derive(Clone, Debug, PartialEq)]
 #[non_exhaustive]
-pub(crate) enum ServerApiVersion {
+pub enum ServerApiVersion {
+    /// Use API version 1.
     V1,
 }
 
@@ -337,7 +342,7 @@ impl<'de> Deserialize<'de> for ServerApiVersion {
 #[builder(field_defaults(setter(into)))]
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
-pub(crate) struct ServerApi {
+pub struct ServerApi {
     /// The declared API version.
     pub version: ServerApiVersion,
 
@@ -351,6 +356,70 @@ pub(crate) struct ServerApi {
     /// Note that at the time of this writing, no deprecations in version 1 exist.
     #[builder(default)]
     pub deprecation_errors: Option<bool>,
+
+    /// Whether the driver should reject aggregation pipelines containing stages that are known not
+    /// to be part of the declared API version before sending them to the server. This only has an
+    /// effect when `strict` is `true`. Only the top-level stages of a pipeline are checked.
+    ///
+    /// The default value is `false`, in which case detecting such stages is left to the server.
+    #[builder(default)]
+    #[serde(skip)]
+    pub check_pipeline_stages: Option<bool>,
+}
+
+impl ServerApi {
+    /// The aggregation stages that are not included in version 1 of the API.
+    const V1_EXCLUDED_STAGES: &'static [&'static str] = &[
+        "$collStats",
+        "$currentOp",
+        "$indexStats",
+        "$listLocalSessions",
+        "$listSessions",
+        "$planCacheStats",
+        "$search",
+    ];
+
+    /// Returns an error if `check_pipeline_stages` and `strict` are both enabled and `command` is
+    /// an aggregation whose pipeline contains a stage excluded from the declared API version.
+    pub(crate) fn check_command(&self, command: &Command) -> Result<()> {
+        if self.strict != Some(true) || self.check_pipeline_stages != Some(true) {
+            return Ok(());
+        }
+
+        if command.name != "aggregate" {
+            return Ok(());
+        }
+
+        let excluded_stages = match self.version {
+            ServerApiVersion::V1 => Self::V1_EXCLUDED_STAGES,
+        };
+
+        let pipeline = match command.body.get_array("pipeline") {
+            Ok(pipeline) => pipeline,
+            Err(_) => return Ok(()),
+        };
+
+        for stage in pipeline {
+            let stage_name = match stage {
+                Bson::Document(stage) => stage.keys().next(),
+                _ => None,
+            };
+            if let Some(stage_name) = stage_name {
+                if excluded_stages.contains(&stage_name.as_str()) {
+                    return Err(ErrorKind::InvalidArgument {
+                        message: format!(
+                            "the {} aggregation stage is not supported in strict mode for API \
+                             version {}",
+                            stage_name, self.version
+                        ),
+                    }
+                    .into());
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Contains the options that can be used to create a new [`Client`](../struct.Client.html).
@@ -395,6 +464,13 @@ pub struct ClientOptions {
     #[serde(skip)]
     pub command_event_handler: Option<Arc<dyn CommandEventHandler>>,
 
+    /// The handler that should process all server heartbeat events. See the SdamEventHandler type
+    /// documentation for more details.
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    #[builder(default)]
+    #[serde(skip)]
+    pub sdam_event_handler: Option<Arc<dyn SdamEventHandler>>,
+
     /// The connect timeout passed to each underlying TcpStream when attemtping to connect to the
     /// server.
     ///
@@ -413,6 +489,24 @@ pub struct ClientOptions {
     #[builder(default)]
     pub direct_connection: Option<bool>,
 
+    /// Whether to never let the server stream the batches of exhaust cursors, even if
+    /// [`FindOptions::exhaust`](crate::options::FindOptions::exhaust) is set and the server
+    /// supports it; a getMore is sent for each batch instead. This can be used when connecting
+    /// through a proxy that doesn't handle exhaust replies correctly.
+    ///
+    /// The default value is false.
+    #[builder(default)]
+    pub disable_exhaust: Option<bool>,
+
+    /// Whether to monitor servers by polling them every
+    /// [`heartbeat_freq`](#structfield.heartbeat_freq), even if they support the streaming
+    /// protocol, in which case the server sends a reply as soon as its state changes. This can be
+    /// used when connecting through a proxy that doesn't handle streamed replies correctly.
+    ///
+    /// The default value is false.
+    #[builder(default)]
+    pub disable_streaming_monitoring: Option<bool>,
+
     /// Extra information to append to the driver version in the metadata of the handshake with the
     /// server. This should be used by libraries wrapping the driver, e.g. ODMs.
     #[builder(default)]
@@ -425,6 +519,16 @@ pub struct ClientOptions {
     #[builder(default)]
     pub heartbeat_freq: Option<Duration>,
 
+    /// Whether the driver is connecting to a MongoDB deployment through a load balancer. If true,
+    /// the single host is treated as a load balancer rather than a server: no server monitoring is
+    /// performed, and every operation is routed through it. Cursors and transactions are pinned
+    /// to the connection that they were started on, so that all of their commands reach the same
+    /// backend server.
+    ///
+    /// The default value is false.
+    #[builder(default)]
+    pub load_balanced: Option<bool>,
+
     /// When running a read operation with a ReadPreference that allows selecting secondaries,
     /// `local_threshold` is used to determine how much longer the average round trip time between
     /// the driver and server is allowed compared to the least round trip time of all the suitable
@@ -446,6 +550,15 @@ pub struct ClientOptions {
     #[builder(default)]
     pub max_idle_time: Option<Duration>,
 
+    /// The maximum number of connections that a connection pool may be establishing concurrently
+    /// for a given server. Operations that need a new connection while `max_connecting`
+    /// connections are already being established will wait for one of them to finish or for an
+    /// in-use connection to be checked back in.
+    ///
+    /// The default value is 2, and a value of 0 is invalid.
+    #[builder(default)]
+    pub max_connecting: Option<u32>,
+
     /// The maximum amount of connections that the Client should allow to be created in a
     /// connection pool for a given server. If an operation is attempted on a server while
     /// `max_pool_size` connections are checked out, the operation will block until an in-progress
@@ -498,8 +611,8 @@ pub struct ClientOptions {
     /// supported and is considered undefined behaviour. To run any command with a different API
     /// version or without declaring one, create a separate client that declares the
     /// appropriate API version.
-    #[builder(default, setter(skip))]
-    pub(crate) server_api: Option<ServerApi>,
+    #[builder(default)]
+    pub server_api: Option<ServerApi>,
 
     /// The amount of time the Client should attempt to select a server for an operation before
     /// timing outs
@@ -546,6 +659,13 @@ pub struct ClientOptions {
     #[builder(default)]
     #[cfg(test)]
     pub(crate) heartbeat_freq_test: Option<Duration>,
+
+    /// Used by tests to override the clock used for elapsed-time computations.
+    #[builder(default, setter(skip))]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    #[serde(skip)]
+    #[cfg(test)]
+    pub(crate) clock_test: Option<Arc<dyn Clock>>,
 }
 
 fn default_hosts() -> Vec<ServerAddress> {
@@ -580,12 +700,16 @@ impl Serialize for ClientOptions {
             #[serde(serialize_with = "serialize_duration_as_int_millis")]
             heartbeatfrequencyms: &'a Option<Duration>,
 
+            loadbalanced: &'a Option<bool>,
+
             #[serde(serialize_with = "serialize_duration_as_int_millis")]
             localthresholdms: &'a Option<Duration>,
 
             #[serde(serialize_with = "serialize_duration_as_int_millis")]
             maxidletimems: &'a Option<Duration>,
 
+            maxconnecting: &'a Option<u32>,
+
             maxpoolsize: &'a Option<u32>,
 
             minpoolsize: &'a Option<u32>,
@@ -627,8 +751,10 @@ impl Serialize for ClientOptions {
             credential: &self.credential,
             directconnection: &self.direct_connection,
             heartbeatfrequencyms: &self.heartbeat_freq,
+            loadbalanced: &self.load_balanced,
             localthresholdms: &self.local_threshold,
             maxidletimems: &self.max_idle_time,
+            maxconnecting: &self.max_connecting,
             maxpoolsize: &self.max_pool_size,
             minpoolsize: &self.min_pool_size,
             readconcern: &self.read_concern,
@@ -660,6 +786,7 @@ struct ClientOptionsParser {
     pub repl_set_name: Option<String>,
     pub write_concern: Option<WriteConcern>,
     pub server_selection_timeout: Option<Duration>,
+    pub max_connecting: Option<u32>,
     pub max_pool_size: Option<u32>,
     pub min_pool_size: Option<u32>,
     pub max_idle_time: Option<Duration>,
@@ -671,6 +798,7 @@ struct ClientOptionsParser {
     pub socket_timeout: Option<Duration>,
     pub zlib_compression: Option<i32>,
     pub direct_connection: Option<bool>,
+    pub load_balanced: Option<bool>,
     pub credential: Option<Credential>,
     max_staleness: Option<Duration>,
     tls_insecure: Option<bool>,
@@ -890,6 +1018,7 @@ impl From<ClientOptionsParser> for ClientOptions {
             selection_criteria: parser.selection_criteria,
             repl_set_name: parser.repl_set_name,
             write_concern: parser.write_concern,
+            max_connecting: parser.max_connecting,
             max_pool_size: parser.max_pool_size,
             min_pool_size: parser.min_pool_size,
             max_idle_time: parser.max_idle_time,
@@ -901,16 +1030,22 @@ impl From<ClientOptionsParser> for ClientOptions {
             socket_timeout: parser.socket_timeout,
             zlib_compression: parser.zlib_compression,
             direct_connection: parser.direct_connection,
+            load_balanced: parser.load_balanced,
             driver_info: None,
             credential: parser.credential,
             cmap_event_handler: None,
             command_event_handler: None,
+            sdam_event_handler: None,
+            disable_exhaust: None,
+            disable_streaming_monitoring: None,
             original_srv_info: None,
             original_uri: Some(parser.original_uri),
             resolver_config: None,
             server_api: None,
             #[cfg(test)]
             heartbeat_freq_test: None,
+            #[cfg(test)]
+            clock_test: None,
         }
     }
 }
@@ -949,7 +1084,9 @@ impl ClientOptions {
     ///   * `direct`: maps to the `direct` field
     ///   * `heartbeatFrequencyMS`: maps to the `heartbeat_frequency` field
     ///   * `journal`: maps to the `journal` field of the `write_concern` field
+    ///   * `loadBalanced`: maps to the `load_balanced` field
     ///   * `localThresholdMS`: maps to the `local_threshold` field
+    ///   * `maxConnecting`: maps to the `max_connecting` field
     ///   * `maxIdleTimeMS`: maps to the `max_idle_time` field
     ///   * `maxStalenessSeconds`: maps to the `max_staleness` field of the `selection_criteria`
     ///     field
@@ -992,7 +1129,7 @@ impl ClientOptions {
     #[cfg(any(feature = "sync", docsrs))]
     #[cfg_attr(docsrs, doc(cfg(feature = "sync")))]
     pub fn parse(s: impl AsRef<str>) -> Result<Self> {
-        crate::RUNTIME.block_on(Self::parse_uri(s.as_ref(), None))
+        crate::RUNTIME.block_on_sync(Self::parse_uri(s.as_ref(), None))
     }
 
     /// Parses a MongoDB connection string into a `ClientOptions` struct.
@@ -1022,7 +1159,7 @@ impl ClientOptions {
     #[cfg(any(feature = "sync", docsrs))]
     #[cfg_attr(docsrs, doc(cfg(feature = "sync")))]
     pub fn parse_with_resolver_config(uri: &str, resolver_config: ResolverConfig) -> Result<Self> {
-        crate::RUNTIME.block_on(Self::parse_uri(uri, Some(resolver_config)))
+        crate::RUNTIME.block_on_sync(Self::parse_uri(uri, Some(resolver_config)))
     }
 
     /// Populate this `ClientOptions` from the given URI, optionally using the resolver config for
@@ -1098,6 +1235,17 @@ impl ClientOptions {
         }
     }
 
+    /// The clock that clients created from these options should use for elapsed-time
+    /// computations.
+    pub(crate) fn clock(&self) -> Arc<dyn Clock> {
+        #[cfg(test)]
+        if let Some(ref clock) = self.clock_test {
+            return clock.clone();
+        }
+
+        Arc::new(SystemClock)
+    }
+
     /// Ensure the options set are valid, returning an error descirbing the problem if they are not.
     pub(crate) fn validate(&self) -> Result<()> {
         if let Some(true) = self.direct_connection {
@@ -1109,9 +1257,44 @@ impl ClientOptions {
             }
         }
 
+        if let Some(true) = self.load_balanced {
+            if self.hosts.len() > 1 {
+                return Err(ErrorKind::InvalidArgument {
+                    message: "cannot specify multiple seeds with loadBalanced=true".to_string(),
+                }
+                .into());
+            }
+
+            if self.repl_set_name.is_some() {
+                return Err(ErrorKind::InvalidArgument {
+                    message: "cannot specify replicaSet with loadBalanced=true".to_string(),
+                }
+                .into());
+            }
+
+            if let Some(true) = self.direct_connection {
+                return Err(ErrorKind::InvalidArgument {
+                    message: "cannot specify directConnection=true with loadBalanced=true"
+                        .to_string(),
+                }
+                .into());
+            }
+        }
+
+        if let Some(0) = self.max_connecting {
+            return Err(ErrorKind::InvalidArgument {
+                message: "cannot specify maxConnecting=0".to_string(),
+            }
+            .into());
+        }
+
         if let Some(ref write_concern) = self.write_concern {
             write_concern.validate()?;
         }
+
+        if let Some(SelectionCriteria::ReadPreference(ref read_pref)) = self.selection_criteria {
+            read_pref.validate()?;
+        }
         Ok(())
     }
 
@@ -1126,12 +1309,17 @@ impl ClientOptions {
                 compressors,
                 cmap_event_handler,
                 command_event_handler,
+                sdam_event_handler,
                 connect_timeout,
                 credential,
                 direct_connection,
+                disable_exhaust,
+                disable_streaming_monitoring,
                 driver_info,
                 heartbeat_freq,
+                load_balanced,
                 local_threshold,
+                max_connecting,
                 max_idle_time,
                 max_pool_size,
                 min_pool_size,
@@ -1525,7 +1713,11 @@ impl ClientOptionsParser {
             };
         }
 
-        self.selection_criteria = self.read_preference.take().map(Into::into);
+        self.selection_criteria = self
+            .read_preference
+            .take()
+            .map(SelectionCriteria::try_from)
+            .transpose()?;
 
         if let Some(true) = self.direct_connection {
             if self.srv {
@@ -1648,6 +1840,9 @@ impl ClientOptionsParser {
             k @ "directconnection" => {
                 self.direct_connection = Some(get_bool!(value, k));
             }
+            k @ "loadbalanced" => {
+                self.load_balanced = Some(get_bool!(value, k));
+            }
             k @ "heartbeatfrequencyms" => {
                 let duration = get_duration!(value, k);
 
@@ -1687,6 +1882,9 @@ impl ClientOptionsParser {
 
                 self.max_staleness = Some(max_staleness);
             }
+            k @ "maxconnecting" => {
+                self.max_connecting = Some(get_u32!(value, k));
+            }
             k @ "maxpoolsize" => {
                 self.max_pool_size = Some(get_u32!(value, k));
             }
@@ -1941,7 +2139,7 @@ mod tests {
     use super::{ClientOptions, ServerAddress};
     use crate::{
         concern::{Acknowledgment, ReadConcernLevel, WriteConcern},
-        selection_criteria::{ReadPreference, ReadPreferenceOptions},
+        selection_criteria::{ReadPreference, ReadPreferenceOptions, SelectionCriteria},
     };
 
     macro_rules! tag_set {
@@ -2262,7 +2460,7 @@ mod tests {
                         port: Some(27018),
                     },
                 ],
-                selection_criteria: Some(
+                selection_criteria: Some(SelectionCriteria::ReadPreference(
                     ReadPreference::SecondaryPreferred {
                         options: ReadPreferenceOptions::builder()
                             .tag_sets(vec![
@@ -2277,8 +2475,7 @@ mod tests {
                             ])
                             .build()
                     }
-                    .into()
-                ),
+                )),
                 read_concern: Some(ReadConcernLevel::Majority.into()),
                 write_concern: Some(write_concern),
                 repl_set_name: Some("foo".to_string()),
@@ -2307,6 +2504,49 @@ pub struct SessionOptions {
     /// on the [`Database`](../struct.Database.html) or [`Collection`](../struct.Collection.html)
     /// associated with the operations within the transaction.
     pub default_transaction_options: Option<TransactionOptions>,
+
+    /// If true, read operations performed using this session will be causally consistent with the
+    /// operations previously performed using it; e.g. a read will observe the results of a
+    /// preceding write. This is done by sending the session's operation time as the
+    /// `afterClusterTime` of each read.
+    ///
+    /// The default value is true unless `snapshot` is true. This option cannot be set to true if
+    /// `snapshot` is also true.
+    pub causal_consistency: Option<bool>,
+
+    /// If true, all read operations performed using this session will read from the same
+    /// snapshot, which is established by the first read. Writes and transactions are not
+    /// supported on snapshot sessions.
+    ///
+    /// Snapshot reads require MongoDB 5.0+. The default value is false.
+    pub snapshot: Option<bool>,
+}
+
+impl SessionOptions {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if let (Some(true), Some(true)) = (self.causal_consistency, self.snapshot) {
+            return Err(ErrorKind::InvalidArgument {
+                message: "snapshot and causal_consistency are mutually exclusive".to_string(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Contains the options that can be used for
+/// [`Client::warm_up`](../struct.Client.html#method.warm_up).
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct WarmUpOptions {
+    /// The maximum amount of time to spend establishing connections. If this elapses before the
+    /// pool is warmed up, an error is returned. Any connections that were established will remain
+    /// in the pool.
+    ///
+    /// If this is not specified, only the client's server selection and connection timeouts
+    /// apply.
+    pub timeout: Option<Duration>,
 }
 
 /// Contains the options that can be used for a transaction.
@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+
 use pretty_assertions::assert_eq;
 use serde::Deserialize;
 
@@ -5,7 +7,9 @@ use crate::{
     bson::{Bson, Document},
     client::options::{ClientOptions, ClientOptionsParser, ServerAddress},
     error::ErrorKind,
+    selection_criteria::{ReadPreference, ReadPreferenceOptions, SelectionCriteria, TagSet},
     test::run_spec_test,
+    Client,
 };
 #[derive(Debug, Deserialize)]
 struct TestFile {
@@ -189,3 +193,105 @@ async fn parse_unknown_options() {
     .await;
     parse_uri("maxstalenessms", Some("maxstalenessseconds")).await;
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn parse_max_connecting() {
+    let options = ClientOptions::parse("mongodb://localhost/?maxConnecting=5")
+        .await
+        .unwrap();
+    assert_eq!(options.max_connecting, Some(5));
+
+    let error = ClientOptions::parse("mongodb://localhost/?maxConnecting=0")
+        .await
+        .expect_err("maxConnecting=0 should be rejected");
+    assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn read_preference_tag_set_validation() {
+    fn read_pref(tag_sets: Vec<Vec<(&str, &str)>>) -> ReadPreference {
+        let tag_sets = tag_sets
+            .into_iter()
+            .map(|tags| {
+                tags.into_iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect::<TagSet>()
+            })
+            .collect::<Vec<_>>();
+        ReadPreference::Secondary {
+            options: ReadPreferenceOptions::builder().tag_sets(tag_sets).build(),
+        }
+    }
+
+    let valid = read_pref(vec![
+        vec![("dc", "ny"), ("rack", "1")],
+        vec![("dc", "")],
+        vec![],
+    ]);
+    valid
+        .validate()
+        .expect("valid tag sets should pass validation");
+    SelectionCriteria::try_from(valid).expect("valid tag sets should convert");
+
+    let invalid = [
+        read_pref(vec![vec![("", "ny")]]),
+        read_pref(vec![vec![("dc", "ny")], vec![("dc\0", "sf")]]),
+        read_pref(vec![vec![("dc", "n\0y")], vec![]]),
+    ];
+    for read_pref in invalid.iter() {
+        match SelectionCriteria::try_from(read_pref.clone()).map_err(|e| *e.kind) {
+            Ok(_) => panic!("expected conversion error for {:?}", read_pref),
+            Err(ErrorKind::InvalidArgument { .. }) => {}
+            Err(e) => panic!("expected InvalidArgument, but got {:?}", e),
+        }
+
+        let options = ClientOptions::builder()
+            .selection_criteria(SelectionCriteria::ReadPreference(read_pref.clone()))
+            .build();
+        match Client::with_options(options).map_err(|e| *e.kind) {
+            Ok(_) => panic!("expected error for {:?}", read_pref),
+            Err(ErrorKind::InvalidArgument { .. }) => {}
+            Err(e) => panic!("expected InvalidArgument, but got {:?}", e),
+        }
+    }
+
+    match ClientOptions::parse(
+        "mongodb://localhost/?readPreference=secondary&readPreferenceTags=:ny",
+    )
+    .await
+    .map_err(|e| *e.kind)
+    {
+        Ok(_) => panic!("expected error for empty tag key"),
+        Err(ErrorKind::InvalidArgument { .. }) => {}
+        Err(e) => panic!("expected InvalidArgument, but got {:?}", e),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn parse_load_balanced() {
+    let options = ClientOptions::parse("mongodb://localhost/?loadBalanced=true")
+        .await
+        .expect("loadBalanced with a single host should be valid");
+    assert_eq!(options.load_balanced, Some(true));
+
+    let options = ClientOptions::parse("mongodb://a,b/?loadBalanced=false")
+        .await
+        .expect("loadBalanced=false with multiple hosts should be valid");
+    assert_eq!(options.load_balanced, Some(false));
+
+    let invalid = [
+        "mongodb://a,b/?loadBalanced=true",
+        "mongodb://localhost/?loadBalanced=true&replicaSet=rs",
+        "mongodb://localhost/?loadBalanced=true&directConnection=true",
+    ];
+    for uri in invalid.iter() {
+        match ClientOptions::parse(uri).await.map_err(|e| *e.kind) {
+            Ok(_) => panic!("expected error for {}", uri),
+            Err(ErrorKind::InvalidArgument { .. }) => {}
+            Err(e) => panic!("expected InvalidArgument for {}, but got {:?}", uri, e),
+        }
+    }
+}
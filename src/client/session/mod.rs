@@ -5,17 +5,20 @@ mod test;
 
 use std::{
     collections::HashSet,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
+use futures_util::future::BoxFuture;
 use lazy_static::lazy_static;
 use uuid::Uuid;
 
 use crate::{
-    bson::{doc, spec::BinarySubtype, Binary, Bson, Document},
-    error::{ErrorKind, Result},
+    bson::{doc, spec::BinarySubtype, Binary, Bson, Document, Timestamp},
+    cmap::PinnedConnectionHandle,
+    error::{ErrorKind, Result, UNKNOWN_TRANSACTION_COMMIT_RESULT},
     operation::{AbortTransaction, CommitTransaction, Operation},
-    options::{SessionOptions, TransactionOptions},
+    options::{SelectionCriteria, ServerAddress, SessionOptions, TransactionOptions},
     sdam::TransactionSupportStatus,
     Client,
     RUNTIME,
@@ -43,10 +46,13 @@ lazy_static! {
 /// collections atomically. For more information about when and how to use transactions in MongoDB,
 /// see the [manual](https://docs.mongodb.com/manual/core/transactions/).
 ///
-/// Replica set transactions are supported on MongoDB 4.0+. Transactions are associated with a
-/// `ClientSession`. To begin a transaction, call [`ClientSession::start_transaction`] on a
-/// `ClientSession`. The `ClientSession` must be passed to operations to be executed within the
-/// transaction.
+/// Replica set transactions are supported on MongoDB 4.0+, and sharded transactions are supported
+/// on MongoDB 4.2+. Transactions are associated with a `ClientSession`. To begin a transaction,
+/// call [`ClientSession::start_transaction`] on a `ClientSession`. The `ClientSession` must be
+/// passed to operations to be executed within the transaction.
+///
+/// If a `ClientSession` is dropped while its transaction is in progress, the transaction is
+/// aborted in the background on a best-effort basis, and any errors from doing so are ignored.
 ///
 /// ```rust
 /// use mongodb::{
@@ -95,29 +101,41 @@ lazy_static! {
 ///     }
 /// }
 /// ```
-// TODO RUST-122 Remove this note and adjust the above description to indicate that sharded
-// transactions are supported on 4.2+
-/// Note: the driver does not currently support transactions on sharded clusters.
 #[derive(Clone, Debug)]
 pub struct ClientSession {
     cluster_time: Option<ClusterTime>,
+    operation_time: Option<Timestamp>,
+    snapshot_time: Option<Timestamp>,
     server_session: ServerSession,
     client: Client,
     is_implicit: bool,
     options: Option<SessionOptions>,
     pub(crate) transaction: Transaction,
+
+    /// Whether this is a copy of another session made by `fork`, which leaves the server session
+    /// and any transaction to the original when dropped.
+    forked: bool,
 }
 
 #[derive(Clone, Debug)]
 pub(crate) struct Transaction {
     pub(crate) state: TransactionState,
     pub(crate) options: Option<TransactionOptions>,
+
+    /// The mongos or connection that the transaction's operations are sent to, if any.
+    pub(crate) pinned: Option<TransactionPin>,
+
+    /// The token returned by mongos that allows a different mongos to commit or abort the
+    /// transaction once the session is no longer pinned.
+    pub(crate) recovery_token: Option<Document>,
 }
 
 impl Transaction {
     pub(crate) fn start(&mut self, options: Option<TransactionOptions>) {
         self.state = TransactionState::Starting;
         self.options = options;
+        self.pinned = None;
+        self.recovery_token = None;
     }
 
     pub(crate) fn commit(&mut self, data_committed: bool) {
@@ -132,6 +150,40 @@ impl Transaction {
     pub(crate) fn reset(&mut self) {
         self.state = TransactionState::None;
         self.options = None;
+        self.pinned = None;
+        self.recovery_token = None;
+    }
+
+    /// Pins the transaction to the mongos at `address`.
+    pub(crate) fn pin_mongos(&mut self, address: ServerAddress) {
+        self.pinned = Some(TransactionPin::Mongos(SelectionCriteria::Predicate(
+            Arc::new(move |server_info| *server_info.address() == address),
+        )));
+    }
+
+    /// Pins the transaction to the connection that `handle` refers to.
+    pub(crate) fn pin_connection(&mut self, handle: PinnedConnectionHandle) {
+        self.pinned = Some(TransactionPin::Connection(handle));
+    }
+
+    pub(crate) fn unpin(&mut self) {
+        self.pinned = None;
+    }
+
+    /// The criteria selecting the mongos that the transaction is pinned to, if any.
+    pub(crate) fn pinned_mongos(&self) -> Option<&SelectionCriteria> {
+        match self.pinned {
+            Some(TransactionPin::Mongos(ref criteria)) => Some(criteria),
+            _ => None,
+        }
+    }
+
+    /// The connection that the transaction is pinned to, if any.
+    pub(crate) fn pinned_connection(&self) -> Option<&PinnedConnectionHandle> {
+        match self.pinned {
+            Some(TransactionPin::Connection(ref handle)) => Some(handle),
+            _ => None,
+        }
     }
 }
 
@@ -140,10 +192,25 @@ impl Default for Transaction {
         Self {
             state: TransactionState::None,
             options: None,
+            pinned: None,
+            recovery_token: None,
         }
     }
 }
 
+/// What a transaction's operations are pinned to, since only the server that the transaction was
+/// started on knows about it.
+#[derive(Clone, Debug)]
+pub(crate) enum TransactionPin {
+    /// On sharded clusters, the transaction is pinned to the mongos that its first operation is
+    /// sent to.
+    Mongos(SelectionCriteria),
+
+    /// Through a load balancer, the transaction is pinned to the connection that its first
+    /// operation is sent on, since the load balancer routes each connection to one mongos.
+    Connection(PinnedConnectionHandle),
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) enum TransactionState {
     None,
@@ -170,9 +237,47 @@ impl ClientSession {
             client,
             server_session,
             cluster_time: None,
+            operation_time: None,
+            snapshot_time: None,
             is_implicit,
             options,
             transaction: Default::default(),
+            forked: false,
+        }
+    }
+
+    /// Creates a copy of this session to run an operation with that may outlive the borrow of this
+    /// session. Once the operation completes, the state that it picked up from the server is
+    /// applied back to this session with `merge`.
+    pub(crate) fn fork(&self) -> Self {
+        let mut fork = self.clone();
+        fork.forked = true;
+        fork
+    }
+
+    /// Applies the state that `fork` picked up from the server to this session.
+    pub(crate) fn merge(&mut self, mut fork: Self) {
+        if let Some(ref cluster_time) = fork.cluster_time {
+            self.advance_cluster_time(cluster_time);
+        }
+        if let Some(operation_time) = fork.operation_time {
+            self.advance_operation_time(operation_time);
+        }
+        if self.snapshot_time.is_none() {
+            self.snapshot_time = fork.snapshot_time;
+        }
+        if fork.server_session.dirty {
+            self.mark_dirty();
+        }
+        self.server_session.last_use =
+            std::cmp::max(self.server_session.last_use, fork.server_session.last_use);
+
+        // An error can unpin the transaction, and mongos can hand out a new recovery token.
+        if fork.transaction.pinned.is_none() {
+            self.transaction.unpin();
+        }
+        if let Some(recovery_token) = fork.transaction.recovery_token.take() {
+            self.transaction.recovery_token = Some(recovery_token);
         }
     }
 
@@ -191,8 +296,9 @@ impl ClientSession {
         self.is_implicit
     }
 
-    /// Whether this session is currently in a transaction.
-    pub(crate) fn in_transaction(&self) -> bool {
+    /// Whether this session is currently in a transaction, i.e. a transaction has been started and
+    /// has not yet been committed or aborted.
+    pub fn in_transaction(&self) -> bool {
         self.transaction.state == TransactionState::Starting
             || self.transaction.state == TransactionState::InProgress
     }
@@ -216,6 +322,50 @@ impl ClientSession {
         }
     }
 
+    /// The operation time of the most recent operation performed using this session. This will be
+    /// `None` if this session has not been used in an operation yet.
+    pub fn operation_time(&self) -> Option<Timestamp> {
+        self.operation_time
+    }
+
+    /// Set the operation time to the provided one if it is greater than this session's operation
+    /// time or if this session's operation time is `None`. This can be used along with
+    /// [`advance_cluster_time`](#method.advance_cluster_time) to make this session causally
+    /// consistent with the operations performed using another session.
+    pub fn advance_operation_time(&mut self, to: Timestamp) {
+        if self.operation_time.map(|time| time < to).unwrap_or(true) {
+            self.operation_time = Some(to);
+        }
+    }
+
+    /// Whether read operations performed using this session should be causally consistent.
+    pub(crate) fn causal_consistency(&self) -> bool {
+        !self.is_implicit
+            && self
+                .options
+                .as_ref()
+                .and_then(|options| options.causal_consistency)
+                .unwrap_or(!self.is_snapshot())
+    }
+
+    /// Whether this session was created for snapshot reads.
+    pub(crate) fn is_snapshot(&self) -> bool {
+        self.options
+            .as_ref()
+            .and_then(|options| options.snapshot)
+            .unwrap_or(false)
+    }
+
+    /// The time of the snapshot read from by this session, if it's a snapshot session that has
+    /// performed a read.
+    pub(crate) fn snapshot_time(&self) -> Option<Timestamp> {
+        self.snapshot_time
+    }
+
+    pub(crate) fn set_snapshot_time(&mut self, snapshot_time: Timestamp) {
+        self.snapshot_time = Some(snapshot_time);
+    }
+
     /// Mark this session (and the underlying server session) as dirty.
     pub(crate) fn mark_dirty(&mut self) {
         self.server_session.dirty = true;
@@ -278,6 +428,12 @@ impl ClientSession {
         &mut self,
         options: impl Into<Option<TransactionOptions>>,
     ) -> Result<()> {
+        if self.is_snapshot() {
+            return Err(ErrorKind::Transaction {
+                message: "transactions are not supported in snapshot sessions".into(),
+            }
+            .into());
+        }
         match self.transaction.state {
             TransactionState::Starting | TransactionState::InProgress => {
                 return Err(ErrorKind::Transaction {
@@ -472,6 +628,90 @@ impl ClientSession {
         }
     }
 
+    /// Aborts the transaction that is currently active on this session, discarding any error.
+    ///
+    /// This is intended for best-effort cleanup, e.g. after an operation in the transaction has
+    /// failed, where an error from aborting would only obscure the original one. Unlike
+    /// [`ClientSession::abort_transaction`], calling this when there is no active transaction is
+    /// not an error.
+    pub async fn abort_transaction_ignoring_errors(&mut self) {
+        let _result = self.abort_transaction().await;
+    }
+
+    /// Starts a transaction, runs `callback` within it, and then commits the transaction if
+    /// `callback` succeeded or aborts it if not. The result of `callback` is returned if the
+    /// transaction was committed.
+    ///
+    /// If committing fails with an error labeled "UnknownTransactionCommitResult", e.g. due to a
+    /// network error, the commit is retried once with the same transaction number. If `callback`
+    /// commits or aborts the transaction itself, it is left as is.
+    ///
+    /// ```rust
+    /// # use futures::future::FutureExt;
+    /// # use mongodb::{bson::{doc, Document}, error::Result, Client, ClientSession};
+    /// #
+    /// # async fn do_stuff() -> Result<()> {
+    /// # let client = Client::with_uri_str("mongodb://example.com").await?;
+    /// # let mut session = client.start_session(None).await?;
+    /// let coll = client.database("foo").collection::<Document>("bar");
+    /// session
+    ///     .with_transaction(
+    ///         move |session| {
+    ///             async move {
+    ///                 coll.insert_one_with_session(doc! { "x": 1 }, None, session).await?;
+    ///                 coll.delete_one_with_session(doc! { "y": 2 }, None, session).await?;
+    ///                 Ok(())
+    ///             }
+    ///             .boxed()
+    ///         },
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn with_transaction<R, F>(
+        &mut self,
+        callback: F,
+        options: impl Into<Option<TransactionOptions>>,
+    ) -> Result<R>
+    where
+        F: for<'a> FnOnce(&'a mut ClientSession) -> BoxFuture<'a, Result<R>>,
+    {
+        self.start_transaction(options).await?;
+        let result = callback(self).await;
+        self.finish_transaction(result).await
+    }
+
+    /// Commits the transaction started by `with_transaction` if its callback returned `result`
+    /// successfully, or aborts it otherwise.
+    pub(crate) async fn finish_transaction<R>(&mut self, result: Result<R>) -> Result<R> {
+        let in_transaction = matches!(
+            self.transaction.state,
+            TransactionState::Starting | TransactionState::InProgress
+        );
+
+        let value = match result {
+            Ok(value) => value,
+            Err(error) => {
+                if in_transaction {
+                    self.abort_transaction_ignoring_errors().await;
+                }
+                return Err(error);
+            }
+        };
+
+        if in_transaction {
+            match self.commit_transaction().await {
+                Err(error) if error.contains_label(UNKNOWN_TRANSACTION_COMMIT_RESULT) => {
+                    self.commit_transaction().await?
+                }
+                result => result?,
+            }
+        }
+        Ok(value)
+    }
+
     fn default_transaction_options(&self) -> Option<&TransactionOptions> {
         self.options
             .as_ref()
@@ -481,6 +721,7 @@ impl ClientSession {
 
 struct DroppedClientSession {
     cluster_time: Option<ClusterTime>,
+    operation_time: Option<Timestamp>,
     server_session: ServerSession,
     client: Client,
     is_implicit: bool,
@@ -492,20 +733,27 @@ impl From<DroppedClientSession> for ClientSession {
     fn from(dropped_session: DroppedClientSession) -> Self {
         Self {
             cluster_time: dropped_session.cluster_time,
+            operation_time: dropped_session.operation_time,
+            snapshot_time: None,
             server_session: dropped_session.server_session,
             client: dropped_session.client,
             is_implicit: dropped_session.is_implicit,
             options: dropped_session.options,
             transaction: dropped_session.transaction,
+            forked: false,
         }
     }
 }
 
 impl Drop for ClientSession {
     fn drop(&mut self) {
+        if self.forked {
+            return;
+        }
         if self.transaction.state == TransactionState::InProgress {
             let dropped_session = DroppedClientSession {
                 cluster_time: self.cluster_time.clone(),
+                operation_time: self.operation_time,
                 server_session: self.server_session.clone(),
                 client: self.client.clone(),
                 is_implicit: self.is_implicit,
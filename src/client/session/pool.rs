@@ -40,7 +40,7 @@ impl ServerSessionPool {
     pub(crate) async fn check_in(&self, session: ServerSession, logical_session_timeout: Duration) {
         let mut pool = self.pool.lock().await;
         while let Some(pooled_session) = pool.pop_back() {
-            if session.is_about_to_expire(logical_session_timeout) {
+            if pooled_session.is_about_to_expire(logical_session_timeout) {
                 continue;
             }
             pool.push_back(pooled_session);
@@ -57,6 +57,11 @@ impl ServerSessionPool {
         self.pool.lock().await.clear();
     }
 
+    #[cfg(test)]
+    pub(crate) async fn len(&self) -> usize {
+        self.pool.lock().await.len()
+    }
+
     #[cfg(test)]
     pub(crate) async fn contains(&self, id: &Document) -> bool {
         self.pool.lock().await.iter().any(|s| &s.id == id)
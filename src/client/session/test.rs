@@ -1,15 +1,44 @@
-use std::{future::Future, time::Duration};
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
 
 use bson::Document;
 use futures::stream::StreamExt;
-use tokio::sync::RwLockReadGuard;
+use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
+use super::{ServerSession, ServerSessionPool, TransactionState};
 use crate::{
-    bson::{doc, Bson},
-    error::Result,
-    options::{Acknowledgment, FindOptions, InsertOneOptions, ReadPreference, WriteConcern},
+    bson::{doc, Bson, Timestamp},
+    cmap::Command,
+    concern::ReadConcernLevel,
+    error::{ErrorKind, Result},
+    options::{
+        Acknowledgment,
+        DatabaseOptions,
+        FindOptions,
+        InsertOneOptions,
+        ReadConcern,
+        ReadPreference,
+        SessionOptions,
+        TransactionOptions,
+        WriteConcern,
+    },
     selection_criteria::SelectionCriteria,
-    test::{EventClient, TestClient, CLIENT_OPTIONS, LOCK},
+    test::{
+        CommandEvent,
+        Event,
+        EventClient,
+        FailCommandOptions,
+        FailPoint,
+        FailPointData,
+        FailPointMode,
+        TestClient,
+        CLIENT_OPTIONS,
+        LOCK,
+    },
+    Client,
+    ClientSession,
     Collection,
     RUNTIME,
 };
@@ -227,6 +256,111 @@ async fn pool_is_lifo() {
     assert_eq!(s2.id(), &a_id);
 }
 
+const LOGICAL_SESSION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn server_session_pool_is_lifo() {
+    let pool = ServerSessionPool::new();
+
+    let a = ServerSession::new();
+    let b = ServerSession::new();
+    let a_id = a.id.clone();
+    let b_id = b.id.clone();
+
+    pool.check_in(a, LOGICAL_SESSION_TIMEOUT).await;
+    pool.check_in(b, LOGICAL_SESSION_TIMEOUT).await;
+    assert_eq!(pool.len().await, 2);
+
+    assert_eq!(pool.check_out(LOGICAL_SESSION_TIMEOUT).await.id, b_id);
+    assert_eq!(pool.check_out(LOGICAL_SESSION_TIMEOUT).await.id, a_id);
+    assert_eq!(pool.len().await, 0);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn server_session_pool_discards_dirty_sessions() {
+    let pool = ServerSessionPool::new();
+
+    let clean = ServerSession::new();
+    let mut dirty = ServerSession::new();
+    dirty.dirty = true;
+    let dirty_id = dirty.id.clone();
+
+    pool.check_in(clean, LOGICAL_SESSION_TIMEOUT).await;
+    pool.check_in(dirty, LOGICAL_SESSION_TIMEOUT).await;
+
+    assert_eq!(pool.len().await, 1);
+    assert!(!pool.contains(&dirty_id).await);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn server_session_pool_prunes_expired_sessions_on_check_in() {
+    let pool = ServerSessionPool::new();
+
+    let mut stale = ServerSession::new();
+    stale.last_use = Instant::now() - LOGICAL_SESSION_TIMEOUT;
+    let stale_id = stale.id.clone();
+
+    // Check in the stale session with a longer timeout so that it is still considered valid.
+    pool.check_in(stale, LOGICAL_SESSION_TIMEOUT * 4).await;
+    assert_eq!(pool.len().await, 1);
+
+    let fresh = ServerSession::new();
+    let fresh_id = fresh.id.clone();
+    pool.check_in(fresh, LOGICAL_SESSION_TIMEOUT).await;
+
+    assert_eq!(pool.len().await, 1);
+    assert!(pool.contains(&fresh_id).await);
+    assert!(!pool.contains(&stale_id).await);
+}
+
+/// A network error encountered while using a session should cause its server session to be
+/// discarded rather than returned to the pool.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn dirty_session_discarded_after_network_error() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.hosts.drain(1..);
+    options.retry_writes = Some(false);
+    let client = TestClient::with_options(Some(options)).await;
+    if client.is_standalone() || !client.supports_fail_command().await {
+        println!(
+            "skipping {} due to sessions or failCommand not being supported",
+            function_name!()
+        );
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let failpoint = FailPointData::close_connection(Some(1)).fail_command(&["insert"]);
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    client.clear_session_pool().await;
+    let mut session = client.start_session(None).await.unwrap();
+    let session_id = session.id().clone();
+
+    let error = coll
+        .insert_one_with_session(doc! { "x": 1 }, None, &mut session)
+        .await
+        .expect_err("insert should fail due to the closed connection");
+    assert!(error.is_network_error());
+    assert!(session.is_dirty());
+
+    drop(session);
+    RUNTIME.delay_for(Duration::from_millis(250)).await;
+
+    assert!(!client.is_session_checked_in(&session_id).await);
+    assert_eq!(client.session_pool_size().await, 0);
+}
+
 /// Prose test 2 from sessions spec.
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
@@ -518,3 +652,659 @@ async fn find_and_getmore_share_session() {
         run_test(&client, &coll, read_pref).await;
     }
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn snapshot_and_causal_consistency_are_mutually_exclusive() {
+    let client = Client::with_options(CLIENT_OPTIONS.clone()).unwrap();
+    let options = SessionOptions::builder()
+        .snapshot(true)
+        .causal_consistency(true)
+        .build();
+
+    let error = client
+        .start_session(Some(options))
+        .await
+        .expect_err("starting the session should fail");
+    assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn causal_and_snapshot_reads_after_write() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(5, 0) {
+        println!(
+            "skipping {} due to not running against a 5.0+ replica set",
+            function_name!()
+        );
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let majority = InsertOneOptions::builder()
+        .write_concern(WriteConcern::builder().w(Acknowledgment::Majority).build())
+        .build();
+    coll.insert_one(doc! { "x": 1 }, majority.clone())
+        .await
+        .unwrap();
+
+    // A causally consistent session reads after its own writes.
+    let mut causal = client.start_session(None).await.unwrap();
+    coll.insert_one_with_session(doc! { "x": 2 }, None, &mut causal)
+        .await
+        .unwrap();
+    let operation_time = causal
+        .operation_time()
+        .expect("operation time should be set after a write");
+    assert_eq!(
+        coll.count_documents_with_session(None, None, &mut causal)
+            .await
+            .unwrap(),
+        2
+    );
+    let (started, _) = client.get_successful_command_execution("aggregate");
+    let read_concern = started.command.get_document("readConcern").unwrap();
+    assert_eq!(
+        read_concern.get_timestamp("afterClusterTime"),
+        Ok(operation_time)
+    );
+
+    // A snapshot session keeps reading from the snapshot established by its first read, even
+    // after more writes have happened.
+    let options = SessionOptions::builder().snapshot(true).build();
+    let mut snapshot = client.start_session(Some(options)).await.unwrap();
+    assert_eq!(
+        coll.count_documents_with_session(None, None, &mut snapshot)
+            .await
+            .unwrap(),
+        2
+    );
+    let (started, _) = client.get_successful_command_execution("aggregate");
+    let read_concern = started.command.get_document("readConcern").unwrap();
+    assert_eq!(read_concern.get_str("level"), Ok("snapshot"));
+    assert!(!read_concern.contains_key("atClusterTime"));
+    assert!(!read_concern.contains_key("afterClusterTime"));
+    let snapshot_time = snapshot
+        .snapshot_time()
+        .expect("snapshot time should be set after the first read");
+
+    coll.insert_one(doc! { "x": 3 }, majority).await.unwrap();
+
+    assert_eq!(
+        coll.count_documents_with_session(None, None, &mut snapshot)
+            .await
+            .unwrap(),
+        2
+    );
+    let (started, _) = client.get_successful_command_execution("aggregate");
+    let read_concern = started.command.get_document("readConcern").unwrap();
+    assert_eq!(
+        read_concern.get_timestamp("atClusterTime"),
+        Ok(snapshot_time)
+    );
+
+    assert_eq!(
+        coll.count_documents_with_session(None, None, &mut causal)
+            .await
+            .unwrap(),
+        3
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn snapshot_session_rejects_writes() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if client.is_standalone() {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let options = SessionOptions::builder().snapshot(true).build();
+    let mut session = client.start_session(Some(options)).await.unwrap();
+
+    let error = coll
+        .insert_one_with_session(doc! { "x": 1 }, None, &mut session)
+        .await
+        .expect_err("insert should fail with a snapshot session");
+    assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+
+    let out = doc! { "$out": format!("{}_out", function_name!()) };
+    let error = coll
+        .aggregate_with_session(vec![doc! { "$match": {} }, out], None, &mut session)
+        .await
+        .expect_err("aggregate with $out should fail with a snapshot session");
+    assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+
+    let merge = doc! { "$merge": { "into": format!("{}_merge", function_name!()) } };
+    let error = coll
+        .aggregate_with_session(vec![merge], None, &mut session)
+        .await
+        .expect_err("aggregate with $merge should fail with a snapshot session");
+    assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+
+    // The errors are raised before anything is sent to the server.
+    assert!(client
+        .get_command_started_events(&["insert", "aggregate"])
+        .is_empty());
+}
+
+/// Dropping a session with a transaction in progress should abort the transaction rather than
+/// leaving it open on the server until it times out.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn transaction_aborted_on_drop() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 0) {
+        println!(
+            "skipping {} due to not running against a 4.0+ replica set",
+            function_name!()
+        );
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    // collections can't be created implicitly in a transaction before 4.4.
+    client
+        .database(function_name!())
+        .create_collection(function_name!(), None)
+        .await
+        .unwrap();
+
+    let mut session = client.start_session(None).await.unwrap();
+    let session_id = session.id().clone();
+    session.start_transaction(None).await.unwrap();
+    coll.insert_one_with_session(doc! { "x": 1 }, None, &mut session)
+        .await
+        .unwrap();
+
+    let mut subscriber = client.subscribe_to_events();
+    drop(session);
+
+    let event = subscriber
+        .wait_for_event(Duration::from_millis(500), |event| {
+            matches!(
+                event,
+                Event::CommandEvent(CommandEvent::Started(started))
+                    if started.command_name == "abortTransaction"
+            )
+        })
+        .await
+        .expect("abortTransaction should be sent when the session is dropped");
+    match event {
+        Event::CommandEvent(CommandEvent::Started(started)) => {
+            assert_eq!(started.command.get_document("lsid"), Ok(&session_id));
+        }
+        _ => unreachable!(),
+    }
+
+    // the insert was rolled back.
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 0);
+}
+
+/// Write concerns should only be sent when explicitly configured, and never with the individual
+/// statements of a transaction; the transaction's write concern is sent on commit instead.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn write_concern_omitted_by_default_and_in_transactions() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 0) {
+        println!(
+            "skipping {} due to not running against a 4.0+ replica set",
+            function_name!()
+        );
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+
+    let insert = client
+        .get_command_started_events(&["insert"])
+        .pop()
+        .unwrap();
+    assert!(!insert.command.contains_key("writeConcern"));
+
+    let majority = WriteConcern::builder().w(Acknowledgment::Majority).build();
+    let db = client.database_with_options(
+        function_name!(),
+        DatabaseOptions::builder()
+            .write_concern(majority.clone())
+            .build(),
+    );
+    let coll = db.collection::<Document>(function_name!());
+
+    let mut session = client.start_session(None).await.unwrap();
+    session
+        .start_transaction(
+            TransactionOptions::builder()
+                .write_concern(majority)
+                .build(),
+        )
+        .await
+        .unwrap();
+    coll.update_one_with_session(
+        doc! { "x": 1 },
+        doc! { "$inc": { "x": 1 } },
+        None,
+        &mut session,
+    )
+    .await
+    .unwrap();
+    // DDL operations are allowed in transactions on 4.4+, and inherit the database's write
+    // concern outside of them.
+    if client.server_version_gte(4, 4) {
+        db.create_collection_with_session(
+            format!("{}_created", function_name!()),
+            None,
+            &mut session,
+        )
+        .await
+        .unwrap();
+        let create = client
+            .get_command_started_events(&["create"])
+            .pop()
+            .unwrap();
+        assert!(!create.command.contains_key("writeConcern"));
+    }
+    session.commit_transaction().await.unwrap();
+
+    let update = client
+        .get_command_started_events(&["update"])
+        .pop()
+        .unwrap();
+    assert!(!update.command.contains_key("writeConcern"));
+
+    let commit = client
+        .get_command_started_events(&["commitTransaction"])
+        .pop()
+        .unwrap();
+    assert_eq!(
+        commit.command.get_document("writeConcern"),
+        Ok(&doc! { "w": "majority" })
+    );
+}
+
+/// Builds the read concern that would be sent with a command executed with a session in the given
+/// state. `level` is the read concern of the operation, or of the transaction if `in_transaction`
+/// is set; a level of "snapshot" outside of a transaction indicates a snapshot session.
+fn read_concern_for(
+    client: &Client,
+    level: Option<ReadConcernLevel>,
+    time: Option<Timestamp>,
+    in_transaction: bool,
+    supports_read_concern: bool,
+) -> Option<Document> {
+    let snapshot = !in_transaction && level == Some(ReadConcernLevel::Snapshot);
+    let options = SessionOptions::builder().snapshot(snapshot).build();
+    let mut session =
+        ClientSession::new(ServerSession::new(), client.clone(), Some(options), false);
+    session.operation_time = time;
+    if snapshot {
+        session.snapshot_time = time;
+    }
+
+    let mut body = doc! { "find": "coll" };
+    if in_transaction {
+        let options = TransactionOptions::builder()
+            .read_concern(level.map(ReadConcern::from))
+            .build();
+        session.transaction.start(Some(options));
+    } else if let Some(level) = level.filter(|_| !snapshot) {
+        body.insert("readConcern", doc! { "level": level.as_str() });
+    }
+
+    let mut cmd = Command::new("find".to_string(), "db".to_string(), body);
+    cmd.set_read_concern(&session, supports_read_concern);
+    cmd.body.get_document("readConcern").ok().cloned()
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn read_concern_merging() {
+    let client = Client::with_options(CLIENT_OPTIONS.clone()).unwrap();
+    let time = Timestamp {
+        time: 10,
+        increment: 1,
+    };
+    let local = Some(ReadConcernLevel::Local);
+    let majority = Some(ReadConcernLevel::Majority);
+    let snapshot = Some(ReadConcernLevel::Snapshot);
+
+    // (level, operation time, in transaction, expected read concern)
+    let cases = vec![
+        (None, None, false, None),
+        (None, None, true, None),
+        (
+            None,
+            Some(time),
+            false,
+            Some(doc! { "afterClusterTime": time }),
+        ),
+        (
+            None,
+            Some(time),
+            true,
+            Some(doc! { "afterClusterTime": time }),
+        ),
+        (local.clone(), None, false, Some(doc! { "level": "local" })),
+        (local.clone(), None, true, Some(doc! { "level": "local" })),
+        (
+            local.clone(),
+            Some(time),
+            false,
+            Some(doc! { "level": "local", "afterClusterTime": time }),
+        ),
+        (
+            local,
+            Some(time),
+            true,
+            Some(doc! { "level": "local", "afterClusterTime": time }),
+        ),
+        (
+            majority.clone(),
+            None,
+            false,
+            Some(doc! { "level": "majority" }),
+        ),
+        (
+            majority.clone(),
+            None,
+            true,
+            Some(doc! { "level": "majority" }),
+        ),
+        (
+            majority.clone(),
+            Some(time),
+            false,
+            Some(doc! { "level": "majority", "afterClusterTime": time }),
+        ),
+        (
+            majority,
+            Some(time),
+            true,
+            Some(doc! { "level": "majority", "afterClusterTime": time }),
+        ),
+        (
+            snapshot.clone(),
+            None,
+            false,
+            Some(doc! { "level": "snapshot" }),
+        ),
+        (
+            snapshot.clone(),
+            None,
+            true,
+            Some(doc! { "level": "snapshot" }),
+        ),
+        (
+            snapshot.clone(),
+            Some(time),
+            false,
+            Some(doc! { "level": "snapshot", "atClusterTime": time }),
+        ),
+        (
+            snapshot,
+            Some(time),
+            true,
+            Some(doc! { "level": "snapshot", "afterClusterTime": time }),
+        ),
+    ];
+    for (level, time, in_transaction, expected) in cases {
+        assert_eq!(
+            read_concern_for(&client, level.clone(), time, in_transaction, true),
+            expected,
+            "level: {:?}, time: {:?}, in transaction: {}",
+            level,
+            time,
+            in_transaction
+        );
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn read_concern_not_merged_into_unsupported_commands() {
+    let client = Client::with_options(CLIENT_OPTIONS.clone()).unwrap();
+    let time = Timestamp {
+        time: 10,
+        increment: 1,
+    };
+
+    // commands such as getMore don't accept a read concern, so causal and snapshot times aren't
+    // attached to them.
+    assert_eq!(
+        read_concern_for(&client, None, Some(time), false, false),
+        None
+    );
+    assert_eq!(
+        read_concern_for(
+            &client,
+            Some(ReadConcernLevel::Snapshot),
+            Some(time),
+            false,
+            false
+        ),
+        None
+    );
+
+    // the first command in a transaction carries the transaction's read concern regardless.
+    assert_eq!(
+        read_concern_for(
+            &client,
+            Some(ReadConcernLevel::Majority),
+            Some(time),
+            true,
+            false
+        ),
+        Some(doc! { "level": "majority", "afterClusterTime": time })
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn read_concern_omitted_after_first_command_in_transaction() {
+    let client = Client::with_options(CLIENT_OPTIONS.clone()).unwrap();
+    let mut session = ClientSession::new(ServerSession::new(), client, None, false);
+    session.operation_time = Some(Timestamp {
+        time: 10,
+        increment: 1,
+    });
+    let options = TransactionOptions::builder()
+        .read_concern(ReadConcern::majority())
+        .build();
+    session.transaction.start(Some(options));
+    session.transaction.state = TransactionState::InProgress;
+
+    let mut cmd = Command::new(
+        "find".to_string(),
+        "db".to_string(),
+        doc! { "find": "coll" },
+    );
+    cmd.set_read_concern(&session, true);
+    assert!(cmd.body.get("readConcern").is_none());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn in_transaction() {
+    let client = Client::with_options(CLIENT_OPTIONS.clone()).unwrap();
+    let mut session = ClientSession::new(ServerSession::new(), client, None, false);
+    assert!(!session.in_transaction());
+
+    session.transaction.start(None);
+    assert!(session.in_transaction());
+    session.transaction.state = TransactionState::InProgress;
+    assert!(session.in_transaction());
+
+    session.transaction.commit(true);
+    assert!(!session.in_transaction());
+
+    session.transaction.start(None);
+    assert!(session.in_transaction());
+    session.transaction.abort();
+    assert!(!session.in_transaction());
+
+    session.transaction.reset();
+    assert!(!session.in_transaction());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn fork_merges_server_state() {
+    let client = Client::with_options(CLIENT_OPTIONS.clone()).unwrap();
+    let mut session = ClientSession::new(ServerSession::new(), client, None, false);
+    let time = Timestamp {
+        time: 10,
+        increment: 1,
+    };
+
+    let mut fork = session.fork();
+    assert_eq!(fork.id(), session.id());
+    fork.advance_operation_time(time);
+    fork.mark_dirty();
+    assert_eq!(session.operation_time(), None);
+
+    session.merge(fork);
+    assert_eq!(session.operation_time(), Some(time));
+    assert!(session.server_session.dirty);
+
+    // an older operation time reported to a fork doesn't move the session's back.
+    let mut fork = session.fork();
+    fork.operation_time = Some(Timestamp {
+        time: 5,
+        increment: 1,
+    });
+    session.merge(fork);
+    assert_eq!(session.operation_time(), Some(time));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn session_cursor_stream_hands_back_buffer() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.is_standalone() {
+        return;
+    }
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0..3).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    let mut session = client.start_session(None).await.unwrap();
+    let options = FindOptions::builder().sort(doc! { "_id": 1 }).build();
+    let mut cursor = coll
+        .find_with_session(None, options, &mut session)
+        .await
+        .unwrap();
+
+    // Documents not yet yielded by a dropped stream are picked up by the next one, exactly once.
+    let mut stream = cursor.stream(&mut session);
+    assert_eq!(stream.next().await.unwrap().unwrap(), doc! { "_id": 0 });
+    drop(stream);
+    assert_eq!(
+        cursor.next(&mut session).await.unwrap().unwrap(),
+        doc! { "_id": 1 }
+    );
+    let next = cursor.stream(&mut session).next().await;
+    assert_eq!(next.unwrap().unwrap(), doc! { "_id": 2 });
+    assert!(cursor.next(&mut session).await.is_none());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn session_cursor_get_more_cancelled() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let setup_client = TestClient::new().await;
+    if !setup_client.supports_block_connection() {
+        println!(
+            "skipping {} due to blockConnection not being supported",
+            function_name!()
+        );
+        return;
+    }
+    let coll = setup_client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0..6).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    let fail_point = FailPoint::fail_command(
+        &["getMore"],
+        FailPointMode::Times(1),
+        FailCommandOptions::builder()
+            .app_name(function_name!().to_string())
+            .block_connection(Duration::from_millis(500))
+            .build(),
+    );
+    let _fp_guard = setup_client
+        .enable_failpoint(fail_point, None)
+        .await
+        .unwrap();
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.app_name = Some(function_name!().to_string());
+    let client = TestClient::with_options(Some(options)).await;
+    let coll = client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+    let mut session = client.start_session(None).await.unwrap();
+    let options = FindOptions::builder()
+        .batch_size(2)
+        .sort(doc! { "_id": 1 })
+        .build();
+    let mut cursor = coll
+        .find_with_session(None, options, &mut session)
+        .await
+        .unwrap();
+
+    let mut seen = Vec::new();
+    for _ in 0..2 {
+        let doc = cursor.next(&mut session).await.unwrap().unwrap();
+        seen.push(doc.get_i32("_id").unwrap());
+    }
+
+    // Cancel the first getMore while the server is blocking it.
+    let cancelled = RUNTIME
+        .timeout(Duration::from_millis(100), async {
+            cursor.stream(&mut session).next().await
+        })
+        .await;
+    assert!(cancelled.is_err());
+
+    // Continued iteration picks up the batch of the cancelled getMore, so no documents are skipped
+    // or repeated.
+    while let Some(doc) = cursor.next(&mut session).await {
+        seen.push(doc.unwrap().get_i32("_id").unwrap());
+    }
+    assert_eq!(seen, (0..6).collect::<Vec<_>>());
+}
@@ -2,9 +2,10 @@ use serde::{de::DeserializeOwned, Deserialize};
 
 use super::wire::Message;
 use crate::{
-    bson::{Bson, Document},
+    bson::{doc, Bson, Document, Timestamp},
     bson_util,
-    client::{options::ServerApi, ClusterTime},
+    client::{options::ServerApi, session::TransactionState, ClusterTime},
+    concern::ReadConcernLevel,
     error::{CommandError, Error, ErrorKind, Result},
     options::ServerAddress,
     selection_criteria::ReadPreference,
@@ -18,6 +19,19 @@ pub(crate) struct Command {
     pub(crate) name: String,
     pub(crate) target_db: String,
     pub(crate) body: Document,
+
+    /// Whether invalid UTF-8 in the response to this command should be replaced with the Unicode
+    /// replacement character rather than causing an error.
+    pub(crate) utf8_lossy: bool,
+
+    /// Whether this command is sent with the OP_MSG `moreToCome` flag set, in which case the
+    /// server won't send a reply to it. This is only used for unacknowledged writes.
+    pub(crate) more_to_come: bool,
+
+    /// Whether this command is sent with the OP_MSG `exhaustAllowed` flag set, in which case the
+    /// server may keep streaming replies to it without waiting for further requests. This is only
+    /// used for the getMores of exhaust cursors.
+    pub(crate) exhaust_allowed: bool,
 }
 
 impl Command {
@@ -27,6 +41,9 @@ impl Command {
             name,
             target_db,
             body,
+            utf8_lossy: false,
+            more_to_come: false,
+            exhaust_allowed: false,
         }
     }
 
@@ -75,14 +92,97 @@ impl Command {
         self.body.insert("autocommit", false);
     }
 
-    pub(crate) fn set_txn_read_concern(&mut self, session: &ClientSession) -> Result<()> {
-        if let Some(ref options) = session.transaction.options {
-            if let Some(ref read_concern) = options.read_concern {
-                self.body
-                    .insert("readConcern", bson::to_document(read_concern)?);
+    pub(crate) fn set_recovery_token(&mut self, recovery_token: &Document) {
+        self.body.insert("recoveryToken", recovery_token.clone());
+    }
+
+    /// Removes any write concern from this command. Statements within a transaction must not
+    /// specify a write concern; the transaction's write concern is sent on commit or abort instead.
+    pub(crate) fn clear_write_concern(&mut self) {
+        self.body.remove("writeConcern");
+    }
+
+    /// Sets this command's read concern for execution with the given session, merging the level
+    /// specified for the operation or the transaction being started with the session's causal
+    /// consistency and snapshot times. Commands run within a transaction after its first command
+    /// are left untouched, as are commands that don't accept a read concern outside of starting a
+    /// transaction.
+    pub(crate) fn set_read_concern(
+        &mut self,
+        session: &ClientSession,
+        supports_read_concern: bool,
+    ) {
+        let after_cluster_time = if session.causal_consistency() {
+            session.operation_time()
+        } else {
+            None
+        };
+
+        let read_concern = match session.transaction.state {
+            TransactionState::Starting => {
+                let level = session
+                    .transaction
+                    .options
+                    .as_ref()
+                    .and_then(|options| options.read_concern.as_ref())
+                    .map(|read_concern| &read_concern.level);
+                merge_read_concern(level, after_cluster_time, None)
+            }
+            TransactionState::InProgress => return,
+            _ if !supports_read_concern => return,
+            _ if session.is_snapshot() => merge_read_concern(
+                Some(&ReadConcernLevel::Snapshot),
+                None,
+                session.snapshot_time(),
+            ),
+            _ => {
+                let level = self
+                    .body
+                    .get_document("readConcern")
+                    .ok()
+                    .and_then(|read_concern| read_concern.get_str("level").ok())
+                    .map(ReadConcernLevel::from_str);
+                merge_read_concern(level.as_ref(), after_cluster_time, None)
+            }
+        };
+
+        match read_concern {
+            Some(read_concern) => {
+                self.body.insert("readConcern", read_concern);
+            }
+            None => {
+                self.body.remove("readConcern");
             }
         }
-        Ok(())
+    }
+}
+
+/// Builds a read concern document from its level and cluster times. `atClusterTime` takes
+/// precedence over `afterClusterTime`, since a snapshot read at a specific time can't also specify
+/// a time to read after. Returns `None` if none of the fields are set.
+pub(crate) fn merge_read_concern(
+    level: Option<&ReadConcernLevel>,
+    after_cluster_time: Option<Timestamp>,
+    at_cluster_time: Option<Timestamp>,
+) -> Option<Document> {
+    let mut read_concern = Document::new();
+    if let Some(level) = level {
+        read_concern.insert("level", level.as_str());
+    }
+    match (at_cluster_time, after_cluster_time) {
+        (Some(at_cluster_time), _) => {
+            read_concern.insert("atClusterTime", at_cluster_time);
+        }
+        (None, Some(after_cluster_time)) => {
+            read_concern.insert("afterClusterTime", after_cluster_time);
+        }
+        (None, None) => {}
+    }
+
+    if read_concern.is_empty() {
+        None
+    } else {
+        Some(read_concern)
     }
 }
 
@@ -91,15 +191,28 @@ pub(crate) struct CommandResponse {
     source: ServerAddress,
     pub(crate) raw_response: Document,
     cluster_time: Option<ClusterTime>,
+    raw_batch_lengths: Option<Vec<usize>>,
 }
 
 impl CommandResponse {
+    /// Creates the response used in place of a reply for commands sent with `moreToCome` set,
+    /// which the server never responds to.
+    pub(crate) fn unacknowledged(source: ServerAddress) -> Self {
+        Self {
+            source,
+            raw_response: doc! { "ok": 1 },
+            cluster_time: None,
+            raw_batch_lengths: None,
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn with_document_and_address(source: ServerAddress, doc: Document) -> Self {
         Self {
             source,
             raw_response: doc,
             cluster_time: None,
+            raw_batch_lengths: None,
         }
     }
 
@@ -115,7 +228,8 @@ impl CommandResponse {
         )
     }
 
-    pub(crate) fn new(source: ServerAddress, message: Message) -> Result<Self> {
+    pub(crate) fn new(source: ServerAddress, mut message: Message) -> Result<Self> {
+        let raw_batch_lengths = message.raw_batch_lengths.take();
         let raw_response = message.single_document_response()?;
         let cluster_time = raw_response
             .get("$clusterTime")
@@ -125,9 +239,16 @@ impl CommandResponse {
             source,
             raw_response,
             cluster_time,
+            raw_batch_lengths,
         })
     }
 
+    /// Takes the lengths of the documents in this cursor reply's batch as they were read from the
+    /// server, if known.
+    pub(crate) fn take_raw_batch_lengths(&mut self) -> Option<Vec<usize>> {
+        self.raw_batch_lengths.take()
+    }
+
     /// Returns whether this response indicates a success or not (i.e. if "ok: 1")
     pub(crate) fn is_success(&self) -> bool {
         match self.raw_response.get("ok") {
@@ -165,6 +286,27 @@ impl CommandResponse {
         }
     }
 
+    /// Gets the operation time from the response, if any.
+    pub(crate) fn operation_time(&self) -> Option<Timestamp> {
+        self.raw_response.get_timestamp("operationTime").ok()
+    }
+
+    /// Gets the time of the snapshot that the server read from, if any. This is reported in the
+    /// cursor for cursor-returning commands and at the top level for other commands.
+    pub(crate) fn snapshot_time(&self) -> Option<Timestamp> {
+        self.raw_response
+            .get_document("cursor")
+            .unwrap_or(&self.raw_response)
+            .get_timestamp("atClusterTime")
+            .ok()
+    }
+
+    /// Gets the token that mongos returns for recovering the outcome of a sharded transaction, if
+    /// any.
+    pub(crate) fn recovery_token(&self) -> Option<&Document> {
+        self.raw_response.get_document("recoveryToken").ok()
+    }
+
     /// Gets the cluster time from the response, if any.
     pub(crate) fn cluster_time(&self) -> Option<&ClusterTime> {
         self.cluster_time.as_ref()
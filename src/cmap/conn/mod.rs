@@ -8,12 +8,14 @@ use std::{
 };
 
 use derivative::Derivative;
+use tokio::sync::{mpsc, Mutex};
 
-use self::wire::Message;
+use self::wire::{Message, MessageFlags};
 use super::manager::PoolManager;
 use crate::{
+    bson::oid::ObjectId,
     cmap::options::{ConnectionOptions, StreamOptions},
-    error::{ErrorKind, Result},
+    error::{Error, ErrorKind, Result},
     event::cmap::{
         CmapEventHandler,
         ConnectionCheckedInEvent,
@@ -25,6 +27,7 @@ use crate::{
     },
     options::{ServerAddress, TlsOptions},
     runtime::AsyncStream,
+    RUNTIME,
 };
 pub(crate) use command::{Command, CommandResponse};
 pub(crate) use stream_description::StreamDescription;
@@ -48,6 +51,9 @@ pub(crate) struct Connection {
     pub(super) address: ServerAddress,
     pub(crate) generation: u32,
 
+    /// The time at which this connection was established.
+    time_created: Instant,
+
     /// The cached StreamDescription from the connection's handshake.
     pub(super) stream_description: Option<StreamDescription>,
 
@@ -59,16 +65,30 @@ pub(crate) struct Connection {
     /// None when checked into the pool.
     pub(super) pool_manager: Option<PoolManager>,
 
+    /// The sender used to return this connection to its `PinnedConnectionHandle` when dropped,
+    /// if the connection is pinned to a cursor or transaction. Pinned connections are only
+    /// checked back into the pool once they've been unpinned.
+    pinned_sender: Option<mpsc::Sender<Connection>>,
+
     /// Whether or not a command is currently being run on this connection. This is set to `true`
     /// right before sending bytes to the server and set back to `false` once a full response has
     /// been read.
     command_executing: bool,
 
+    /// Whether the server has indicated that it will stream further replies on this connection
+    /// without being sent another request, as it does for the getMores of exhaust cursors. The
+    /// connection can't be used for other commands until the final reply has been read.
+    more_to_come: bool,
+
     /// Whether or not this connection has experienced a network error while reading or writing.
     /// Once the connection has received an error, it should not be used again or checked back
     /// into a pool.
     error: bool,
 
+    /// The amount of time to wait for a command to complete before giving up on it and closing
+    /// this connection.
+    socket_timeout: Option<Duration>,
+
     stream: AsyncStream,
 
     #[derivative(Debug = "ignore")]
@@ -92,10 +112,14 @@ impl Connection {
             id,
             generation,
             pool_manager: None,
+            pinned_sender: None,
             command_executing: false,
+            more_to_come: false,
             ready_and_available_time: None,
             stream: AsyncStream::connect(stream_options).await?,
+            time_created: Instant::now(),
             address,
+            socket_timeout: options.as_ref().and_then(|opts| opts.socket_timeout),
             handler: options.and_then(|options| options.event_handler),
             stream_description: None,
             error: false,
@@ -127,6 +151,7 @@ impl Connection {
             0,
             Some(ConnectionOptions {
                 connect_timeout,
+                socket_timeout: None,
                 tls_options,
                 event_handler: None,
             }),
@@ -155,11 +180,16 @@ impl Connection {
         &self.address
     }
 
+    /// The amount of time that has passed since this connection was established.
+    pub(crate) fn age(&self) -> Duration {
+        self.time_created.elapsed()
+    }
+
     /// Helper to mark the time that the connection was checked into the pool for the purpose of
     /// detecting when it becomes idle.
-    pub(super) fn mark_as_available(&mut self) {
+    pub(super) fn mark_as_available(&mut self, now: Instant) {
         self.pool_manager.take();
-        self.ready_and_available_time = Some(Instant::now());
+        self.ready_and_available_time = Some(now);
     }
 
     /// Helper to mark that the connection has been checked out of the pool. This ensures that the
@@ -170,17 +200,43 @@ impl Connection {
         self.ready_and_available_time.take();
     }
 
-    /// Checks if the connection is idle.
-    pub(super) fn is_idle(&self, max_idle_time: Option<Duration>) -> bool {
+    /// Checks if the connection is idle as of `now`.
+    pub(super) fn is_idle(&self, max_idle_time: Option<Duration>, now: Instant) -> bool {
         self.ready_and_available_time
             .and_then(|ready_and_available_time| {
                 max_idle_time.map(|max_idle_time| {
-                    Instant::now().duration_since(ready_and_available_time) >= max_idle_time
+                    now.saturating_duration_since(ready_and_available_time) >= max_idle_time
                 })
             })
             .unwrap_or(false)
     }
 
+    /// The identifier of the backend server that this connection is routed to by a load balancer,
+    /// if any.
+    pub(crate) fn service_id(&self) -> Option<ObjectId> {
+        self.stream_description
+            .as_ref()
+            .and_then(|description| description.service_id)
+    }
+
+    /// Pins this connection, so that it's returned to the returned handle rather than the pool
+    /// whenever it's dropped. This is used to keep the commands for a cursor or transaction on the
+    /// same backend server when connected through a load balancer.
+    pub(crate) fn pin(&mut self) -> Result<PinnedConnectionHandle> {
+        if self.pinned_sender.is_some() {
+            return Err(ErrorKind::Internal {
+                message: format!("cannot pin already-pinned connection (id = {})", self.id),
+            }
+            .into());
+        }
+        let (sender, receiver) = mpsc::channel(1);
+        self.pinned_sender = Some(sender);
+        Ok(PinnedConnectionHandle {
+            id: self.id,
+            receiver: Arc::new(Mutex::new(receiver)),
+        })
+    }
+
     /// Checks if the connection is stale.
     pub(super) fn is_stale(&self, current_generation: u32) -> bool {
         self.generation != current_generation
@@ -239,18 +295,153 @@ impl Connection {
         command: Command,
         request_id: impl Into<Option<i32>>,
     ) -> Result<CommandResponse> {
+        self.send_command_with_timeout(command, request_id, None)
+            .await
+    }
+
+    /// Executes a `Command` and returns a `CommandResponse`, giving up after the smaller of the
+    /// socket timeout and `operation_timeout`. If `operation_timeout` is what elapsed, the error
+    /// returned is an `ErrorKind::Timeout` rather than an I/O timeout.
+    pub(crate) async fn send_command_with_timeout(
+        &mut self,
+        command: Command,
+        request_id: impl Into<Option<i32>>,
+        operation_timeout: Option<Duration>,
+    ) -> Result<CommandResponse> {
+        let utf8_lossy = command.utf8_lossy;
         let message = Message::with_command(command, request_id.into());
 
+        let socket_timeout = self
+            .socket_timeout
+            .filter(|timeout| *timeout > Duration::from_secs(0));
+        let timeout = match (socket_timeout, operation_timeout) {
+            (Some(socket_timeout), Some(operation_timeout)) => {
+                Some(socket_timeout.min(operation_timeout))
+            }
+            (socket_timeout, operation_timeout) => socket_timeout.or(operation_timeout),
+        };
+
+        match timeout {
+            Some(timeout) => {
+                match RUNTIME
+                    .timeout(timeout, self.send_message(message, utf8_lossy))
+                    .await
+                {
+                    Ok(result) => result,
+                    Err(error) => {
+                        // The timeout may have interrupted the connection partway through reading
+                        // or writing a message, so it can't be used again. Only this connection
+                        // needs to be closed; a timeout doesn't indicate anything about the state
+                        // of the server or the rest of the pool.
+                        self.error = true;
+
+                        if operation_timeout == Some(timeout) {
+                            Err(ErrorKind::Timeout {
+                                message: format!(
+                                    "no reply was received from {} within {:?}",
+                                    self.address, timeout
+                                ),
+                            }
+                            .into())
+                        } else {
+                            Err(error)
+                        }
+                    }
+                }
+            }
+            None => self.send_message(message, utf8_lossy).await,
+        }
+    }
+
+    async fn send_message(
+        &mut self,
+        mut message: Message,
+        utf8_lossy: bool,
+    ) -> Result<CommandResponse> {
+        let request_id = *message.request_id.get_or_insert_with(next_request_id);
+
         self.command_executing = true;
         let write_result = message.write_to(&mut self.stream).await;
         self.error = write_result.is_err();
         write_result?;
 
-        let response_message_result = Message::read_from(&mut self.stream).await;
+        // The server doesn't reply to messages sent with moreToCome set, so the connection is
+        // ready for the next command as soon as the message has been written.
+        if message.flags.contains(MessageFlags::MORE_TO_COME) {
+            self.command_executing = false;
+            return Ok(CommandResponse::unacknowledged(self.address.clone()));
+        }
+
+        let response_message_result = Message::read_from(&mut self.stream, utf8_lossy).await;
         self.command_executing = false;
         self.error = response_message_result.is_err();
+        let response_message = response_message_result?;
+        self.more_to_come = response_message.flags.contains(MessageFlags::MORE_TO_COME);
+
+        // A reply to anything other than the message just sent means the connection is out of
+        // sync with the server, so it can't be used again.
+        if response_message.response_to != request_id {
+            self.error = true;
+            return Err(ErrorKind::InvalidResponse {
+                message: format!(
+                    "expected a reply to request {}, but got a reply to request {}",
+                    request_id, response_message.response_to
+                ),
+            }
+            .into());
+        }
+
+        CommandResponse::new(self.address.clone(), response_message)
+    }
+
+    /// Reads the next reply that the server streams on this connection after replying to an
+    /// exhaust getMore. This can only be called while [`Connection::is_streaming`] is true.
+    pub(crate) async fn receive_message(&mut self, utf8_lossy: bool) -> Result<CommandResponse> {
+        if !self.more_to_come {
+            return Err(ErrorKind::Internal {
+                message: format!(
+                    "no further replies are expected on connection (id = {})",
+                    self.id
+                ),
+            }
+            .into());
+        }
+
+        let socket_timeout = self
+            .socket_timeout
+            .filter(|timeout| *timeout > Duration::from_secs(0));
+
+        self.command_executing = true;
+        let read = Message::read_from(&mut self.stream, utf8_lossy);
+        let response_message_result = match socket_timeout {
+            Some(timeout) => RUNTIME
+                .timeout(timeout, read)
+                .await
+                .and_then(|result| result),
+            None => read.await,
+        };
+        self.command_executing = false;
+        self.error = response_message_result.is_err();
+        let response_message = response_message_result?;
+        self.more_to_come = response_message.flags.contains(MessageFlags::MORE_TO_COME);
+
+        CommandResponse::new(self.address.clone(), response_message)
+    }
+
+    /// Whether the server is still streaming replies on this connection, which must be read with
+    /// [`Connection::receive_message`] before it can be used for anything else.
+    pub(crate) fn is_streaming(&self) -> bool {
+        self.more_to_come
+    }
 
-        CommandResponse::new(self.address.clone(), response_message_result?)
+    /// Moves this connection out, leaving behind an empty connection that does nothing when
+    /// dropped. This is used to hand a connection that the server is still streaming replies on
+    /// over to the exhaust cursor that reads them.
+    pub(crate) fn detach(&mut self) -> Connection {
+        let pool_manager = self.pool_manager.take();
+        let mut connection = self.take();
+        connection.pool_manager = pool_manager;
+        connection
     }
 
     /// Gets the connection's StreamDescription.
@@ -283,12 +474,16 @@ impl Connection {
             id: self.id,
             address: self.address.clone(),
             generation: self.generation,
+            time_created: self.time_created,
             stream: std::mem::replace(&mut self.stream, AsyncStream::Null),
             handler: self.handler.take(),
             stream_description: self.stream_description.take(),
             command_executing: self.command_executing,
+            more_to_come: self.more_to_come,
             error: self.error,
+            socket_timeout: self.socket_timeout,
             pool_manager: None,
+            pinned_sender: self.pinned_sender.take(),
             ready_and_available_time: None,
         }
     }
@@ -306,14 +501,72 @@ impl Drop for Connection {
         // being dropped while it's not checked out. This means that the pool called
         // the `close_and_drop` helper explicitly, so we don't add it back to the
         // pool or emit any events.
-        if let Some(pool_manager) = self.pool_manager.take() {
-            let dropped_connection = self.take();
-            if let Err(mut conn) = pool_manager.check_in(dropped_connection) {
-                // the check in failed because the pool has been dropped, so we emit the event
-                // here and drop the connection.
-                conn.close(ConnectionClosedReason::PoolClosed);
+        let pool_manager = match self.pool_manager.take() {
+            Some(pool_manager) => pool_manager,
+            None => return,
+        };
+        let mut dropped_connection = self.take();
+
+        // A connection that the server is still streaming replies on is out of sync with it, so
+        // it can't be used again.
+        if dropped_connection.more_to_come {
+            dropped_connection.error = true;
+        }
+
+        // A pinned connection is returned to its handle, unless it can't be used again or the
+        // handle has been dropped, in which case it's checked back into the pool instead.
+        if let Some(sender) = dropped_connection.pinned_sender.take() {
+            if !dropped_connection.has_errored() {
+                dropped_connection.pool_manager = Some(pool_manager.clone());
+                dropped_connection.pinned_sender = Some(sender.clone());
+                match sender.try_send(dropped_connection) {
+                    Ok(()) => return,
+                    Err(mpsc::error::TrySendError::Closed(mut conn))
+                    | Err(mpsc::error::TrySendError::Full(mut conn)) => {
+                        conn.pool_manager.take();
+                        conn.pinned_sender.take();
+                        dropped_connection = conn;
+                    }
+                }
             }
         }
+
+        if let Err(mut conn) = pool_manager.check_in(dropped_connection) {
+            // the check in failed because the pool has been dropped, so we emit the event
+            // here and drop the connection.
+            conn.close(ConnectionClosedReason::PoolClosed);
+        }
+    }
+}
+
+/// A handle to a connection that's pinned to a cursor or transaction. The connection is returned
+/// to the handle whenever it's dropped, and the next command for the cursor or transaction takes
+/// it back out. Once every clone of the handle has been dropped, the connection is unpinned and
+/// checked back into the pool.
+#[derive(Clone, Debug)]
+pub(crate) struct PinnedConnectionHandle {
+    id: u32,
+    receiver: Arc<Mutex<mpsc::Receiver<Connection>>>,
+}
+
+impl PinnedConnectionHandle {
+    /// The id of the pinned connection.
+    pub(crate) fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Takes the pinned connection, waiting for it to be returned if it's currently in use. This
+    /// fails if the connection was closed after an error.
+    pub(crate) async fn take_connection(&self) -> Result<Connection> {
+        let mut receiver = self.receiver.lock().await;
+        receiver.recv().await.ok_or_else(|| {
+            Error::from(ErrorKind::Internal {
+                message: format!(
+                    "pinned connection (id = {}) was closed after an error",
+                    self.id
+                ),
+            })
+        })
     }
 }
 
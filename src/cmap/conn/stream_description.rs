@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use crate::{is_master::IsMasterReply, sdam::ServerType};
+use crate::{bson::oid::ObjectId, is_master::IsMasterReply, sdam::ServerType};
 
 /// Contains information about a given server in a format digestible by a connection.
 #[derive(Debug, Default, Clone)]
@@ -28,6 +28,10 @@ pub(crate) struct StreamDescription {
     /// can be included in a write batch.  If more than this number of writes are included, the
     /// server cannot guarantee space in the response document to reply to the batch.
     pub(crate) max_write_batch_size: i64,
+
+    /// The identifier of the backend server behind a load balancer that the connection is routed
+    /// to. This is only present for connections made with `load_balanced` set.
+    pub(crate) service_id: Option<ObjectId>,
 }
 
 impl StreamDescription {
@@ -45,6 +49,7 @@ impl StreamDescription {
                 .map(|mins| Duration::from_secs(mins as u64 * 60)),
             max_bson_object_size: reply.command_response.max_bson_object_size,
             max_write_batch_size: reply.command_response.max_write_batch_size,
+            service_id: reply.command_response.service_id,
         }
     }
 
@@ -66,6 +71,7 @@ impl StreamDescription {
             logical_session_timeout: Some(Duration::from_secs(30 * 60)),
             max_bson_object_size: 16 * 1024 * 1024,
             max_write_batch_size: 100_000,
+            service_id: None,
         }
     }
 }
@@ -12,7 +12,7 @@ use super::{
 };
 use crate::{
     bson::Document,
-    bson_util::async_encoding,
+    bson_util::{self, async_encoding},
     cmap::conn::command::Command,
     error::{ErrorKind, Result},
     runtime::{AsyncLittleEndianRead, AsyncLittleEndianWrite, AsyncStream},
@@ -26,6 +26,10 @@ pub(crate) struct Message {
     pub(crate) sections: Vec<MessageSection>,
     pub(crate) checksum: Option<u32>,
     pub(crate) request_id: Option<i32>,
+
+    /// The lengths of the documents in the batch of a cursor reply as read from the server,
+    /// which can differ from the sizes of the parsed documents if invalid UTF-8 was replaced.
+    pub(crate) raw_batch_lengths: Option<Vec<usize>>,
 }
 
 impl Message {
@@ -35,12 +39,21 @@ impl Message {
     pub(crate) fn with_command(mut command: Command, request_id: Option<i32>) -> Self {
         command.body.insert("$db", command.target_db);
 
+        let mut flags = MessageFlags::empty();
+        if command.more_to_come {
+            flags |= MessageFlags::MORE_TO_COME;
+        }
+        if command.exhaust_allowed {
+            flags |= MessageFlags::EXHAUST_ALLOWED;
+        }
+
         Self {
             response_to: 0,
-            flags: MessageFlags::empty(),
+            flags,
             sections: vec![MessageSection::Document(command.body)],
             checksum: None,
             request_id,
+            raw_batch_lengths: None,
         }
     }
 
@@ -73,8 +86,9 @@ impl Message {
             .collect()
     }
 
-    /// Reads bytes from `reader` and deserializes them into a Message.
-    pub(crate) async fn read_from(reader: &mut AsyncStream) -> Result<Self> {
+    /// Reads bytes from `reader` and deserializes them into a Message. If `utf8_lossy` is true,
+    /// invalid UTF-8 in the message's documents will be replaced rather than causing an error.
+    pub(crate) async fn read_from(reader: &mut AsyncStream, utf8_lossy: bool) -> Result<Self> {
         let mut reader = BufReader::new(reader);
         let header = Header::read_from(&mut reader).await?;
         let mut length_remaining = header.length - Header::LENGTH as i32;
@@ -87,9 +101,14 @@ impl Message {
 
         let mut count_reader = CountReader::new(&mut reader);
         let mut sections = Vec::new();
+        let mut raw_batch_lengths = None;
 
         while length_remaining - count_reader.bytes_read() as i32 > 4 {
-            sections.push(MessageSection::read(&mut count_reader).await?);
+            let (section, lengths) = MessageSection::read(&mut count_reader, utf8_lossy).await?;
+            if sections.is_empty() {
+                raw_batch_lengths = lengths;
+            }
+            sections.push(section);
         }
 
         length_remaining -= count_reader.bytes_read() as i32;
@@ -116,6 +135,7 @@ impl Message {
             sections,
             checksum,
             request_id: None,
+            raw_batch_lengths,
         })
     }
 
@@ -179,13 +199,20 @@ pub(crate) enum MessageSection {
 }
 
 impl MessageSection {
-    /// Reads bytes from `reader` and deserializes them into a MessageSection.
-    async fn read<R: AsyncRead + Unpin + Send>(reader: &mut R) -> Result<Self> {
+    /// Reads bytes from `reader` and deserializes them into a MessageSection. If the section is a
+    /// single cursor reply, the raw lengths of the documents in its batch are returned with it.
+    async fn read<R: AsyncRead + Unpin + Send>(
+        reader: &mut R,
+        utf8_lossy: bool,
+    ) -> Result<(Self, Option<Vec<usize>>)> {
         let payload_type = reader.read_u8().await?;
 
         if payload_type == 0 {
-            return Ok(MessageSection::Document(
-                async_encoding::decode_document(reader).await?,
+            let bytes = async_encoding::read_document_bytes(reader).await?;
+            let document = async_encoding::parse_document(&bytes, utf8_lossy)?;
+            return Ok((
+                MessageSection::Document(document),
+                bson_util::raw_batch_lengths(&bytes),
             ));
         }
 
@@ -199,7 +226,7 @@ impl MessageSection {
         let mut count_reader = CountReader::new(reader);
 
         while length_remaining > count_reader.bytes_read() as i32 {
-            documents.push(async_encoding::decode_document(&mut count_reader).await?);
+            documents.push(async_encoding::decode_document(&mut count_reader, utf8_lossy).await?);
         }
 
         if length_remaining != count_reader.bytes_read() as i32 {
@@ -214,11 +241,14 @@ impl MessageSection {
             .into());
         }
 
-        Ok(MessageSection::Sequence {
-            size,
-            identifier,
-            documents,
-        })
+        Ok((
+            MessageSection::Sequence {
+                size,
+                identifier,
+                documents,
+            },
+            None,
+        ))
     }
 
     /// Serializes the MessageSection to bytes and writes them to `writer`.
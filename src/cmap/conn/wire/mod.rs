@@ -4,4 +4,7 @@ mod message;
 mod test;
 mod util;
 
-pub(crate) use self::{message::Message, util::next_request_id};
+pub(crate) use self::{
+    message::{Message, MessageFlags},
+    util::next_request_id,
+};
@@ -1,13 +1,34 @@
 use tokio::sync::RwLockReadGuard;
 
-use super::message::{Message, MessageFlags, MessageSection};
+use futures_util::AsyncWriteExt;
+
+use super::{
+    header::{Header, OpCode},
+    message::{Message, MessageFlags, MessageSection},
+};
 use crate::{
     bson::{doc, Bson},
-    cmap::options::StreamOptions,
+    cmap::{options::StreamOptions, Command},
+    options::FindOneOptions,
     runtime::AsyncStream,
-    test::{CLIENT_OPTIONS, LOCK},
+    test::{TestClient, CLIENT_OPTIONS, LOCK},
 };
 
+#[test]
+fn more_to_come() {
+    let mut command = Command::new(
+        "insert".to_string(),
+        "db".to_string(),
+        doc! { "insert": "coll" },
+    );
+    let message = Message::with_command(command.clone(), None);
+    assert!(!message.flags.contains(MessageFlags::MORE_TO_COME));
+
+    command.more_to_come = true;
+    let message = Message::with_command(command, None);
+    assert!(message.flags.contains(MessageFlags::MORE_TO_COME));
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn basic() {
@@ -25,6 +46,7 @@ async fn basic() {
         )],
         checksum: None,
         request_id: None,
+        raw_batch_lengths: None,
     };
 
     let options = StreamOptions {
@@ -36,7 +58,7 @@ async fn basic() {
     let mut stream = AsyncStream::connect(options).await.unwrap();
     message.write_to(&mut stream).await.unwrap();
 
-    let reply = Message::read_from(&mut stream).await.unwrap();
+    let reply = Message::read_from(&mut stream, false).await.unwrap();
 
     let response_doc = match reply.sections.into_iter().next().unwrap() {
         MessageSection::Document(doc) => doc,
@@ -45,3 +67,75 @@ async fn basic() {
 
     assert_eq!(response_doc.get("ok"), Some(&Bson::Double(1.0)));
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn find_utf8_lossy() {
+    if CLIENT_OPTIONS.tls_options().is_some() || CLIENT_OPTIONS.credential.is_some() {
+        println!(
+            "skipping {} due to raw connections not supporting TLS or auth",
+            function_name!()
+        );
+        return;
+    }
+
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    // The driver can't serialize invalid UTF-8, so hand-craft an insert containing some and send
+    // it directly over a raw connection.
+    let mut command = Vec::new();
+    doc! {
+        "insert": function_name!(),
+        "documents": [{ "_id": 1, "s": "a__b" }],
+        "$db": function_name!(),
+    }
+    .to_writer(&mut command)
+    .unwrap();
+    let start = command.windows(2).position(|w| w == b"__").unwrap();
+    command[start..start + 2].copy_from_slice(&[0xff, 0xfe]);
+
+    let header = Header {
+        length: (Header::LENGTH + 4 + 1 + command.len()) as i32,
+        request_id: 1,
+        response_to: 0,
+        op_code: OpCode::Message,
+    };
+    let mut message = Vec::new();
+    header.write_to(&mut message).await.unwrap();
+    message.extend_from_slice(&0u32.to_le_bytes());
+    message.push(0);
+    message.extend_from_slice(&command);
+
+    let options = StreamOptions {
+        address: CLIENT_OPTIONS.hosts[0].clone(),
+        connect_timeout: None,
+        tls_options: None,
+    };
+    let mut stream = AsyncStream::connect(options).await.unwrap();
+    stream.write_all(&message).await.unwrap();
+    stream.flush().await.unwrap();
+    let reply = Message::read_from(&mut stream, false)
+        .await
+        .unwrap()
+        .single_document_response()
+        .unwrap();
+    assert_eq!(reply.get_i32("n"), Ok(1), "{:?}", reply);
+
+    coll.find_one(doc! { "_id": 1 }, None)
+        .await
+        .expect_err("strict find should fail");
+
+    let options = FindOneOptions::builder().utf8_lossy(true).build();
+    let doc = coll
+        .find_one(doc! { "_id": 1 }, options)
+        .await
+        .expect("lossy find should succeed")
+        .expect("document should be found");
+    assert_eq!(doc.get_str("s"), Ok("a\u{FFFD}\u{FFFD}b"));
+}
@@ -8,7 +8,7 @@ use crate::{
     bson::{doc, Bson, Document},
     client::auth::{ClientFirst, FirstRound},
     cmap::{options::ConnectionPoolOptions, Command, Connection, StreamDescription},
-    error::Result,
+    error::{ErrorKind, Result},
     is_master::{is_master_command, run_is_master, IsMasterReply},
     options::{AuthMechanism, ClientOptions, Credential, DriverInfo, ServerApi},
 };
@@ -139,6 +139,10 @@ pub(crate) struct Handshaker {
     /// given the same pool options, so it can be created at the time the Handshaker is created.
     command: Command,
     credential: Option<Credential>,
+
+    /// Whether the connections being handshaked go through a load balancer, in which case the
+    /// server must report the `serviceId` of the backend it routed the connection to.
+    load_balanced: bool,
 }
 
 impl Handshaker {
@@ -146,6 +150,7 @@ impl Handshaker {
     pub(crate) fn new(options: Option<HandshakerOptions>) -> Self {
         let mut metadata = BASE_CLIENT_METADATA.clone();
         let mut credential = None;
+        let mut load_balanced = false;
 
         let mut command =
             is_master_command(options.as_ref().and_then(|opts| opts.server_api.as_ref()));
@@ -177,6 +182,11 @@ impl Handshaker {
                 command.target_db = cred.resolved_source().to_string();
                 credential = Some(cred);
             }
+
+            if let Some(true) = options.load_balanced {
+                command.body.insert("loadBalanced", true);
+                load_balanced = true;
+            }
         }
 
         command.body.insert("client", metadata);
@@ -184,6 +194,7 @@ impl Handshaker {
         Self {
             command,
             credential,
+            load_balanced,
         }
     }
 
@@ -194,6 +205,16 @@ impl Handshaker {
         let client_first = set_speculative_auth_info(&mut command.body, self.credential.as_ref())?;
 
         let mut is_master_reply = run_is_master(command, conn).await?;
+
+        if self.load_balanced && is_master_reply.command_response.service_id.is_none() {
+            return Err(ErrorKind::InvalidResponse {
+                message: "Driver attempted to initialize in load balancing mode, but the server \
+                          does not support this mode"
+                    .to_string(),
+            }
+            .into());
+        }
+
         conn.stream_description = Some(StreamDescription::from_is_master(is_master_reply.clone()));
 
         // Record the client's message and the server's response from speculative authentication if
@@ -232,6 +253,7 @@ pub(crate) struct HandshakerOptions {
     credential: Option<Credential>,
     driver_info: Option<DriverInfo>,
     server_api: Option<ServerApi>,
+    load_balanced: Option<bool>,
 }
 
 impl From<ConnectionPoolOptions> for HandshakerOptions {
@@ -241,6 +263,7 @@ impl From<ConnectionPoolOptions> for HandshakerOptions {
             credential: options.credential,
             driver_info: options.driver_info,
             server_api: options.server_api,
+            load_balanced: options.load_balanced,
         }
     }
 }
@@ -252,6 +275,7 @@ impl From<ClientOptions> for HandshakerOptions {
             credential: options.credential,
             driver_info: options.driver_info,
             server_api: options.server_api,
+            load_balanced: options.load_balanced,
         }
     }
 }
@@ -57,3 +57,13 @@ fn metadata_with_options() {
     assert_eq!(os.get_str("type"), Ok(std::env::consts::OS));
     assert_eq!(os.get_str("architecture"), Ok(std::env::consts::ARCH));
 }
+
+#[test]
+fn load_balanced() {
+    let handshaker = Handshaker::new(None);
+    assert!(!handshaker.command.body.contains_key("loadBalanced"));
+
+    let options = ConnectionPoolOptions::builder().load_balanced(true).build();
+    let handshaker = Handshaker::new(Some(options.into()));
+    assert_eq!(handshaker.command.body.get_bool("loadBalanced"), Ok(true));
+}
@@ -7,6 +7,7 @@ use super::{conn::PendingConnection, options::ConnectionPoolOptions, Connection}
 use crate::{
     client::{auth::Credential, options::ServerApi},
     error::Result,
+    is_master::IsMasterReply,
     runtime::HttpClient,
 };
 
@@ -34,18 +35,15 @@ impl ConnectionEstablisher {
         }
     }
 
-    /// Establishes a connection.
+    /// Establishes a connection, returning it along with the server's reply to the handshake.
     pub(super) async fn establish_connection(
         &self,
         pending_connection: PendingConnection,
-    ) -> Result<Connection> {
+    ) -> Result<(Connection, IsMasterReply)> {
         let mut connection = Connection::connect(pending_connection).await?;
 
-        let first_round = self
-            .handshaker
-            .handshake(&mut connection)
-            .await?
-            .first_round;
+        let handshake_result = self.handshaker.handshake(&mut connection).await?;
+        let first_round = handshake_result.first_round;
 
         if let Some(ref credential) = self.credential {
             credential
@@ -58,6 +56,6 @@ impl ConnectionEstablisher {
                 .await?;
         }
 
-        Ok(connection)
+        Ok((connection, handshake_result.is_master_reply))
     }
 }
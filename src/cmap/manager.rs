@@ -1,7 +1,7 @@
 use tokio::sync::mpsc;
 
 use super::Connection;
-use crate::{error::Error, runtime::AcknowledgedMessage};
+use crate::{bson::oid::ObjectId, error::Error, runtime::AcknowledgedMessage};
 
 pub(super) fn channel() -> (PoolManager, ManagementRequestReceiver) {
     let (sender, receiver) = mpsc::unbounded_channel();
@@ -19,14 +19,16 @@ pub(super) struct PoolManager {
 }
 
 impl PoolManager {
-    /// Lazily clear the pool.
-    pub(super) async fn clear(&self, cause: Error) {
+    /// Lazily clear the pool, or only its connections to the backend with the given `service_id`
+    /// if one is specified.
+    pub(super) async fn clear(&self, cause: Error, service_id: Option<ObjectId>) {
         let (message, acknowledgment_receiver) = AcknowledgedMessage::package(());
         if self
             .sender
             .send(PoolManagementRequest::Clear {
                 completion_handler: message,
                 cause,
+                service_id,
             })
             .is_ok()
         {
@@ -86,10 +88,12 @@ impl ManagementRequestReceiver {
 
 #[derive(Debug)]
 pub(super) enum PoolManagementRequest {
-    /// Clear the pool, transitioning it to Paused.
+    /// Clear the pool, transitioning it to Paused. If a service ID is specified, only the
+    /// connections to that backend are cleared, and the pool remains Ready.
     Clear {
         completion_handler: AcknowledgedMessage<()>,
         cause: Error,
+        service_id: Option<ObjectId>,
     },
 
     /// Mark the pool as Ready, allowing connections to be created and checked out.
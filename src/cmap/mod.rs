@@ -15,12 +15,13 @@ use derivative::Derivative;
 
 pub use self::conn::ConnectionInfo;
 pub(crate) use self::{
-    conn::{Command, CommandResponse, Connection, StreamDescription},
+    conn::{Command, CommandResponse, Connection, PinnedConnectionHandle, StreamDescription},
     establish::handshake::Handshaker,
     status::PoolGenerationSubscriber,
 };
 use self::{connection_requester::ConnectionRequestResult, options::ConnectionPoolOptions};
 use crate::{
+    bson::oid::ObjectId,
     error::{Error, Result},
     event::cmap::{
         CmapEventHandler,
@@ -154,10 +155,11 @@ impl ConnectionPool {
         conn
     }
 
-    /// Increments the generation of the pool. Rather than eagerly removing stale connections from
-    /// the pool, they are left for the background thread to clean up.
-    pub(crate) async fn clear(&self, cause: Error) {
-        self.manager.clear(cause).await
+    /// Increments the generation of the pool, or only that of its connections to the backend with
+    /// the given `service_id` if one is specified. Rather than eagerly removing stale connections
+    /// from the pool, they are left for the background thread to clean up.
+    pub(crate) async fn clear(&self, cause: Error, service_id: Option<ObjectId>) {
+        self.manager.clear(cause, service_id).await
     }
 
     /// Mark the pool as "ready", allowing connections to be created and checked out.
@@ -168,4 +170,8 @@ impl ConnectionPool {
     pub(crate) fn generation(&self) -> u32 {
         self.generation_subscriber.generation()
     }
+
+    pub(crate) fn service_generation(&self, service_id: &ObjectId) -> u32 {
+        self.generation_subscriber.service_generation(service_id)
+    }
 }
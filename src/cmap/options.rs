@@ -9,6 +9,7 @@ use crate::{
     client::{auth::Credential, options::ServerApi},
     event::cmap::{CmapEventHandler, ConnectionPoolOptions as EventOptions},
     options::{ClientOptions, DriverInfo, ServerAddress, TlsOptions},
+    runtime::Clock,
 };
 
 /// Contains the options for creating a connection pool.
@@ -26,6 +27,11 @@ pub(crate) struct ConnectionPoolOptions {
     #[serde(skip)]
     pub(crate) connect_timeout: Option<Duration>,
 
+    /// The amount of time to wait for a command sent on a connection in this pool to complete
+    /// before closing that connection. A value of zero or `None` means no timeout.
+    #[serde(skip)]
+    pub(crate) socket_timeout: Option<Duration>,
+
     /// The credential to use for authenticating connections in this pool.
     #[serde(skip)]
     pub(crate) credential: Option<Credential>,
@@ -40,11 +46,22 @@ pub(crate) struct ConnectionPoolOptions {
     #[serde(skip)]
     pub(crate) event_handler: Option<Arc<dyn CmapEventHandler>>,
 
+    /// The clock used for idle connection pruning. Defaults to the system's monotonic clock.
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    #[serde(skip)]
+    pub(crate) clock: Option<Arc<dyn Clock>>,
+
     /// How often the background thread performs its maintenance (e.g. ensure minPoolSize).
     #[cfg(test)]
     #[serde(skip)]
     pub(crate) maintenance_frequency: Option<Duration>,
 
+    /// Whether the pool's connections go through a load balancer. If so, the pool starts out ready
+    /// rather than waiting for server monitoring to mark it as such, and connections are
+    /// handshaked with `loadBalanced: true`.
+    #[serde(skip)]
+    pub(crate) load_balanced: Option<bool>,
+
     /// Connections that have been ready for usage in the pool for longer than `max_idle_time` will
     /// not be used.
     ///
@@ -54,6 +71,11 @@ pub(crate) struct ConnectionPoolOptions {
     #[serde(deserialize_with = "bson_util::deserialize_duration_from_u64_millis")]
     pub(crate) max_idle_time: Option<Duration>,
 
+    /// The maximum number of connections that the pool can be establishing at once.
+    ///
+    /// The default is 2.
+    pub(crate) max_connecting: Option<u32>,
+
     /// The maximum number of connections that the pool can have at a given time. This includes
     /// connections which are currently checked out of the pool.
     ///
@@ -93,13 +115,17 @@ impl ConnectionPoolOptions {
             app_name: options.app_name.clone(),
             connect_timeout: options.connect_timeout,
             driver_info: options.driver_info.clone(),
+            load_balanced: options.load_balanced,
             max_idle_time: options.max_idle_time,
+            max_connecting: options.max_connecting,
             min_pool_size: options.min_pool_size,
             max_pool_size: options.max_pool_size,
             server_api: options.server_api.clone(),
+            socket_timeout: options.socket_timeout,
             tls_options: options.tls_options(),
             credential: options.credential.clone(),
             event_handler: options.cmap_event_handler.clone(),
+            clock: Some(options.clock()),
             #[cfg(test)]
             maintenance_frequency: None,
             #[cfg(test)]
@@ -123,6 +149,8 @@ impl ConnectionPoolOptions {
 pub(crate) struct ConnectionOptions {
     pub(crate) connect_timeout: Option<Duration>,
 
+    pub(crate) socket_timeout: Option<Duration>,
+
     pub(crate) tls_options: Option<TlsOptions>,
 
     #[derivative(Debug = "ignore")]
@@ -133,6 +161,7 @@ impl From<ConnectionPoolOptions> for ConnectionOptions {
     fn from(pool_options: ConnectionPoolOptions) -> Self {
         Self {
             connect_timeout: pool_options.connect_timeout,
+            socket_timeout: pool_options.socket_timeout,
             tls_options: pool_options.tls_options,
             event_handler: pool_options.event_handler,
         }
@@ -1,13 +1,24 @@
+use std::collections::HashMap;
+
+use crate::bson::oid::ObjectId;
+
 /// Struct used to track the latest status of the pool.
 #[derive(Clone, Debug)]
 struct PoolStatus {
     /// The current generation of the pool.
     generation: u32,
+
+    /// The current generation of the pool's connections to each backend server behind a load
+    /// balancer, keyed by the backend's `serviceId`.
+    service_generations: HashMap<ObjectId, u32>,
 }
 
 impl Default for PoolStatus {
     fn default() -> Self {
-        PoolStatus { generation: 0 }
+        PoolStatus {
+            generation: 0,
+            service_generations: HashMap::new(),
+        }
     }
 }
 
@@ -28,9 +39,14 @@ pub(super) struct PoolGenerationPublisher {
 
 impl PoolGenerationPublisher {
     /// Publish a new generation.
-    pub(super) fn publish(&self, new_generation: u32) {
+    pub(super) fn publish(
+        &self,
+        new_generation: u32,
+        new_service_generations: HashMap<ObjectId, u32>,
+    ) {
         let new_status = PoolStatus {
             generation: new_generation,
+            service_generations: new_service_generations,
         };
 
         // if nobody is listening, this will return an error, which we don't mind.
@@ -49,4 +65,14 @@ impl PoolGenerationSubscriber {
     pub(crate) fn generation(&self) -> u32 {
         self.receiver.borrow().generation
     }
+
+    /// Get a copy of the latest generation for the backend server with the given `serviceId`.
+    pub(crate) fn service_generation(&self, service_id: &ObjectId) -> u32 {
+        self.receiver
+            .borrow()
+            .service_generations
+            .get(service_id)
+            .copied()
+            .unwrap_or(0)
+    }
 }
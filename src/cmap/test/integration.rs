@@ -6,12 +6,15 @@ use super::{
     EVENT_TIMEOUT,
 };
 use crate::{
-    bson::doc,
+    bson::{doc, Document},
     cmap::{options::ConnectionPoolOptions, Command, ConnectionPool},
     event::cmap::{CmapEventHandler, ConnectionClosedReason},
+    options::{FindOptions, WarmUpOptions},
+    runtime::MockClock,
     sdam::ServerUpdateSender,
     selection_criteria::ReadPreference,
     test::{FailCommandOptions, FailPoint, FailPointMode, TestClient, CLIENT_OPTIONS, LOCK},
+    Client,
     RUNTIME,
 };
 use semver::VersionReq;
@@ -154,6 +157,91 @@ async fn concurrent_connections() {
         .expect("disabling fail point should succeed");
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn max_connecting_is_enforced() {
+    let _guard = LOCK.run_exclusively().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.direct_connection = Some(true);
+    options.hosts.drain(1..);
+
+    let client = TestClient::with_options(Some(options)).await;
+    let version = VersionReq::parse(">= 4.2.9").unwrap();
+    // blockConnection failpoint option only supported in 4.2.9+.
+    if !version.matches(&client.server_version) {
+        println!(
+            "skipping max_connecting_is_enforced test due to server not supporting failpoint \
+             option"
+        );
+        return;
+    }
+
+    // stall creating connections so that the checkouts overlap
+    let failpoint = doc! {
+        "configureFailPoint": "failCommand",
+        "mode": "alwaysOn",
+        "data": { "failCommands": [ "isMaster" ], "blockConnection": true, "blockTimeMS": 500 }
+    };
+    client
+        .database("admin")
+        .run_command(failpoint, None)
+        .await
+        .expect("failpoint should succeed");
+
+    let handler = Arc::new(EventHandler::new());
+    let mut client_options = CLIENT_OPTIONS.clone();
+    client_options.max_connecting = Some(1);
+    let mut options = ConnectionPoolOptions::from_client_options(&client_options);
+    options.event_handler = Some(handler.clone() as Arc<dyn crate::cmap::CmapEventHandler>);
+    options.ready = Some(true);
+
+    let pool = ConnectionPool::new(
+        CLIENT_OPTIONS.hosts[0].clone(),
+        Default::default(),
+        ServerUpdateSender::channel().0,
+        Some(options),
+    );
+
+    let tasks = (0..2).map(|_| {
+        let pool_clone = pool.clone();
+        RUNTIME
+            .spawn(async move {
+                pool_clone.check_out().await.unwrap();
+            })
+            .unwrap()
+    });
+    futures::future::join_all(tasks).await;
+
+    {
+        // ensure that each connection finished being established before the next was created.
+        let events = handler.events.read().unwrap();
+        let mut pending = 0;
+        for event in events.iter() {
+            match event {
+                Event::ConnectionCreated(_) => {
+                    pending += 1;
+                    assert!(pending <= 1, "more than maxConnecting connections pending");
+                }
+                Event::ConnectionReady(_) => {
+                    pending -= 1;
+                }
+                _ => (),
+            }
+        }
+    }
+
+    // clear the fail point
+    client
+        .database("admin")
+        .run_command(
+            doc! { "configureFailPoint": "failCommand", "mode": "off" },
+            None,
+        )
+        .await
+        .expect("disabling fail point should succeed");
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -248,3 +336,263 @@ async fn connection_error_during_operation() {
         .await
         .expect("closed event with error reason should have been seen");
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn socket_timeout_closes_only_timed_out_connection() {
+    let _guard: RwLockWriteGuard<_> = LOCK.run_exclusively().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    let handler = Arc::new(EventHandler::new());
+    options.cmap_event_handler = Some(handler.clone() as Arc<dyn CmapEventHandler>);
+    options.hosts.drain(1..);
+    options.socket_timeout = Some(Duration::from_millis(500));
+
+    let client = TestClient::with_options(options.into()).await;
+    if !client.supports_block_connection() {
+        println!(
+            "skipping {} due to blockConnection not being supported",
+            function_name!()
+        );
+        return;
+    }
+
+    let options = FailCommandOptions::builder()
+        .block_connection(Duration::from_secs(2))
+        .build();
+    let failpoint = FailPoint::fail_command(&["ping"], FailPointMode::Times(1), Some(options));
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let mut subscriber = handler.subscribe();
+
+    let error = client
+        .database("test")
+        .run_command(doc! { "ping": 1 }, None)
+        .await
+        .expect_err("ping should time out due to fail point");
+    assert!(error.is_network_timeout());
+
+    subscriber
+        .wait_for_event(EVENT_TIMEOUT, |e| {
+            matches!(e, Event::ConnectionClosed(event) if event.reason == ConnectionClosedReason::Error)
+        })
+        .await
+        .expect("closed event with error reason should have been seen");
+
+    // The rest of the pool should be unaffected by the timeout.
+    client
+        .database("test")
+        .run_command(doc! { "ping": 1 }, None)
+        .await
+        .expect("ping should succeed once the fail point is exhausted");
+
+    let closed_or_cleared =
+        subscriber.all(|e| matches!(e, Event::ConnectionClosed(_) | Event::PoolCleared(_)));
+    assert!(
+        closed_or_cleared.is_empty(),
+        "expected no further connections to be closed or pool clears, got {:?}",
+        closed_or_cleared
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn max_time_elapsed_closes_connection() {
+    let _guard: RwLockWriteGuard<_> = LOCK.run_exclusively().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    let handler = Arc::new(EventHandler::new());
+    options.cmap_event_handler = Some(handler.clone() as Arc<dyn CmapEventHandler>);
+    options.hosts.drain(1..);
+
+    let client = TestClient::with_options(options.into()).await;
+    if !client.supports_block_connection() {
+        println!(
+            "skipping {} due to blockConnection not being supported",
+            function_name!()
+        );
+        return;
+    }
+
+    let options = FailCommandOptions::builder()
+        .block_connection(Duration::from_secs(3))
+        .build();
+    let failpoint = FailPoint::fail_command(&["find"], FailPointMode::Times(1), Some(options));
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let mut subscriber = handler.subscribe();
+
+    let find_options = FindOptions::builder()
+        .max_time(Duration::from_millis(100))
+        .build();
+    let error = client
+        .database(function_name!())
+        .collection::<Document>(function_name!())
+        .find(None, find_options)
+        .await
+        .expect_err("find should time out due to fail point");
+    assert!(
+        error.is_timeout(),
+        "expected timeout error, got {:?}",
+        error
+    );
+
+    subscriber
+        .wait_for_event(EVENT_TIMEOUT, |e| {
+            matches!(e, Event::ConnectionClosed(event) if event.reason == ConnectionClosedReason::Error)
+        })
+        .await
+        .expect("closed event with error reason should have been seen");
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn max_time_ms_expired_is_timeout() {
+    let _guard: RwLockWriteGuard<_> = LOCK.run_exclusively().await;
+
+    let client = TestClient::new().await;
+    if !client.supports_fail_command().await {
+        println!(
+            "skipping {} due to failCommand not being supported",
+            function_name!()
+        );
+        return;
+    }
+
+    let options = FailCommandOptions::builder().error_code(50).build();
+    let failpoint = FailPoint::fail_command(&["find"], FailPointMode::Times(1), Some(options));
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let error = client
+        .database(function_name!())
+        .collection::<Document>(function_name!())
+        .find(None, None)
+        .await
+        .expect_err("find should fail due to fail point");
+    assert!(
+        error.is_timeout(),
+        "expected timeout error, got {:?}",
+        error
+    );
+    assert_eq!(error.code(), Some(50));
+    assert_eq!(error.code_name(), Some("MaxTimeMSExpired"));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn idle_connections_pruned_by_clock() {
+    let _guard: RwLockReadGuard<_> = LOCK.run_concurrently().await;
+
+    let handler = Arc::new(EventHandler::new());
+    let mut subscriber = handler.subscribe();
+    let clock = Arc::new(MockClock::new());
+
+    let client_options = CLIENT_OPTIONS.clone();
+    let mut options = ConnectionPoolOptions::from_client_options(&client_options);
+    options.event_handler = Some(handler.clone() as Arc<dyn crate::cmap::CmapEventHandler>);
+    options.max_idle_time = Some(Duration::from_secs(60));
+    options.maintenance_frequency = Some(Duration::from_millis(10));
+    options.clock = Some(clock.clone());
+    options.ready = Some(true);
+
+    let pool = ConnectionPool::new(
+        client_options.hosts[0].clone(),
+        Default::default(),
+        ServerUpdateSender::channel().0,
+        Some(options),
+    );
+    drop(pool.check_out().await.unwrap());
+
+    subscriber
+        .wait_for_event(EVENT_TIMEOUT, |e| {
+            matches!(e, Event::ConnectionCheckedIn(_))
+        })
+        .await
+        .expect("connection should have been checked in");
+
+    // Several maintenance passes happen in real time here, but the connection should only become
+    // idle once the clock is advanced.
+    RUNTIME.delay_for(Duration::from_millis(100)).await;
+    assert!(subscriber
+        .all(|e| matches!(e, Event::ConnectionClosed(_)))
+        .is_empty());
+
+    clock.advance(Duration::from_secs(61));
+
+    subscriber
+        .wait_for_event(EVENT_TIMEOUT, |e| {
+            matches!(e, Event::ConnectionClosed(event) if event.reason == ConnectionClosedReason::Idle)
+        })
+        .await
+        .expect("closed event with idle reason should have been seen");
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn warm_up_establishes_min_pool_size_connections() {
+    let _guard: RwLockReadGuard<_> = LOCK.run_concurrently().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    let handler = Arc::new(EventHandler::new());
+    options.cmap_event_handler = Some(handler.clone() as Arc<dyn CmapEventHandler>);
+    options.hosts.drain(1..);
+    options.direct_connection = Some(true);
+    options.min_pool_size = Some(3);
+
+    let mut subscriber = handler.subscribe();
+
+    let client = Client::with_options(options).unwrap();
+    client
+        .warm_up(WarmUpOptions::builder().timeout(EVENT_TIMEOUT).build())
+        .await
+        .expect("warm up should succeed");
+
+    // The pool's background maintenance may also establish connections, but it never checks them
+    // out, so three distinct connections having been checked out by the time warm_up returns shows
+    // that warm_up itself made them ready.
+    let mut checked_out: Vec<u32> = subscriber
+        .all(|e| matches!(e, Event::ConnectionCheckedOut(_)))
+        .into_iter()
+        .filter_map(|e| match e {
+            Event::ConnectionCheckedOut(event) => Some(event.connection_id),
+            _ => None,
+        })
+        .collect();
+    checked_out.sort_unstable();
+    checked_out.dedup();
+    assert_eq!(checked_out.len(), 3, "checked out {:?}", checked_out);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn warm_up_without_min_pool_size() {
+    let _guard: RwLockReadGuard<_> = LOCK.run_concurrently().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    let handler = Arc::new(EventHandler::new());
+    options.cmap_event_handler = Some(handler.clone() as Arc<dyn CmapEventHandler>);
+    options.hosts.drain(1..);
+    options.direct_connection = Some(true);
+    options.min_pool_size = None;
+
+    let mut subscriber = handler.subscribe();
+
+    let client = Client::with_options(options).unwrap();
+    client
+        .warm_up(WarmUpOptions::builder().timeout(EVENT_TIMEOUT).build())
+        .await
+        .expect("warm up should succeed");
+
+    // Without a minimum pool size, the pool doesn't establish any connections in the background,
+    // so the only one is the one that warm_up established.
+    let ready = subscriber.all(|e| matches!(e, Event::ConnectionReady(_)));
+    assert_eq!(
+        ready.len(),
+        1,
+        "expected 1 ready connection, got {:?}",
+        ready
+    );
+}
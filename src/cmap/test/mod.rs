@@ -168,7 +168,8 @@ impl Executor {
         RUNTIME.execute(async move {
             while let Some(update) = update_receiver.recv().await {
                 match update.into_message() {
-                    ServerUpdate::Error { error, .. } => manager.clear(error).await,
+                    ServerUpdate::Error { error, .. } => manager.clear(error, None).await,
+                    ServerUpdate::LoadBalancerHandshake { .. } => {}
                 }
             }
         });
@@ -290,6 +291,7 @@ impl Operation {
                             message: "test error".to_string(),
                         }
                         .into(),
+                        None,
                     )
                     .await;
                 }
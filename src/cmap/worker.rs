@@ -19,6 +19,7 @@ use super::{
     DEFAULT_MAX_POOL_SIZE,
 };
 use crate::{
+    bson::oid::ObjectId,
     error::{Error, ErrorKind, Result},
     event::cmap::{
         CmapEventHandler,
@@ -29,15 +30,19 @@ use crate::{
         PoolReadyEvent,
     },
     options::ServerAddress,
-    runtime::HttpClient,
+    runtime::{Clock, HttpClient, SystemClock},
     sdam::ServerUpdateSender,
     RUNTIME,
 };
 
-use std::{collections::VecDeque, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::sync::mpsc;
 
-const MAX_CONNECTING: u32 = 2;
+const DEFAULT_MAX_CONNECTING: u32 = 2;
 const MAINTENACE_FREQUENCY: Duration = Duration::from_millis(500);
 
 /// A worker task that manages the shared state of the pool.
@@ -66,6 +71,12 @@ pub(crate) struct ConnectionPoolWorker {
     /// closed when checked back in or when popped off of the set of available connections.
     generation: u32,
 
+    /// The current generation of the pool's connections to each backend server behind a load
+    /// balancer, keyed by the backend's `serviceId`. When the pool is connected to a load
+    /// balancer, clearing it only affects the connections to the backend that caused the error,
+    /// so these are tracked separately from `generation`.
+    service_generations: HashMap<ObjectId, u32>,
+
     /// The established connections that are currently checked into the pool and awaiting usage in
     /// future operations.
     available_connections: VecDeque<Connection>,
@@ -84,6 +95,9 @@ pub(crate) struct ConnectionPoolWorker {
     /// The time between maintenance tasks.
     maintenance_frequency: Duration,
 
+    /// The clock used to determine when connections have become idle.
+    clock: Arc<dyn Clock>,
+
     /// Connections that have been ready for usage in the pool for longer than `max_idle_time` will
     /// be closed either by the background thread or when popped off of the set of available
     /// connections. If `max_idle_time` is `None`, then connections will not be closed due to being
@@ -102,6 +116,10 @@ pub(crate) struct ConnectionPoolWorker {
     /// wait_queue_timeout is exceeded.
     max_pool_size: u32,
 
+    /// The maximum number of connections that the pool can be establishing at once. Requests for
+    /// new connections beyond this will wait until a pending connection finishes establishing.
+    max_connecting: u32,
+
     /// Receiver used to determine if any threads hold references to this pool. If all the
     /// sender ends of this receiver drop, this worker will be notified and drop too.
     handle_listener: HandleListener,
@@ -119,6 +137,10 @@ pub(crate) struct ConnectionPoolWorker {
     /// encountered by the pool.
     generation_publisher: PoolGenerationPublisher,
 
+    /// Subscriber used to look up the generation of newly established connections to a backend
+    /// behind a load balancer, since the backend isn't known until the handshake completes.
+    generation_subscriber: PoolGenerationSubscriber,
+
     /// A pool manager that can be cloned and attached to connections checked out of the pool.
     manager: PoolManager,
 
@@ -154,6 +176,16 @@ impl ConnectionPoolWorker {
 
         let min_pool_size = options.as_ref().and_then(|opts| opts.min_pool_size);
 
+        let max_connecting = options
+            .as_ref()
+            .and_then(|opts| opts.max_connecting)
+            .unwrap_or(DEFAULT_MAX_CONNECTING);
+
+        let clock = options
+            .as_ref()
+            .and_then(|opts| opts.clock.clone())
+            .unwrap_or_else(|| Arc::new(SystemClock));
+
         let connection_options: Option<ConnectionOptions> = options
             .as_ref()
             .map(|pool_options| ConnectionOptions::from(pool_options.clone()));
@@ -183,6 +215,17 @@ impl ConnectionPoolWorker {
         #[cfg(not(test))]
         let (state, maintenance_frequency) = (PoolState::New, MAINTENACE_FREQUENCY);
 
+        // A load balancer is never monitored, so nothing would ever mark the pool as ready.
+        let state = if options
+            .as_ref()
+            .and_then(|opts| opts.load_balanced)
+            .unwrap_or(false)
+        {
+            PoolState::Ready
+        } else {
+            state
+        };
+
         let worker = ConnectionPoolWorker {
             address,
             event_handler: event_handler.clone(),
@@ -193,9 +236,11 @@ impl ConnectionPoolWorker {
             total_connection_count: 0,
             pending_connection_count: 0,
             generation: 0,
+            service_generations: HashMap::new(),
             connection_options,
             available_connections: VecDeque::new(),
             max_pool_size,
+            max_connecting,
             request_receiver,
             wait_queue: Default::default(),
             management_receiver,
@@ -203,7 +248,9 @@ impl ConnectionPoolWorker {
             handle_listener,
             state,
             generation_publisher,
+            generation_subscriber: generation_subscriber.clone(),
             maintenance_frequency,
+            clock,
             server_updater,
         };
 
@@ -263,8 +310,9 @@ impl ConnectionPoolWorker {
                 PoolTask::HandleManagementRequest(PoolManagementRequest::Clear {
                     completion_handler: _,
                     cause,
+                    service_id,
                 }) => {
-                    self.clear(cause);
+                    self.clear(cause, service_id);
                 }
                 PoolTask::HandleManagementRequest(PoolManagementRequest::MarkAsReady {
                     completion_handler: _handler,
@@ -308,20 +356,20 @@ impl ConnectionPoolWorker {
         }
 
         self.total_connection_count < self.max_pool_size
-            && self.pending_connection_count < MAX_CONNECTING
+            && self.pending_connection_count < self.max_connecting
     }
 
     async fn check_out(&mut self, request: ConnectionRequest) {
         // first attempt to check out an available connection
         while let Some(mut conn) = self.available_connections.pop_back() {
             // Close the connection if it's stale.
-            if conn.is_stale(self.generation) {
+            if self.is_stale(&conn) {
                 self.close_connection(conn, ConnectionClosedReason::Stale);
                 continue;
             }
 
             // Close the connection if it's idle.
-            if conn.is_idle(self.max_idle_time) {
+            if conn.is_idle(self.max_idle_time, self.clock.now()) {
                 self.close_connection(conn, ConnectionClosedReason::Idle);
                 continue;
             }
@@ -331,7 +379,7 @@ impl ConnectionPoolWorker {
                 // checking out thread stopped listening, indicating it hit the WaitQueue
                 // timeout, so we put connection back into pool.
                 let mut connection = request.unwrap_pooled_connection();
-                connection.mark_as_available();
+                connection.mark_as_available(self.clock.now());
                 self.available_connections.push_back(connection);
             }
 
@@ -345,6 +393,7 @@ impl ConnectionPoolWorker {
             let pending_connection = self.create_pending_connection();
             let manager = self.manager.clone();
             let mut server_updater = self.server_updater.clone();
+            let generation_subscriber = self.generation_subscriber.clone();
 
             let handle = RUNTIME.spawn(async move {
                 let mut establish_result = establish_connection(
@@ -352,6 +401,7 @@ impl ConnectionPoolWorker {
                     pending_connection,
                     &mut server_updater,
                     &manager,
+                    &generation_subscriber,
                     event_handler.as_ref(),
                 )
                 .await;
@@ -416,7 +466,7 @@ impl ConnectionPoolWorker {
     fn handle_connection_succeeded(&mut self, connection: Option<Connection>) {
         self.pending_connection_count -= 1;
         if let Some(mut connection) = connection {
-            connection.mark_as_available();
+            connection.mark_as_available(self.clock.now());
             self.available_connections.push_back(connection);
         }
     }
@@ -426,11 +476,11 @@ impl ConnectionPoolWorker {
             handler.handle_connection_checked_in_event(conn.checked_in_event());
         });
 
-        conn.mark_as_available();
+        conn.mark_as_available(self.clock.now());
 
         if conn.has_errored() {
             self.close_connection(conn, ConnectionClosedReason::Error);
-        } else if conn.is_stale(self.generation) {
+        } else if self.is_stale(&conn) {
             self.close_connection(conn, ConnectionClosedReason::Stale);
         } else if conn.is_executing() {
             self.close_connection(conn, ConnectionClosedReason::Dropped)
@@ -439,15 +489,48 @@ impl ConnectionPoolWorker {
         }
     }
 
-    fn clear(&mut self, cause: Error) {
+    /// Checks whether the connection belongs to a previous generation of the pool, or, if it's
+    /// routed through a load balancer, to a previous generation of its backend's connections.
+    fn is_stale(&self, conn: &Connection) -> bool {
+        let current_generation = match conn.service_id() {
+            Some(ref service_id) => self
+                .service_generations
+                .get(service_id)
+                .copied()
+                .unwrap_or(0),
+            None => self.generation,
+        };
+        conn.is_stale(current_generation)
+    }
+
+    fn clear(&mut self, cause: Error, service_id: Option<ObjectId>) {
+        // Connections to other backends behind the load balancer are unaffected, so the pool stays
+        // ready.
+        if let Some(service_id) = service_id {
+            *self.service_generations.entry(service_id).or_insert(0) += 1;
+            self.generation_publisher
+                .publish(self.generation, self.service_generations.clone());
+            self.emit_event(|handler| {
+                let event = PoolClearedEvent {
+                    address: self.address.clone(),
+                    service_id: Some(service_id),
+                };
+
+                handler.handle_pool_cleared_event(event);
+            });
+            return;
+        }
+
         self.generation += 1;
         let previous_state = std::mem::replace(&mut self.state, PoolState::Paused(cause.clone()));
-        self.generation_publisher.publish(self.generation);
+        self.generation_publisher
+            .publish(self.generation, self.service_generations.clone());
 
         if matches!(previous_state, PoolState::Ready) {
             self.emit_event(|handler| {
                 let event = PoolClearedEvent {
                     address: self.address.clone(),
+                    service_id: None,
                 };
 
                 handler.handle_pool_cleared_event(event);
@@ -505,10 +588,10 @@ impl ConnectionPoolWorker {
     /// Iterate over the connections and remove any that are stale or idle.
     fn remove_perished_connections(&mut self) {
         while let Some(connection) = self.available_connections.pop_front() {
-            if connection.is_stale(self.generation) {
+            if self.is_stale(&connection) {
                 // the following unwrap is okay becaue we asserted the pool was nonempty
                 self.close_connection(connection, ConnectionClosedReason::Stale);
-            } else if connection.is_idle(self.max_idle_time) {
+            } else if connection.is_idle(self.max_idle_time, self.clock.now()) {
                 self.close_connection(connection, ConnectionClosedReason::Idle);
             } else {
                 self.available_connections.push_front(connection);
@@ -523,19 +606,21 @@ impl ConnectionPoolWorker {
     fn ensure_min_connections(&mut self) {
         if let Some(min_pool_size) = self.min_pool_size {
             while self.total_connection_count < min_pool_size
-                && self.pending_connection_count < MAX_CONNECTING
+                && self.pending_connection_count < self.max_connecting
             {
                 let pending_connection = self.create_pending_connection();
                 let event_handler = self.event_handler.clone();
                 let manager = self.manager.clone();
                 let establisher = self.establisher.clone();
                 let mut updater = self.server_updater.clone();
+                let generation_subscriber = self.generation_subscriber.clone();
                 RUNTIME.execute(async move {
                     let connection = establish_connection(
                         &establisher,
                         pending_connection,
                         &mut updater,
                         &manager,
+                        &generation_subscriber,
                         event_handler.as_ref(),
                     )
                     .await;
@@ -557,16 +642,15 @@ async fn establish_connection(
     pending_connection: PendingConnection,
     server_updater: &mut ServerUpdateSender,
     manager: &PoolManager,
+    generation_subscriber: &PoolGenerationSubscriber,
     event_handler: Option<&Arc<dyn CmapEventHandler>>,
 ) -> Result<Connection> {
     let connection_id = pending_connection.id;
     let generation = pending_connection.generation;
     let address = pending_connection.address.clone();
 
-    let mut establish_result = establisher.establish_connection(pending_connection).await;
-
-    match establish_result {
-        Err(ref e) => {
+    match establisher.establish_connection(pending_connection).await {
+        Err(e) => {
             if let Some(handler) = event_handler {
                 let event = ConnectionClosedEvent {
                     address,
@@ -577,15 +661,26 @@ async fn establish_connection(
             }
             server_updater.handle_error(e.clone(), generation).await;
             manager.handle_connection_failed();
+            Err(e)
         }
-        Ok(ref mut connection) => {
+        Ok((mut connection, is_master_reply)) => {
+            // A connection through a load balancer belongs to whichever generation its backend's
+            // connections are on, which can only be determined once the handshake has completed.
+            // Since the load balancer is never sent heartbeats, the handshake is also the only way
+            // to learn about the deployment behind it.
+            if let Some(ref service_id) = connection.service_id() {
+                connection.generation = generation_subscriber.service_generation(service_id);
+                server_updater
+                    .handle_load_balancer_handshake(is_master_reply)
+                    .await;
+            }
+
             if let Some(handler) = event_handler {
                 handler.handle_connection_ready_event(connection.ready_event())
             };
+            Ok(connection)
         }
     }
-
-    establish_result
 }
 
 /// Enum modeling the possible pool states as described in the CMAP spec.
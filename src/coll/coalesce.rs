@@ -0,0 +1,283 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+        Mutex,
+    },
+};
+
+use futures_util::future::{BoxFuture, FutureExt, WeakShared};
+
+use super::{
+    options::{FindOneOptions, FindOptions},
+    Namespace,
+};
+use crate::{
+    bson::{doc, Bson, Document},
+    error::Result,
+    selection_criteria::SelectionCriteria,
+};
+
+type FindOneFuture = BoxFuture<'static, Result<Option<Document>>>;
+
+/// Identifies a `find_one` call by everything that can affect its result, i.e. the namespace,
+/// filter, and resolved options. Two calls with equal keys are interchangeable.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(crate) struct FindOneKey {
+    namespace: String,
+    filter: Vec<u8>,
+    options: Vec<u8>,
+}
+
+impl FindOneKey {
+    /// Creates the key for a `find_one` call, or returns `None` if the call can't be coalesced
+    /// because its selection criteria is a predicate, which can't be compared.
+    pub(crate) fn new(
+        namespace: &Namespace,
+        filter: Option<&Document>,
+        options: Option<&FindOneOptions>,
+    ) -> Result<Option<Self>> {
+        let read_preference = match options.and_then(|opts| opts.selection_criteria.as_ref()) {
+            Some(SelectionCriteria::ReadPreference(read_pref)) => {
+                Bson::Document(read_pref.clone().into_document())
+            }
+            Some(SelectionCriteria::Predicate(_)) => return Ok(None),
+            None => Bson::Null,
+        };
+
+        let options = match options {
+            Some(options) => doc! {
+                "find": bson::to_document(&FindOptions::from(options.clone()))?,
+                "readPreference": read_preference,
+                "utf8Lossy": options.utf8_lossy.unwrap_or(false),
+            },
+            None => Document::new(),
+        };
+
+        Ok(Some(Self {
+            namespace: namespace.to_string(),
+            filter: to_bytes(filter.unwrap_or(&Document::new()))?,
+            options: to_bytes(&options)?,
+        }))
+    }
+}
+
+fn to_bytes(doc: &Document) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    doc.to_writer(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Shares the server round trip of a `find_one` call with identical calls made while it's in
+/// progress.
+///
+/// Each in-progress call is tracked until it resolves, at which point it's removed so that later
+/// calls go to the server again. Only weak references are held here, so a call that every waiter
+/// has stopped awaiting is dropped and removed as well.
+#[derive(Debug, Default)]
+pub(crate) struct FindOneCoalescer {
+    in_flight: Arc<Mutex<HashMap<FindOneKey, InFlight>>>,
+    next_id: AtomicU64,
+}
+
+#[derive(Debug)]
+struct InFlight {
+    id: u64,
+    result: WeakShared<FindOneFuture>,
+}
+
+impl FindOneCoalescer {
+    /// Waits for the result of the in-progress call for `key`, or starts one using `find_one` if
+    /// there isn't one.
+    pub(crate) async fn run<F>(&self, key: FindOneKey, find_one: F) -> Result<Option<Document>>
+    where
+        F: Future<Output = Result<Option<Document>>> + Send + 'static,
+    {
+        let result = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(&key).and_then(|call| call.result.upgrade()) {
+                Some(result) => result,
+                None => {
+                    let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+                    let guard = RemoveOnDrop {
+                        in_flight: self.in_flight.clone(),
+                        key: key.clone(),
+                        id,
+                    };
+                    let result = async move {
+                        let _guard = guard;
+                        find_one.await
+                    }
+                    .boxed()
+                    .shared();
+
+                    if let Some(weak) = result.downgrade() {
+                        in_flight.insert(key, InFlight { id, result: weak });
+                    }
+                    result
+                }
+            }
+        };
+
+        result.await
+    }
+
+    #[cfg(test)]
+    pub(crate) fn in_flight_count(&self) -> usize {
+        self.in_flight.lock().unwrap().len()
+    }
+}
+
+/// Removes an in-progress call from the coalescer once it resolves or is dropped.
+struct RemoveOnDrop {
+    in_flight: Arc<Mutex<HashMap<FindOneKey, InFlight>>>,
+    key: FindOneKey,
+    id: u64,
+}
+
+impl Drop for RemoveOnDrop {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        // A newer call for the same key may have replaced this one if this one was dropped without
+        // resolving, in which case it must be left alone.
+        if in_flight.get(&self.key).map(|call| call.id) == Some(self.id) {
+            in_flight.remove(&self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use futures::future::join_all;
+    use tokio::sync::oneshot;
+
+    use super::{FindOneCoalescer, FindOneKey};
+    use crate::{
+        bson::doc,
+        coll::{options::FindOneOptions, Namespace},
+        error::{ErrorKind, Result},
+        options::{ReadPreference, SelectionCriteria},
+    };
+
+    fn key(filter: bson::Document) -> FindOneKey {
+        FindOneKey::new(&Namespace::empty(), Some(&filter), None)
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn find_one_key() {
+        assert_eq!(key(doc! { "x": 1 }), key(doc! { "x": 1 }));
+        assert_ne!(key(doc! { "x": 1 }), key(doc! { "x": 2 }));
+
+        let options = |criteria| {
+            FindOneOptions::builder()
+                .selection_criteria(criteria)
+                .build()
+        };
+        let secondary = options(SelectionCriteria::ReadPreference(
+            ReadPreference::Secondary {
+                options: Default::default(),
+            },
+        ));
+        let primary = options(SelectionCriteria::ReadPreference(ReadPreference::Primary));
+        assert_ne!(
+            FindOneKey::new(&Namespace::empty(), None, Some(&secondary)).unwrap(),
+            FindOneKey::new(&Namespace::empty(), None, Some(&primary)).unwrap(),
+        );
+
+        let predicate = options(SelectionCriteria::Predicate(Arc::new(|_| true)));
+        assert!(FindOneKey::new(&Namespace::empty(), None, Some(&predicate))
+            .unwrap()
+            .is_none());
+    }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn concurrent_calls_share_result() {
+        let coalescer = FindOneCoalescer::default();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = oneshot::channel();
+        let mut receiver = Some(receiver);
+
+        let waiters = (0..10).map(|_| {
+            let calls = calls.clone();
+            let receiver = receiver.take();
+            coalescer.run(key(doc! { "x": 1 }), async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                receiver.unwrap().await.unwrap();
+                Ok(Some(doc! { "x": 1 }))
+            })
+        });
+        // the waiters are all polled before the call is allowed to resolve.
+        let (results, _) = futures::join!(join_all(waiters), async { sender.send(()).unwrap() });
+
+        for result in results {
+            assert_eq!(result.unwrap(), Some(doc! { "x": 1 }));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(coalescer.in_flight_count(), 0);
+
+        // once the call has resolved, the next one goes to the server again.
+        let result = coalescer
+            .run(key(doc! { "x": 1 }), async { Ok(None) })
+            .await;
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn errors_propagate_to_all_waiters() {
+        let coalescer = FindOneCoalescer::default();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = oneshot::channel::<()>();
+        let mut receiver = Some(receiver);
+
+        let waiters = (0..3).map(|_| {
+            let calls = calls.clone();
+            let receiver = receiver.take();
+            coalescer.run(key(doc! {}), async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                let _: std::result::Result<_, _> = receiver.unwrap().await;
+                Err(ErrorKind::Internal {
+                    message: "find failed".to_string(),
+                }
+                .into())
+            })
+        });
+        let (results, _) = futures::join!(join_all(waiters), async { drop(sender) });
+
+        let results: Vec<Result<_>> = results;
+        assert_eq!(results.len(), 3);
+        for result in results {
+            assert!(matches!(
+                *result.unwrap_err().kind,
+                ErrorKind::Internal { .. }
+            ));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(coalescer.in_flight_count(), 0);
+    }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn abandoned_calls_are_removed() {
+        let coalescer = FindOneCoalescer::default();
+        let (_sender, receiver) = oneshot::channel::<()>();
+
+        let waiter = Box::pin(coalescer.run(key(doc! {}), async move {
+            let _: std::result::Result<_, _> = receiver.await;
+            Ok(None)
+        }));
+        // poll the call once so that it's registered, then stop waiting on it.
+        assert!(futures::poll!(waiter).is_pending());
+        assert_eq!(coalescer.in_flight_count(), 0);
+    }
+}
@@ -1,6 +1,14 @@
+mod coalesce;
 pub mod options;
 
-use std::{borrow::Borrow, collections::HashSet, fmt, fmt::Debug, sync::Arc};
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    fmt,
+    fmt::Debug,
+    sync::Arc,
+    time::Instant,
+};
 
 use futures_util::stream::StreamExt;
 use serde::{
@@ -10,26 +18,44 @@ use serde::{
     Serialize,
 };
 
-use self::options::*;
+pub(crate) use self::coalesce::FindOneCoalescer;
+use self::{coalesce::FindOneKey, options::*};
 use crate::{
     bson::{doc, to_document, Bson, Document},
     bson_util,
-    client::session::TransactionState,
+    change_stream::{
+        event::ChangeStreamEvent,
+        options::ChangeStreamOptions,
+        ChangeStream,
+        WatchArgs,
+    },
+    client::{options::ServerAddress, session::TransactionState},
+    cmap::PinnedConnectionHandle,
     concern::{ReadConcern, WriteConcern},
     error::{convert_bulk_errors, BulkWriteError, BulkWriteFailure, Error, ErrorKind, Result},
     operation::{
         Aggregate,
         Count,
         CountDocuments,
+        CreateIndexes,
         Delete,
         Distinct,
         DropCollection,
         Find,
         FindAndModify,
         Insert,
+        RunCommand,
         Update,
     },
-    results::{DeleteResult, InsertManyResult, InsertOneResult, UpdateResult},
+    results::{
+        CreateIndexResult,
+        DeleteResult,
+        FindOneAndModifyResult,
+        InsertManyResult,
+        InsertOneResult,
+        OperationOutcome,
+        UpdateResult,
+    },
     selection_criteria::SelectionCriteria,
     Client,
     ClientSession,
@@ -173,11 +199,27 @@ impl<T> Collection<T> {
         options: impl Into<Option<DropCollectionOptions>>,
         session: impl Into<Option<&mut ClientSession>>,
     ) -> Result<()> {
-        let session = session.into();
+        let mut session = session.into();
 
         let mut options = options.into();
         resolve_options!(self, options, [write_concern]);
 
+        if let Some(encrypted_fields) = options
+            .as_ref()
+            .and_then(|opts| opts.encrypted_fields.as_ref())
+        {
+            for coll in state_collection_names(self.name(), encrypted_fields) {
+                let ns = Namespace {
+                    db: self.inner.db.name().to_string(),
+                    coll,
+                };
+                let drop = DropCollection::new(ns, options.clone());
+                self.client()
+                    .execute_operation(drop, session.as_deref_mut())
+                    .await?;
+            }
+        }
+
         let drop = DropCollection::new(self.namespace(), options);
         self.client().execute_operation(drop, session).await
     }
@@ -197,6 +239,86 @@ impl<T> Collection<T> {
         self.drop_common(options, session).await
     }
 
+    async fn create_index_common(
+        &self,
+        index: IndexModel,
+        options: impl Into<Option<CreateIndexOptions>>,
+        session: impl Into<Option<&mut ClientSession>>,
+    ) -> Result<CreateIndexResult> {
+        let session = session.into();
+
+        let mut options = options.into();
+        resolve_options!(self, options, [write_concern]);
+
+        let create_indexes = CreateIndexes::new(self.namespace(), vec![index], options);
+        let mut names = self
+            .client()
+            .execute_operation(create_indexes, session)
+            .await?;
+        Ok(CreateIndexResult {
+            index_name: names.remove(0),
+        })
+    }
+
+    /// Creates the given index on this collection.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/indexes/) for more
+    /// information on indexes.
+    pub async fn create_index(
+        &self,
+        index: IndexModel,
+        options: impl Into<Option<CreateIndexOptions>>,
+    ) -> Result<CreateIndexResult> {
+        self.create_index_common(index, options, None).await
+    }
+
+    /// Creates the given index on this collection using the provided `ClientSession`.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/indexes/) for more
+    /// information on indexes.
+    pub async fn create_index_with_session(
+        &self,
+        index: IndexModel,
+        options: impl Into<Option<CreateIndexOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<CreateIndexResult> {
+        self.create_index_common(index, options, session).await
+    }
+
+    /// Creates an ascending (`direction` of `1`) or descending (`direction` of `-1`) index on
+    /// `field`, returning the name of the index.
+    ///
+    /// This is shorthand for calling [`create_index`](#method.create_index) with an
+    /// [`IndexModel`](options/struct.IndexModel.html) whose keys are `{ field: direction }`.
+    pub async fn ensure_index(
+        &self,
+        field: &str,
+        direction: i32,
+        options: impl Into<Option<CreateIndexOptions>>,
+    ) -> Result<String> {
+        let index = IndexModel::builder()
+            .keys(doc! { field: direction })
+            .build();
+        Ok(self.create_index(index, options).await?.index_name)
+    }
+
+    /// Creates an ascending unique index on `field`, returning the name of the index.
+    ///
+    /// This is shorthand for calling [`create_index`](#method.create_index) with an
+    /// [`IndexModel`](options/struct.IndexModel.html) whose keys are `{ field: 1 }` and whose
+    /// `unique` option is set.
+    pub async fn ensure_unique_index(
+        &self,
+        field: &str,
+        options: impl Into<Option<CreateIndexOptions>>,
+    ) -> Result<String> {
+        let index = IndexModel::builder()
+            .keys(doc! { field: 1 })
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+        Ok(self.create_index(index, options).await?.index_name)
+    }
+
     /// Runs an aggregation operation.
     ///
     /// See the documentation [here](https://docs.mongodb.com/manual/aggregation/) for more
@@ -206,6 +328,52 @@ impl<T> Collection<T> {
         pipeline: impl IntoIterator<Item = Document>,
         options: impl Into<Option<AggregateOptions>>,
     ) -> Result<Cursor<Document>> {
+        self.aggregate_as(pipeline, options).await
+    }
+
+    /// Runs an aggregation operation, deserializing the resulting documents into `R`.
+    ///
+    /// This is useful when the shape of the pipeline's output differs from the collection's
+    /// documents, e.g. after a `$project` or `$group` stage.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/aggregation/) for more
+    /// information on aggregations.
+    pub async fn aggregate_as<R>(
+        &self,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<AggregateOptions>>,
+    ) -> Result<Cursor<R>>
+    where
+        R: DeserializeOwned,
+    {
+        self.aggregate_as_common(pipeline, options)
+            .await
+            .map(|(cursor, _)| cursor)
+    }
+
+    /// Runs an aggregation operation, also returning an
+    /// [`OperationOutcome`](results/struct.OperationOutcome.html) describing how the initial
+    /// `aggregate` command was executed, including whether it was retried. The outcome doesn't
+    /// cover the `getMore` commands issued while iterating the returned cursor.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/aggregation/) for more
+    /// information on aggregations.
+    pub async fn aggregate_with_outcome(
+        &self,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<AggregateOptions>>,
+    ) -> Result<(Cursor<Document>, OperationOutcome)> {
+        self.aggregate_as_common(pipeline, options).await
+    }
+
+    async fn aggregate_as_common<R>(
+        &self,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<AggregateOptions>>,
+    ) -> Result<(Cursor<R>, OperationOutcome)>
+    where
+        R: DeserializeOwned,
+    {
         let mut options = options.into();
         resolve_options!(
             self,
@@ -215,10 +383,10 @@ impl<T> Collection<T> {
 
         let aggregate = Aggregate::new(self.namespace(), pipeline, options);
         let client = self.client();
-        client
-            .execute_cursor_operation(aggregate)
-            .await
-            .map(|(spec, session)| Cursor::new(client.clone(), spec, session))
+        let (spec, session, outcome) = client
+            .execute_cursor_operation_with_outcome(aggregate)
+            .await?;
+        Ok((Cursor::new(client.clone(), spec, session), outcome))
     }
 
     /// Runs an aggregation operation using the provided `ClientSession`.
@@ -231,6 +399,22 @@ impl<T> Collection<T> {
         options: impl Into<Option<AggregateOptions>>,
         session: &mut ClientSession,
     ) -> Result<SessionCursor<Document>> {
+        self.aggregate_as_with_session(pipeline, options, session)
+            .await
+    }
+
+    /// Runs an aggregation operation using the provided `ClientSession`, deserializing the
+    /// resulting documents into `R`. See [`Collection::aggregate_as`](#method.aggregate_as) for
+    /// more details.
+    pub async fn aggregate_as_with_session<R>(
+        &self,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<AggregateOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<SessionCursor<R>>
+    where
+        R: DeserializeOwned,
+    {
         let mut options = options.into();
         resolve_read_concern_with_session!(self, options, Some(&mut *session))?;
         resolve_write_concern_with_session!(self, options, Some(&mut *session))?;
@@ -244,6 +428,40 @@ impl<T> Collection<T> {
             .map(|result| SessionCursor::new(client.clone(), result))
     }
 
+    /// Runs an aggregation operation that writes its results into the collection `target` by
+    /// appending a `$merge` stage built from `options` to `pipeline`. The aggregation is always
+    /// executed on the primary and is never retried.
+    ///
+    /// `pipeline` must not contain its own `$out` or `$merge` stage.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/reference/operator/aggregation/merge/)
+    /// for more information on `$merge`.
+    pub async fn aggregate_to(
+        &self,
+        target: Namespace,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<MergeOptions>>,
+    ) -> Result<()> {
+        let (pipeline, options) = merge_pipeline(&target, pipeline, options.into())?;
+        self.aggregate(pipeline, options).await.map(|_| ())
+    }
+
+    /// Runs an aggregation operation that writes its results into the collection `target` using
+    /// the provided `ClientSession`. See
+    /// [`Collection::aggregate_to`](#method.aggregate_to) for more details.
+    pub async fn aggregate_to_with_session(
+        &self,
+        target: Namespace,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<MergeOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<()> {
+        let (pipeline, options) = merge_pipeline(&target, pipeline, options.into())?;
+        self.aggregate_with_session(pipeline, options, session)
+            .await
+            .map(|_| ())
+    }
+
     /// Estimates the number of documents in the collection using collection metadata.
     pub async fn estimated_document_count(
         &self,
@@ -262,7 +480,7 @@ impl<T> Collection<T> {
         filter: impl Into<Option<Document>>,
         options: impl Into<Option<CountOptions>>,
         session: impl Into<Option<&mut ClientSession>>,
-    ) -> Result<u64> {
+    ) -> Result<(u64, OperationOutcome)> {
         let session = session.into();
 
         let mut options = options.into();
@@ -270,7 +488,9 @@ impl<T> Collection<T> {
         resolve_selection_criteria_with_session!(self, options, session.as_ref())?;
 
         let op = CountDocuments::new(self.namespace(), filter.into(), options);
-        self.client().execute_operation(op, session).await
+        self.client()
+            .execute_operation_with_outcome(op, session)
+            .await
     }
 
     /// Gets the number of documents matching `filter`.
@@ -282,6 +502,19 @@ impl<T> Collection<T> {
         filter: impl Into<Option<Document>>,
         options: impl Into<Option<CountOptions>>,
     ) -> Result<u64> {
+        self.count_documents_common(filter, options, None)
+            .await
+            .map(|(count, _)| count)
+    }
+
+    /// Gets the number of documents matching `filter`, also returning an
+    /// [`OperationOutcome`](results/struct.OperationOutcome.html) describing how the count was
+    /// executed, including whether it was retried.
+    pub async fn count_documents_with_outcome(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<CountOptions>>,
+    ) -> Result<(u64, OperationOutcome)> {
         self.count_documents_common(filter, options, None).await
     }
 
@@ -295,7 +528,9 @@ impl<T> Collection<T> {
         options: impl Into<Option<CountOptions>>,
         session: &mut ClientSession,
     ) -> Result<u64> {
-        self.count_documents_common(filter, options, session).await
+        self.count_documents_common(filter, options, session)
+            .await
+            .map(|(count, _)| count)
     }
 
     async fn delete_many_common(
@@ -303,14 +538,16 @@ impl<T> Collection<T> {
         query: Document,
         options: impl Into<Option<DeleteOptions>>,
         session: impl Into<Option<&mut ClientSession>>,
-    ) -> Result<DeleteResult> {
+    ) -> Result<(DeleteResult, OperationOutcome)> {
         let session = session.into();
 
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
 
         let delete = Delete::new(self.namespace(), query, None, options);
-        self.client().execute_operation(delete, session).await
+        self.client()
+            .execute_operation_with_outcome(delete, session)
+            .await
     }
 
     /// Deletes all documents stored in the collection matching `query`.
@@ -319,6 +556,19 @@ impl<T> Collection<T> {
         query: Document,
         options: impl Into<Option<DeleteOptions>>,
     ) -> Result<DeleteResult> {
+        self.delete_many_common(query, options, None)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Deletes all documents stored in the collection matching `query`, also returning an
+    /// [`OperationOutcome`](results/struct.OperationOutcome.html) describing how the delete was
+    /// executed.
+    pub async fn delete_many_with_outcome(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>>,
+    ) -> Result<(DeleteResult, OperationOutcome)> {
         self.delete_many_common(query, options, None).await
     }
 
@@ -330,7 +580,9 @@ impl<T> Collection<T> {
         options: impl Into<Option<DeleteOptions>>,
         session: &mut ClientSession,
     ) -> Result<DeleteResult> {
-        self.delete_many_common(query, options, session).await
+        self.delete_many_common(query, options, session)
+            .await
+            .map(|(result, _)| result)
     }
 
     async fn delete_one_common(
@@ -338,14 +590,16 @@ impl<T> Collection<T> {
         query: Document,
         options: impl Into<Option<DeleteOptions>>,
         session: impl Into<Option<&mut ClientSession>>,
-    ) -> Result<DeleteResult> {
+    ) -> Result<(DeleteResult, OperationOutcome)> {
         let session = session.into();
 
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
 
         let delete = Delete::new(self.namespace(), query, Some(1), options);
-        self.client().execute_operation(delete, session).await
+        self.client()
+            .execute_operation_with_outcome(delete, session)
+            .await
     }
 
     /// Deletes up to one document found matching `query`.
@@ -359,6 +613,19 @@ impl<T> Collection<T> {
         query: Document,
         options: impl Into<Option<DeleteOptions>>,
     ) -> Result<DeleteResult> {
+        self.delete_one_common(query, options, None)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Deletes up to one document found matching `query`, also returning an
+    /// [`OperationOutcome`](results/struct.OperationOutcome.html) describing how the delete was
+    /// executed, including whether it was retried.
+    pub async fn delete_one_with_outcome(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>>,
+    ) -> Result<(DeleteResult, OperationOutcome)> {
         self.delete_one_common(query, options, None).await
     }
 
@@ -374,7 +641,9 @@ impl<T> Collection<T> {
         options: impl Into<Option<DeleteOptions>>,
         session: &mut ClientSession,
     ) -> Result<DeleteResult> {
-        self.delete_one_common(query, options, session).await
+        self.delete_one_common(query, options, session)
+            .await
+            .map(|(result, _)| result)
     }
 
     async fn distinct_common(
@@ -429,7 +698,7 @@ impl<T> Collection<T> {
         update: impl Into<UpdateModifications>,
         options: impl Into<Option<UpdateOptions>>,
         session: impl Into<Option<&mut ClientSession>>,
-    ) -> Result<UpdateResult> {
+    ) -> Result<(UpdateResult, OperationOutcome)> {
         let update = update.into();
 
         if let UpdateModifications::Document(ref d) = update {
@@ -442,7 +711,9 @@ impl<T> Collection<T> {
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
 
         let update = Update::new(self.namespace(), query, update, true, options);
-        self.client().execute_operation(update, session).await
+        self.client()
+            .execute_operation_with_outcome(update, session)
+            .await
     }
 
     /// Updates all documents matching `query` in the collection.
@@ -457,6 +728,20 @@ impl<T> Collection<T> {
         update: impl Into<UpdateModifications>,
         options: impl Into<Option<UpdateOptions>>,
     ) -> Result<UpdateResult> {
+        self.update_many_common(query, update, options, None)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Updates all documents matching `query` in the collection, also returning an
+    /// [`OperationOutcome`](results/struct.OperationOutcome.html) describing how the update was
+    /// executed.
+    pub async fn update_many_with_outcome(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications>,
+        options: impl Into<Option<UpdateOptions>>,
+    ) -> Result<(UpdateResult, OperationOutcome)> {
         self.update_many_common(query, update, options, None).await
     }
 
@@ -475,6 +760,7 @@ impl<T> Collection<T> {
     ) -> Result<UpdateResult> {
         self.update_many_common(query, update, options, session)
             .await
+            .map(|(result, _)| result)
     }
 
     async fn update_one_common(
@@ -483,7 +769,7 @@ impl<T> Collection<T> {
         update: impl Into<UpdateModifications>,
         options: impl Into<Option<UpdateOptions>>,
         session: impl Into<Option<&mut ClientSession>>,
-    ) -> Result<UpdateResult> {
+    ) -> Result<(UpdateResult, OperationOutcome)> {
         let update = update.into();
         if let UpdateModifications::Document(ref d) = update {
             bson_util::update_document_check(d)?;
@@ -495,7 +781,9 @@ impl<T> Collection<T> {
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
 
         let update = Update::new(self.namespace(), query, update, false, options);
-        self.client().execute_operation(update, session).await
+        self.client()
+            .execute_operation_with_outcome(update, session)
+            .await
     }
 
     /// Updates up to one document matching `query` in the collection.
@@ -515,6 +803,20 @@ impl<T> Collection<T> {
         update: impl Into<UpdateModifications>,
         options: impl Into<Option<UpdateOptions>>,
     ) -> Result<UpdateResult> {
+        self.update_one_common(query, update, options, None)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Updates up to one document matching `query` in the collection, also returning an
+    /// [`OperationOutcome`](results/struct.OperationOutcome.html) describing how the update was
+    /// executed, including whether it was retried.
+    pub async fn update_one_with_outcome(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications>,
+        options: impl Into<Option<UpdateOptions>>,
+    ) -> Result<(UpdateResult, OperationOutcome)> {
         self.update_one_common(query, update, options, None).await
     }
 
@@ -539,29 +841,45 @@ impl<T> Collection<T> {
     ) -> Result<UpdateResult> {
         self.update_one_common(query, update, options, session)
             .await
+            .map(|(result, _)| result)
     }
 
-    /// Kill the server side cursor that id corresponds to.
-    pub(super) async fn kill_cursor(&self, cursor_id: i64) -> Result<()> {
+    /// Kill the server side cursor that id corresponds to on `address`, the server that created
+    /// it, attaching the cursor's comment if it has one. If the cursor was created with an explicit
+    /// session, that session should be provided so the server can release the cursor along with
+    /// the session. If the cursor is pinned to a connection, the command is sent on it.
+    pub(super) async fn kill_cursor(
+        &self,
+        cursor_id: i64,
+        address: ServerAddress,
+        comment: Option<String>,
+        pinned_connection: Option<&PinnedConnectionHandle>,
+        session: Option<&mut ClientSession>,
+    ) -> Result<()> {
         let ns = self.namespace();
 
-        self.client()
-            .database(ns.db.as_str())
-            .run_command(
-                doc! {
-                    "killCursors": ns.coll.as_str(),
-                    "cursors": [cursor_id]
-                },
-                None,
-            )
-            .await?;
+        let mut command = doc! {
+            "killCursors": ns.coll.as_str(),
+            "cursors": [cursor_id]
+        };
+        if let Some(comment) = comment {
+            command.insert("comment", comment);
+        }
+
+        let operation = RunCommand::new(
+            ns.db,
+            command,
+            Some(SelectionCriteria::host(address)),
+            pinned_connection,
+        )?;
+        self.client().execute_operation(operation, session).await?;
         Ok(())
     }
 }
 
 impl<T> Collection<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     /// Finds the documents in the collection matching `filter`.
     pub async fn find(
@@ -569,16 +887,66 @@ where
         filter: impl Into<Option<Document>>,
         options: impl Into<Option<FindOptions>>,
     ) -> Result<Cursor<T>> {
+        self.find_with_outcome(filter, options)
+            .await
+            .map(|(cursor, _)| cursor)
+    }
+
+    /// Finds the documents in the collection matching `filter`, also returning an
+    /// [`OperationOutcome`](results/struct.OperationOutcome.html) describing how the initial
+    /// `find` command was executed, including whether it was retried. The outcome doesn't cover
+    /// the `getMore` commands issued while iterating the returned cursor.
+    pub async fn find_with_outcome(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<FindOptions>>,
+    ) -> Result<(Cursor<T>, OperationOutcome)> {
         let mut options = options.into();
         resolve_options!(self, options, [read_concern, selection_criteria]);
 
         let find = Find::new(self.namespace(), filter.into(), options);
         let client = self.client();
 
-        client
-            .execute_cursor_operation(find)
-            .await
-            .map(|(result, session)| Cursor::new(client.clone(), result, session))
+        let (spec, session, outcome) = client.execute_cursor_operation_with_outcome(find).await?;
+        Ok((Cursor::new(client.clone(), spec, session), outcome))
+    }
+
+    /// Finds the documents in the collection matching `filter`, using the index specified by
+    /// `hint`. Any hint already set in `options` is overridden.
+    pub async fn find_with_hint(
+        &self,
+        filter: impl Into<Option<Document>>,
+        hint: impl Into<Hint>,
+        options: impl Into<Option<FindOptions>>,
+    ) -> Result<Cursor<T>> {
+        let mut options = options.into().unwrap_or_default();
+        options.hint = Some(hint.into());
+        self.find(filter, options).await
+    }
+
+    /// Starts a new [`ChangeStream`](change_stream/struct.ChangeStream.html) that receives events
+    /// for all changes in this collection. The events can be filtered or transformed by
+    /// `pipeline`, which is appended to the `$changeStream` stage that opens the stream.
+    ///
+    /// Change streams require either a replica set or a sharded cluster.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/changeStreams/) for more
+    /// information on change streams.
+    pub async fn watch(
+        &self,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<ChangeStreamOptions>>,
+    ) -> Result<ChangeStream<ChangeStreamEvent<T>>> {
+        let mut options = options.into();
+        resolve_options!(self, options, [read_concern, selection_criteria]);
+
+        ChangeStream::open(WatchArgs {
+            client: self.client().clone(),
+            target: self.namespace(),
+            pipeline: pipeline.into_iter().collect(),
+            options,
+        })
+        .await
     }
 
     /// Finds the documents in the collection matching `filter` using the provided `ClientSession`.
@@ -588,7 +956,16 @@ where
         options: impl Into<Option<FindOptions>>,
         session: &mut ClientSession,
     ) -> Result<SessionCursor<T>> {
-        let mut options = options.into();
+        let mut options: Option<FindOptions> = options.into();
+        // The batches that the server streams to an exhaust cursor are read in the background,
+        // outside of the transaction's sequence of operations.
+        if session.in_transaction() && options.as_ref().and_then(|opts| opts.exhaust) == Some(true)
+        {
+            return Err(ErrorKind::Transaction {
+                message: "exhaust cursors cannot be used in a transaction".into(),
+            }
+            .into());
+        }
         resolve_read_concern_with_session!(self, options, Some(&mut *session))?;
         resolve_selection_criteria_with_session!(self, options, Some(&mut *session))?;
 
@@ -606,13 +983,82 @@ where
         &self,
         filter: impl Into<Option<Document>>,
         options: impl Into<Option<FindOneOptions>>,
+    ) -> Result<Option<T>> {
+        self.find_one_with_outcome(filter, options)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Finds a single document in the collection matching `filter`, using the index specified by
+    /// `hint`. Any hint already set in `options` is overridden.
+    pub async fn find_one_with_hint(
+        &self,
+        filter: impl Into<Option<Document>>,
+        hint: impl Into<Hint>,
+        options: impl Into<Option<FindOneOptions>>,
+    ) -> Result<Option<T>> {
+        let mut options = options.into().unwrap_or_default();
+        options.hint = Some(hint.into());
+        self.find_one(filter, options).await
+    }
+
+    /// Finds a single document in the collection matching `filter`, sharing a single server round
+    /// trip with any identical calls that are already in progress.
+    ///
+    /// Calls are identical if they target the same namespace with the same filter and options,
+    /// including the read concern and selection criteria inherited from the collection. When a
+    /// call finds an identical one in progress, it waits for that call's result (or error)
+    /// rather than sending its own query. Once the query completes, the next call sends a new
+    /// one. This can be used to avoid storms of identical queries, e.g. when many tasks miss
+    /// a cache for the same key at once.
+    ///
+    /// Calls with a predicate as their selection criteria are never coalesced. Coalescing doesn't
+    /// apply to sessions, so there is no `_with_session` variant of this method.
+    pub async fn find_one_coalesced(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<FindOneOptions>>,
     ) -> Result<Option<T>> {
         let mut options = options.into();
         resolve_options!(self, options, [read_concern, selection_criteria]);
+        let filter = filter.into();
+
+        let key = match FindOneKey::new(&self.namespace(), filter.as_ref(), options.as_ref())? {
+            Some(key) => key,
+            None => return self.find_one(filter, options).await,
+        };
+
+        let coll = self.clone_with_type::<Document>();
+        let result = self
+            .client()
+            .find_one_coalescer()
+            .run(key, async move { coll.find_one(filter, options).await })
+            .await?;
+
+        result
+            .map(|doc| bson::from_document(doc).map_err(Into::into))
+            .transpose()
+    }
+
+    /// Finds a single document in the collection matching `filter`, also returning an
+    /// [`OperationOutcome`](results/struct.OperationOutcome.html) describing how the find was
+    /// executed, including whether it was retried.
+    pub async fn find_one_with_outcome(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<FindOneOptions>>,
+    ) -> Result<(Option<T>, OperationOutcome)> {
+        let mut options = options.into();
+        resolve_options!(self, options, [read_concern, selection_criteria]);
 
         let options: FindOptions = options.map(Into::into).unwrap_or_else(Default::default);
-        let mut cursor = self.find(filter, Some(options)).await?;
-        cursor.next().await.transpose()
+        let find = Find::new(self.namespace(), filter.into(), Some(options));
+        let client = self.client();
+
+        let (spec, session, outcome) = client.execute_cursor_operation_with_outcome(find).await?;
+        let mut cursor: Cursor<T> = Cursor::new(client.clone(), spec, session);
+        let result = cursor.next().await.transpose()?;
+        Ok((result, outcome))
     }
 
     /// Finds a single document in the collection matching `filter` using the provided
@@ -634,6 +1080,38 @@ where
         let mut cursor = cursor.stream(session);
         cursor.next().await.transpose()
     }
+
+    /// Selects `size` documents from the collection at random by running an aggregation with a
+    /// single `$sample` stage.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/reference/operator/aggregation/sample/)
+    /// for more information on `$sample`.
+    pub async fn sample(
+        &self,
+        size: u32,
+        options: impl Into<Option<AggregateOptions>>,
+    ) -> Result<Cursor<T>> {
+        let mut options = options.into();
+        resolve_options!(self, options, [read_concern, selection_criteria]);
+
+        let pipeline = vec![doc! { "$sample": { "size": i64::from(size) } }];
+        let aggregate = Aggregate::new(self.namespace(), pipeline, options);
+        let client = self.client();
+        client
+            .execute_cursor_operation(aggregate)
+            .await
+            .map(|(spec, session)| Cursor::new(client.clone(), spec, session))
+    }
+
+    /// Selects a single document from the collection at random, or returns `None` if the
+    /// collection is empty. See [`Collection::sample`](#method.sample) for more details.
+    pub async fn random_document(
+        &self,
+        options: impl Into<Option<AggregateOptions>>,
+    ) -> Result<Option<T>> {
+        let mut cursor = self.sample(1, options).await?;
+        cursor.next().await.transpose()
+    }
 }
 
 impl<T> Collection<T>
@@ -645,7 +1123,7 @@ where
         filter: Document,
         options: impl Into<Option<FindOneAndDeleteOptions>>,
         session: impl Into<Option<&mut ClientSession>>,
-    ) -> Result<Option<T>> {
+    ) -> Result<FindOneAndModifyResult<T>> {
         let session = session.into();
 
         let mut options = options.into();
@@ -665,7 +1143,7 @@ where
         &self,
         filter: Document,
         options: impl Into<Option<FindOneAndDeleteOptions>>,
-    ) -> Result<Option<T>> {
+    ) -> Result<FindOneAndModifyResult<T>> {
         self.find_one_and_delete_common(filter, options, None).await
     }
 
@@ -681,7 +1159,7 @@ where
         filter: Document,
         options: impl Into<Option<FindOneAndDeleteOptions>>,
         session: &mut ClientSession,
-    ) -> Result<Option<T>> {
+    ) -> Result<FindOneAndModifyResult<T>> {
         self.find_one_and_delete_common(filter, options, session)
             .await
     }
@@ -692,7 +1170,7 @@ where
         update: impl Into<UpdateModifications>,
         options: impl Into<Option<FindOneAndUpdateOptions>>,
         session: impl Into<Option<&mut ClientSession>>,
-    ) -> Result<Option<T>> {
+    ) -> Result<FindOneAndModifyResult<T>> {
         let update = update.into();
 
         let session = session.into();
@@ -718,7 +1196,7 @@ where
         filter: Document,
         update: impl Into<UpdateModifications>,
         options: impl Into<Option<FindOneAndUpdateOptions>>,
-    ) -> Result<Option<T>> {
+    ) -> Result<FindOneAndModifyResult<T>> {
         self.find_one_and_update_common(filter, update, options, None)
             .await
     }
@@ -738,7 +1216,7 @@ where
         update: impl Into<UpdateModifications>,
         options: impl Into<Option<FindOneAndUpdateOptions>>,
         session: &mut ClientSession,
-    ) -> Result<Option<T>> {
+    ) -> Result<FindOneAndModifyResult<T>> {
         self.find_one_and_update_common(filter, update, options, session)
             .await
     }
@@ -755,7 +1233,7 @@ where
         options: impl Into<Option<FindOneAndReplaceOptions>>,
         session: impl Into<Option<&mut ClientSession>>,
         // isabeltodo decide whether to split this out
-    ) -> Result<Option<T>> {
+    ) -> Result<FindOneAndModifyResult<T>> {
         let replacement = to_document(replacement.borrow())?;
 
         let session = session.into();
@@ -779,7 +1257,7 @@ where
         filter: Document,
         replacement: impl Borrow<T>,
         options: impl Into<Option<FindOneAndReplaceOptions>>,
-    ) -> Result<Option<T>> {
+    ) -> Result<FindOneAndModifyResult<T>> {
         self.find_one_and_replace_common(filter, replacement, options, None)
             .await
     }
@@ -797,7 +1275,7 @@ where
         replacement: impl Borrow<T>,
         options: impl Into<Option<FindOneAndReplaceOptions>>,
         session: &mut ClientSession,
-    ) -> Result<Option<T>> {
+    ) -> Result<FindOneAndModifyResult<T>> {
         self.find_one_and_replace_common(filter, replacement, options, session)
             .await
     }
@@ -812,7 +1290,8 @@ where
         docs: impl IntoIterator<Item = impl Borrow<T>>,
         options: impl Into<Option<InsertManyOptions>>,
         mut session: Option<&mut ClientSession>,
-    ) -> Result<InsertManyResult> {
+    ) -> Result<(InsertManyResult, OperationOutcome)> {
+        let start_time = Instant::now();
         let ds: Vec<_> = docs.into_iter().collect();
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
@@ -825,10 +1304,18 @@ where
         }
 
         let ordered = options.as_ref().and_then(|o| o.ordered).unwrap_or(true);
+        let acknowledged = options
+            .as_ref()
+            .and_then(|o| o.write_concern.as_ref())
+            .map(WriteConcern::is_acknowledged)
+            .unwrap_or(true);
 
         let mut cumulative_failure: Option<BulkWriteFailure> = None;
         let mut error_labels: HashSet<String> = Default::default();
-        let mut cumulative_result: Option<InsertManyResult> = None;
+        // The ids of all successfully inserted documents, keyed by their index in `ds` rather than
+        // their index within the batch they were sent in.
+        let mut inserted_ids: HashMap<usize, Bson> = HashMap::new();
+        let mut outcome: Option<OperationOutcome> = None;
 
         let mut n_attempted = 0;
 
@@ -838,20 +1325,27 @@ where
 
             match self
                 .client()
-                .execute_operation(insert, session.as_deref_mut())
+                .execute_operation_with_outcome(insert, session.as_deref_mut())
                 .await
             {
-                Ok(result) => {
+                Ok((result, batch_outcome)) => {
                     let current_batch_size = result.inserted_ids.len();
 
-                    let cumulative_result =
-                        cumulative_result.get_or_insert_with(InsertManyResult::new);
                     for (index, id) in result.inserted_ids {
-                        cumulative_result
-                            .inserted_ids
-                            .insert(index + n_attempted, id);
+                        inserted_ids.insert(index + n_attempted, id);
                     }
 
+                    // The outcome describes the last batch, but keeps the highest number of
+                    // attempts any batch needed so that retries of earlier batches aren't hidden.
+                    let attempts = outcome
+                        .as_ref()
+                        .map_or(0, |outcome| outcome.attempts)
+                        .max(batch_outcome.attempts);
+                    outcome = Some(OperationOutcome {
+                        attempts,
+                        ..batch_outcome
+                    });
+
                     n_attempted += current_batch_size;
                 }
                 Err(e) => match *e.kind {
@@ -862,6 +1356,10 @@ where
                         let current_batch_size = bw.inserted_ids.len()
                             + bw.write_errors.as_ref().map(|we| we.len()).unwrap_or(0);
 
+                        for (index, id) in bw.inserted_ids {
+                            inserted_ids.insert(index + n_attempted, id);
+                        }
+
                         let failure_ref =
                             cumulative_failure.get_or_insert_with(BulkWriteFailure::new);
                         if let Some(write_errors) = bw.write_errors {
@@ -882,13 +1380,7 @@ where
                         error_labels.extend(e.labels);
 
                         if ordered {
-                            // this will always be true since we invoked get_or_insert_with above.
-                            if let Some(failure) = cumulative_failure {
-                                return Err(Error {
-                                    kind: Box::new(ErrorKind::BulkWrite(failure)),
-                                    labels: error_labels,
-                                });
-                            }
+                            break;
                         }
                         n_attempted += current_batch_size;
                     }
@@ -898,11 +1390,24 @@ where
         }
 
         match cumulative_failure {
-            Some(failure) => Err(Error::new(
-                ErrorKind::BulkWrite(failure),
-                Some(error_labels),
-            )),
-            None => Ok(cumulative_result.unwrap_or_else(InsertManyResult::new)),
+            Some(mut failure) => {
+                failure.inserted_ids = inserted_ids;
+                Err(Error::new(
+                    ErrorKind::BulkWrite(failure),
+                    Some(error_labels),
+                ))
+            }
+            None => {
+                // Every batch succeeded if there was no failure, so there's always an outcome.
+                let mut outcome = outcome.ok_or_else(|| ErrorKind::Internal {
+                    message: "insert_many completed without executing any batches".to_string(),
+                })?;
+                outcome.duration = start_time.elapsed();
+
+                let mut result = InsertManyResult::new(acknowledged);
+                result.inserted_ids = inserted_ids;
+                Ok((result, outcome))
+            }
         }
     }
 
@@ -917,6 +1422,23 @@ where
         docs: impl IntoIterator<Item = impl Borrow<T>>,
         options: impl Into<Option<InsertManyOptions>>,
     ) -> Result<InsertManyResult> {
+        self.insert_many_common(docs, options, None)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Inserts the data in `docs` into the collection, also returning an
+    /// [`OperationOutcome`](results/struct.OperationOutcome.html) describing how the insert was
+    /// executed.
+    ///
+    /// If the documents are split into multiple batches, the outcome's `server_address` and
+    /// `connection_age` describe the last batch, `attempts` is the highest number of attempts
+    /// that any batch needed, and `duration` covers all of the batches.
+    pub async fn insert_many_with_outcome(
+        &self,
+        docs: impl IntoIterator<Item = impl Borrow<T>>,
+        options: impl Into<Option<InsertManyOptions>>,
+    ) -> Result<(InsertManyResult, OperationOutcome)> {
         self.insert_many_common(docs, options, None).await
     }
 
@@ -932,7 +1454,9 @@ where
         options: impl Into<Option<InsertManyOptions>>,
         session: &mut ClientSession,
     ) -> Result<InsertManyResult> {
-        self.insert_many_common(docs, options, Some(session)).await
+        self.insert_many_common(docs, options, Some(session))
+            .await
+            .map(|(result, _)| result)
     }
 
     async fn insert_one_common(
@@ -940,7 +1464,7 @@ where
         doc: &T,
         options: impl Into<Option<InsertOneOptions>>,
         session: impl Into<Option<&mut ClientSession>>,
-    ) -> Result<InsertOneResult> {
+    ) -> Result<(InsertOneResult, OperationOutcome)> {
         let session = session.into();
 
         let mut options = options.into();
@@ -952,9 +1476,9 @@ where
             options.map(InsertManyOptions::from_insert_one_options),
         );
         self.client()
-            .execute_operation(insert, session)
+            .execute_operation_with_outcome(insert, session)
             .await
-            .map(InsertOneResult::from_insert_many_result)
+            .map(|(result, outcome)| (InsertOneResult::from_insert_many_result(result), outcome))
             .map_err(convert_bulk_errors)
     }
 
@@ -969,6 +1493,19 @@ where
         doc: impl Borrow<T>,
         options: impl Into<Option<InsertOneOptions>>,
     ) -> Result<InsertOneResult> {
+        self.insert_one_common(doc.borrow(), options, None)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Inserts `doc` into the collection, also returning an
+    /// [`OperationOutcome`](results/struct.OperationOutcome.html) describing how the insert was
+    /// executed, including whether it was retried.
+    pub async fn insert_one_with_outcome(
+        &self,
+        doc: impl Borrow<T>,
+        options: impl Into<Option<InsertOneOptions>>,
+    ) -> Result<(InsertOneResult, OperationOutcome)> {
         self.insert_one_common(doc.borrow(), options, None).await
     }
 
@@ -984,7 +1521,9 @@ where
         options: impl Into<Option<InsertOneOptions>>,
         session: &mut ClientSession,
     ) -> Result<InsertOneResult> {
-        self.insert_one_common(doc.borrow(), options, session).await
+        self.insert_one_common(doc.borrow(), options, session)
+            .await
+            .map(|(result, _)| result)
     }
 
     async fn replace_one_common(
@@ -993,7 +1532,7 @@ where
         replacement: impl Borrow<T>,
         options: impl Into<Option<ReplaceOptions>>,
         session: impl Into<Option<&mut ClientSession>>,
-    ) -> Result<UpdateResult> {
+    ) -> Result<(UpdateResult, OperationOutcome)> {
         let replacement = to_document(replacement.borrow())?;
 
         bson_util::replacement_document_check(&replacement)?;
@@ -1003,14 +1542,10 @@ where
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
 
-        let update = Update::new(
-            self.namespace(),
-            query,
-            UpdateModifications::Document(replacement),
-            false,
-            options.map(UpdateOptions::from_replace_options),
-        );
-        self.client().execute_operation(update, session).await
+        let update = Update::replace(self.namespace(), query, replacement, options);
+        self.client()
+            .execute_operation_with_outcome(update, session)
+            .await
     }
 
     /// Replaces up to one document matching `query` in the collection with `replacement`.
@@ -1025,6 +1560,20 @@ where
         replacement: impl Borrow<T>,
         options: impl Into<Option<ReplaceOptions>>,
     ) -> Result<UpdateResult> {
+        self.replace_one_common(query, replacement, options, None)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Replaces up to one document matching `query` in the collection with `replacement`, also
+    /// returning an [`OperationOutcome`](results/struct.OperationOutcome.html) describing how the
+    /// replacement was executed, including whether it was retried.
+    pub async fn replace_one_with_outcome(
+        &self,
+        query: Document,
+        replacement: impl Borrow<T>,
+        options: impl Into<Option<ReplaceOptions>>,
+    ) -> Result<(UpdateResult, OperationOutcome)> {
         self.replace_one_common(query, replacement, options, None)
             .await
     }
@@ -1045,9 +1594,34 @@ where
     ) -> Result<UpdateResult> {
         self.replace_one_common(query, replacement, options, session)
             .await
+            .map(|(result, _)| result)
     }
 }
 
+/// Appends a `$merge` stage writing to `target` to `pipeline`, returning the resulting pipeline
+/// along with the options to run it with.
+fn merge_pipeline(
+    target: &Namespace,
+    pipeline: impl IntoIterator<Item = Document>,
+    options: Option<MergeOptions>,
+) -> Result<(Vec<Document>, AggregateOptions)> {
+    let mut pipeline: Vec<Document> = pipeline.into_iter().collect();
+    if pipeline
+        .iter()
+        .any(|stage| matches!(bson_util::first_key(stage), Some("$out") | Some("$merge")))
+    {
+        return Err(ErrorKind::InvalidArgument {
+            message: "pipeline passed to aggregate_to must not contain a $out or $merge stage"
+                .to_string(),
+        }
+        .into());
+    }
+
+    let options = options.unwrap_or_default();
+    pipeline.push(options.to_stage(target));
+    Ok((pipeline, options.to_aggregate_options()))
+}
+
 /// A struct modeling the canonical name for a collection in MongoDB.
 #[derive(Debug, Clone)]
 pub struct Namespace {
@@ -1068,6 +1642,20 @@ impl Namespace {
     }
 }
 
+/// The names of the state collections of a collection using queryable encryption, which are
+/// either set in its `encryptedFields` configuration or derived from its name.
+fn state_collection_names(coll: &str, encrypted_fields: &Document) -> Vec<String> {
+    ["esc", "ecc", "ecoc"]
+        .iter()
+        .map(|suffix| {
+            encrypted_fields
+                .get_str(format!("{}Collection", suffix))
+                .map(String::from)
+                .unwrap_or_else(|_| format!("enxcol_.{}.{}", coll, suffix))
+        })
+        .collect()
+}
+
 impl fmt::Display for Namespace {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         write!(fmt, "{}.{}", self.db, self.coll)
@@ -7,6 +7,7 @@ use typed_builder::TypedBuilder;
 use crate::{
     bson::{doc, Bson, Document},
     bson_util::{
+        self,
         deserialize_duration_from_u64_millis,
         serialize_batch_size,
         serialize_duration_as_int_millis,
@@ -15,7 +16,8 @@ use crate::{
     },
     concern::{ReadConcern, WriteConcern},
     options::Collation,
-    selection_criteria::SelectionCriteria,
+    selection_criteria::{ReadPreference, SelectionCriteria},
+    Namespace,
 };
 
 /// These are the valid options for creating a [`Collection`](../struct.Collection.html) with
@@ -82,6 +84,24 @@ impl Hint {
     }
 }
 
+impl From<Document> for Hint {
+    fn from(keys: Document) -> Self {
+        Hint::Keys(keys)
+    }
+}
+
+impl From<String> for Hint {
+    fn from(name: String) -> Self {
+        Hint::Name(name)
+    }
+}
+
+impl From<&str> for Hint {
+    fn from(name: &str) -> Self {
+        Hint::Name(name.to_string())
+    }
+}
+
 /// Specifies the type of cursor to return from a find operation.
 #[derive(Debug, Clone, Copy)]
 #[non_exhaustive]
@@ -256,6 +276,12 @@ pub struct ReplaceOptions {
     /// [documentation](https://docs.mongodb.com/manual/reference/command/update/#ex-update-command-hint) for examples.
     pub hint: Option<Hint>,
 
+    /// Determines which document is replaced if the filter matches more than one. The first
+    /// document in this sort order is replaced.
+    ///
+    /// Only available in MongoDB 8.0+.
+    pub sort: Option<Document>,
+
     /// The write concern for the operation.
     pub write_concern: Option<WriteConcern>,
 }
@@ -337,7 +363,8 @@ pub struct FindOneAndReplaceOptions {
     /// Limits the fields of the document being returned.
     pub projection: Option<Document>,
 
-    /// Whether the operation should return the document before or after modification.
+    /// Whether the operation should return the document before or after modification. Defaults
+    /// to [`ReturnDocument::Before`] if not specified.
     pub return_document: Option<ReturnDocument>,
 
     /// The order of the documents for the purposes of the operation.
@@ -386,7 +413,8 @@ pub struct FindOneAndUpdateOptions {
     /// Limits the fields of the document being returned.
     pub projection: Option<Document>,
 
-    /// Whether the operation should return the document before or after modification.
+    /// Whether the operation should return the document before or after modification. Defaults
+    /// to [`ReturnDocument::Before`] if not specified.
     pub return_document: Option<ReturnDocument>,
 
     /// The order of the documents for the purposes of the operation.
@@ -446,6 +474,14 @@ pub struct AggregateOptions {
     /// The index to use for the operation.
     pub hint: Option<Hint>,
 
+    /// Variables that can be accessed within the pipeline stages using the `$$` prefix, e.g.
+    /// `$$varName`.
+    ///
+    /// This option is only supported on server versions 5.0 and above, and is omitted from the
+    /// command when running against older servers.
+    #[serde(rename = "let")]
+    pub let_vars: Option<Document>,
+
     /// The maximum amount of time for the server to wait on new documents to satisfy a tailable
     /// await cursor query.
     ///
@@ -492,6 +528,167 @@ pub struct AggregateOptions {
     pub write_concern: Option<WriteConcern>,
 }
 
+/// Specifies how a `$merge` stage should handle a result document that matches an existing
+/// document in the target collection.
+///
+/// See the [documentation](https://docs.mongodb.com/manual/reference/operator/aggregation/merge/)
+/// for more information.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum MergeWhenMatched {
+    /// Replace the existing document with the result document.
+    Replace,
+
+    /// Keep the existing document.
+    KeepExisting,
+
+    /// Merge the result document into the existing document.
+    Merge,
+
+    /// Stop the aggregation and fail. Writes which occurred before the failure are not rolled
+    /// back.
+    Fail,
+
+    /// Update the existing document using the provided update pipeline.
+    Pipeline(Vec<Document>),
+}
+
+impl MergeWhenMatched {
+    fn to_bson(&self) -> Bson {
+        match self {
+            Self::Replace => "replace".into(),
+            Self::KeepExisting => "keepExisting".into(),
+            Self::Merge => "merge".into(),
+            Self::Fail => "fail".into(),
+            Self::Pipeline(pipeline) => bson_util::to_bson_array(pipeline),
+        }
+    }
+}
+
+/// Specifies how a `$merge` stage should handle a result document that does not match an existing
+/// document in the target collection.
+///
+/// See the [documentation](https://docs.mongodb.com/manual/reference/operator/aggregation/merge/)
+/// for more information.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum MergeWhenNotMatched {
+    /// Insert the result document into the target collection.
+    Insert,
+
+    /// Discard the result document.
+    Discard,
+
+    /// Stop the aggregation and fail. Writes which occurred before the failure are not rolled
+    /// back.
+    Fail,
+}
+
+impl MergeWhenNotMatched {
+    fn to_bson(&self) -> Bson {
+        match self {
+            Self::Insert => "insert".into(),
+            Self::Discard => "discard".into(),
+            Self::Fail => "fail".into(),
+        }
+    }
+}
+
+/// Specifies the options to a
+/// [`Collection::aggregate_to`](../struct.Collection.html#method.aggregate_to) operation.
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct MergeOptions {
+    /// The field or fields that act as a unique identifier for documents in the target collection.
+    /// If not specified, `_id` is used.
+    pub on: Option<Vec<String>>,
+
+    /// Variables that can be referenced by a `MergeWhenMatched::Pipeline` update pipeline.
+    pub let_vars: Option<Document>,
+
+    /// How to handle a result document that matches an existing document in the target
+    /// collection. If not specified, the server will merge the two documents.
+    pub when_matched: Option<MergeWhenMatched>,
+
+    /// How to handle a result document that does not match an existing document in the target
+    /// collection. If not specified, the server will insert the document.
+    pub when_not_matched: Option<MergeWhenNotMatched>,
+
+    /// Enables writing to temporary files. When set to true, aggregation stages can write data to
+    /// the _tmp subdirectory in the dbPath directory.
+    pub allow_disk_use: Option<bool>,
+
+    /// Opt out of document-level validation.
+    pub bypass_document_validation: Option<bool>,
+
+    /// The collation to use for the operation.
+    ///
+    /// See the [documentation](https://docs.mongodb.com/manual/reference/collation/) for more
+    /// information on how to use this option.
+    pub collation: Option<Collation>,
+
+    /// Tags the query with an arbitrary string to help trace the operation through the database
+    /// profiler, currentOp and logs.
+    pub comment: Option<String>,
+
+    /// The maximum amount of time to allow the query to run.
+    ///
+    /// This options maps to the `maxTimeMS` MongoDB query option, so the duration will be sent
+    /// across the wire as an integer number of milliseconds.
+    pub max_time: Option<Duration>,
+
+    /// The read concern to use for the operation.
+    ///
+    /// If none is specified, the read concern defined on the object executing this operation will
+    /// be used.
+    pub read_concern: Option<ReadConcern>,
+
+    /// The write concern to use for the operation.
+    ///
+    /// If none is specified, the write concern defined on the object executing this operation will
+    /// be used.
+    pub write_concern: Option<WriteConcern>,
+}
+
+impl MergeOptions {
+    /// Builds the `$merge` stage that writes to `target`.
+    pub(crate) fn to_stage(&self, target: &Namespace) -> Document {
+        let mut stage = doc! {
+            "into": { "db": target.db.as_str(), "coll": target.coll.as_str() },
+        };
+        if let Some(ref on) = self.on {
+            stage.insert("on", on.clone());
+        }
+        if let Some(ref let_vars) = self.let_vars {
+            stage.insert("let", let_vars.clone());
+        }
+        if let Some(ref when_matched) = self.when_matched {
+            stage.insert("whenMatched", when_matched.to_bson());
+        }
+        if let Some(ref when_not_matched) = self.when_not_matched {
+            stage.insert("whenNotMatched", when_not_matched.to_bson());
+        }
+        doc! { "$merge": stage }
+    }
+
+    /// Builds the options for the aggregate command. The aggregation is always routed to the
+    /// primary, since `$merge` performs writes.
+    pub(crate) fn to_aggregate_options(&self) -> AggregateOptions {
+        AggregateOptions {
+            allow_disk_use: self.allow_disk_use,
+            bypass_document_validation: self.bypass_document_validation,
+            collation: self.collation.clone(),
+            comment: self.comment.clone(),
+            max_time: self.max_time,
+            read_concern: self.read_concern.clone(),
+            selection_criteria: Some(SelectionCriteria::ReadPreference(ReadPreference::Primary)),
+            write_concern: self.write_concern.clone(),
+            ..Default::default()
+        }
+    }
+}
+
 /// Specifies the options to a
 /// [`Collection::count_documents`](../struct.Collection.html#method.count_documents) operation.
 #[derive(Clone, Debug, Default, Deserialize, TypedBuilder)]
@@ -630,9 +827,25 @@ pub struct FindOptions {
     pub comment: Option<String>,
 
     /// The type of cursor to return.
+    ///
+    /// Tailable cursors cannot be combined with `limit`, or with any `sort` other than
+    /// `{ "$natural": 1 }`.
     #[serde(skip)]
     pub cursor_type: Option<CursorType>,
 
+    /// Whether the server should stream the remaining batches of results as soon as the cursor's
+    /// first getMore is sent, rather than waiting for a request for each one. This saves a round
+    /// trip per batch, at the cost of dedicating a connection to the cursor until it's exhausted
+    /// or dropped. Batches are read ahead of the cursor into a small bounded buffer, so a slow
+    /// consumer doesn't cause the results to accumulate in memory. Only supported in server
+    /// versions 4.2+; older servers are sent regular getMores instead.
+    ///
+    /// This cannot be used with a session that's in a transaction.
+    ///
+    /// The default value is `false`.
+    #[serde(skip)]
+    pub exhaust: Option<bool>,
+
     /// The index to use for the operation.
     pub hint: Option<Hint>,
 
@@ -705,6 +918,14 @@ pub struct FindOptions {
     /// See the [documentation](https://docs.mongodb.com/manual/reference/collation/) for more
     /// information on how to use this option.
     pub collation: Option<Collation>,
+
+    /// Whether invalid UTF-8 in the strings of the returned documents should be replaced with the
+    /// Unicode replacement character (U+FFFD) rather than causing the operation to fail. This
+    /// applies to every batch of results returned by the cursor.
+    ///
+    /// The default value is `false`.
+    #[serde(skip)]
+    pub utf8_lossy: Option<bool>,
 }
 
 impl From<FindOneOptions> for FindOptions {
@@ -727,10 +948,12 @@ impl From<FindOneOptions> for FindOptions {
             skip: options.skip,
             batch_size: None,
             cursor_type: None,
+            exhaust: None,
             limit: Some(-1),
             max_await_time: None,
             no_cursor_timeout: None,
             sort: options.sort,
+            utf8_lossy: options.utf8_lossy,
         }
     }
 }
@@ -818,6 +1041,13 @@ pub struct FindOneOptions {
 
     /// The order of the documents for the purposes of the operation.
     pub sort: Option<Document>,
+
+    /// Whether invalid UTF-8 in the strings of the returned document should be replaced with the
+    /// Unicode replacement character (U+FFFD) rather than causing the operation to fail.
+    ///
+    /// The default value is `false`.
+    #[serde(skip)]
+    pub utf8_lossy: Option<bool>,
 }
 
 /// Specifies the options to a [`Collection::drop`](../struct.Collection.html#method.drop)
@@ -830,4 +1060,119 @@ pub struct FindOneOptions {
 pub struct DropCollectionOptions {
     /// The write concern for the operation.
     pub write_concern: Option<WriteConcern>,
+
+    /// The `encryptedFields` configuration of a collection using queryable encryption. If this is
+    /// set, the collection's state collections are dropped along with it.
+    #[serde(skip_serializing)]
+    pub encrypted_fields: Option<Document>,
+}
+
+/// Specifies an index to create with
+/// [`Collection::create_index`](../struct.Collection.html#method.create_index).
+#[derive(Clone, Debug, Default, Deserialize, TypedBuilder, Serialize)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct IndexModel {
+    /// The fields to index, along with their sort order or index type (e.g. `1`, `-1`,
+    /// `"text"`).
+    #[serde(rename = "key")]
+    #[builder(!default)]
+    pub keys: Document,
+
+    /// The options for the index.
+    #[serde(flatten)]
+    pub options: Option<IndexOptions>,
+}
+
+impl IndexModel {
+    /// Returns the name of the index, either as specified in its options or as generated by the
+    /// server's naming convention (e.g. `"a_1_b_-1"` for the keys `{ a: 1, b: -1 }`).
+    pub(crate) fn name(&self) -> String {
+        if let Some(ref name) = self.options.as_ref().and_then(|opts| opts.name.as_ref()) {
+            return name.to_string();
+        }
+
+        self.keys
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    Bson::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                format!("{}_{}", key, value)
+            })
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+}
+
+/// Specifies the options for an index created with
+/// [`Collection::create_index`](../struct.Collection.html#method.create_index).
+///
+/// See the [documentation](https://docs.mongodb.com/manual/reference/method/db.collection.createIndex/#options-for-all-index-types)
+/// for more information on these options.
+#[serde_with::skip_serializing_none]
+#[derive(Clone, Debug, Default, Deserialize, TypedBuilder, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct IndexOptions {
+    /// The name of the index. If none is specified, one is generated from the index keys.
+    pub name: Option<String>,
+
+    /// Whether the index should reject documents with duplicate values for the indexed fields.
+    pub unique: Option<bool>,
+
+    /// Whether the index should only reference documents that contain the indexed fields.
+    pub sparse: Option<bool>,
+
+    /// Whether the index should be built in the background. Ignored by MongoDB 4.2+.
+    pub background: Option<bool>,
+
+    /// How long documents in a TTL index are kept before being removed.
+    ///
+    /// This option maps to the `expireAfterSeconds` index option, so the duration will be sent
+    /// across the wire as an integer number of seconds.
+    #[serde(
+        rename = "expireAfterSeconds",
+        serialize_with = "bson_util::serialize_duration_option_as_int_secs",
+        deserialize_with = "bson_util::deserialize_duration_from_u64_seconds"
+    )]
+    pub expire_after: Option<Duration>,
+
+    /// Restricts the index to documents that match this filter.
+    pub partial_filter_expression: Option<Document>,
+
+    /// The collation to use for the index.
+    ///
+    /// See the [documentation](https://docs.mongodb.com/manual/reference/collation/) for more
+    /// information on how to use this option.
+    pub collation: Option<Collation>,
+
+    /// Whether the index should be hidden from the query planner. Only available in MongoDB
+    /// 4.4+.
+    pub hidden: Option<bool>,
+}
+
+/// Specifies the options to a
+/// [`Collection::create_index`](../struct.Collection.html#method.create_index) operation.
+#[serde_with::skip_serializing_none]
+#[derive(Clone, Debug, Default, Deserialize, TypedBuilder, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct CreateIndexOptions {
+    /// The maximum amount of time to allow the index build to run.
+    ///
+    /// This options maps to the `maxTimeMS` MongoDB query option, so the duration will be sent
+    /// across the wire as an integer number of milliseconds.
+    #[serde(
+        serialize_with = "serialize_duration_as_int_millis",
+        rename = "maxTimeMS",
+        deserialize_with = "deserialize_duration_from_u64_millis"
+    )]
+    pub max_time: Option<Duration>,
+
+    /// The write concern for the operation.
+    pub write_concern: Option<WriteConcern>,
 }
@@ -13,6 +13,7 @@ use crate::{
     bson::{doc, serde_helpers},
     bson_util,
     error::{ErrorKind, Result},
+    selection_criteria::{ReadPreference, SelectionCriteria},
 };
 
 /// Specifies the consistency and isolation properties of read operations from replica sets and
@@ -65,6 +66,22 @@ impl ReadConcern {
         ReadConcernLevel::from_str(level.as_str()).into()
     }
 
+    /// Validates that this read concern can be used with the given selection criteria. A
+    /// linearizable read concern can only be used with a primary read preference.
+    pub(crate) fn validate(&self, criteria: Option<&SelectionCriteria>) -> Result<()> {
+        if self.level == ReadConcernLevel::Linearizable
+            && matches!(criteria, Some(SelectionCriteria::ReadPreference(read_pref)) if *read_pref != ReadPreference::Primary)
+        {
+            return Err(ErrorKind::InvalidArgument {
+                message: "linearizable read concern can only be used with a primary read \
+                          preference"
+                    .to_string(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
     #[cfg(test)]
     pub(crate) fn serialize_for_client_options<S>(
         read_concern: &Option<ReadConcern>,
@@ -255,6 +272,21 @@ impl From<String> for Acknowledgment {
 }
 
 impl WriteConcern {
+    /// Returns this write concern with its `w_timeout` set to `timeout`.
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use mongodb::options::{Acknowledgment, WriteConcern};
+    /// let write_concern = WriteConcern::builder()
+    ///     .w(Acknowledgment::Majority)
+    ///     .build()
+    ///     .with_timeout(Duration::from_secs(5));
+    /// ```
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.w_timeout = Some(timeout);
+        self
+    }
+
     #[allow(dead_code)]
     pub(crate) fn is_acknowledged(&self) -> bool {
         self.w != Some(Acknowledgment::Nodes(0)) || self.journal == Some(true)
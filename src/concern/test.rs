@@ -1,12 +1,13 @@
 use std::time::Duration;
-use tokio::sync::RwLockReadGuard;
+use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 use crate::{
     bson::{doc, Bson, Document},
-    error::ErrorKind,
+    error::{ErrorKind, WriteFailure},
     options::{
         Acknowledgment,
         AggregateOptions,
+        ClientOptions,
         CreateCollectionOptions,
         DeleteOptions,
         DropCollectionOptions,
@@ -22,7 +23,7 @@ use crate::{
         UpdateOptions,
         WriteConcern,
     },
-    test::{EventClient, TestClient, LOCK},
+    test::{EventClient, FailCommandOptions, FailPoint, FailPointMode, TestClient, LOCK},
     Collection,
 };
 
@@ -141,6 +142,87 @@ async fn inconsistent_write_concern_rejected() {
     assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn write_concern_with_timeout() {
+    let write_concern = WriteConcern::builder()
+        .w(Acknowledgment::Majority)
+        .build()
+        .with_timeout(Duration::from_millis(5000));
+    assert_eq!(
+        write_concern,
+        WriteConcern {
+            w: Some(Acknowledgment::Majority),
+            w_timeout: Some(Duration::from_millis(5000)),
+            journal: None,
+        }
+    );
+
+    let options = ClientOptions::parse_uri(
+        "mongodb://localhost:27017/?w=majority&wTimeoutMS=5000",
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(options.write_concern, Some(write_concern));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn write_concern_timeout_error() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = TestClient::new().await;
+    if !client.is_replica_set() || !client.supports_fail_command().await {
+        println!(
+            "skipping {} due to not running against a replica set with failCommand support",
+            function_name!()
+        );
+        return;
+    }
+
+    let coll: Collection<Document> = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    // Simulate the write concern not being satisfied within the timeout.
+    let options = FailCommandOptions::builder()
+        .write_concern_error(doc! {
+            "code": 64,
+            "codeName": "WriteConcernFailed",
+            "errmsg": "waiting for replication timed out",
+            "errInfo": { "wtimeout": true },
+        })
+        .build();
+    let failpoint = FailPoint::fail_command(&["insert"], FailPointMode::Times(1), options);
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let write_concern = WriteConcern::builder()
+        .w(Acknowledgment::Majority)
+        .build()
+        .with_timeout(Duration::from_millis(1));
+    let options = InsertOneOptions::builder()
+        .write_concern(write_concern)
+        .build();
+    let error = coll
+        .insert_one(doc! { "x": 1 }, options)
+        .await
+        .expect_err("insert should fail with a write concern error");
+
+    match *error.kind {
+        ErrorKind::Write(WriteFailure::WriteConcernError(ref wc_error)) => {
+            assert_eq!(wc_error.code, 64);
+            let wtimeout = wc_error
+                .details
+                .as_ref()
+                .and_then(|details| details.get_bool("wtimeout").ok());
+            assert_eq!(wtimeout, Some(true));
+        }
+        ref e => panic!("expected write concern error, got {:?}", e),
+    }
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -179,8 +261,9 @@ async fn snapshot_read_concern() {
         .database(function_name!())
         .collection::<Document>(function_name!());
 
-    // TODO RUST-122 run this test on sharded clusters
-    if client.is_replica_set() && client.server_version_gte(4, 0) {
+    if client.is_replica_set() && client.server_version_gte(4, 0)
+        || client.is_sharded() && client.server_version_gte(4, 2)
+    {
         let mut session = client.start_session(None).await.unwrap();
         let options = TransactionOptions::builder()
             .read_concern(ReadConcern::snapshot())
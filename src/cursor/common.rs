@@ -7,16 +7,34 @@ use std::{
 
 use derivative::Derivative;
 use futures_core::{Future, Stream};
+use futures_util::StreamExt;
+use tokio::sync::mpsc;
 
 use crate::{
     bson::Document,
+    bson_util,
+    cmap::{Connection, PinnedConnectionHandle},
     error::{Error, ErrorKind, Result},
+    event::command::{CursorClosedEvent, CursorClosedReason, CursorCreatedEvent},
+    operation::GetMore,
     options::ServerAddress,
     results::GetMoreResult,
     Client,
     Namespace,
+    RUNTIME,
 };
 
+/// The number of batches streamed to an exhaust cursor that can be read ahead of the consumer.
+/// Once this many are waiting, no more replies are read from the connection until the cursor
+/// catches up, and the server stops sending them once the socket's buffers are full.
+pub(super) const EXHAUST_BATCH_BUFFER_SIZE: usize = 2;
+
+/// The receiving end of the batches that the server streams to an exhaust cursor.
+pub(super) type ExhaustBatches = mpsc::Receiver<Result<GetMoreResult>>;
+
+/// A document taken from a cursor's buffer, along with its length as read from the server if known.
+type BufferedDocument = (Document, Option<usize>);
+
 /// An internal cursor that can be used in a variety of contexts depending on its `GetMoreProvider`.
 #[derive(Derivative)]
 #[derivative(Debug)]
@@ -26,29 +44,96 @@ pub(super) struct GenericCursor<T: GetMoreProvider> {
     client: Client,
     info: CursorInformation,
     buffer: VecDeque<Document>,
+
+    /// The lengths of the buffered documents as they were read from the server, in the same order
+    /// as `buffer`. The length of a document is `None` if it wasn't known when it was buffered.
+    raw_lengths: VecDeque<Option<usize>>,
+
     exhausted: bool,
+
+    /// The resume token reported with the most recent batch, if this is a change stream cursor.
+    post_batch_resume_token: Option<Document>,
+
+    /// Whether the in-flight getMore, if any, has been polled, meaning it may already have been
+    /// sent to the server.
+    get_more_in_flight: bool,
+
+    /// The batches that the server streams to an exhaust cursor, which are read from the
+    /// connection ahead of the consumer by a background task. This is set once the server starts
+    /// streaming replies to the cursor's first getMore.
+    #[derivative(Debug = "ignore")]
+    exhaust_batches: Option<ExhaustBatches>,
+
+    /// Whether the cursor is waiting for the next batch streamed by the server rather than for a
+    /// getMore from the provider.
+    awaiting_exhaust_batch: bool,
 }
 
 impl<T: GetMoreProvider> GenericCursor<T> {
-    pub(super) fn new(client: Client, spec: CursorSpecification, get_more_provider: T) -> Self {
+    pub(super) fn new(client: Client, spec: CursorSpecification, mut get_more_provider: T) -> Self {
         let exhausted = spec.id() == 0;
+        // A provider is only executing a getMore already if it was handed one that a previous
+        // cursor over the same results had sent.
+        let get_more_in_flight = get_more_provider.executing_future().is_some();
+        let raw_lengths = batch_lengths(&spec.initial_buffer, spec.initial_batch_lengths);
         Self {
             exhausted,
             client,
             provider: get_more_provider,
             buffer: spec.initial_buffer,
+            raw_lengths,
             info: spec.info,
+            post_batch_resume_token: spec.post_batch_resume_token,
+            get_more_in_flight,
+            exhaust_batches: None,
+            awaiting_exhaust_batch: false,
         }
     }
 
     pub(super) fn take_buffer(&mut self) -> VecDeque<Document> {
+        self.raw_lengths.clear();
         std::mem::take(&mut self.buffer)
     }
 
+    /// Takes the batches that the server is streaming to this cursor, if it's an exhaust cursor.
+    pub(super) fn take_exhaust_batches(&mut self) -> Option<ExhaustBatches> {
+        self.awaiting_exhaust_batch = false;
+        self.exhaust_batches.take()
+    }
+
+    /// Continues reading the batches that the server streamed to a previous cursor over the same
+    /// results.
+    pub(super) fn set_exhaust_batches(&mut self, exhaust_batches: Option<ExhaustBatches>) {
+        self.exhaust_batches = exhaust_batches;
+    }
+
+    /// Return a document to the front of the buffer so that it will be yielded again by the next
+    /// call to `poll_next`. `size` is the size that `poll_next_sized` returned for it.
+    pub(super) fn push_front(&mut self, doc: Document, size: u64) {
+        self.buffer.push_front(doc);
+        self.raw_lengths.push_front(Some(size as usize));
+    }
+
     pub(super) fn is_exhausted(&self) -> bool {
         self.exhausted
     }
 
+    /// Whether the cursor is exhausted and every buffered document has been returned.
+    pub(super) fn is_finished(&self) -> bool {
+        self.exhausted && self.buffer.is_empty()
+    }
+
+    /// Whether a getMore has been started and polled but has not yet completed. If the cursor is
+    /// dropped in this state, the batch that the server returns for the getMore will be lost
+    /// unless the provider's future is kept.
+    pub(super) fn get_more_in_flight(&self) -> bool {
+        self.get_more_in_flight
+    }
+
+    pub(super) fn provider_mut(&mut self) -> &mut T {
+        &mut self.provider
+    }
+
     pub(super) fn id(&self) -> i64 {
         self.info.id
     }
@@ -57,40 +142,172 @@ impl<T: GetMoreProvider> GenericCursor<T> {
         &self.info.ns
     }
 
+    pub(super) fn comment(&self) -> Option<&String> {
+        self.info.comment.as_ref()
+    }
+
+    pub(super) fn partial_results_returned(&self) -> bool {
+        self.info.partial_results_returned
+    }
+
+    pub(super) fn info(&self) -> &CursorInformation {
+        &self.info
+    }
+
+    pub(super) fn post_batch_resume_token(&self) -> Option<&Document> {
+        self.post_batch_resume_token.as_ref()
+    }
+
     fn start_get_more(&mut self) {
+        if self.exhaust_batches.is_some() {
+            self.awaiting_exhaust_batch = true;
+            return;
+        }
         let info = self.info.clone();
         let client = self.client.clone();
         self.provider.start_execution(info, client);
     }
-}
 
-impl<T: GetMoreProvider> Stream for GenericCursor<T> {
-    type Item = Result<Document>;
+    /// If there is a get more in flight, check on its status. Once it completes, its batch is added
+    /// to the buffer and the exhausted status is updated.
+    fn poll_get_more(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if self.awaiting_exhaust_batch {
+            return self.poll_exhaust_batch(cx);
+        }
 
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        loop {
-            // If there is a get more in flight, check on its status.
-            if let Some(future) = self.provider.executing_future() {
-                match Pin::new(future).poll(cx) {
-                    // If a result is ready, retrieve the buffer and update the exhausted status.
-                    Poll::Ready(get_more_result) => {
-                        let exhausted = get_more_result.exhausted();
-                        let (result, session) = get_more_result.into_parts();
-
-                        self.exhausted = exhausted;
-                        self.provider.clear_execution(session, exhausted);
-                        self.buffer = result?.batch;
-                    }
-                    Poll::Pending => return Poll::Pending,
+        if let Some(future) = self.provider.executing_future() {
+            match Pin::new(future).poll(cx) {
+                Poll::Ready(get_more_result) => {
+                    self.get_more_in_flight = false;
+                    let exhausted = get_more_result.exhausted();
+                    let (result, session) = get_more_result.into_parts();
+                    self.provider.clear_execution(session, exhausted);
+                    self.handle_get_more_result(result, exhausted)?;
+                }
+                Poll::Pending => {
+                    self.get_more_in_flight = true;
+                    return Poll::Pending;
+                }
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Check on the next batch streamed by the server to an exhaust cursor. If streaming stopped
+    /// because of an error, or before the cursor was exhausted, regular getMores are used to fetch
+    /// any remaining batches.
+    fn poll_exhaust_batch(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let receiver = match self.exhaust_batches {
+            Some(ref mut receiver) => receiver,
+            None => return Poll::Ready(Ok(())),
+        };
+        let next = match receiver.poll_recv(cx) {
+            Poll::Ready(next) => next,
+            Poll::Pending => return Poll::Pending,
+        };
+
+        self.awaiting_exhaust_batch = false;
+        match next {
+            Some(result) => {
+                let exhausted = get_more_exhausted(result.as_ref());
+                if result.is_err() {
+                    self.exhaust_batches = None;
                 }
+                self.handle_get_more_result(result, exhausted)?;
+            }
+            None => self.exhaust_batches = None,
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Records the outcome of a getMore and buffers its batch. If the server started streaming the
+    /// remaining batches in reply, a task is spawned to read them.
+    fn handle_get_more_result(
+        &mut self,
+        result: Result<GetMoreResult>,
+        exhausted: bool,
+    ) -> Result<()> {
+        if exhausted {
+            self.info
+                .emit_closed_event(&self.client, CursorClosedReason::Exhausted);
+            self.info.pinned_connection = None;
+        }
+        self.exhausted = exhausted;
+        let mut result = result?;
+        if let Some(connection) = result.streaming_connection.take() {
+            self.exhaust_batches = Some(spawn_exhaust_reader(connection, self.info.utf8_lossy));
+        }
+        self.post_batch_resume_token = result.post_batch_resume_token;
+        self.raw_lengths
+            .extend(batch_lengths(&result.batch, result.raw_batch_lengths));
+        self.buffer.extend(result.batch);
+        Ok(())
+    }
+
+    /// Take all of the documents currently in the buffer, running a single getMore first if the
+    /// buffer is empty and the cursor is not exhausted. If that getMore returns an empty batch
+    /// without exhausting the cursor, an empty batch is returned rather than issuing another.
+    pub(super) fn poll_next_batch(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<VecDeque<Document>>>> {
+        loop {
+            let get_more_started =
+                self.provider.executing_future().is_some() || self.awaiting_exhaust_batch;
+            match self.poll_get_more(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Poll::Pending => return Poll::Pending,
+            }
+
+            if !self.buffer.is_empty() || (get_more_started && !self.exhausted) {
+                return Poll::Ready(Some(Ok(self.take_buffer())));
+            }
+            if self.exhausted {
+                return Poll::Ready(None);
+            }
+            self.start_get_more();
+        }
+    }
+
+    /// Polls for the next document along with its size in bytes. This is the length of the
+    /// document as it was read from the server, which can differ from the size of the parsed
+    /// document if invalid UTF-8 in it was replaced.
+    pub(super) fn poll_next_sized(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<(Document, u64)>>> {
+        self.poll_next_buffered(cx).map(|next| {
+            next.map(|result| {
+                result.map(|(doc, raw_length)| {
+                    let size = match raw_length {
+                        Some(length) => length as u64,
+                        None => bson_util::doc_size_bytes(&doc),
+                    };
+                    (doc, size)
+                })
+            })
+        })
+    }
+
+    fn poll_next_buffered(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<BufferedDocument>>> {
+        loop {
+            match self.poll_get_more(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Poll::Pending => return Poll::Pending,
             }
 
             match self.buffer.pop_front() {
                 Some(doc) => {
+                    let raw_length = self.raw_lengths.pop_front().flatten();
                     if self.buffer.is_empty() && !self.exhausted {
                         self.start_get_more();
                     }
-                    return Poll::Ready(Some(Ok(doc)));
+                    return Poll::Ready(Some(Ok((doc, raw_length))));
                 }
                 None if !self.exhausted => {
                     self.start_get_more();
@@ -101,6 +318,27 @@ impl<T: GetMoreProvider> Stream for GenericCursor<T> {
     }
 }
 
+/// Pairs the lengths read from the server for a batch with its documents, falling back to unknown
+/// lengths if they weren't read or don't match up with the batch.
+fn batch_lengths(
+    batch: &VecDeque<Document>,
+    raw_lengths: Option<Vec<usize>>,
+) -> VecDeque<Option<usize>> {
+    match raw_lengths {
+        Some(lengths) if lengths.len() == batch.len() => lengths.into_iter().map(Some).collect(),
+        _ => batch.iter().map(|_| None).collect(),
+    }
+}
+
+impl<T: GetMoreProvider> Stream for GenericCursor<T> {
+    type Item = Result<Document>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_buffered(cx)
+            .map(|next| next.map(|result| result.map(|(doc, _)| doc)))
+    }
+}
+
 /// A trait implemented by objects that can provide batches of documents to a cursor via the getMore
 /// command.
 pub(super) trait GetMoreProvider: Unpin {
@@ -134,11 +372,51 @@ pub(super) trait GetMoreProviderResult {
 
     /// Whether the response from the server indicated the cursor was exhausted or not.
     fn exhausted(&self) -> bool {
-        match self.as_ref() {
-            Ok(res) => res.exhausted,
-            Err(e) => {
-                matches!(*e.kind, ErrorKind::Command(ref e) if e.code == 43 || e.code == 237)
-            }
+        get_more_exhausted(self.as_ref())
+    }
+}
+
+/// Whether the result of a getMore indicates that the cursor is exhausted, either because the
+/// server said so or because the cursor no longer exists on the server.
+fn get_more_exhausted(result: std::result::Result<&GetMoreResult, &Error>) -> bool {
+    match result {
+        Ok(res) => res.exhausted,
+        Err(e) => matches!(*e.kind, ErrorKind::Command(ref e) if e.code == 43 || e.code == 237),
+    }
+}
+
+/// Spawns a task that reads the batches that the server streams on `connection` in reply to an
+/// exhaust getMore, returning the receiver that they're forwarded to.
+fn spawn_exhaust_reader(connection: Connection, utf8_lossy: bool) -> ExhaustBatches {
+    let batches = futures_util::stream::unfold(connection, move |mut connection| async move {
+        if !connection.is_streaming() {
+            return None;
+        }
+        let result = async {
+            let response = connection.receive_message(utf8_lossy).await?;
+            response.validate()?;
+            GetMore::parse_response(response)
+        }
+        .await;
+        Some((result, connection))
+    });
+
+    let (sender, receiver) = mpsc::channel(EXHAUST_BATCH_BUFFER_SIZE);
+    RUNTIME.execute(forward_batches(batches, sender));
+    receiver
+}
+
+/// Forwards `batches` to `sender` until the cursor is exhausted, an error occurs or the receiver
+/// is dropped. Because the channel is bounded, no further batches are read while it's full.
+pub(super) async fn forward_batches(
+    batches: impl Stream<Item = Result<GetMoreResult>>,
+    sender: mpsc::Sender<Result<GetMoreResult>>,
+) {
+    futures_util::pin_mut!(batches);
+    while let Some(result) = batches.next().await {
+        let done = result.as_ref().map_or(true, |result| result.exhausted);
+        if sender.send(result).await.is_err() || done {
+            return;
         }
     }
 }
@@ -148,6 +426,13 @@ pub(super) trait GetMoreProviderResult {
 pub(crate) struct CursorSpecification {
     pub(crate) info: CursorInformation,
     pub(crate) initial_buffer: VecDeque<Document>,
+
+    /// The lengths of the documents in the initial buffer as they were read from the server, if
+    /// known.
+    pub(crate) initial_batch_lengths: Option<Vec<usize>>,
+
+    /// The resume token reported with the initial batch, if this is a change stream cursor.
+    pub(crate) post_batch_resume_token: Option<Document>,
 }
 
 impl CursorSpecification {
@@ -157,6 +442,7 @@ impl CursorSpecification {
         id: i64,
         batch_size: impl Into<Option<u32>>,
         max_time: impl Into<Option<Duration>>,
+        comment: impl Into<Option<String>>,
         initial_buffer: VecDeque<Document>,
     ) -> Self {
         Self {
@@ -166,8 +452,16 @@ impl CursorSpecification {
                 address,
                 batch_size: batch_size.into(),
                 max_time: max_time.into(),
+                comment: comment.into(),
+                utf8_lossy: false,
+                partial_results_returned: false,
+                operation_name: "",
+                pinned_connection: None,
+                exhaust: false,
             },
             initial_buffer,
+            initial_batch_lengths: None,
+            post_batch_resume_token: None,
         }
     }
 
@@ -199,4 +493,60 @@ pub(crate) struct CursorInformation {
     pub(crate) id: i64,
     pub(crate) batch_size: Option<u32>,
     pub(crate) max_time: Option<Duration>,
+
+    /// The comment from the command that created this cursor, which is attached to subsequent
+    /// getMore and killCursors commands. This is only set if the server supports comments on those
+    /// commands (i.e. 4.4+).
+    pub(crate) comment: Option<String>,
+
+    /// Whether invalid UTF-8 in the results of subsequent getMore commands should be replaced
+    /// rather than causing an error.
+    pub(crate) utf8_lossy: bool,
+
+    /// Whether the server reported in the initial response that some shards were unavailable and
+    /// the results are therefore incomplete. Only set when `allowPartialResults` was specified.
+    pub(crate) partial_results_returned: bool,
+
+    /// The name of the command that created this cursor, reported in cursor events. Set by the
+    /// operation that creates the cursor.
+    pub(crate) operation_name: &'static str,
+
+    /// The connection that the cursor's getMore and killCursors commands are sent on. This is
+    /// only set when connected through a load balancer, which could otherwise route them to a
+    /// different backend server than the one holding the cursor.
+    pub(crate) pinned_connection: Option<PinnedConnectionHandle>,
+
+    /// Whether the cursor's getMores allow the server to stream the remaining batches without
+    /// waiting for further requests. Set by `find` when
+    /// [`FindOptions::exhaust`](crate::options::FindOptions::exhaust) is specified.
+    pub(crate) exhaust: bool,
+}
+
+impl CursorInformation {
+    /// Notifies the client's command event handler that this cursor was opened on the server.
+    /// Cursors that were already exhausted by their initial reply are not reported.
+    pub(super) fn emit_created_event(&self, client: &Client) {
+        if self.id == 0 {
+            return;
+        }
+
+        client.emit_command_event(|handler| {
+            handler.handle_cursor_created_event(CursorCreatedEvent {
+                cursor_id: self.id,
+                namespace: self.ns.clone(),
+                operation_name: self.operation_name.to_string(),
+            });
+        });
+    }
+
+    /// Notifies the client's command event handler that this cursor was closed.
+    pub(super) fn emit_closed_event(&self, client: &Client, reason: CursorClosedReason) {
+        client.emit_command_event(|handler| {
+            handler.handle_cursor_closed_event(CursorClosedEvent {
+                cursor_id: self.id,
+                namespace: self.ns.clone(),
+                reason,
+            });
+        });
+    }
 }
@@ -1,19 +1,24 @@
 mod common;
 pub(crate) mod session;
+#[cfg(test)]
+mod test;
 
 use std::{
+    collections::VecDeque,
     pin::Pin,
     task::{Context, Poll},
 };
 
 use futures_core::{future::BoxFuture, Stream};
+use futures_util::TryStreamExt;
 use serde::de::DeserializeOwned;
 
 use crate::{
     bson::{from_document, Document},
     error::{Error, Result},
+    event::command::CursorClosedReason,
     operation::GetMore,
-    results::GetMoreResult,
+    results::{CollectResult, GetMoreResult},
     Client,
     ClientSession,
     RUNTIME,
@@ -81,16 +86,19 @@ use common::{GenericCursor, GetMoreProvider, GetMoreProviderResult};
 #[derive(Debug)]
 pub struct Cursor<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     client: Client,
     wrapped_cursor: ImplicitSessionCursor,
-    _phantom: std::marker::PhantomData<T>,
+    consumed: u64,
+    // No `T` is ever stored, so use a function pointer marker to keep the cursor `Unpin`
+    // regardless of whether `T` is.
+    _phantom: std::marker::PhantomData<fn() -> T>,
 }
 
 impl<T> Cursor<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     pub(crate) fn new(
         client: Client,
@@ -98,27 +106,260 @@ where
         session: Option<ClientSession>,
     ) -> Self {
         let provider = ImplicitSessionGetMoreProvider::new(&spec, session);
+        spec.info.emit_created_event(&client);
 
         Self {
             client: client.clone(),
             wrapped_cursor: ImplicitSessionCursor::new(client, spec, provider),
+            consumed: 0,
             _phantom: Default::default(),
         }
     }
+
+    /// Takes the documents that are currently buffered without running a getMore.
+    pub(crate) fn take_buffer(&mut self) -> VecDeque<Document> {
+        self.wrapped_cursor.take_buffer()
+    }
+
+    /// Polls for the next batch of raw documents, as described in
+    /// [`Cursor::next_batch`](#method.next_batch).
+    pub(crate) fn poll_next_raw_batch(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<VecDeque<Document>>>> {
+        self.wrapped_cursor.poll_next_batch(cx)
+    }
+
+    /// The resume token reported with the most recent batch, if this is a change stream cursor.
+    pub(crate) fn post_batch_resume_token(&self) -> Option<&Document> {
+        self.wrapped_cursor.post_batch_resume_token()
+    }
+
+    /// Whether the server indicated that some shards were unavailable when this cursor was
+    /// created, meaning the results may be incomplete. This can only be `true` if
+    /// [`FindOptions::allow_partial_results`](crate::options::FindOptions::allow_partial_results)
+    /// was set.
+    pub fn partial_results_returned(&self) -> bool {
+        self.wrapped_cursor.partial_results_returned()
+    }
+
+    /// The number of results this cursor has yielded so far, whether individually via the `Stream`
+    /// implementation or in bulk via methods such as
+    /// [`Cursor::next_batch`](#method.next_batch). Results that could not be deserialized into `T`
+    /// are not counted.
+    ///
+    /// Note that this only reflects progress through the results; it cannot be used to determine
+    /// how many results remain, since the total number of matching documents isn't known to the
+    /// cursor.
+    pub fn count_consumed(&self) -> u64 {
+        self.consumed
+    }
+
+    /// Retrieves all of the results this cursor currently has buffered in memory as a single batch,
+    /// or returns `None` once the cursor is exhausted.
+    ///
+    /// If the buffer is empty, this issues a single `getMore` to fetch the next batch from the
+    /// server first. If that batch is empty but the cursor isn't exhausted, as can happen with a
+    /// tailable cursor, an empty batch is returned. This can be used to process the results in
+    /// bulk rather than one at a time.
+    ///
+    /// ```rust
+    /// # use mongodb::{bson::Document, Client, error::Result};
+    /// #
+    /// # async fn do_stuff() -> Result<()> {
+    /// # let client = Client::with_uri_str("mongodb://example.com").await?;
+    /// # let coll = client.database("foo").collection::<Document>("bar");
+    /// #
+    /// let mut cursor = coll.find(None, None).await?;
+    /// while let Some(batch) = cursor.next_batch().await? {
+    ///     println!("got {} documents", batch.len());
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn next_batch(&mut self) -> Result<Option<Vec<T>>> {
+        let batch =
+            futures_util::future::poll_fn(|cx| self.wrapped_cursor.poll_next_batch(cx)).await;
+        let batch = batch
+            .transpose()?
+            .map(|docs| {
+                docs.into_iter()
+                    .map(|doc| from_document(doc).map_err(Into::into))
+                    .collect::<Result<Vec<T>>>()
+            })
+            .transpose()?;
+        if let Some(ref batch) = batch {
+            self.consumed += batch.len() as u64;
+        }
+        Ok(batch)
+    }
+
+    /// Collects the results of this cursor into a `Vec`, stopping once either `max_docs` documents
+    /// have been collected or collecting the next document would cause the total size of the
+    /// collected documents to exceed `max_bytes`. The size of each document is the size of its
+    /// BSON as returned by the server.
+    ///
+    /// If a limit is reached while the cursor still has results remaining, the returned
+    /// [`CollectResult`](results/struct.CollectResult.html) will have `truncated` set to `true`,
+    /// and the remaining results can be retrieved by continuing to iterate this cursor. Note
+    /// that if the first remaining document alone is larger than `max_bytes`, no documents will
+    /// be collected.
+    ///
+    /// If an error occurs while retrieving results from the server, it is returned and any results
+    /// collected by this call are left in the cursor. If a result can't be deserialized into `T`,
+    /// the results before it are returned with `truncated` set to `true`, and it is left in the
+    /// cursor so that the next call returns the error. The result can be skipped by advancing the
+    /// cursor with `next`, which yields the error.
+    ///
+    /// This can be used in place of
+    /// [`TryStreamExt::try_collect`](https://docs.rs/futures/latest/futures/stream/trait.TryStreamExt.html#method.try_collect)
+    /// to bound the amount of memory used when the size of a result set isn't known ahead of time.
+    ///
+    /// ```rust
+    /// # use mongodb::{bson::Document, Client, error::Result};
+    /// #
+    /// # async fn do_stuff() -> Result<()> {
+    /// # let client = Client::with_uri_str("mongodb://example.com").await?;
+    /// # let coll = client.database("foo").collection::<Document>("bar");
+    /// #
+    /// let mut cursor = coll.find(None, None).await?;
+    /// let result = cursor.collect_with_limit(1000, 16 * 1024 * 1024).await?;
+    /// if result.truncated {
+    ///     println!("more than {} results", result.documents.len());
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn collect_with_limit(
+        &mut self,
+        max_docs: impl Into<Option<usize>>,
+        max_bytes: impl Into<Option<u64>>,
+    ) -> Result<CollectResult<T>> {
+        let max_docs = max_docs.into();
+        let max_bytes = max_bytes.into();
+
+        // The documents are kept as BSON with their sizes until the limits have been reached so
+        // that they can be returned to the cursor if an error occurs.
+        let mut collected: Vec<(Document, u64)> = Vec::new();
+        let mut collected_bytes = 0;
+
+        let mut truncated = loop {
+            // Check the document limit before polling so that no getMore is sent for results that
+            // won't be collected.
+            if matches!(max_docs, Some(max) if collected.len() >= max) {
+                break !self.wrapped_cursor.is_finished();
+            }
+
+            let next =
+                futures_util::future::poll_fn(|cx| self.wrapped_cursor.poll_next_sized(cx)).await;
+            let (doc, size) = match next {
+                Some(Ok(next)) => next,
+                Some(Err(error)) => {
+                    self.return_to_cursor(collected.into_iter());
+                    return Err(error);
+                }
+                None => break false,
+            };
+
+            if matches!(max_bytes, Some(max) if collected_bytes + size > max) {
+                // Leave the document in the cursor so that it can be retrieved by further
+                // iteration.
+                self.wrapped_cursor.push_front(doc, size);
+                break true;
+            }
+
+            collected_bytes += size;
+            collected.push((doc, size));
+        };
+
+        let mut documents = Vec::with_capacity(collected.len());
+        let mut bytes = 0;
+        let mut collected = collected.into_iter();
+        while let Some((doc, size)) = collected.next() {
+            // The document is cloned so that it isn't lost if it can't be deserialized.
+            match from_document(doc.clone()) {
+                Ok(result) => {
+                    documents.push(result);
+                    bytes += size;
+                }
+                Err(error) => {
+                    // Leave the document and everything after it in the cursor, and return the
+                    // results before it if there are any. Otherwise, the error is returned.
+                    self.return_to_cursor(std::iter::once((doc, size)).chain(collected));
+                    if documents.is_empty() {
+                        return Err(error.into());
+                    }
+                    truncated = true;
+                    break;
+                }
+            }
+        }
+
+        self.consumed += documents.len() as u64;
+        Ok(CollectResult {
+            documents,
+            bytes,
+            truncated,
+        })
+    }
+
+    /// Returns documents taken from the cursor by `collect_with_limit` to the front of its buffer,
+    /// in order, so that they will be yielded again by further iteration.
+    fn return_to_cursor(&mut self, docs: impl DoubleEndedIterator<Item = (Document, u64)>) {
+        for (doc, size) in docs.rev() {
+            self.wrapped_cursor.push_front(doc, size);
+        }
+    }
+
+    /// Transforms each successfully retrieved result from this cursor using `f`, passing any
+    /// errors through unchanged.
+    ///
+    /// ```rust
+    /// # use mongodb::{bson::Document, Client, error::Result};
+    /// #
+    /// # async fn do_stuff() -> Result<()> {
+    /// # let client = Client::with_uri_str("mongodb://example.com").await?;
+    /// # let coll = client.database("foo").collection::<Document>("bar");
+    /// #
+    /// use futures::stream::TryStreamExt;
+    ///
+    /// let cursor = coll.find(None, None).await?;
+    /// let names: Vec<Option<String>> = cursor
+    ///     .map_ok(|doc| doc.get_str("name").ok().map(String::from))
+    ///     .try_collect()
+    ///     .await?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn map_ok<U, F>(self, f: F) -> impl Stream<Item = Result<U>>
+    where
+        F: FnMut(T) -> U,
+    {
+        TryStreamExt::map_ok(self, f)
+    }
 }
 
 impl<T> Stream for Cursor<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     type Item = Result<T>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let next = Pin::new(&mut self.wrapped_cursor).poll_next(cx);
         match next {
-            Poll::Ready(opt) => Poll::Ready(
-                opt.map(|result| result.and_then(|doc| from_document(doc).map_err(Into::into))),
-            ),
+            Poll::Ready(opt) => {
+                let next = opt.map(|result| {
+                    result.and_then(|doc| from_document::<T>(doc).map_err(Into::into))
+                });
+                if let Some(Ok(_)) = next {
+                    self.consumed += 1;
+                }
+                Poll::Ready(next)
+            }
             Poll::Pending => Poll::Pending,
         }
     }
@@ -126,7 +367,7 @@ where
 
 impl<T> Drop for Cursor<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     fn drop(&mut self) {
         if self.wrapped_cursor.is_exhausted() {
@@ -139,7 +380,22 @@ where
             .database(ns.db.as_str())
             .collection::<Document>(ns.coll.as_str());
         let cursor_id = self.wrapped_cursor.id();
-        RUNTIME.execute(async move { coll.kill_cursor(cursor_id).await });
+        let address = self.wrapped_cursor.info().address.clone();
+        let comment = self.wrapped_cursor.comment().cloned();
+        let pinned_connection = self.wrapped_cursor.info().pinned_connection.clone();
+        self.wrapped_cursor
+            .info()
+            .emit_closed_event(&self.client, CursorClosedReason::Killed);
+        RUNTIME.execute(async move {
+            coll.kill_cursor(
+                cursor_id,
+                address,
+                comment,
+                pinned_connection.as_ref(),
+                None,
+            )
+            .await
+        });
     }
 }
 
@@ -4,15 +4,23 @@ use std::{
     task::{Context, Poll},
 };
 
+use derivative::Derivative;
 use futures_core::{future::BoxFuture, Stream};
 use futures_util::StreamExt;
 use serde::de::DeserializeOwned;
 
-use super::common::{CursorInformation, GenericCursor, GetMoreProvider, GetMoreProviderResult};
+use super::common::{
+    CursorInformation,
+    ExhaustBatches,
+    GenericCursor,
+    GetMoreProvider,
+    GetMoreProviderResult,
+};
 use crate::{
     bson::{from_document, Document},
     cursor::CursorSpecification,
     error::{Error, Result},
+    event::command::CursorClosedReason,
     operation::GetMore,
     results::GetMoreResult,
     Client,
@@ -47,34 +55,59 @@ use crate::{
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug)]
+#[derive(Derivative)]
+#[derivative(Debug)]
 pub struct SessionCursor<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     exhausted: bool,
     client: Client,
     info: CursorInformation,
     buffer: VecDeque<Document>,
-    _phantom: std::marker::PhantomData<T>,
+
+    /// A getMore that was in flight when the last stream over this cursor was dropped. It runs
+    /// with a fork of the session, so it's kept here and picked up by the next stream rather
+    /// than being cancelled, which would lose the batch that the server returns for it.
+    #[derivative(Debug = "ignore")]
+    in_flight_get_more: Option<InFlightGetMore>,
+
+    /// The batches that the server is streaming to this cursor, if it's an exhaust cursor. These
+    /// are handed to each [`SessionCursorStream`] in turn.
+    exhaust_batches: Option<ExhaustBatches>,
+
+    // No `T` is ever stored, so use a function pointer marker to keep the cursor `Unpin`
+    // regardless of whether `T` is.
+    _phantom: std::marker::PhantomData<fn() -> T>,
 }
 
 impl<T> SessionCursor<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     pub(crate) fn new(client: Client, spec: CursorSpecification) -> Self {
         let exhausted = spec.id() == 0;
+        spec.info.emit_created_event(&client);
 
         Self {
             exhausted,
             client,
             info: spec.info,
             buffer: spec.initial_buffer,
+            in_flight_get_more: None,
+            exhaust_batches: None,
             _phantom: Default::default(),
         }
     }
 
+    /// Whether the server indicated that some shards were unavailable when this cursor was
+    /// created, meaning the results may be incomplete. This can only be `true` if
+    /// [`FindOptions::allow_partial_results`](crate::options::FindOptions::allow_partial_results)
+    /// was set.
+    pub fn partial_results_returned(&self) -> bool {
+        self.info.partial_results_returned
+    }
+
     /// Retrieves a [`SessionCursorStream`] to iterate this cursor. The session provided must be the
     /// same session used to create the cursor.
     ///
@@ -82,6 +115,11 @@ where
     /// of this stream. In order to do that, either use [`SessionCursor::next`] instead or drop
     /// the stream before using the session.
     ///
+    /// Any results the stream has buffered but not yet yielded are handed back to this cursor when
+    /// the stream is dropped, including when a future polling the stream is cancelled. A `getMore`
+    /// that is in flight at that point is handed back as well, and its results are returned by the
+    /// next iteration of this cursor.
+    ///
     /// ```
     /// # use bson::{doc, Document};
     /// # use mongodb::{Client, error::Result};
@@ -121,20 +159,22 @@ where
         &mut self,
         session: &'session mut ClientSession,
     ) -> SessionCursorStream<'_, 'session, T> {
-        let get_more_provider = ExplicitSessionGetMoreProvider::new(session);
+        let get_more_provider =
+            ExplicitSessionGetMoreProvider::new(session, self.in_flight_get_more.take());
 
         // Pass the buffer into this cursor handle for iteration.
         // It will be returned in the handle's `Drop` implementation.
         let spec = CursorSpecification {
             info: self.info.clone(),
             initial_buffer: std::mem::take(&mut self.buffer),
+            initial_batch_lengths: None,
+            post_batch_resume_token: None,
         };
+        let mut generic_cursor =
+            ExplicitSessionCursor::new(self.client.clone(), spec, get_more_provider);
+        generic_cursor.set_exhaust_batches(self.exhaust_batches.take());
         SessionCursorStream {
-            generic_cursor: ExplicitSessionCursor::new(
-                self.client.clone(),
-                spec,
-                get_more_provider,
-            ),
+            generic_cursor,
             session_cursor: self,
         }
     }
@@ -165,11 +205,56 @@ where
     pub async fn next(&mut self, session: &mut ClientSession) -> Option<Result<T>> {
         self.stream(session).next().await
     }
+
+    /// Closes this cursor, killing the server-side cursor if it hasn't been exhausted yet. The
+    /// session provided must be the same session used to create the cursor.
+    ///
+    /// Dropping a [`SessionCursor`] also kills the server-side cursor, but does so in the
+    /// background and without the session. Use this method to close the cursor with its session
+    /// and observe any error that occurs while doing so.
+    ///
+    /// ```
+    /// # use bson::{doc, Document};
+    /// # use mongodb::Client;
+    /// # fn main() {
+    /// # async {
+    /// # let client = Client::with_uri_str("foo").await?;
+    /// # let coll = client.database("foo").collection::<Document>("bar");
+    /// # let mut session = client.start_session(None).await?;
+    /// let mut cursor = coll.find_with_session(doc! { "x": 1 }, None, &mut session).await?;
+    /// let first = cursor.next(&mut session).await.transpose()?;
+    /// cursor.close(&mut session).await?;
+    /// # Ok::<(), mongodb::error::Error>(())
+    /// # };
+    /// # }
+    /// ```
+    pub async fn close(mut self, session: &mut ClientSession) -> Result<()> {
+        if self.exhausted {
+            return Ok(());
+        }
+        // The cursor is killed here rather than in `Drop`.
+        self.exhausted = true;
+        self.info
+            .emit_closed_event(&self.client, CursorClosedReason::Killed);
+
+        let ns = &self.info.ns;
+        self.client
+            .database(ns.db.as_str())
+            .collection::<Document>(ns.coll.as_str())
+            .kill_cursor(
+                self.info.id,
+                self.info.address.clone(),
+                self.info.comment.clone(),
+                self.info.pinned_connection.as_ref(),
+                Some(session),
+            )
+            .await
+    }
 }
 
 impl<T> Drop for SessionCursor<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     fn drop(&mut self) {
         if self.exhausted {
@@ -182,7 +267,21 @@ where
             .database(ns.db.as_str())
             .collection::<Document>(ns.coll.as_str());
         let cursor_id = self.info.id;
-        RUNTIME.execute(async move { coll.kill_cursor(cursor_id).await });
+        let address = self.info.address.clone();
+        let comment = self.info.comment.clone();
+        let pinned_connection = self.info.pinned_connection.clone();
+        self.info
+            .emit_closed_event(&self.client, CursorClosedReason::Killed);
+        RUNTIME.execute(async move {
+            coll.kill_cursor(
+                cursor_id,
+                address,
+                comment,
+                pinned_connection.as_ref(),
+                None,
+            )
+            .await
+        });
     }
 }
 
@@ -197,7 +296,7 @@ type ExplicitSessionCursor<'session> = GenericCursor<ExplicitSessionGetMoreProvi
 /// any further streams created from [`SessionCursor::stream`] will pick up where this one left off.
 pub struct SessionCursorStream<'cursor, 'session, T = Document>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     session_cursor: &'cursor mut SessionCursor<T>,
     generic_cursor: ExplicitSessionCursor<'session>,
@@ -205,7 +304,7 @@ where
 
 impl<'cursor, 'session, T> Stream for SessionCursorStream<'cursor, 'session, T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     type Item = Result<T>;
 
@@ -222,80 +321,80 @@ where
 
 impl<'cursor, 'session, T> Drop for SessionCursorStream<'cursor, 'session, T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     fn drop(&mut self) {
         // Update the parent cursor's state based on any iteration performed on this handle.
         self.session_cursor.buffer = self.generic_cursor.take_buffer();
         self.session_cursor.exhausted = self.generic_cursor.is_exhausted();
+        self.session_cursor.exhaust_batches = self.generic_cursor.take_exhaust_batches();
+        if self.session_cursor.exhausted {
+            self.session_cursor.info.pinned_connection = None;
+        }
+        // A getMore that hasn't been polled yet hasn't been sent either, so it's simply started
+        // again if it's still needed.
+        if self.generic_cursor.get_more_in_flight() {
+            self.session_cursor.in_flight_get_more =
+                self.generic_cursor.provider_mut().in_flight.take();
+        }
     }
 }
 
-/// Enum determining whether a `SessionCursorHandle` is excuting a getMore or not.
-/// In charge of maintaining ownership of the session reference.
-enum ExplicitSessionGetMoreProvider<'session> {
-    /// The handle is currently executing a getMore via the future.
-    ///
-    /// This future owns the reference to the session and will return it on completion.
-    Executing(BoxFuture<'session, ExecutionResult<'session>>),
+/// A getMore running with a fork of the cursor's session, which doesn't borrow the session and so
+/// can outlive the stream that started it.
+type InFlightGetMore = BoxFuture<'static, ForkedGetMoreResult>;
 
-    /// No future is being executed.
-    ///
-    /// This variant needs a `MutableSessionReference` struct that can be moved in order to
-    /// transition to `Executing` via `take_mut`.
-    Idle(MutableSessionReference<'session>),
+/// Runs the getMores for a [`SessionCursorStream`] with forks of the borrowed session, merging each
+/// fork back into the session once its getMore completes.
+struct ExplicitSessionGetMoreProvider<'session> {
+    session: &'session mut ClientSession,
+    in_flight: Option<InFlightGetMore>,
 }
 
 impl<'session> ExplicitSessionGetMoreProvider<'session> {
-    fn new(session: &'session mut ClientSession) -> Self {
-        Self::Idle(MutableSessionReference { reference: session })
+    fn new(session: &'session mut ClientSession, in_flight: Option<InFlightGetMore>) -> Self {
+        Self { session, in_flight }
     }
 }
 
 impl<'session> GetMoreProvider for ExplicitSessionGetMoreProvider<'session> {
-    type GetMoreResult = ExecutionResult<'session>;
-    type GetMoreFuture = BoxFuture<'session, ExecutionResult<'session>>;
+    type GetMoreResult = ForkedGetMoreResult;
+    type GetMoreFuture = InFlightGetMore;
 
     fn executing_future(&mut self) -> Option<&mut Self::GetMoreFuture> {
-        match self {
-            Self::Executing(future) => Some(future),
-            Self::Idle(_) => None,
-        }
+        self.in_flight.as_mut()
     }
 
-    fn clear_execution(&mut self, session: &'session mut ClientSession, _exhausted: bool) {
-        *self = Self::Idle(MutableSessionReference { reference: session })
+    fn clear_execution(&mut self, fork: ClientSession, _exhausted: bool) {
+        self.in_flight = None;
+        self.session.merge(fork);
     }
 
     fn start_execution(&mut self, info: CursorInformation, client: Client) {
-        take_mut::take(self, |self_| {
-            if let ExplicitSessionGetMoreProvider::Idle(session) = self_ {
-                let future = Box::pin(async move {
-                    let get_more = GetMore::new(info);
-                    let get_more_result = client
-                        .execute_operation(get_more, Some(&mut *session.reference))
-                        .await;
-                    ExecutionResult {
-                        get_more_result,
-                        session: session.reference,
-                    }
-                });
-                return ExplicitSessionGetMoreProvider::Executing(future);
+        if self.in_flight.is_some() {
+            return;
+        }
+        let mut session = self.session.fork();
+        self.in_flight = Some(Box::pin(async move {
+            let get_more = GetMore::new(info);
+            let get_more_result = client.execute_operation(get_more, &mut session).await;
+            ForkedGetMoreResult {
+                get_more_result,
+                session,
             }
-            self_
-        });
+        }));
     }
 }
 
-/// Struct returned from awaiting on a `GetMoreFuture` containing the result of the getMore as
-/// well as the reference to the `ClientSession` used for the getMore.
-struct ExecutionResult<'session> {
+/// Struct returned from awaiting on an [`InFlightGetMore`] containing the result of the getMore as
+/// well as the fork of the session used for it.
+struct ForkedGetMoreResult {
     get_more_result: Result<GetMoreResult>,
-    session: &'session mut ClientSession,
+    session: ClientSession,
 }
 
-impl<'session> GetMoreProviderResult for ExecutionResult<'session> {
-    type Session = &'session mut ClientSession;
+impl GetMoreProviderResult for ForkedGetMoreResult {
+    type Session = ClientSession;
 
     fn as_ref(&self) -> std::result::Result<&GetMoreResult, &Error> {
         self.get_more_result.as_ref()
@@ -305,10 +404,3 @@ impl<'session> GetMoreProviderResult for ExecutionResult<'session> {
         (self.get_more_result, self.session)
     }
 }
-
-/// Wrapper around a mutable reference to a `ClientSession` that provides move semantics.
-/// This is used to prevent re-borrowing of the session and forcing it to be moved instead
-/// by moving the wrapping struct.
-struct MutableSessionReference<'a> {
-    reference: &'a mut ClientSession,
-}
@@ -0,0 +1,57 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures_util::stream::StreamExt;
+use tokio::sync::mpsc;
+
+use super::common::{forward_batches, EXHAUST_BATCH_BUFFER_SIZE};
+use crate::{bson::doc, results::GetMoreResult, RUNTIME};
+
+/// A consumer that's slower than the server streaming an exhaust cursor's batches only has a
+/// bounded number of them read ahead of it.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn exhaust_batches_bounded_for_slow_consumer() {
+    const TOTAL_BATCHES: usize = 20;
+
+    let read = Arc::new(AtomicUsize::new(0));
+    let counter = read.clone();
+    let batches = futures_util::stream::iter(1..=TOTAL_BATCHES).map(move |i| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Ok(GetMoreResult {
+            batch: VecDeque::from(vec![doc! { "_id": i as i32 }]),
+            exhausted: i == TOTAL_BATCHES,
+            raw_batch_lengths: None,
+            post_batch_resume_token: None,
+            streaming_connection: None,
+        })
+    });
+
+    let (sender, mut receiver) = mpsc::channel(EXHAUST_BATCH_BUFFER_SIZE);
+    RUNTIME.execute(forward_batches(batches, sender));
+
+    let mut received = 0;
+    loop {
+        // Give the reader time to get as far ahead as it can before each batch is taken.
+        RUNTIME.delay_for(Duration::from_millis(10)).await;
+        // Besides the batches in the channel, the reader holds at most one that it's waiting for
+        // room to send.
+        assert!(read.load(Ordering::SeqCst) <= received + EXHAUST_BATCH_BUFFER_SIZE + 1);
+
+        let result = receiver.recv().await.unwrap().unwrap();
+        received += 1;
+        assert_eq!(result.batch, vec![doc! { "_id": received as i32 }]);
+        if result.exhausted {
+            break;
+        }
+    }
+
+    assert_eq!(received, TOTAL_BATCHES);
+    assert!(receiver.recv().await.is_none());
+}
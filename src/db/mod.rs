@@ -10,17 +10,22 @@ use crate::{
     concern::{ReadConcern, WriteConcern},
     cursor::Cursor,
     error::{Error, ErrorKind, Result},
-    operation::{Aggregate, Create, DropDatabase, ListCollections, RunCommand},
+    gridfs::GridFsBucket,
+    operation::{Aggregate, Create, DropDatabase, ListCollections, Profile, RunCommand},
     options::{
         AggregateOptions,
         CollectionOptions,
         CreateCollectionOptions,
         DatabaseOptions,
         DropDatabaseOptions,
+        FindOptions,
+        GridFsBucketOptions,
         ListCollectionsOptions,
+        ProfilingLevel,
+        SetProfilingLevelOptions,
     },
     results::CollectionSpecification,
-    selection_criteria::SelectionCriteria,
+    selection_criteria::{ReadPreference, SelectionCriteria},
     Client,
     ClientSession,
     Collection,
@@ -154,6 +159,16 @@ impl Database {
         Collection::new(self.clone(), name, Some(options))
     }
 
+    /// Gets a handle to a GridFS bucket in the database, which can be used to retrieve files that
+    /// are too large to fit in a single document. The bucket's read preference and read concern
+    /// default to those of the `Database`.
+    ///
+    /// This method does not send or receive anything across the wire to the database, so it can be
+    /// used repeatedly without incurring any costs from I/O.
+    pub fn gridfs_bucket(&self, options: impl Into<Option<GridFsBucketOptions>>) -> GridFsBucket {
+        GridFsBucket::new(self.clone(), options.into())
+    }
+
     async fn drop_common(
         &self,
         options: impl Into<Option<DropDatabaseOptions>>,
@@ -327,7 +342,8 @@ impl Database {
         selection_criteria: impl Into<Option<SelectionCriteria>>,
         session: impl Into<Option<&mut ClientSession>>,
     ) -> Result<Document> {
-        let operation = RunCommand::new(self.name().into(), command, selection_criteria.into())?;
+        let operation =
+            RunCommand::new(self.name().into(), command, selection_criteria.into(), None)?;
         self.client().execute_operation(operation, session).await
     }
 
@@ -390,6 +406,27 @@ impl Database {
     ///
     /// See the documentation [here](https://docs.mongodb.com/manual/aggregation/) for more
     /// information on aggregations.
+    ///
+    /// Pipelines run against a database rather than a collection, which includes those that begin
+    /// with stages such as `$currentOp`, `$queryStats`, or `$listSampledQueries`, should be run
+    /// using this method on the `admin` database:
+    ///
+    /// ```rust
+    /// # use mongodb::{bson::doc, Client, error::Result};
+    /// #
+    /// # async fn do_stuff() -> Result<()> {
+    /// # let client = Client::with_uri_str("mongodb://example.com").await?;
+    /// use futures::stream::TryStreamExt;
+    ///
+    /// let admin = client.database("admin");
+    /// let mut cursor = admin.aggregate(vec![doc! { "$queryStats": {} }], None).await?;
+    /// while let Some(stats) = cursor.try_next().await? {
+    ///     println!("{}", stats);
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn aggregate(
         &self,
         pipeline: impl IntoIterator<Item = Document>,
@@ -434,4 +471,45 @@ impl Database {
             .await
             .map(|spec| SessionCursor::new(client.clone(), spec))
     }
+
+    /// Gets the level at which the database profiler is currently collecting data.
+    ///
+    /// See the MongoDB [manual](https://docs.mongodb.com/manual/reference/command/profile/) for
+    /// more information on the profiler.
+    pub async fn profiling_level(&self) -> Result<ProfilingLevel> {
+        let profile = Profile::get(self.name().to_string());
+        self.client().execute_operation(profile, None).await
+    }
+
+    /// Sets the level at which the database profiler collects data, along with the threshold and
+    /// sample rate for slow operations.
+    ///
+    /// This command is always sent to the primary and does not use the database's default write
+    /// concern.
+    pub async fn set_profiling_level(
+        &self,
+        level: ProfilingLevel,
+        options: impl Into<Option<SetProfilingLevelOptions>>,
+    ) -> Result<()> {
+        let profile = Profile::set(self.name().to_string(), level, options.into());
+        self.client()
+            .execute_operation(profile, None)
+            .await
+            .map(|_| ())
+    }
+
+    /// Queries the `system.profile` collection of the database, which contains the data collected
+    /// by the profiler. The cursor will yield a document for each profiled operation matching
+    /// `filter`.
+    pub async fn profiling_info(
+        &self,
+        filter: impl Into<Option<Document>>,
+    ) -> Result<Cursor<Document>> {
+        let options = FindOptions::builder()
+            .selection_criteria(SelectionCriteria::ReadPreference(ReadPreference::Primary))
+            .build();
+        self.collection::<Document>("system.profile")
+            .find(filter, options)
+            .await
+    }
 }
@@ -1,11 +1,11 @@
 use std::time::Duration;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_with::skip_serializing_none;
 use typed_builder::TypedBuilder;
 
 use crate::{
-    bson::Document,
+    bson::{doc, Bson, Document},
     bson_util,
     concern::{ReadConcern, WriteConcern},
     options::Collation,
@@ -97,6 +97,12 @@ pub struct CreateCollectionOptions {
         serialize_with = "bson_util::serialize_duration_option_as_int_secs"
     )]
     pub expire_after_seconds: Option<Duration>,
+
+    /// Creates a clustered collection, which stores documents in order of their `_id` rather than
+    /// maintaining a separate `_id` index. This feature is only available on server versions 5.3
+    /// and above.
+    #[serde(default, deserialize_with = "deserialize_clustered_index")]
+    pub clustered_index: Option<ClusteredIndex>,
 }
 
 /// Specifies how strictly the database should apply validation rules to existing documents during
@@ -170,6 +176,41 @@ pub enum TimeseriesGranularity {
     Hours,
 }
 
+/// Specifies the clustered index of a clustered collection.
+#[derive(Clone, Debug, TypedBuilder, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ClusteredIndex {
+    /// The key of the clustered index. This must be `{ _id: 1 }`.
+    #[builder(default = doc! { "_id": 1 })]
+    pub key: Document,
+
+    /// Whether the index is unique. This must be `true`.
+    #[builder(default = true)]
+    pub unique: bool,
+}
+
+impl Default for ClusteredIndex {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Time-series collections report `clusteredIndex: true` rather than an index specification, since
+/// they are implicitly clustered on their time field.
+fn deserialize_clustered_index<'de, D>(deserializer: D) -> Result<Option<ClusteredIndex>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Bson>::deserialize(deserializer)? {
+        Some(Bson::Document(index)) => bson::from_document(index)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+        Some(Bson::Boolean(true)) => Ok(Some(ClusteredIndex::default())),
+        _ => Ok(None),
+    }
+}
+
 /// Specifies the options to a [`Database::drop`](../struct.Database.html#method.drop) operation.
 #[derive(Debug, Default, TypedBuilder, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -180,6 +221,66 @@ pub struct DropDatabaseOptions {
     pub write_concern: Option<WriteConcern>,
 }
 
+/// The level at which the database profiler collects data about operations.
+///
+/// See the MongoDB [manual](https://docs.mongodb.com/manual/tutorial/manage-the-database-profiler/)
+/// for more information on the profiler.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ProfilingLevel {
+    /// The profiler is off and does not collect any data.
+    Off,
+
+    /// The profiler collects data for operations that take longer than the slow operation
+    /// threshold.
+    SlowOnly,
+
+    /// The profiler collects data for all operations.
+    All,
+}
+
+impl ProfilingLevel {
+    pub(crate) fn as_i32(self) -> i32 {
+        match self {
+            Self::Off => 0,
+            Self::SlowOnly => 1,
+            Self::All => 2,
+        }
+    }
+
+    pub(crate) fn from_i32(level: i32) -> Option<Self> {
+        match level {
+            0 => Some(Self::Off),
+            1 => Some(Self::SlowOnly),
+            2 => Some(Self::All),
+            _ => None,
+        }
+    }
+}
+
+/// Specifies the options to a
+/// [`Database::set_profiling_level`](../struct.Database.html#method.set_profiling_level)
+/// operation.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, TypedBuilder, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct SetProfilingLevelOptions {
+    /// The threshold above which operations are considered slow. Slow operations are logged, and
+    /// profiled when the level is [`ProfilingLevel::SlowOnly`].
+    ///
+    /// The duration will be sent across the wire as an integer number of milliseconds.
+    #[serde(
+        rename = "slowms",
+        serialize_with = "bson_util::serialize_duration_as_int_millis"
+    )]
+    pub slow_ms: Option<Duration>,
+
+    /// The fraction of slow operations that should be profiled or logged, between 0 and 1.
+    pub sample_rate: Option<f64>,
+}
+
 /// Specifies the options to a
 /// [`Database::list_collections`](../struct.Database.html#method.list_collections) operation.
 #[derive(Clone, Debug, Default, Deserialize, TypedBuilder, Serialize)]
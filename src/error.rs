@@ -6,17 +6,24 @@ use bson::Bson;
 use serde::Deserialize;
 use thiserror::Error;
 
-use crate::{bson::Document, options::ServerAddress};
+use crate::{bson::Document, operation::Retryability, options::ServerAddress};
 
 const RECOVERING_CODES: [i32; 5] = [11600, 11602, 13436, 189, 91];
 const NOTMASTER_CODES: [i32; 3] = [10107, 13435, 10058];
 const SHUTTING_DOWN_CODES: [i32; 2] = [11600, 91];
-const RETRYABLE_READ_CODES: [i32; 11] =
-    [11600, 11602, 10107, 13435, 13436, 189, 91, 7, 6, 89, 9001];
-const RETRYABLE_WRITE_CODES: [i32; 12] = [
-    11600, 11602, 10107, 13435, 13436, 189, 91, 7, 6, 89, 9001, 262,
-];
+/// Codes for errors after which an operation can be retried. Writes can also be retried after
+/// `ExceededTimeLimit` (262) errors.
+const RETRYABLE_CODES: [i32; 11] = [11600, 11602, 10107, 13435, 13436, 189, 91, 7, 6, 89, 9001];
+const RETRYABLE_WRITE_ONLY_CODES: [i32; 1] = [262];
 const UNKNOWN_TRANSACTION_COMMIT_RESULT_LABEL_CODES: [i32; 3] = [50, 64, 91];
+const MAX_TIME_MS_EXPIRED_CODE: i32 = 50;
+/// Codes for errors after which a change stream can be resumed. Servers 4.4 and newer label these
+/// errors instead, but older ones don't.
+const RESUMABLE_CHANGE_STREAM_CODES: [i32; 17] = [
+    6, 7, 63, 89, 91, 133, 150, 189, 234, 262, 9001, 10107, 11600, 11602, 13388, 13435, 13436,
+];
+const CURSOR_NOT_FOUND_CODE: i32 = 43;
+const RESUMABLE_CHANGE_STREAM_ERROR: &str = "ResumableChangeStreamError";
 
 /// Retryable write error label. This label will be added to an error when the error is
 /// write-retryable.
@@ -109,17 +116,54 @@ impl Error {
         matches!(self.kind.as_ref(), ErrorKind::ServerSelection { .. })
     }
 
-    /// Whether a read operation should be retried if this error occurs.
-    pub(crate) fn is_read_retryable(&self) -> bool {
+    /// Whether a change stream should resume after this error occurs while fetching its events.
+    pub(crate) fn is_resumable(&self) -> bool {
+        if self.is_network_error() {
+            return true;
+        }
+        match self.kind.as_ref() {
+            ErrorKind::Command(ref err) => {
+                err.code == CURSOR_NOT_FOUND_CODE
+                    || self.contains_label(RESUMABLE_CHANGE_STREAM_ERROR)
+                    || RESUMABLE_CHANGE_STREAM_CODES.contains(&err.code)
+            }
+            _ => false,
+        }
+    }
+
+    /// Classifies whether an operation with the given retryability can be retried after this
+    /// error based on its kind and code alone: network errors are always retryable, and command
+    /// errors are retryable if their code is one of the retryable codes. Labels are not
+    /// considered.
+    pub(crate) fn is_retryable_error(&self, retryability: &Retryability) -> bool {
+        if *retryability == Retryability::None {
+            return false;
+        }
         if self.is_network_error() {
             return true;
         }
         match self.code() {
-            Some(code) => RETRYABLE_READ_CODES.contains(&code),
+            Some(code) => is_retryable_code(code, retryability),
             None => false,
         }
     }
 
+    /// Whether an operation with the given retryability should be retried if this error occurs.
+    /// Reads are retried based on the error itself, whereas writes are only retried if the error
+    /// has been labeled with "RetryableWriteError".
+    pub(crate) fn is_retryable(&self, retryability: &Retryability) -> bool {
+        match retryability {
+            Retryability::Read => self.is_read_retryable(),
+            Retryability::Write => self.is_write_retryable(),
+            Retryability::None => false,
+        }
+    }
+
+    /// Whether a read operation should be retried if this error occurs.
+    pub(crate) fn is_read_retryable(&self) -> bool {
+        self.is_retryable_error(&Retryability::Read)
+    }
+
     pub(crate) fn is_write_retryable(&self) -> bool {
         self.contains_label(RETRYABLE_WRITE_ERROR)
     }
@@ -132,13 +176,7 @@ impl Error {
         if max_wire_version > 8 {
             return self.is_network_error();
         }
-        if self.is_network_error() {
-            return true;
-        }
-        match &self.code() {
-            Some(code) => RETRYABLE_WRITE_CODES.contains(code),
-            None => false,
-        }
+        self.is_retryable_error(&Retryability::Write)
     }
 
     pub(crate) fn should_add_unknown_transaction_commit_result_label(&self) -> bool {
@@ -250,6 +288,7 @@ impl Error {
                 Some(write_error.message.clone())
             }
             ErrorKind::Transaction { message } => Some(message.clone()),
+            ErrorKind::Timeout { message } => Some(message.clone()),
             _ => None,
         }
     }
@@ -295,6 +334,28 @@ impl Error {
     pub(crate) fn is_pool_cleared(&self) -> bool {
         matches!(self.kind.as_ref(), ErrorKind::ConnectionPoolCleared { .. })
     }
+
+    /// Whether this error was caused by an operation exceeding its time limit, either because no
+    /// reply arrived in time or because the server returned a `MaxTimeMSExpired` error.
+    pub fn is_timeout(&self) -> bool {
+        match self.kind.as_ref() {
+            ErrorKind::Timeout { .. } => true,
+            ErrorKind::Command(command_error) => command_error.code == MAX_TIME_MS_EXPIRED_CODE,
+            _ => false,
+        }
+    }
+}
+
+/// Whether an error with the given code indicates that an operation with the given retryability
+/// can be retried.
+pub(crate) fn is_retryable_code(code: i32, retryability: &Retryability) -> bool {
+    match retryability {
+        Retryability::Read => RETRYABLE_CODES.contains(&code),
+        Retryability::Write => {
+            RETRYABLE_CODES.contains(&code) || RETRYABLE_WRITE_ONLY_CODES.contains(&code)
+        }
+        Retryability::None => false,
+    }
 }
 
 impl<E> From<E> for Error
@@ -410,6 +471,82 @@ pub enum ErrorKind {
     #[error("{message}")]
     #[non_exhaustive]
     Transaction { message: String },
+
+    /// A change stream event didn't contain a resume token, so the change stream wouldn't be able
+    /// to resume after it.
+    #[error("Cannot provide resume functionality when the resume token is missing")]
+    MissingResumeToken,
+
+    /// No reply to the operation arrived within its `max_time`. If the server itself reports that
+    /// the time limit was exceeded, a [`ErrorKind::Command`] error is returned instead; use
+    /// [`Error::is_timeout`] to check for either.
+    #[error("The operation timed out: {message}")]
+    #[non_exhaustive]
+    Timeout { message: String },
+
+    /// An error occurred while reading or writing a file in a GridFS bucket.
+    #[error("A GridFS error occurred: {0:?}")]
+    GridFs(GridFsErrorKind),
+}
+
+/// The types of errors that can occur while reading or writing a file in a
+/// [`GridFsBucket`](crate::gridfs::GridFsBucket).
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum GridFsErrorKind {
+    /// No file matching the given identifier was found in the bucket.
+    #[non_exhaustive]
+    FileNotFound {
+        /// The `_id` or filename that was looked up.
+        identifier: GridFsFileIdentifier,
+    },
+
+    /// Files with the requested name exist in the bucket, but the requested revision doesn't.
+    #[non_exhaustive]
+    RevisionNotFound {
+        /// The revision that was requested.
+        revision: i32,
+    },
+
+    /// The document describing the file being downloaded has a chunk size of 0, so the file's
+    /// chunks can't be read.
+    #[non_exhaustive]
+    InvalidChunkSize {
+        /// The chunk size stored in the file's document.
+        chunk_size: u32,
+    },
+
+    /// A chunk of the file being downloaded was missing from the chunks collection.
+    #[non_exhaustive]
+    MissingChunk {
+        /// The index of the missing chunk.
+        n: u32,
+    },
+
+    /// A chunk of the file being downloaded didn't have the size implied by the file's length
+    /// and chunk size.
+    #[non_exhaustive]
+    WrongSizeChunk {
+        /// The size of the chunk that was read.
+        actual_size: usize,
+
+        /// The size that the chunk should have had.
+        expected_size: u32,
+
+        /// The index of the chunk.
+        n: u32,
+    },
+}
+
+/// Identifies a file that was looked up in a [`GridFsBucket`](crate::gridfs::GridFsBucket).
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum GridFsFileIdentifier {
+    /// The file was looked up by its filename.
+    Filename(String),
+
+    /// The file was looked up by its `_id`.
+    Id(Bson),
 }
 
 /// An error that occurred due to a database command failing.
@@ -452,6 +589,12 @@ pub struct WriteConcernError {
     /// A document identifying the write concern setting related to the error.
     #[serde(rename = "errInfo")]
     pub details: Option<Document>,
+
+    /// Labels categorizing the error. Servers prior to 4.4 may report these here rather than at
+    /// the top level of the response; either way, they are included in the labels of the
+    /// [`Error`] containing this write concern error.
+    #[serde(rename = "errorLabels", default)]
+    pub(crate) labels: Vec<String>,
 }
 
 /// An error that occurred during a write operation that wasn't due to being unable to satisfy a
@@ -519,8 +662,11 @@ pub struct BulkWriteFailure {
     /// The error that occurred on account of write concern failure.
     pub write_concern_error: Option<WriteConcernError>,
 
+    /// The `_id` field of the documents that were successfully inserted before the failure, keyed
+    /// by the index of the document in the input to insert_many. This is only populated for
+    /// insert operations.
     #[serde(skip)]
-    pub(crate) inserted_ids: HashMap<usize, Bson>,
+    pub inserted_ids: HashMap<usize, Bson>,
 }
 
 impl BulkWriteFailure {
@@ -577,3 +723,65 @@ pub(crate) fn convert_bulk_errors(error: Error) -> Error {
         _ => error,
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{is_retryable_code, CommandError, Error, ErrorKind};
+    use crate::operation::Retryability;
+
+    fn command_error(code: i32) -> Error {
+        ErrorKind::Command(CommandError {
+            code,
+            code_name: String::new(),
+            message: String::new(),
+        })
+        .into()
+    }
+
+    #[test]
+    fn retryable_codes() {
+        let codes = [6, 7, 89, 91, 189, 9001, 10107, 11600, 11602, 13435, 13436];
+        for code in codes.iter().copied() {
+            assert!(is_retryable_code(code, &Retryability::Read), "{}", code);
+            assert!(is_retryable_code(code, &Retryability::Write), "{}", code);
+            assert!(!is_retryable_code(code, &Retryability::None), "{}", code);
+            assert!(command_error(code).is_retryable_error(&Retryability::Read));
+            assert!(command_error(code).should_add_retryable_write_label(8));
+        }
+
+        // ExceededTimeLimit is only retryable for writes.
+        assert!(!is_retryable_code(262, &Retryability::Read));
+        assert!(is_retryable_code(262, &Retryability::Write));
+        assert!(!command_error(262).is_read_retryable());
+        assert!(command_error(262).should_add_retryable_write_label(8));
+
+        for code in [2, 11000, 50, 13].iter().copied() {
+            assert!(!is_retryable_code(code, &Retryability::Read), "{}", code);
+            assert!(!is_retryable_code(code, &Retryability::Write), "{}", code);
+            assert!(!command_error(code).is_retryable_error(&Retryability::Write));
+        }
+    }
+
+    #[test]
+    fn retryable_network_errors() {
+        let error: Error = ErrorKind::from(std::io::ErrorKind::ConnectionReset).into();
+        assert!(error.is_retryable_error(&Retryability::Read));
+        assert!(error.is_retryable_error(&Retryability::Write));
+        assert!(!error.is_retryable_error(&Retryability::None));
+        assert!(error.should_add_retryable_write_label(9));
+
+        // 4.4+ servers add the label for retryable codes themselves.
+        assert!(!command_error(91).should_add_retryable_write_label(9));
+    }
+
+    #[test]
+    fn write_retries_require_label() {
+        let mut error = command_error(91);
+        assert!(error.is_retryable(&Retryability::Read));
+        assert!(!error.is_retryable(&Retryability::Write));
+
+        error.add_label(super::RETRYABLE_WRITE_ERROR);
+        assert!(error.is_retryable(&Retryability::Write));
+        assert!(!error.is_retryable(&Retryability::None));
+    }
+}
@@ -5,7 +5,7 @@ use std::time::Duration;
 
 use serde::Deserialize;
 
-use crate::options::ServerAddress;
+use crate::{bson::oid::ObjectId, options::ServerAddress};
 
 /// We implement `Deserialize` for all of the event types so that we can more easily parse the CMAP
 /// spec tests. However, we have no need to parse the address field from the JSON files (if it's
@@ -79,6 +79,11 @@ pub struct PoolClearedEvent {
     #[serde(default = "self::empty_address")]
     #[serde(skip)]
     pub address: ServerAddress,
+
+    /// If the pool is connected to a load balancer, the `serviceId` of the backend server whose
+    /// connections were cleared. Connections to other backends are unaffected.
+    #[serde(skip)]
+    pub service_id: Option<ObjectId>,
 }
 
 /// Event emitted when a connection pool is cleared.
@@ -3,7 +3,7 @@
 
 use std::time::Duration;
 
-use crate::{bson::Document, cmap::ConnectionInfo, error::Error};
+use crate::{bson::Document, cmap::ConnectionInfo, error::Error, Namespace};
 
 /// An event that triggers when a database command is initiated.
 #[derive(Clone, Debug)]
@@ -26,6 +26,12 @@ pub struct CommandStartedEvent {
 
     /// Information about the connect the command will be run on.
     pub connection: ConnectionInfo,
+
+    /// The id of the cursor the command operates on. This is only set for `getMore` and
+    /// `killCursors` commands, and can be matched against a
+    /// [`CursorCreatedEvent`](struct.CursorCreatedEvent.html) to find the operation that created
+    /// the cursor.
+    pub cursor_id: Option<i64>,
 }
 
 /// An event that triggers when a database command completes without an error.
@@ -72,6 +78,48 @@ pub struct CommandFailedEvent {
     pub connection: ConnectionInfo,
 }
 
+/// An event that triggers when an operation opens a cursor on the server, i.e. when the initial
+/// reply contains a non-zero cursor id.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct CursorCreatedEvent {
+    /// The id of the server-side cursor.
+    pub cursor_id: i64,
+
+    /// The namespace the cursor iterates over.
+    pub namespace: Namespace,
+
+    /// The name of the command that created the cursor, e.g. "find" or "aggregate".
+    pub operation_name: String,
+}
+
+/// An event that triggers when a cursor previously reported by a
+/// [`CursorCreatedEvent`](struct.CursorCreatedEvent.html) is closed.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct CursorClosedEvent {
+    /// The id of the server-side cursor.
+    pub cursor_id: i64,
+
+    /// The namespace the cursor iterated over.
+    pub namespace: Namespace,
+
+    /// The reason that the cursor was closed.
+    pub reason: CursorClosedReason,
+}
+
+/// The reasons that a cursor may be closed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum CursorClosedReason {
+    /// The server reported that the cursor was exhausted.
+    Exhausted,
+
+    /// The driver killed the cursor before it was exhausted, e.g. because it was dropped or
+    /// explicitly closed.
+    Killed,
+}
+
 /// Applications can implement this trait to specify custom logic to run on each command event sent
 /// by the driver.
 ///
@@ -122,4 +170,12 @@ pub trait CommandEventHandler: Send + Sync {
     /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
     /// whenever a database command fails to complete successfully.
     fn handle_command_failed_event(&self, _event: CommandFailedEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever an operation opens a cursor on the server.
+    fn handle_cursor_created_event(&self, _event: CursorCreatedEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a cursor it opened is exhausted or killed.
+    fn handle_cursor_closed_event(&self, _event: CursorClosedEvent) {}
 }
@@ -2,3 +2,4 @@
 
 pub mod cmap;
 pub mod command;
+pub mod sdam;
@@ -0,0 +1,106 @@
+//! Contains the events and functionality to monitor the heartbeats that a `Client` sends to the
+//! servers in its topology.
+
+use std::time::Duration;
+
+use crate::{bson::Document, error::Error, options::ServerAddress};
+
+/// An event that triggers when a server monitor starts checking a server.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ServerHeartbeatStartedEvent {
+    /// The address of the server being checked.
+    pub server_address: ServerAddress,
+
+    /// Whether the check is an awaited one, i.e. the server only replies once its state changes
+    /// or the heartbeat frequency elapses, rather than straight away. Awaited checks are used
+    /// when monitoring a 4.4+ server with the streaming protocol.
+    pub awaited: bool,
+}
+
+/// An event that triggers when a server monitor's check of a server succeeds.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ServerHeartbeatSucceededEvent {
+    /// The execution time of the check. For awaited checks, this includes the time that the
+    /// server waited before replying.
+    pub duration: Duration,
+
+    /// The server's reply to the check.
+    pub reply: Document,
+
+    /// The address of the server that was checked.
+    pub server_address: ServerAddress,
+
+    /// Whether the check was an awaited one. See [`ServerHeartbeatStartedEvent::awaited`].
+    pub awaited: bool,
+}
+
+/// An event that triggers when a server monitor's check of a server fails.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ServerHeartbeatFailedEvent {
+    /// The execution time of the check.
+    pub duration: Duration,
+
+    /// The error that caused the check to fail.
+    pub failure: Error,
+
+    /// The address of the server that was checked.
+    pub server_address: ServerAddress,
+
+    /// Whether the check was an awaited one. See [`ServerHeartbeatStartedEvent::awaited`].
+    pub awaited: bool,
+}
+
+/// Applications can implement this trait to specify custom logic to run on each server heartbeat
+/// event sent by the driver.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// #
+/// # use mongodb::{
+/// #     error::Result,
+/// #     event::sdam::{
+/// #         SdamEventHandler,
+/// #         ServerHeartbeatFailedEvent
+/// #     },
+/// #     options::ClientOptions,
+/// # };
+/// # #[cfg(feature = "sync")]
+/// # use mongodb::sync::Client;
+/// # #[cfg(not(feature = "sync"))]
+/// # use mongodb::Client;
+/// #
+/// struct FailedHeartbeatLogger;
+///
+/// impl SdamEventHandler for FailedHeartbeatLogger {
+///     fn handle_server_heartbeat_failed_event(&self, event: ServerHeartbeatFailedEvent) {
+///         eprintln!("Failed heartbeat: {:?}", event);
+///     }
+/// }
+///
+/// # fn do_stuff() -> Result<()> {
+/// let handler: Arc<dyn SdamEventHandler> = Arc::new(FailedHeartbeatLogger);
+/// let options = ClientOptions::builder()
+///                   .sdam_event_handler(handler)
+///                   .build();
+/// let client = Client::with_options(options)?;
+///
+/// // Do things with the client, and failed heartbeat events will be logged to stderr.
+/// # Ok(())
+/// # }
+/// ```
+pub trait SdamEventHandler: Send + Sync {
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a server monitor starts checking a server.
+    fn handle_server_heartbeat_started_event(&self, _event: ServerHeartbeatStartedEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a server monitor's check of a server succeeds.
+    fn handle_server_heartbeat_succeeded_event(&self, _event: ServerHeartbeatSucceededEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a server monitor's check of a server fails.
+    fn handle_server_heartbeat_failed_event(&self, _event: ServerHeartbeatFailedEvent) {}
+}
@@ -0,0 +1,369 @@
+//! Contains the types for storing and retrieving files in GridFS buckets.
+
+pub(crate) mod options;
+
+use std::{
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+use futures_util::io::AsyncRead;
+use serde::Deserialize;
+
+use crate::{
+    bson::{doc, Binary, Bson, DateTime, Document},
+    concern::ReadConcern,
+    cursor::Cursor,
+    error::{Error, ErrorKind, GridFsErrorKind, GridFsFileIdentifier, Result},
+    options::{
+        CollectionOptions,
+        FindOneOptions,
+        FindOptions,
+        GridFsBucketOptions,
+        GridFsDownloadByNameOptions,
+        SelectionCriteria,
+    },
+    Collection,
+    Database,
+};
+
+const DEFAULT_BUCKET_NAME: &str = "fs";
+
+/// The document describing a file stored in a [`GridFsBucket`], as stored in the bucket's files
+/// collection.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct FilesCollectionDocument {
+    /// The file's unique identifier.
+    #[serde(rename = "_id")]
+    pub id: Bson,
+
+    /// The length of the file in bytes.
+    pub length: u64,
+
+    /// The size in bytes of the chunks the file is split into. Every chunk is this size except for
+    /// the last one, which may be smaller.
+    pub chunk_size: u32,
+
+    /// When the file was uploaded.
+    pub upload_date: DateTime,
+
+    /// The name of the file.
+    pub filename: Option<String>,
+
+    /// Arbitrary data stored along with the file.
+    pub metadata: Option<Document>,
+}
+
+impl FilesCollectionDocument {
+    fn num_chunks(&self) -> u32 {
+        if self.length == 0 {
+            return 0;
+        }
+        ((self.length - 1) / self.chunk_size as u64 + 1) as u32
+    }
+
+    /// The size that chunk `n` of this file should have.
+    fn expected_chunk_size(&self, n: u32) -> u32 {
+        let remainder = self.length - n as u64 * self.chunk_size as u64;
+        std::cmp::min(remainder, self.chunk_size as u64) as u32
+    }
+}
+
+/// A document in a bucket's chunks collection, which holds one piece of a file.
+#[derive(Debug, Deserialize)]
+struct Chunk {
+    n: u32,
+
+    data: Binary,
+}
+
+/// A `GridFsBucket` reads files stored in GridFS, which stores files that are too large to fit in a
+/// single document by splitting them into chunks. Each file is described by a document in the
+/// bucket's files collection, and its contents are stored in order in the bucket's chunks
+/// collection. A `GridFsBucket` can only be obtained through a [`Database`] by calling
+/// [`Database::gridfs_bucket`].
+///
+/// `GridFsBucket` uses [`std::sync::Arc`] internally, so it can safely be shared across threads or
+/// async tasks.
+///
+/// ```rust
+/// # use futures::io::AsyncReadExt;
+/// # #[cfg(not(feature = "sync"))]
+/// # use mongodb::{error::Result, Client};
+/// #
+/// # #[cfg(not(feature = "sync"))]
+/// # async fn do_stuff() -> Result<()> {
+/// # let client = Client::with_uri_str("mongodb://example.com").await?;
+/// let bucket = client.database("db").gridfs_bucket(None);
+///
+/// let mut contents = Vec::new();
+/// bucket
+///     .open_download_stream_by_name("hello.txt", None)
+///     .await?
+///     .read_to_end(&mut contents)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct GridFsBucket {
+    inner: Arc<GridFsBucketInner>,
+}
+
+#[derive(Debug)]
+struct GridFsBucketInner {
+    bucket_name: String,
+    files: Collection<FilesCollectionDocument>,
+    chunks: Collection<Chunk>,
+}
+
+impl GridFsBucket {
+    pub(crate) fn new(db: Database, options: Option<GridFsBucketOptions>) -> Self {
+        let options = options.unwrap_or_default();
+        let bucket_name = options
+            .bucket_name
+            .unwrap_or_else(|| DEFAULT_BUCKET_NAME.to_string());
+        let collection_options = CollectionOptions::builder()
+            .selection_criteria(
+                options
+                    .selection_criteria
+                    .or_else(|| db.selection_criteria().cloned()),
+            )
+            .read_concern(options.read_concern.or_else(|| db.read_concern().cloned()))
+            .build();
+
+        Self {
+            inner: Arc::new(GridFsBucketInner {
+                files: db.collection_with_options(
+                    &format!("{}.files", bucket_name),
+                    collection_options.clone(),
+                ),
+                chunks: db.collection_with_options(
+                    &format!("{}.chunks", bucket_name),
+                    collection_options,
+                ),
+                bucket_name,
+            }),
+        }
+    }
+
+    /// Gets the name of the bucket.
+    pub fn bucket_name(&self) -> &str {
+        &self.inner.bucket_name
+    }
+
+    /// Gets the read preference of the bucket.
+    pub fn selection_criteria(&self) -> Option<&SelectionCriteria> {
+        self.inner.files.selection_criteria()
+    }
+
+    /// Gets the read concern of the bucket.
+    pub fn read_concern(&self) -> Option<&ReadConcern> {
+        self.inner.files.read_concern()
+    }
+
+    /// Opens a stream from which the contents of the file with the given `_id` can be read.
+    pub async fn open_download_stream(&self, id: Bson) -> Result<GridFsDownloadStream> {
+        match self
+            .inner
+            .files
+            .find_one(doc! { "_id": id.clone() }, None)
+            .await?
+        {
+            Some(file) => self.download_file(file).await,
+            None => Err(ErrorKind::GridFs(GridFsErrorKind::FileNotFound {
+                identifier: GridFsFileIdentifier::Id(id),
+            })
+            .into()),
+        }
+    }
+
+    /// Opens a stream from which the contents of the file named `filename` can be read. If there
+    /// are several files with that name, the one to download can be chosen with the `revision`
+    /// option; by default, the most recently uploaded one is downloaded.
+    pub async fn open_download_stream_by_name(
+        &self,
+        filename: &str,
+        options: impl Into<Option<GridFsDownloadByNameOptions>>,
+    ) -> Result<GridFsDownloadStream> {
+        let revision = options.into().and_then(|opts| opts.revision).unwrap_or(-1);
+
+        // Revisions are ordered by upload date, so non-negative revisions count forward from the
+        // oldest file and negative ones count back from the newest.
+        let (sort, skip) = if revision >= 0 {
+            (1, revision as u64)
+        } else {
+            (-1, (-(revision as i64) - 1) as u64)
+        };
+        let options = FindOneOptions::builder()
+            .sort(doc! { "uploadDate": sort })
+            .skip(skip)
+            .build();
+
+        match self
+            .inner
+            .files
+            .find_one(doc! { "filename": filename }, options)
+            .await?
+        {
+            Some(file) => self.download_file(file).await,
+            None => {
+                let kind = if self
+                    .inner
+                    .files
+                    .find_one(doc! { "filename": filename }, None)
+                    .await?
+                    .is_some()
+                {
+                    GridFsErrorKind::RevisionNotFound { revision }
+                } else {
+                    GridFsErrorKind::FileNotFound {
+                        identifier: GridFsFileIdentifier::Filename(filename.to_string()),
+                    }
+                };
+                Err(ErrorKind::GridFs(kind).into())
+            }
+        }
+    }
+
+    async fn download_file(&self, file: FilesCollectionDocument) -> Result<GridFsDownloadStream> {
+        // The chunk size comes from the server, so it has to be checked before the number and
+        // sizes of the chunks are computed from it.
+        if file.chunk_size == 0 {
+            return Err(ErrorKind::GridFs(GridFsErrorKind::InvalidChunkSize {
+                chunk_size: file.chunk_size,
+            })
+            .into());
+        }
+
+        // Empty files have no chunks, so there's no need to query for them.
+        let chunks = if file.length == 0 {
+            None
+        } else {
+            let options = FindOptions::builder().sort(doc! { "n": 1 }).build();
+            let cursor = self
+                .inner
+                .chunks
+                .find(doc! { "files_id": file.id.clone() }, options)
+                .await?;
+            Some(cursor)
+        };
+
+        Ok(GridFsDownloadStream {
+            chunks,
+            buffer: Vec::new(),
+            offset: 0,
+            next_n: 0,
+            file,
+        })
+    }
+}
+
+/// A stream from which the contents of a file stored in a [`GridFsBucket`] can be read. It
+/// implements [`futures_io::AsyncRead`](https://docs.rs/futures-io/latest/futures_io/trait.AsyncRead.html),
+/// and its chunks are fetched from the server as the stream is read.
+///
+/// A `GridFsDownloadStream` can be obtained with [`GridFsBucket::open_download_stream`] or
+/// [`GridFsBucket::open_download_stream_by_name`].
+#[derive(Debug)]
+pub struct GridFsDownloadStream {
+    /// The cursor over the file's chunks, which is `None` if the file is empty.
+    chunks: Option<Cursor<Chunk>>,
+
+    /// The contents of the chunk currently being read.
+    buffer: Vec<u8>,
+
+    /// How much of `buffer` has been read.
+    offset: usize,
+
+    /// The index of the next chunk to read.
+    next_n: u32,
+
+    file: FilesCollectionDocument,
+}
+
+impl GridFsDownloadStream {
+    /// Gets the document describing the file being downloaded.
+    pub fn file(&self) -> &FilesCollectionDocument {
+        &self.file
+    }
+
+    /// Validates that `chunk` is the next chunk of the file and moves its contents into the buffer.
+    fn load_chunk(&mut self, chunk: Option<Result<Chunk>>) -> Result<()> {
+        let chunk = match chunk {
+            Some(chunk) => chunk?,
+            None => {
+                return Err(
+                    ErrorKind::GridFs(GridFsErrorKind::MissingChunk { n: self.next_n }).into(),
+                )
+            }
+        };
+        if chunk.n != self.next_n {
+            return Err(ErrorKind::GridFs(GridFsErrorKind::MissingChunk { n: self.next_n }).into());
+        }
+
+        let expected_size = self.file.expected_chunk_size(self.next_n);
+        if chunk.data.bytes.len() != expected_size as usize {
+            return Err(ErrorKind::GridFs(GridFsErrorKind::WrongSizeChunk {
+                actual_size: chunk.data.bytes.len(),
+                expected_size,
+                n: self.next_n,
+            })
+            .into());
+        }
+
+        self.buffer = chunk.data.bytes;
+        self.offset = 0;
+        self.next_n += 1;
+        Ok(())
+    }
+}
+
+/// Wraps `error` in an `io::Error` so that it can be returned from `poll_read`. Network errors keep
+/// their original kind, and corrupted files are reported as invalid data.
+fn to_io_error(error: Error) -> io::Error {
+    let kind = match *error.kind {
+        ErrorKind::Io(ref io_error) => io_error.kind(),
+        ErrorKind::GridFs(_) => io::ErrorKind::InvalidData,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, error)
+}
+
+impl AsyncRead for GridFsDownloadStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        while this.offset == this.buffer.len() {
+            if this.next_n == this.file.num_chunks() {
+                return Poll::Ready(Ok(0));
+            }
+
+            let chunks = match this.chunks {
+                Some(ref mut chunks) => chunks,
+                None => return Poll::Ready(Ok(0)),
+            };
+            let chunk = match Pin::new(chunks).poll_next(cx) {
+                Poll::Ready(chunk) => chunk,
+                Poll::Pending => return Poll::Pending,
+            };
+            if let Err(error) = this.load_chunk(chunk) {
+                return Poll::Ready(Err(to_io_error(error)));
+            }
+        }
+
+        let read = std::cmp::min(buf.len(), this.buffer.len() - this.offset);
+        buf[..read].copy_from_slice(&this.buffer[this.offset..this.offset + read]);
+        this.offset += read;
+        Poll::Ready(Ok(read))
+    }
+}
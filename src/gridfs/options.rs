@@ -0,0 +1,40 @@
+use serde::Deserialize;
+use typed_builder::TypedBuilder;
+
+use crate::{concern::ReadConcern, selection_criteria::SelectionCriteria};
+
+/// These are the valid options for creating a [`GridFsBucket`](../gridfs/struct.GridFsBucket.html)
+/// with [`Database::gridfs_bucket`](../struct.Database.html#method.gridfs_bucket).
+#[derive(Clone, Debug, Default, Deserialize, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct GridFsBucketOptions {
+    /// The name of the bucket, which is used as the prefix of the names of its files and chunks
+    /// collections. Defaults to "fs".
+    pub bucket_name: Option<String>,
+
+    /// The read concern to use for the bucket's reads. Defaults to the read concern of the
+    /// database.
+    pub read_concern: Option<ReadConcern>,
+
+    /// The read preference to use for the bucket's reads. Defaults to the read preference of the
+    /// database.
+    pub selection_criteria: Option<SelectionCriteria>,
+}
+
+/// These are the valid options for downloading a file with
+/// [`GridFsBucket::open_download_stream_by_name`](crate::gridfs::GridFsBucket::open_download_stream_by_name).
+#[derive(Clone, Debug, Default, Deserialize, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct GridFsDownloadByNameOptions {
+    /// Which revision of the file to download, where the revisions of a file are all of the files
+    /// with its name, ordered by when they were uploaded. Defaults to -1, the most recent
+    /// revision.
+    ///
+    /// Non-negative values count from the oldest revision, so 0 is the original file and 1 is the
+    /// first revision of it. Negative values count back from the most recent revision, so -1 is
+    /// the most recent revision and -2 is the one before it.
+    pub revision: Option<i32>,
+}
@@ -5,7 +5,7 @@ use serde::Deserialize;
 use crate::{
     bson::{doc, oid::ObjectId, DateTime, Document, Timestamp},
     client::{options::ServerApi, ClusterTime},
-    cmap::{Command, Connection},
+    cmap::{Command, CommandResponse, Connection},
     error::{ErrorKind, Result},
     sdam::ServerType,
     selection_criteria::TagSet,
@@ -21,6 +21,23 @@ pub(crate) fn is_master_command(api: Option<&ServerApi>) -> Command {
     command
 }
 
+/// Construct an awaitable isMaster command. The server only replies to it once its state has
+/// changed from the one described by `topology_version` or `max_await_time` has elapsed, and then
+/// keeps streaming a reply whenever either happens again.
+pub(crate) fn awaitable_is_master_command(
+    api: Option<&ServerApi>,
+    topology_version: Document,
+    max_await_time: Duration,
+) -> Command {
+    let mut command = is_master_command(api);
+    command.body.insert("topologyVersion", topology_version);
+    command
+        .body
+        .insert("maxAwaitTimeMS", max_await_time.as_millis() as i64);
+    command.exhaust_allowed = true;
+    command
+}
+
 /// Run the given isMaster command.
 ///
 /// If the given command is not an isMaster, this function will return an error.
@@ -39,13 +56,29 @@ pub(crate) async fn run_is_master(
     let response = conn.send_command(command, None).await?;
     let end_time = Instant::now();
 
+    parse_is_master_response(response, Some(end_time.duration_since(start_time)))
+}
+
+/// Reads the next reply that the server streams in response to an awaitable isMaster. Since the
+/// server waits before sending each reply, no round trip time is measured.
+pub(crate) async fn receive_is_master(conn: &mut Connection) -> Result<IsMasterReply> {
+    let response = conn.receive_message(false).await?;
+    parse_is_master_response(response, None)
+}
+
+fn parse_is_master_response(
+    response: CommandResponse,
+    round_trip_time: Option<Duration>,
+) -> Result<IsMasterReply> {
     response.validate()?;
     let cluster_time = response.cluster_time().cloned();
+    let raw_command_response = response.raw_response.clone();
     let command_response: IsMasterCommandResponse = response.body()?;
 
     Ok(IsMasterReply {
         command_response,
-        round_trip_time: Some(end_time.duration_since(start_time)),
+        raw_command_response,
+        round_trip_time,
         cluster_time,
     })
 }
@@ -53,6 +86,7 @@ pub(crate) async fn run_is_master(
 #[derive(Debug, Clone)]
 pub(crate) struct IsMasterReply {
     pub command_response: IsMasterCommandResponse,
+    pub raw_command_response: Document,
     pub round_trip_time: Option<Duration>,
     pub cluster_time: Option<ClusterTime>,
 }
@@ -82,11 +116,16 @@ pub(crate) struct IsMasterCommandResponse {
     pub max_wire_version: Option<i32>,
     pub tags: Option<TagSet>,
     pub election_id: Option<ObjectId>,
+    pub service_id: Option<ObjectId>,
     pub primary: Option<String>,
     pub sasl_supported_mechs: Option<Vec<String>>,
     pub speculative_authenticate: Option<Document>,
     pub max_bson_object_size: i64,
     pub max_write_batch_size: i64,
+
+    /// Identifies the state of the server that this reply describes. Only reported by servers
+    /// that support the streaming monitoring protocol (4.4+).
+    pub topology_version: Option<Document>,
 }
 
 impl PartialEq for IsMasterCommandResponse {
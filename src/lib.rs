@@ -105,6 +105,7 @@ define_if_single_runtime_enabled! {
     pub use ::bson;
 
     mod bson_util;
+    pub mod change_stream;
     mod client;
     mod cmap;
     mod coll;
@@ -114,6 +115,7 @@ define_if_single_runtime_enabled! {
     mod db;
     pub mod error;
     pub mod event;
+    pub mod gridfs;
     mod is_master;
     mod operation;
     pub mod results;
@@ -131,7 +133,6 @@ define_if_single_runtime_enabled! {
     #[macro_use]
     extern crate derive_more;
 
-    #[cfg(not(feature = "sync"))]
     pub use crate::{
         client::{Client, session::ClientSession},
         coll::Collection,
@@ -139,15 +140,8 @@ define_if_single_runtime_enabled! {
         db::Database,
     };
 
-    #[cfg(feature = "sync")]
-    pub(crate) use crate::{
-        client::{Client, session::ClientSession},
-        coll::Collection,
-        cursor::{Cursor, session::{SessionCursor, SessionCursorStream}},
-        db::Database,
-    };
-
     pub use coll::Namespace;
+    pub use sdam::public::*;
 }
 
 #[cfg(all(
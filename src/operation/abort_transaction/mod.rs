@@ -2,7 +2,7 @@ use crate::{
     bson::doc,
     cmap::{Command, CommandResponse, StreamDescription},
     error::Result,
-    operation::{Operation, Retryability},
+    operation::{encode_write_concern, Operation, Retryability},
     options::WriteConcern,
 };
 
@@ -26,9 +26,7 @@ impl Operation for AbortTransaction {
         let mut body = doc! {
             Self::NAME: 1,
         };
-        if let Some(ref write_concern) = self.write_concern {
-            body.insert("writeConcern", bson::to_bson(write_concern)?);
-        }
+        encode_write_concern(&mut body, self.write_concern())?;
 
         Ok(Command::new(
             Self::NAME.to_string(),
@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use crate::{
+    bson::Timestamp,
+    cmap::{Command, CommandResponse, StreamDescription},
+    cursor::CursorSpecification,
+    error::Result,
+    operation::{Aggregate, Operation, Retryability},
+    options::SelectionCriteria,
+};
+
+/// The first wire version that reports the `operationTime` that change streams start at (4.0).
+const START_AT_OPERATION_TIME_MIN_WIRE_VERSION: i32 = 7;
+
+/// The `aggregate` that opens a change stream. In addition to the cursor, this reports the time at
+/// which the stream started if the caller needs it to be able to resume before any events have
+/// been returned.
+#[derive(Debug)]
+pub(crate) struct ChangeStreamAggregate {
+    inner: Aggregate,
+    capture_operation_time: bool,
+}
+
+impl ChangeStreamAggregate {
+    pub(crate) fn new(inner: Aggregate, capture_operation_time: bool) -> Self {
+        Self {
+            inner,
+            capture_operation_time,
+        }
+    }
+}
+
+impl Operation for ChangeStreamAggregate {
+    type O = (CursorSpecification, Option<Timestamp>);
+    const NAME: &'static str = Aggregate::NAME;
+
+    fn build(&mut self, description: &StreamDescription) -> Result<Command> {
+        self.inner.build(description)
+    }
+
+    fn handle_response(
+        &self,
+        response: CommandResponse,
+        description: &StreamDescription,
+    ) -> Result<Self::O> {
+        let operation_time = if self.capture_operation_time
+            && description.max_wire_version.unwrap_or(0) >= START_AT_OPERATION_TIME_MIN_WIRE_VERSION
+        {
+            response.operation_time()
+        } else {
+            None
+        };
+        let spec = self.inner.handle_response(response, description)?;
+        Ok((spec, operation_time))
+    }
+
+    fn selection_criteria(&self) -> Option<&SelectionCriteria> {
+        self.inner.selection_criteria()
+    }
+
+    fn supports_read_concern(&self) -> bool {
+        self.inner.supports_read_concern()
+    }
+
+    fn max_time(&self) -> Option<Duration> {
+        self.inner.max_time()
+    }
+
+    fn retryability(&self) -> Retryability {
+        self.inner.retryability()
+    }
+
+    fn cursor_specification<'a>(
+        &self,
+        output: &'a mut Self::O,
+    ) -> Option<&'a mut CursorSpecification> {
+        Some(&mut output.0)
+    }
+}
@@ -1,17 +1,33 @@
+mod change_stream;
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use crate::{
     bson::{doc, Bson, Document},
     bson_util,
     cmap::{Command, CommandResponse, StreamDescription},
     cursor::CursorSpecification,
     error::Result,
-    operation::{append_options, CursorBody, Operation, Retryability, WriteConcernOnlyBody},
+    operation::{
+        append_options,
+        encode_max_time,
+        encode_write_concern,
+        CursorBody,
+        Operation,
+        Retryability,
+        WriteConcernOnlyBody,
+    },
     options::{AggregateOptions, SelectionCriteria, WriteConcern},
     Namespace,
 };
 
+pub(crate) use change_stream::ChangeStreamAggregate;
+
+/// The first wire version that supports the `let` option (5.0).
+const LET_MIN_WIRE_VERSION: i32 = 13;
+
 #[derive(Debug)]
 pub(crate) struct Aggregate {
     target: AggregateTarget,
@@ -42,18 +58,38 @@ impl Operation for Aggregate {
     type O = CursorSpecification;
     const NAME: &'static str = "aggregate";
 
-    fn build(&mut self, _description: &StreamDescription) -> Result<Command> {
+    fn build(&mut self, description: &StreamDescription) -> Result<Command> {
+        if let Some(ref options) = self.options {
+            if let Some(ref read_concern) = options.read_concern {
+                read_concern.validate(options.selection_criteria.as_ref())?;
+            }
+        }
+
         let mut body = doc! {
             Self::NAME: self.target.to_bson(),
             "pipeline": bson_util::to_bson_array(&self.pipeline),
             "cursor": {}
         };
         append_options(&mut body, self.options.as_ref())?;
+        encode_max_time(
+            &mut body,
+            self.options.as_ref().and_then(|opts| opts.max_time),
+        )?;
+
+        // Servers older than 5.0 reject the `let` option, so it's omitted for them.
+        if description.max_wire_version.unwrap_or(0) < LET_MIN_WIRE_VERSION {
+            body.remove("let");
+        }
 
         if self.is_out_or_merge() {
             if let Ok(cursor_doc) = body.get_document_mut("cursor") {
                 cursor_doc.remove("batchSize");
             }
+            encode_write_concern(&mut body, self.write_concern())?;
+        } else {
+            // Only aggregations that write their results accept a write concern, so the one
+            // inherited from the collection or database is dropped for all others.
+            body.remove("writeConcern");
         }
 
         Ok(Command::new(
@@ -65,7 +101,7 @@ impl Operation for Aggregate {
 
     fn handle_response(
         &self,
-        response: CommandResponse,
+        mut response: CommandResponse,
         _description: &StreamDescription,
     ) -> Result<Self::O> {
         let source_address = response.source_address().clone();
@@ -75,16 +111,22 @@ impl Operation for Aggregate {
             error_body.validate()?;
         };
 
+        let raw_batch_lengths = response.take_raw_batch_lengths();
         let body: CursorBody = response.body()?;
 
-        Ok(CursorSpecification::new(
+        let mut spec = CursorSpecification::new(
             body.cursor.ns,
             source_address,
             body.cursor.id,
             self.options.as_ref().and_then(|opts| opts.batch_size),
             self.options.as_ref().and_then(|opts| opts.max_await_time),
+            None,
             body.cursor.first_batch,
-        ))
+        );
+        spec.info.operation_name = Self::NAME;
+        spec.initial_batch_lengths = raw_batch_lengths;
+        spec.post_batch_resume_token = body.cursor.post_batch_resume_token;
+        Ok(spec)
     }
 
     fn selection_criteria(&self) -> Option<&SelectionCriteria> {
@@ -94,11 +136,26 @@ impl Operation for Aggregate {
     }
 
     fn write_concern(&self) -> Option<&WriteConcern> {
+        if !self.is_out_or_merge() {
+            return None;
+        }
         self.options
             .as_ref()
             .and_then(|opts| opts.write_concern.as_ref())
     }
 
+    fn is_write(&self) -> bool {
+        self.is_out_or_merge()
+    }
+
+    fn supports_read_concern(&self) -> bool {
+        true
+    }
+
+    fn max_time(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.max_time)
+    }
+
     fn retryability(&self) -> Retryability {
         if self.is_out_or_merge() {
             Retryability::None
@@ -106,6 +163,13 @@ impl Operation for Aggregate {
             Retryability::Read
         }
     }
+
+    fn cursor_specification<'a>(
+        &self,
+        output: &'a mut Self::O,
+    ) -> Option<&'a mut CursorSpecification> {
+        Some(output)
+    }
 }
 
 impl Aggregate {
@@ -7,8 +7,8 @@ use crate::{
     cmap::{CommandResponse, StreamDescription},
     concern::{ReadConcern, ReadConcernLevel},
     error::{ErrorKind, WriteFailure},
-    operation::{test, Aggregate, Operation},
-    options::{AggregateOptions, Hint, ServerAddress},
+    operation::{test, Aggregate, GetMore, Operation},
+    options::{Acknowledgment, AggregateOptions, Hint, ServerAddress, WriteConcern},
     Namespace,
 };
 
@@ -100,6 +100,16 @@ async fn build_batch_size() {
         expected_body.clone(),
     );
 
+    // a batch size of 0 asks the server to open the cursor with an empty first batch.
+    let mut zero_batch_size_body = expected_body.clone();
+    zero_batch_size_body.insert("cursor", doc! { "batchSize": 0 });
+    build_test(
+        ns.clone(),
+        Vec::new(),
+        Some(AggregateOptions::builder().batch_size(0).build()),
+        zero_batch_size_body,
+    );
+
     let out_pipeline = vec![doc! { "$out": "cat" }];
     expected_body.insert("cursor", Document::new());
     expected_body.insert("pipeline", bson_util::to_bson_array(&out_pipeline));
@@ -144,6 +154,90 @@ async fn build_target() {
     build_test(ns.db, pipeline, None, expected_body);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_write_concern() {
+    let ns = Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    };
+    let options = AggregateOptions::builder()
+        .write_concern(WriteConcern::builder().w(Acknowledgment::Majority).build())
+        .build();
+
+    let pipeline = vec![doc! { "$merge": { "into": "out_coll" } }];
+    let expected_body = doc! {
+        "aggregate": "test_coll",
+        "pipeline": bson_util::to_bson_array(&pipeline),
+        "cursor": {},
+        "writeConcern": { "w": "majority" },
+    };
+    build_test(ns.clone(), pipeline, Some(options.clone()), expected_body);
+
+    let pipeline = vec![doc! { "$match": { "x": 3 } }];
+    let expected_body = doc! {
+        "aggregate": "test_coll",
+        "pipeline": bson_util::to_bson_array(&pipeline),
+        "cursor": {},
+    };
+    build_test(ns.clone(), pipeline, Some(options), expected_body);
+
+    // A default write concern is left for the server to fill in.
+    let options = AggregateOptions::builder()
+        .write_concern(WriteConcern::default())
+        .build();
+    let pipeline = vec![doc! { "$out": "out_coll" }];
+    let expected_body = doc! {
+        "aggregate": "test_coll",
+        "pipeline": bson_util::to_bson_array(&pipeline),
+        "cursor": {},
+    };
+    build_test(ns, pipeline, Some(options), expected_body);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn op_write_concern() {
+    let options = AggregateOptions::builder()
+        .write_concern(WriteConcern::builder().w(Acknowledgment::Nodes(0)).build())
+        .build();
+
+    let aggregate = Aggregate::new(
+        Namespace::empty(),
+        vec![doc! { "$out": "out_coll" }],
+        Some(options.clone()),
+    );
+    assert_eq!(aggregate.write_concern(), options.write_concern.as_ref());
+
+    let aggregate = Aggregate::new(
+        Namespace::empty(),
+        vec![doc! { "$match": { "x": 3 } }],
+        Some(options),
+    );
+    assert_eq!(aggregate.write_concern(), None);
+    assert!(aggregate.is_acknowledged());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_let_vars() {
+    let options = AggregateOptions::builder()
+        .let_vars(doc! { "multiplier": 3 })
+        .build();
+    let pipeline = vec![doc! { "$project": { "y": { "$multiply": ["$x", "$$multiplier"] } } }];
+    let mut aggregate = Aggregate::new(Namespace::empty(), pipeline, Some(options));
+
+    let mut description = StreamDescription::new_testing();
+    description.max_wire_version = Some(13);
+    let cmd = aggregate.build(&description).unwrap();
+    assert_eq!(cmd.body.get_document("let"), Ok(&doc! { "multiplier": 3 }));
+
+    // The option is omitted for pre-5.0 servers, which don't support it.
+    description.max_wire_version = Some(12);
+    let cmd = aggregate.build(&description).unwrap();
+    assert!(!cmd.body.contains_key("let"));
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn build_max_await_time() {
@@ -248,6 +342,41 @@ async fn handle_success() {
     );
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_database_target() {
+    let aggregate = Aggregate::new("admin".to_string(), Vec::new(), None);
+
+    let response = doc! {
+        "cursor": {
+            "id": 123,
+            "ns": "admin.$cmd.aggregate",
+            "firstBatch": [],
+        },
+        "ok": 1.0
+    };
+
+    let cursor_spec = aggregate
+        .handle_response(
+            CommandResponse::with_document(response),
+            &Default::default(),
+        )
+        .expect("handle should succeed");
+    assert_eq!(cursor_spec.info.ns.db, "admin");
+    assert_eq!(cursor_spec.info.ns.coll, "$cmd.aggregate");
+
+    // the getMore must use the collection exactly as the server reported it.
+    let mut get_more = GetMore::new(cursor_spec.info);
+    let command = get_more
+        .build(&StreamDescription::new_testing())
+        .expect("build should succeed");
+    assert_eq!(command.target_db, "admin");
+    assert_eq!(
+        command.body,
+        doc! { "getMore": 123_i64, "collection": "$cmd.aggregate" }
+    );
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_max_await_time() {
@@ -5,7 +5,7 @@ use bson::doc;
 use crate::{
     cmap::{Command, CommandResponse, StreamDescription},
     error::Result,
-    operation::{append_options, Operation, Retryability},
+    operation::{append_options, encode_max_time, encode_write_concern, Operation, Retryability},
     options::{Acknowledgment, TransactionOptions, WriteConcern},
 };
 
@@ -31,6 +31,11 @@ impl Operation for CommitTransaction {
         };
 
         append_options(&mut body, self.options.as_ref())?;
+        encode_max_time(
+            &mut body,
+            self.options.as_ref().and_then(|opts| opts.max_commit_time),
+        )?;
+        encode_write_concern(&mut body, self.write_concern())?;
 
         Ok(Command::new(
             Self::NAME.to_string(),
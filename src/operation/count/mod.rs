@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use serde::Deserialize;
 
 use crate::{
@@ -8,7 +10,7 @@ use crate::{
     cmap::{Command, CommandResponse, StreamDescription},
     coll::{options::EstimatedDocumentCountOptions, Namespace},
     error::{Error, ErrorKind, Result},
-    operation::{append_options, CursorBody, Operation, Retryability},
+    operation::{append_options, encode_max_time, CursorBody, Operation, Retryability},
     selection_criteria::SelectionCriteria,
 };
 
@@ -41,6 +43,12 @@ impl Operation for Count {
     const NAME: &'static str = "count";
 
     fn build(&mut self, description: &StreamDescription) -> Result<Command> {
+        if let Some(ref options) = self.options {
+            if let Some(ref read_concern) = options.read_concern {
+                read_concern.validate(options.selection_criteria.as_ref())?;
+            }
+        }
+
         let mut body = match description.max_wire_version {
             Some(v) if v >= SERVER_4_9_0_WIRE_VERSION => {
                 doc! {
@@ -67,6 +75,10 @@ impl Operation for Count {
         };
 
         append_options(&mut body, self.options.as_ref())?;
+        encode_max_time(
+            &mut body,
+            self.options.as_ref().and_then(|opts| opts.max_time),
+        )?;
 
         Ok(Command::new(
             Self::NAME.to_string(),
@@ -115,6 +127,14 @@ impl Operation for Count {
         None
     }
 
+    fn supports_read_concern(&self) -> bool {
+        true
+    }
+
+    fn max_time(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.max_time)
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
@@ -122,5 +142,7 @@ impl Operation for Count {
 
 #[derive(Debug, Deserialize)]
 struct ResponseBody {
+    // Older servers report the count as a double.
+    #[serde(deserialize_with = "crate::bson_util::deserialize_u64_from_bson_number")]
     n: u64,
 }
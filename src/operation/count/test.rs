@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use crate::{
-    bson::doc,
+    bson::{doc, Bson},
     cmap::{CommandResponse, StreamDescription},
     coll::{options::EstimatedDocumentCountOptions, Namespace},
     concern::ReadConcern,
@@ -86,6 +86,27 @@ async fn handle_success() {
     assert_eq!(actual_values, n);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success_any_numeric_type() {
+    let count_op = Count::empty();
+
+    for n in [Bson::Int32(26), Bson::Int64(26), Bson::Double(26.0)].iter() {
+        let response = CommandResponse::with_document(doc! { "n" : n.clone(), "ok" : 1 });
+
+        let actual_value = count_op
+            .handle_response(response, &Default::default())
+            .unwrap_or_else(|e| panic!("count with n = {:?} should succeed: {:?}", n, e));
+
+        assert_eq!(actual_value, 26);
+    }
+
+    let response = CommandResponse::with_document(doc! { "n" : 26.5, "ok" : 1 });
+    count_op
+        .handle_response(response, &Default::default())
+        .expect_err("non-integral count should fail");
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_response_no_n() {
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use bson::{doc, Document};
 
 use super::{Operation, Retryability};
@@ -114,6 +116,14 @@ impl Operation for CountDocuments {
         self.aggregate.selection_criteria()
     }
 
+    fn supports_read_concern(&self) -> bool {
+        true
+    }
+
+    fn max_time(&self) -> Option<Duration> {
+        self.aggregate.max_time()
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
@@ -78,6 +78,37 @@ async fn build_with_options() {
     assert_eq!(count_command.target_db, "test_db");
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_with_document_hint() {
+    let options = CountOptions::builder()
+        .hint(Hint::Keys(doc! { "x": 1 }))
+        .build();
+    let ns = Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    };
+    let mut count_op = CountDocuments::new(ns, None, Some(options));
+    let mut count_command = count_op
+        .build(&StreamDescription::new_testing())
+        .expect("error on build");
+
+    let mut expected_body = doc! {
+        "aggregate": "test_coll",
+        "pipeline": [
+            { "$match": {} },
+            { "$group": { "_id": 1, "n": { "$sum": 1 } } },
+        ],
+        "hint": { "x": 1 },
+        "cursor": { },
+    };
+
+    bson_util::sort_document(&mut expected_body);
+    bson_util::sort_document(&mut count_command.body);
+
+    assert_eq!(count_command.body, expected_body);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn op_selection_criteria() {
@@ -120,3 +151,29 @@ async fn handle_success() {
 
     assert_eq!(actual_values, n);
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success_double() {
+    let count_op = CountDocuments::new(Namespace::empty(), None, None);
+
+    let response = CommandResponse::with_document(doc! {
+        "cursor" : {
+            "firstBatch" : [
+                {
+                    "_id" : 1,
+                    "n" : 26.0
+                }
+            ],
+            "id" : 0,
+            "ns" : "test_db.test_coll"
+        },
+        "ok" : 1
+    });
+
+    let actual_value = count_op
+        .handle_response(response, &Default::default())
+        .expect("supposed to succeed");
+
+    assert_eq!(actual_value, 26);
+}
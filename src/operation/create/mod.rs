@@ -4,12 +4,15 @@ mod test;
 use crate::{
     bson::doc,
     cmap::{Command, CommandResponse, StreamDescription},
-    error::Result,
-    operation::{append_options, Operation, WriteConcernOnlyBody},
+    error::{ErrorKind, Result},
+    operation::{append_options, encode_write_concern, Operation, WriteConcernOnlyBody},
     options::{CreateCollectionOptions, WriteConcern},
     Namespace,
 };
 
+/// The first wire version that supports clustered collections (5.3).
+const CLUSTERED_INDEX_MIN_WIRE_VERSION: i32 = 17;
+
 #[derive(Debug)]
 pub(crate) struct Create {
     ns: Namespace,
@@ -37,11 +40,26 @@ impl Operation for Create {
     type O = ();
     const NAME: &'static str = "create";
 
-    fn build(&mut self, _description: &StreamDescription) -> Result<Command> {
+    fn build(&mut self, description: &StreamDescription) -> Result<Command> {
+        if self
+            .options
+            .as_ref()
+            .and_then(|opts| opts.clustered_index.as_ref())
+            .is_some()
+            && description.max_wire_version.unwrap_or(0) < CLUSTERED_INDEX_MIN_WIRE_VERSION
+        {
+            return Err(ErrorKind::InvalidArgument {
+                message: "Clustered collections are not supported on server versions < 5.3"
+                    .to_string(),
+            }
+            .into());
+        }
+
         let mut body = doc! {
             Self::NAME: self.ns.coll.clone(),
         };
         append_options(&mut body, self.options.as_ref())?;
+        encode_write_concern(&mut body, self.write_concern())?;
 
         Ok(Command::new(
             Self::NAME.to_string(),
@@ -63,4 +81,8 @@ impl Operation for Create {
             .as_ref()
             .and_then(|opts| opts.write_concern.as_ref())
     }
+
+    fn is_write(&self) -> bool {
+        true
+    }
 }
@@ -4,7 +4,7 @@ use crate::{
     concern::WriteConcern,
     error::{ErrorKind, WriteFailure},
     operation::{Create, Operation},
-    options::{CreateCollectionOptions, ValidationAction, ValidationLevel},
+    options::{ClusteredIndex, CreateCollectionOptions, ValidationAction, ValidationLevel},
     Namespace,
 };
 
@@ -72,6 +72,36 @@ async fn build_validator() {
     );
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_clustered_index() {
+    let mut op = Create::new(
+        Namespace {
+            db: "test_db".to_string(),
+            coll: "test_coll".to_string(),
+        },
+        Some(CreateCollectionOptions {
+            clustered_index: Some(ClusteredIndex::default()),
+            ..Default::default()
+        }),
+    );
+
+    let mut description = StreamDescription::new_testing();
+    description.max_wire_version = Some(17);
+    let cmd = op.build(&description).unwrap();
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "create": "test_coll",
+            "clusteredIndex": { "key": { "_id": 1 }, "unique": true },
+        }
+    );
+
+    description.max_wire_version = Some(16);
+    let error = op.build(&description).unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_success() {
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod test;
+
+use std::time::Duration;
+
+use crate::{
+    bson::{doc, Bson},
+    cmap::{Command, CommandResponse, StreamDescription},
+    error::Result,
+    operation::{
+        append_options,
+        encode_max_time,
+        encode_write_concern,
+        Operation,
+        WriteConcernOnlyBody,
+    },
+    options::{CreateIndexOptions, IndexModel, WriteConcern},
+    Namespace,
+};
+
+#[derive(Debug)]
+pub(crate) struct CreateIndexes {
+    ns: Namespace,
+    indexes: Vec<IndexModel>,
+    options: Option<CreateIndexOptions>,
+}
+
+impl CreateIndexes {
+    pub(crate) fn new(
+        ns: Namespace,
+        indexes: Vec<IndexModel>,
+        options: Option<CreateIndexOptions>,
+    ) -> Self {
+        CreateIndexes {
+            ns,
+            indexes,
+            options,
+        }
+    }
+
+    #[cfg(test)]
+    fn empty() -> Self {
+        Self::new(
+            Namespace {
+                db: String::new(),
+                coll: String::new(),
+            },
+            Vec::new(),
+            None,
+        )
+    }
+}
+
+impl Operation for CreateIndexes {
+    type O = Vec<String>;
+    const NAME: &'static str = "createIndexes";
+
+    fn build(&mut self, _description: &StreamDescription) -> Result<Command> {
+        let mut indexes = Vec::with_capacity(self.indexes.len());
+        for index in &self.indexes {
+            let mut index_doc = bson::to_document(index)?;
+            index_doc.insert("name", index.name());
+            indexes.push(Bson::Document(index_doc));
+        }
+
+        let mut body = doc! {
+            Self::NAME: self.ns.coll.clone(),
+            "indexes": indexes,
+        };
+
+        append_options(&mut body, self.options.as_ref())?;
+        encode_max_time(&mut body, self.max_time())?;
+        encode_write_concern(&mut body, self.write_concern())?;
+
+        Ok(Command::new(
+            Self::NAME.to_string(),
+            self.ns.db.clone(),
+            body,
+        ))
+    }
+
+    fn handle_response(
+        &self,
+        response: CommandResponse,
+        _description: &StreamDescription,
+    ) -> Result<Self::O> {
+        response.body::<WriteConcernOnlyBody>()?.validate()?;
+        Ok(self.indexes.iter().map(IndexModel::name).collect())
+    }
+
+    fn write_concern(&self) -> Option<&WriteConcern> {
+        self.options
+            .as_ref()
+            .and_then(|opts| opts.write_concern.as_ref())
+    }
+
+    fn is_write(&self) -> bool {
+        true
+    }
+
+    fn max_time(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.max_time)
+    }
+}
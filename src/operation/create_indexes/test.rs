@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use crate::{
+    bson::doc,
+    cmap::{CommandResponse, StreamDescription},
+    concern::{Acknowledgment, WriteConcern},
+    error::{ErrorKind, WriteFailure},
+    operation::{CreateIndexes, Operation},
+    options::{CreateIndexOptions, IndexModel, IndexOptions},
+    Namespace,
+};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build() {
+    let ns = Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    };
+    let indexes = vec![
+        IndexModel::builder().keys(doc! { "a": 1, "b": -1 }).build(),
+        IndexModel::builder()
+            .keys(doc! { "c": "text" })
+            .options(
+                IndexOptions::builder()
+                    .name("c_text_index".to_string())
+                    .unique(true)
+                    .build(),
+            )
+            .build(),
+    ];
+    let options = CreateIndexOptions::builder()
+        .max_time(Duration::from_millis(500))
+        .write_concern(WriteConcern {
+            w: Some(Acknowledgment::Majority),
+            ..Default::default()
+        })
+        .build();
+
+    let mut op = CreateIndexes::new(ns, indexes, Some(options));
+
+    let description = StreamDescription::new_testing();
+    let cmd = op.build(&description).expect("build should succeed");
+
+    assert_eq!(cmd.name.as_str(), "createIndexes");
+    assert_eq!(cmd.target_db.as_str(), "test_db");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "createIndexes": "test_coll",
+            "indexes": [
+                { "key": { "a": 1, "b": -1 }, "name": "a_1_b_-1" },
+                { "key": { "c": "text" }, "name": "c_text_index", "unique": true },
+            ],
+            "maxTimeMS": 500,
+            "writeConcern": { "w": "majority" },
+        }
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success() {
+    let mut op = CreateIndexes::empty();
+    op.indexes
+        .push(IndexModel::builder().keys(doc! { "x": 1 }).build());
+
+    let response = CommandResponse::with_document(doc! {
+        "createdCollectionAutomatically": false,
+        "numIndexesBefore": 1,
+        "numIndexesAfter": 2,
+        "ok": 1.0,
+    });
+    let names = op
+        .handle_response(response, &Default::default())
+        .expect("handle should succeed");
+    assert_eq!(names, vec!["x_1".to_string()]);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_write_concern_error() {
+    let op = CreateIndexes::empty();
+
+    let response = CommandResponse::with_document(doc! {
+        "writeConcernError": {
+            "code": 100,
+            "codeName": "hello world",
+            "errmsg": "12345"
+        },
+        "ok": 1
+    });
+
+    let result = op.handle_response(response, &Default::default());
+    match *result.unwrap_err().kind {
+        ErrorKind::Write(WriteFailure::WriteConcernError(ref wc_err)) => {
+            assert_eq!(wc_err.code, 100);
+            assert_eq!(wc_err.code_name, "hello world");
+            assert_eq!(wc_err.message, "12345");
+        }
+        ref e => panic!("expected write concern error, got {:?}", e),
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
     coll::Namespace,
     collation::Collation,
     error::{convert_bulk_errors, Result},
-    operation::{append_options, Operation, Retryability, WriteResponseBody},
+    operation::{append_options, encode_write_concern, Operation, Retryability, WriteResponseBody},
     options::{DeleteOptions, Hint, WriteConcern},
     results::DeleteResult,
 };
@@ -68,7 +68,7 @@ impl Operation for Delete {
         }
 
         if let Some(ref hint) = self.hint {
-            delete.insert("hint", bson::to_bson(&hint)?);
+            delete.insert("hint", hint.to_bson());
         }
 
         let mut body = doc! {
@@ -78,6 +78,7 @@ impl Operation for Delete {
         };
 
         append_options(&mut body, self.options.as_ref())?;
+        encode_write_concern(&mut body, self.write_concern())?;
 
         Ok(Command::new(
             Self::NAME.to_string(),
@@ -91,11 +92,16 @@ impl Operation for Delete {
         response: CommandResponse,
         _description: &StreamDescription,
     ) -> Result<Self::O> {
+        if !self.is_acknowledged() {
+            return Ok(DeleteResult::unacknowledged());
+        }
+
         let body: WriteResponseBody = response.body()?;
         body.validate().map_err(convert_bulk_errors)?;
 
         Ok(DeleteResult {
             deleted_count: body.n,
+            acknowledged: true,
         })
     }
 
@@ -105,6 +111,14 @@ impl Operation for Delete {
             .and_then(|opts| opts.write_concern.as_ref())
     }
 
+    fn is_write(&self) -> bool {
+        true
+    }
+
+    fn supports_unacknowledged_writes(&self) -> bool {
+        true
+    }
+
     fn retryability(&self) -> Retryability {
         if self.limit == 1 {
             Retryability::Write
@@ -112,6 +112,25 @@ async fn handle_success() {
 
     let delete_result = ok_result.unwrap();
     assert_eq!(delete_result.deleted_count, 3);
+    assert!(delete_result.acknowledged);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_unacknowledged() {
+    let options = DeleteOptions::builder()
+        .write_concern(WriteConcern::builder().w(Acknowledgment::Nodes(0)).build())
+        .build();
+    let mut op = Delete::new(Namespace::empty(), doc! {}, Some(1), Some(options));
+    assert!(op.supports_unacknowledged_writes());
+
+    // w: 0 has to be sent, otherwise the server would acknowledge the write.
+    let cmd = op.build(&StreamDescription::new_testing()).unwrap();
+    assert_eq!(cmd.body.get_document("writeConcern"), Ok(&doc! { "w": 0 }));
+
+    let response = CommandResponse::with_document(doc! { "ok": 1.0 });
+    let delete_result = op.handle_response(response, &Default::default()).unwrap();
+    assert!(!delete_result.acknowledged);
 }
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
@@ -193,6 +212,7 @@ async fn handle_write_concern_failure() {
                     "wtimeout": 0,
                     "provenance": "clientSupplied"
                 } }),
+                labels: vec![],
             };
             assert_eq!(wc_error, &expected_wc_err);
         }
@@ -1,14 +1,14 @@
 #[cfg(test)]
 mod test;
 
-use serde::Deserialize;
+use std::time::Duration;
 
 use crate::{
     bson::{doc, Bson, Document},
     cmap::{Command, CommandResponse, StreamDescription},
     coll::{options::DistinctOptions, Namespace},
-    error::Result,
-    operation::{append_options, Operation, Retryability},
+    error::{ErrorKind, Result},
+    operation::{append_options, encode_max_time, Operation, Retryability},
     selection_criteria::SelectionCriteria,
 };
 
@@ -53,6 +53,12 @@ impl Operation for Distinct {
     const NAME: &'static str = "distinct";
 
     fn build(&mut self, _description: &StreamDescription) -> Result<Command> {
+        if let Some(ref options) = self.options {
+            if let Some(ref read_concern) = options.read_concern {
+                read_concern.validate(options.selection_criteria.as_ref())?;
+            }
+        }
+
         let mut body: Document = doc! {
             Self::NAME: self.ns.coll.clone(),
             "key": self.field_name.clone(),
@@ -63,6 +69,10 @@ impl Operation for Distinct {
         }
 
         append_options(&mut body, self.options.as_ref())?;
+        encode_max_time(
+            &mut body,
+            self.options.as_ref().and_then(|opts| opts.max_time),
+        )?;
 
         Ok(Command::new(
             Self::NAME.to_string(),
@@ -72,10 +82,18 @@ impl Operation for Distinct {
     }
     fn handle_response(
         &self,
-        response: CommandResponse,
+        mut response: CommandResponse,
         _description: &StreamDescription,
     ) -> Result<Self::O> {
-        response.body::<ResponseBody>().map(|body| body.values)
+        // The values are taken from the raw response rather than deserialized, since the `bson`
+        // crate's deserializer can't produce `Decimal128` values and they'd otherwise be lost.
+        match response.raw_response.remove("values") {
+            Some(Bson::Array(values)) => Ok(values),
+            _ => Err(ErrorKind::InvalidResponse {
+                message: "server response to distinct did not contain a 'values' array".into(),
+            }
+            .into()),
+        }
     }
 
     fn selection_criteria(&self) -> Option<&SelectionCriteria> {
@@ -85,12 +103,15 @@ impl Operation for Distinct {
         None
     }
 
+    fn supports_read_concern(&self) -> bool {
+        true
+    }
+
+    fn max_time(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.max_time)
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
 }
-
-#[derive(Debug, Deserialize)]
-struct ResponseBody {
-    values: Vec<Bson>,
-}
@@ -4,8 +4,10 @@ use crate::{
     bson::{doc, Bson},
     cmap::{CommandResponse, StreamDescription},
     coll::{options::DistinctOptions, Namespace},
+    collation::{Collation, CollationStrength},
     error::ErrorKind,
     operation::{test, Distinct, Operation},
+    test::decimal128_one_and_a_half_and_one_tenth,
 };
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
@@ -80,6 +82,39 @@ async fn build_with_options() {
     assert_eq!(distinct_command.target_db, "test_db");
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_with_collation() {
+    let field_name = "field_name".to_string();
+    let collation = Collation::builder()
+        .locale("en")
+        .strength(CollationStrength::Secondary)
+        .case_level(false)
+        .build();
+    let options = DistinctOptions::builder().collation(collation).build();
+    let ns = Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    };
+    let mut distinct_op = Distinct::new(ns, field_name.clone(), None, Some(options));
+    let distinct_command = distinct_op
+        .build(&StreamDescription::new_testing())
+        .expect("error on build");
+
+    assert_eq!(
+        distinct_command.body,
+        doc! {
+            "distinct": "test_coll",
+            "key": field_name,
+            "collation": {
+                "locale": "en",
+                "strength": 2,
+                "caseLevel": false,
+            },
+        }
+    );
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn op_selection_criteria() {
@@ -112,6 +147,26 @@ async fn handle_success() {
     assert_eq!(actual_values, expected_values);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_response_with_decimal128_values() {
+    let distinct_op = Distinct::empty();
+
+    let (one_and_a_half, one_tenth) = decimal128_one_and_a_half_and_one_tenth();
+    let expected_values = vec![one_and_a_half, one_tenth];
+
+    let response = CommandResponse::with_document(doc! {
+       "values" : expected_values.clone(),
+       "ok" : 1
+    });
+
+    let actual_values = distinct_op
+        .handle_response(response, &Default::default())
+        .expect("supposed to succeed");
+
+    assert_eq!(actual_values, expected_values);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_response_with_empty_values() {
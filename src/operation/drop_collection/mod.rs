@@ -5,7 +5,7 @@ use crate::{
     bson::doc,
     cmap::{Command, CommandResponse, StreamDescription},
     error::{Error, Result},
-    operation::{append_options, Operation, WriteConcernOnlyBody},
+    operation::{append_options, encode_write_concern, Operation, WriteConcernOnlyBody},
     options::{DropCollectionOptions, WriteConcern},
     Namespace,
 };
@@ -43,6 +43,7 @@ impl Operation for DropCollection {
         };
 
         append_options(&mut body, self.options.as_ref())?;
+        encode_write_concern(&mut body, self.write_concern())?;
 
         Ok(Command::new(
             Self::NAME.to_string(),
@@ -72,4 +73,8 @@ impl Operation for DropCollection {
             .as_ref()
             .and_then(|opts| opts.write_concern.as_ref())
     }
+
+    fn is_write(&self) -> bool {
+        true
+    }
 }
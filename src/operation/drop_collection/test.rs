@@ -16,6 +16,7 @@ async fn build() {
             w: Some(Acknowledgment::Custom("abc".to_string())),
             ..Default::default()
         }),
+        encrypted_fields: Some(doc! { "fields": [] }),
     };
 
     let ns = Namespace {
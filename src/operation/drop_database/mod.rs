@@ -5,7 +5,7 @@ use crate::{
     bson::doc,
     cmap::{Command, CommandResponse, StreamDescription},
     error::Result,
-    operation::{append_options, Operation, WriteConcernOnlyBody},
+    operation::{append_options, encode_write_concern, Operation, WriteConcernOnlyBody},
     options::{DropDatabaseOptions, WriteConcern},
 };
 
@@ -36,6 +36,7 @@ impl Operation for DropDatabase {
         };
 
         append_options(&mut body, self.options.as_ref())?;
+        encode_write_concern(&mut body, self.write_concern())?;
 
         Ok(Command::new(
             Self::NAME.to_string(),
@@ -57,4 +58,8 @@ impl Operation for DropDatabase {
             .as_ref()
             .and_then(|opts| opts.write_concern.as_ref())
     }
+
+    fn is_write(&self) -> bool {
+        true
+    }
 }
@@ -1,12 +1,15 @@
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use crate::{
     bson::{doc, Document},
+    bson_util::get_int,
     cmap::{Command, CommandResponse, StreamDescription},
     cursor::CursorSpecification,
     error::{ErrorKind, Result},
-    operation::{append_options, CursorBody, Operation, Retryability},
+    operation::{append_options, encode_max_time, CursorBody, Operation, Retryability},
     options::{CursorType, FindOptions, SelectionCriteria},
     Namespace,
 };
@@ -42,6 +45,10 @@ impl Find {
             options,
         }
     }
+
+    fn utf8_lossy(&self) -> bool {
+        self.options.as_ref().and_then(|opts| opts.utf8_lossy) == Some(true)
+    }
 }
 
 impl Operation for Find {
@@ -54,6 +61,11 @@ impl Operation for Find {
         };
 
         if let Some(ref options) = self.options {
+            validate_options(options)?;
+            if let Some(ref read_concern) = options.read_concern {
+                read_concern.validate(options.selection_criteria.as_ref())?;
+            }
+
             // negative limits should be interpreted as request for single batch as per crud spec.
             if options.limit.map(|limit| limit < 0) == Some(true) {
                 body.insert("singleBatch", true);
@@ -83,34 +95,52 @@ impl Operation for Find {
         }
 
         append_options(&mut body, self.options.as_ref())?;
+        encode_max_time(
+            &mut body,
+            self.options.as_ref().and_then(|opts| opts.max_time),
+        )?;
 
         if let Some(ref filter) = self.filter {
             body.insert("filter", filter.clone());
         }
 
-        Ok(Command::new(
-            Self::NAME.to_string(),
-            self.ns.db.clone(),
-            body,
-        ))
+        let mut command = Command::new(Self::NAME.to_string(), self.ns.db.clone(), body);
+        command.utf8_lossy = self.utf8_lossy();
+        Ok(command)
     }
 
     fn handle_response(
         &self,
-        response: CommandResponse,
-        _description: &StreamDescription,
+        mut response: CommandResponse,
+        description: &StreamDescription,
     ) -> Result<Self::O> {
         let source_address = response.source_address().clone();
+        let raw_batch_lengths = response.take_raw_batch_lengths();
         let body: CursorBody = response.body()?;
 
-        Ok(CursorSpecification::new(
+        // The comment is only propagated to getMore and killCursors for 4.4+ servers, since
+        // older servers reject it on those commands.
+        let comment = if description.max_wire_version.unwrap_or(0) >= 9 {
+            self.options.as_ref().and_then(|opts| opts.comment.clone())
+        } else {
+            None
+        };
+
+        let mut spec = CursorSpecification::new(
             self.ns.clone(),
             source_address,
             body.cursor.id,
             self.options.as_ref().and_then(|opts| opts.batch_size),
             self.options.as_ref().and_then(|opts| opts.max_await_time),
+            comment,
             body.cursor.first_batch,
-        ))
+        );
+        spec.info.utf8_lossy = self.utf8_lossy();
+        spec.info.partial_results_returned = body.cursor.partial_results_returned;
+        spec.info.operation_name = Self::NAME;
+        spec.initial_batch_lengths = raw_batch_lengths;
+        spec.info.exhaust = self.options.as_ref().and_then(|opts| opts.exhaust) == Some(true);
+        Ok(spec)
     }
 
     fn selection_criteria(&self) -> Option<&SelectionCriteria> {
@@ -119,7 +149,63 @@ impl Operation for Find {
             .and_then(|opts| opts.selection_criteria.as_ref())
     }
 
+    fn supports_read_concern(&self) -> bool {
+        true
+    }
+
+    fn max_time(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.max_time)
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
+
+    fn cursor_specification<'a>(
+        &self,
+        output: &'a mut Self::O,
+    ) -> Option<&'a mut CursorSpecification> {
+        Some(output)
+    }
+}
+
+/// Checks for combinations of options that the server would either reject or silently ignore.
+fn validate_options(options: &FindOptions) -> Result<()> {
+    let invalid = |message: &str| -> Result<()> {
+        Err(ErrorKind::InvalidArgument {
+            message: message.to_string(),
+        }
+        .into())
+    };
+
+    let tailable = matches!(
+        options.cursor_type,
+        Some(CursorType::Tailable) | Some(CursorType::TailableAwait)
+    );
+
+    // The server only accepts tailable cursors sorted in ascending natural order, which is the
+    // order they return documents in anyway.
+    let natural_order =
+        |sort: &Document| sort.len() == 1 && sort.get("$natural").and_then(get_int) == Some(1);
+    if tailable && options.sort.as_ref().map(natural_order) == Some(false) {
+        return invalid(
+            "cursor_type and sort cannot both be specified: tailable cursors return documents in \
+             natural order, so only { $natural: 1 } is allowed",
+        );
+    }
+
+    if tailable && options.limit.is_some() {
+        return invalid(
+            "cursor_type and limit cannot both be specified: tailable cursors cannot be limited",
+        );
+    }
+
+    if options.limit.map(|limit| limit < 0) == Some(true) && options.batch_size.is_some() {
+        return invalid(
+            "limit and batch_size cannot both be specified when limit is negative: a negative \
+             limit requests a single batch",
+        );
+    }
+
+    Ok(())
 }
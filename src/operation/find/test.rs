@@ -4,8 +4,19 @@ use crate::{
     bson::{doc, Document},
     bson_util,
     cmap::{CommandResponse, StreamDescription},
+    error::ErrorKind,
     operation::{test, Find, Operation},
-    options::{CursorType, FindOptions, Hint, ReadConcern, ReadConcernLevel, ServerAddress},
+    options::{
+        CursorType,
+        FindOneOptions,
+        FindOptions,
+        Hint,
+        ReadConcern,
+        ReadConcernLevel,
+        ReadPreference,
+        SelectionCriteria,
+        ServerAddress,
+    },
     Namespace,
 };
 
@@ -163,6 +174,64 @@ async fn build_limit() {
     build_test(ns, None, Some(negative_options), negative_body);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_skip() {
+    for skip in [0, 5, i64::MAX as u64].iter() {
+        let options = FindOptions::builder().skip(*skip).build();
+        let body = doc! {
+            "find": "",
+            "skip": *skip as i64,
+        };
+        build_test(Namespace::empty(), None, Some(options), body);
+    }
+
+    // find_one should pass its skip through along with the single batch limit.
+    let options = FindOptions::from(FindOneOptions::builder().skip(1000).build());
+    let body = doc! {
+        "find": "",
+        "skip": 1000_i64,
+        "limit": 1_i64,
+        "singleBatch": true,
+    };
+    build_test(Namespace::empty(), None, Some(options), body);
+
+    let options = FindOptions::builder().skip((i64::MAX as u64) + 1).build();
+    let mut op = Find::new(Namespace::empty(), None, Some(options));
+    assert!(op.build(&StreamDescription::new_testing()).is_err());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_max_time() {
+    let options = FindOptions::builder()
+        .max_time(Duration::from_millis(1500))
+        .build();
+    let body = doc! {
+        "find": "",
+        "maxTimeMS": 1500,
+    };
+    build_test(Namespace::empty(), None, Some(options), body);
+
+    // a max time of zero is omitted rather than sent.
+    let options = FindOptions::builder()
+        .max_time(Duration::from_secs(0))
+        .build();
+    build_test(Namespace::empty(), None, Some(options), doc! { "find": "" });
+
+    let options = FindOptions::builder()
+        .max_time(Duration::from_secs(u64::MAX))
+        .build();
+    let mut op = Find::new(Namespace::empty(), None, Some(options));
+    let error = op.build(&StreamDescription::new_testing()).unwrap_err();
+    match *error.kind {
+        ErrorKind::InvalidArgument { ref message } => {
+            assert_eq!(message, "maxTimeMS value too large")
+        }
+        ref other => panic!("expected InvalidArgument, got {:?}", other),
+    }
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn build_batch_size() {
@@ -180,6 +249,83 @@ async fn build_batch_size() {
     assert!(op.build(&StreamDescription::new_testing()).is_err())
 }
 
+fn assert_invalid_options(options: FindOptions, first: &str, second: &str) {
+    let mut op = Find::new(Namespace::empty(), None, Some(options));
+    let error = op
+        .build(&StreamDescription::new_testing())
+        .expect_err("build should fail");
+    match *error.kind {
+        ErrorKind::InvalidArgument { ref message } => {
+            assert!(message.contains(first), "{}", message);
+            assert!(message.contains(second), "{}", message);
+        }
+        ref e => panic!("expected invalid argument error, got {:?}", e),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_invalid_options() {
+    for cursor_type in [CursorType::Tailable, CursorType::TailableAwait].iter() {
+        let options = FindOptions::builder()
+            .cursor_type(*cursor_type)
+            .sort(doc! { "x": 1 })
+            .build();
+        assert_invalid_options(options, "cursor_type", "sort");
+
+        let options = FindOptions::builder()
+            .cursor_type(*cursor_type)
+            .limit(5)
+            .build();
+        assert_invalid_options(options, "cursor_type", "limit");
+
+        let options = FindOptions::builder()
+            .cursor_type(*cursor_type)
+            .limit(-5)
+            .build();
+        assert_invalid_options(options, "cursor_type", "limit");
+    }
+
+    let options = FindOptions::builder().limit(-5).batch_size(2).build();
+    assert_invalid_options(options, "limit", "batch_size");
+
+    // Tailable cursors may only be sorted in ascending natural order.
+    for sort in [doc! { "$natural": -1 }, doc! { "$natural": 1, "x": 1 }].iter() {
+        let options = FindOptions::builder()
+            .cursor_type(CursorType::Tailable)
+            .sort(sort.clone())
+            .build();
+        assert_invalid_options(options, "cursor_type", "sort");
+    }
+    for sort in [doc! { "$natural": 1 }, doc! { "$natural": 1.0 }].iter() {
+        let options = FindOptions::builder()
+            .cursor_type(CursorType::Tailable)
+            .sort(sort.clone())
+            .build();
+        let body = doc! {
+            "find": "",
+            "sort": sort.clone(),
+            "tailable": true,
+        };
+        build_test(Namespace::empty(), None, Some(options), body);
+    }
+
+    // Non-tailable cursors and positive limits are unaffected.
+    let options = FindOptions::builder()
+        .cursor_type(CursorType::NonTailable)
+        .sort(doc! { "x": 1 })
+        .limit(5)
+        .batch_size(2)
+        .build();
+    let body = doc! {
+        "find": "",
+        "sort": { "x": 1 },
+        "limit": 5_i64,
+        "batchSize": 2,
+    };
+    build_test(Namespace::empty(), None, Some(options), body);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn op_selection_criteria() {
@@ -310,6 +456,49 @@ async fn handle_max_await_time() {
     );
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_partial_results_returned() {
+    let find = Find::new(
+        Namespace::empty(),
+        None,
+        Some(FindOptions::builder().allow_partial_results(true).build()),
+    );
+
+    let response = doc! {
+        "cursor": {
+            "id": 0,
+            "ns": "a.b",
+            "firstBatch": [],
+        },
+        "ok": 1
+    };
+    let spec = find
+        .handle_response(
+            CommandResponse::with_document(response),
+            &Default::default(),
+        )
+        .expect("should handle correctly");
+    assert!(!spec.info.partial_results_returned);
+
+    let response = doc! {
+        "cursor": {
+            "id": 0,
+            "ns": "a.b",
+            "firstBatch": [],
+            "partialResultsReturned": true,
+        },
+        "ok": 1
+    };
+    let spec = find
+        .handle_response(
+            CommandResponse::with_document(response),
+            &Default::default(),
+        )
+        .expect("should handle correctly");
+    assert!(spec.info.partial_results_returned);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_invalid_response() {
@@ -333,3 +522,37 @@ async fn handle_invalid_response() {
         )
         .is_err());
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_linearizable_read_concern() {
+    let secondary = SelectionCriteria::ReadPreference(ReadPreference::Secondary {
+        options: Default::default(),
+    });
+    let options = FindOptions::builder()
+        .read_concern(ReadConcern::linearizable())
+        .selection_criteria(secondary.clone())
+        .build();
+    assert_invalid_options(options, "linearizable", "primary");
+
+    // Other read concerns can still be used with a non-primary read preference.
+    let options = FindOptions::builder()
+        .read_concern(ReadConcern::majority())
+        .selection_criteria(secondary)
+        .build();
+    let mut op = Find::new(Namespace::empty(), None, Some(options));
+    assert!(op.build(&StreamDescription::new_testing()).is_ok());
+
+    let primary = SelectionCriteria::ReadPreference(ReadPreference::Primary);
+    for selection_criteria in [None, Some(primary)].iter() {
+        let options = FindOptions::builder()
+            .read_concern(ReadConcern::linearizable())
+            .selection_criteria(selection_criteria.clone())
+            .build();
+        let body = doc! {
+            "find": "",
+            "readConcern": { "level": "linearizable" },
+        };
+        build_test(Namespace::empty(), None, Some(options), body);
+    }
+}
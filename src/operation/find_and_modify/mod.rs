@@ -2,7 +2,7 @@ mod options;
 #[cfg(test)]
 mod test;
 
-use std::fmt::Debug;
+use std::{fmt::Debug, time::Duration};
 
 use serde::{de::DeserializeOwned, Deserialize};
 
@@ -21,8 +21,9 @@ use crate::{
         Namespace,
     },
     error::{ErrorKind, Result},
-    operation::{append_options, Operation, Retryability},
+    operation::{append_options, encode_max_time, encode_write_concern, Operation, Retryability},
     options::WriteConcern,
+    results::FindOneAndModifyResult,
 };
 
 pub(crate) struct FindAndModify<T = Document>
@@ -99,7 +100,7 @@ impl<T> Operation for FindAndModify<T>
 where
     T: DeserializeOwned,
 {
-    type O = Option<T>;
+    type O = FindOneAndModifyResult<T>;
     const NAME: &'static str = "findAndModify";
 
     fn build(&mut self, description: &StreamDescription) -> Result<Command> {
@@ -118,6 +119,8 @@ where
         };
 
         append_options(&mut body, Some(&self.options))?;
+        encode_max_time(&mut body, self.options.max_time)?;
+        encode_write_concern(&mut body, self.write_concern())?;
 
         Ok(Command::new(
             Self::NAME.to_string(),
@@ -132,24 +135,41 @@ where
         _description: &StreamDescription,
     ) -> Result<Self::O> {
         let body: ResponseBody = response.body()?;
-        match body.value {
-            Bson::Document(doc) => Ok(Some(from_document(doc)?)),
-            Bson::Null => Ok(None),
-            other => Err(ErrorKind::InvalidResponse {
-                message: format!(
-                    "expected document for value field of findAndModify response, but instead got \
-                     {:?}",
-                    other
-                ),
+        let value = match body.value {
+            Bson::Document(doc) => Some(from_document(doc)?),
+            Bson::Null => None,
+            other => {
+                return Err(ErrorKind::InvalidResponse {
+                    message: format!(
+                        "expected document for value field of findAndModify response, but instead \
+                         got {:?}",
+                        other
+                    ),
+                }
+                .into())
             }
-            .into()),
-        }
+        };
+
+        Ok(FindOneAndModifyResult {
+            value,
+            upserted_id: body
+                .last_error_object
+                .and_then(|last_error_object| last_error_object.upserted),
+        })
     }
 
     fn write_concern(&self) -> Option<&WriteConcern> {
         self.options.write_concern.as_ref()
     }
 
+    fn is_write(&self) -> bool {
+        true
+    }
+
+    fn max_time(&self) -> Option<Duration> {
+        self.options.max_time
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Write
     }
@@ -158,4 +178,12 @@ where
 #[derive(Debug, Deserialize)]
 struct ResponseBody {
     value: Bson,
+
+    #[serde(rename = "lastErrorObject")]
+    last_error_object: Option<LastErrorObject>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LastErrorObject {
+    upserted: Option<Bson>,
 }
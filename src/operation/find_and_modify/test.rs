@@ -159,7 +159,10 @@ async fn handle_success_delete() {
 
     let result = op.handle_response(ok_response, &Default::default());
     assert_eq!(
-        result.expect("handle failed").expect("result was None"),
+        result
+            .expect("handle failed")
+            .value
+            .expect("result was None"),
         value
     );
 }
@@ -172,7 +175,7 @@ async fn handle_null_value_delete() {
     let null_value = CommandResponse::with_document(doc! { "ok": 1.0, "value": Bson::Null});
     let result = op.handle_response(null_value, &Default::default());
     assert!(result.is_ok());
-    assert_eq!(result.expect("handle failed"), None);
+    assert_eq!(result.expect("handle failed").value, None);
 }
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
@@ -350,11 +353,37 @@ async fn handle_success_replace() {
 
     let result = op.handle_response(ok_response, &Default::default());
     assert_eq!(
-        result.expect("handle failed").expect("result was None"),
+        result
+            .expect("handle failed")
+            .value
+            .expect("result was None"),
         value
     );
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_upserted_replace() {
+    let op = empty_replace();
+    let id = ObjectId::new();
+    let value = doc! { "_id": id, "x": 1 };
+    let ok_response = CommandResponse::with_document(doc! {
+        "lastErrorObject" : {
+            "n" : 1,
+            "updatedExisting" : false,
+            "upserted" : id,
+        },
+        "value" : value.clone(),
+        "ok" : 1
+    });
+
+    let result = op
+        .handle_response(ok_response, &Default::default())
+        .expect("handle failed");
+    assert_eq!(result.upserted_id, Some(Bson::ObjectId(id)));
+    assert_eq!(result.value, Some(value));
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_null_value_replace() {
@@ -363,7 +392,7 @@ async fn handle_null_value_replace() {
     let null_value = CommandResponse::with_document(doc! { "ok": 1.0, "value": Bson::Null});
     let result = op.handle_response(null_value, &Default::default());
     assert!(result.is_ok());
-    assert_eq!(result.expect("handle failed"), None);
+    assert_eq!(result.expect("handle failed").value, None);
 }
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
@@ -500,6 +529,73 @@ async fn build_with_update() {
     assert_eq!(cmd.body, expected_body);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_with_update_array_filters() {
+    let ns = Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    };
+    let filter = doc! { "_id": 1 };
+    let update = UpdateModifications::Document(doc! { "$set": { "grades.$[g]": 100 } });
+    let array_filters = vec![doc! { "g": { "$gte": 90 } }];
+    let options = FindOneAndUpdateOptions::builder()
+        .array_filters(array_filters.clone())
+        .bypass_document_validation(true)
+        .build();
+
+    let mut op =
+        FindAndModify::<Document>::with_update(ns, filter.clone(), update.clone(), Some(options))
+            .unwrap();
+
+    let description = StreamDescription::new_testing();
+    let mut cmd = op.build(&description).unwrap();
+
+    // unlike the update command, findAndModify takes arrayFilters at the top level rather than
+    // alongside each update statement.
+    let mut expected_body = doc! {
+        "findAndModify": "test_coll",
+        "query": filter,
+        "update": update.to_bson(),
+        "arrayFilters": array_filters,
+        "bypassDocumentValidation": true,
+    };
+
+    bson_util::sort_document(&mut cmd.body);
+    bson_util::sort_document(&mut expected_body);
+
+    assert_eq!(cmd.body, expected_body);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_with_update_return_document() {
+    let filter = doc! { "x": { "$gt": 1 } };
+    let update = UpdateModifications::Document(doc! { "$x": { "$inc": 1 } });
+
+    let cases = vec![
+        (Some(ReturnDocument::After), Some(true)),
+        (Some(ReturnDocument::Before), Some(false)),
+        (None, None),
+    ];
+    for (return_document, expected_new) in cases {
+        let options = FindOneAndUpdateOptions {
+            return_document,
+            ..Default::default()
+        };
+        let mut op = FindAndModify::<Document>::with_update(
+            Namespace::empty(),
+            filter.clone(),
+            update.clone(),
+            Some(options),
+        )
+        .unwrap();
+
+        let cmd = op.build(&StreamDescription::new_testing()).unwrap();
+        assert_eq!(cmd.body.get_bool("new").ok(), expected_new);
+    }
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_success_update() {
@@ -525,11 +621,34 @@ async fn handle_success_update() {
         "ok" : 1
     });
 
-    let result = op.handle_response(ok_response, &Default::default());
-    assert_eq!(
-        result.expect("handle failed").expect("result was None"),
-        value
-    );
+    let result = op
+        .handle_response(ok_response, &Default::default())
+        .expect("handle failed");
+    assert_eq!(result.value.expect("result was None"), value);
+    assert_eq!(result.upserted_id, None);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_upserted_update() {
+    let op = empty_update();
+    let id = ObjectId::new();
+    let value = doc! { "_id": id, "x": 1 };
+    let ok_response = CommandResponse::with_document(doc! {
+        "lastErrorObject" : {
+            "n" : 1,
+            "updatedExisting" : false,
+            "upserted" : id,
+        },
+        "value" : value.clone(),
+        "ok" : 1
+    });
+
+    let result = op
+        .handle_response(ok_response, &Default::default())
+        .expect("handle failed");
+    assert_eq!(result.upserted_id, Some(Bson::ObjectId(id)));
+    assert_eq!(result.value, Some(value));
 }
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
@@ -540,7 +659,7 @@ async fn handle_null_value_update() {
     let null_value = CommandResponse::with_document(doc! { "ok": 1.0, "value": Bson::Null});
     let result = op.handle_response(null_value, &Default::default());
     assert!(result.is_ok());
-    assert_eq!(result.expect("handle failed"), None);
+    assert_eq!(result.expect("handle failed").value, None);
 }
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
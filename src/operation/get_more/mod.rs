@@ -7,15 +7,18 @@ use serde::Deserialize;
 
 use crate::{
     bson::{doc, Document},
-    cmap::{Command, CommandResponse, StreamDescription},
+    cmap::{Command, CommandResponse, Connection, PinnedConnectionHandle, StreamDescription},
     cursor::CursorInformation,
     error::{ErrorKind, Result},
-    operation::Operation,
+    operation::{encode_max_time, Operation},
     options::SelectionCriteria,
     results::GetMoreResult,
     Namespace,
 };
 
+/// The first wire version (MongoDB 4.2) that supports exhaust replies to OP_MSG requests.
+const EXHAUST_MIN_WIRE_VERSION: i32 = 8;
+
 #[derive(Debug)]
 pub(crate) struct GetMore {
     ns: Namespace,
@@ -23,6 +26,10 @@ pub(crate) struct GetMore {
     selection_criteria: SelectionCriteria,
     batch_size: Option<u32>,
     max_time: Option<Duration>,
+    comment: Option<String>,
+    utf8_lossy: bool,
+    pinned_connection: Option<PinnedConnectionHandle>,
+    exhaust: bool,
 }
 
 impl GetMore {
@@ -30,18 +37,36 @@ impl GetMore {
         Self {
             ns: info.ns,
             cursor_id: info.id,
-            selection_criteria: SelectionCriteria::from_address(info.address),
+            selection_criteria: SelectionCriteria::host(info.address),
             batch_size: info.batch_size,
             max_time: info.max_time,
+            comment: info.comment,
+            utf8_lossy: info.utf8_lossy,
+            pinned_connection: info.pinned_connection,
+            exhaust: info.exhaust,
         }
     }
+
+    /// Parses a reply to a getMore, including the replies that the server streams to an exhaust
+    /// getMore.
+    pub(crate) fn parse_response(mut response: CommandResponse) -> Result<GetMoreResult> {
+        let raw_batch_lengths = response.take_raw_batch_lengths();
+        let body: GetMoreResponseBody = response.body()?;
+        Ok(GetMoreResult {
+            batch: body.cursor.next_batch,
+            exhausted: body.cursor.id == 0,
+            raw_batch_lengths,
+            post_batch_resume_token: body.cursor.post_batch_resume_token,
+            streaming_connection: None,
+        })
+    }
 }
 
 impl Operation for GetMore {
     type O = GetMoreResult;
     const NAME: &'static str = "getMore";
 
-    fn build(&mut self, _description: &StreamDescription) -> Result<Command> {
+    fn build(&mut self, description: &StreamDescription) -> Result<Command> {
         let mut body = doc! {
             Self::NAME: self.cursor_id,
             "collection": self.ns.coll.clone(),
@@ -58,15 +83,17 @@ impl Operation for GetMore {
             }
         }
 
-        if let Some(ref max_time) = self.max_time {
-            body.insert("maxTimeMS", max_time.as_millis() as i32);
+        encode_max_time(&mut body, self.max_time)?;
+
+        if let Some(ref comment) = self.comment {
+            body.insert("comment", comment.clone());
         }
 
-        Ok(Command::new(
-            Self::NAME.to_string(),
-            self.ns.db.clone(),
-            body,
-        ))
+        let mut command = Command::new(Self::NAME.to_string(), self.ns.db.clone(), body);
+        command.utf8_lossy = self.utf8_lossy;
+        command.exhaust_allowed =
+            self.exhaust && description.max_wire_version.unwrap_or(0) >= EXHAUST_MIN_WIRE_VERSION;
+        Ok(command)
     }
 
     fn handle_response(
@@ -74,16 +101,20 @@ impl Operation for GetMore {
         response: CommandResponse,
         _description: &StreamDescription,
     ) -> Result<Self::O> {
-        let body: GetMoreResponseBody = response.body()?;
-        Ok(GetMoreResult {
-            batch: body.cursor.next_batch,
-            exhausted: body.cursor.id == 0,
-        })
+        Self::parse_response(response)
     }
 
     fn selection_criteria(&self) -> Option<&SelectionCriteria> {
         Some(&self.selection_criteria)
     }
+
+    fn pinned_connection(&self) -> Option<&PinnedConnectionHandle> {
+        self.pinned_connection.as_ref()
+    }
+
+    fn take_streaming_connection(&self, output: &mut Self::O, connection: Connection) {
+        output.streaming_connection = Some(connection);
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -94,6 +125,8 @@ struct GetMoreResponseBody {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct NextBatchBody {
+    #[serde(deserialize_with = "crate::bson_util::deserialize_i64_from_bson_number")]
     id: i64,
     next_batch: VecDeque<Document>,
+    post_batch_resume_token: Option<Document>,
 }
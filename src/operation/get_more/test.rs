@@ -25,6 +25,12 @@ fn build_test(
         address,
         batch_size,
         max_time,
+        comment: None,
+        utf8_lossy: false,
+        partial_results_returned: false,
+        operation_name: "find",
+        pinned_connection: None,
+        exhaust: false,
     };
     let mut get_more = GetMore::new(info);
 
@@ -116,11 +122,52 @@ async fn build_batch_size() {
         id: cursor_id,
         batch_size: Some((std::i32::MAX as u32) + 1),
         max_time: None,
+        comment: None,
+        utf8_lossy: false,
+        partial_results_returned: false,
+        operation_name: "find",
+        pinned_connection: None,
+        exhaust: false,
     };
     let mut op = GetMore::new(info);
     assert!(op.build(&StreamDescription::new_testing()).is_err())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_comment() {
+    let ns = Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    };
+    let info = CursorInformation {
+        ns: ns.clone(),
+        address: ServerAddress::default(),
+        id: 123,
+        batch_size: None,
+        max_time: None,
+        comment: Some("hello".to_string()),
+        utf8_lossy: false,
+        partial_results_returned: false,
+        operation_name: "find",
+        pinned_connection: None,
+        exhaust: false,
+    };
+    let mut get_more = GetMore::new(info);
+
+    let cmd = get_more
+        .build(&StreamDescription::new_testing())
+        .expect("build should succeed");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "getMore": 123_i64,
+            "collection": ns.coll,
+            "comment": "hello",
+        }
+    );
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn op_selection_criteria() {
@@ -135,6 +182,12 @@ async fn op_selection_criteria() {
         id: 123,
         batch_size: None,
         max_time: None,
+        comment: None,
+        utf8_lossy: false,
+        partial_results_returned: false,
+        operation_name: "find",
+        pinned_connection: None,
+        exhaust: false,
     };
     let get_more = GetMore::new(info);
     let server_description = ServerDescription {
@@ -142,7 +195,9 @@ async fn op_selection_criteria() {
         server_type: ServerType::Unknown,
         reply: Ok(None),
         last_update_time: None,
+        last_update_instant: None,
         average_round_trip_time: None,
+        consecutive_failures: 0,
     };
     let server_info = ServerInfo::new(&server_description);
 
@@ -180,6 +235,12 @@ async fn handle_success() {
         id: cursor_id,
         batch_size: None,
         max_time: None,
+        comment: None,
+        utf8_lossy: false,
+        partial_results_returned: false,
+        operation_name: "find",
+        pinned_connection: None,
+        exhaust: false,
     };
     let get_more = GetMore::new(info);
 
@@ -214,3 +275,34 @@ async fn handle_success() {
     assert!(result.exhausted);
     assert_eq!(result.batch, batch);
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_exhaust() {
+    let info = CursorInformation {
+        ns: Namespace {
+            db: "test_db".to_string(),
+            coll: "test_coll".to_string(),
+        },
+        id: 123,
+        address: ServerAddress::default(),
+        batch_size: None,
+        max_time: None,
+        comment: None,
+        utf8_lossy: false,
+        partial_results_returned: false,
+        operation_name: "find",
+        pinned_connection: None,
+        exhaust: true,
+    };
+    let mut get_more = GetMore::new(info);
+
+    let mut description = StreamDescription::new_testing();
+    description.max_wire_version = Some(8);
+    assert!(get_more.build(&description).unwrap().exhaust_allowed);
+
+    // Servers older than 4.2 don't support exhaust replies to OP_MSG requests, so the cursor
+    // falls back to regular getMores.
+    description.max_wire_version = Some(7);
+    assert!(!get_more.build(&description).unwrap().exhaust_allowed);
+}
@@ -11,7 +11,7 @@ use crate::{
     bson_util,
     cmap::{Command, CommandResponse, StreamDescription},
     error::{BulkWriteFailure, Error, ErrorKind, Result},
-    operation::{append_options, Operation, Retryability, WriteResponseBody},
+    operation::{append_options, encode_write_concern, Operation, Retryability, WriteResponseBody},
     options::{InsertManyOptions, WriteConcern},
     results::InsertManyResult,
     Namespace,
@@ -98,6 +98,7 @@ impl<T: Serialize> Operation for Insert<T> {
         };
 
         append_options(&mut body, self.options.as_ref())?;
+        encode_write_concern(&mut body, self.write_concern())?;
 
         body.insert("ordered", self.is_ordered());
 
@@ -113,6 +114,15 @@ impl<T: Serialize> Operation for Insert<T> {
         response: CommandResponse,
         _description: &StreamDescription,
     ) -> Result<Self::O> {
+        if !self.is_acknowledged() {
+            // the server doesn't report which documents were inserted, so every document that was
+            // sent is included.
+            return Ok(InsertManyResult {
+                inserted_ids: self.inserted_ids.iter().cloned().enumerate().collect(),
+                acknowledged: false,
+            });
+        }
+
         let body: WriteResponseBody = response.body()?;
 
         let mut map = HashMap::new();
@@ -146,7 +156,10 @@ impl<T: Serialize> Operation for Insert<T> {
             ));
         }
 
-        Ok(InsertManyResult { inserted_ids: map })
+        Ok(InsertManyResult {
+            inserted_ids: map,
+            acknowledged: true,
+        })
     }
 
     fn write_concern(&self) -> Option<&WriteConcern> {
@@ -155,6 +168,14 @@ impl<T: Serialize> Operation for Insert<T> {
             .and_then(|opts| opts.write_concern.as_ref())
     }
 
+    fn is_write(&self) -> bool {
+        true
+    }
+
+    fn supports_unacknowledged_writes(&self) -> bool {
+        true
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Write
     }
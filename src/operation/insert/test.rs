@@ -1,7 +1,7 @@
 use crate::{
     bson::{doc, Bson, Document},
     cmap::{CommandResponse, StreamDescription},
-    concern::WriteConcern,
+    concern::{Acknowledgment, WriteConcern},
     error::{BulkWriteError, ErrorKind, WriteConcernError},
     operation::{Insert, Operation},
     options::InsertManyOptions,
@@ -149,6 +149,7 @@ async fn handle_success() {
     assert!(ok_result.is_ok());
 
     let response = ok_result.unwrap();
+    assert!(response.acknowledged);
     let inserted_ids = response.inserted_ids;
     assert_eq!(inserted_ids.len(), 3);
     assert_eq!(
@@ -157,6 +158,38 @@ async fn handle_success() {
     );
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_unacknowledged() {
+    let options = InsertManyOptions {
+        write_concern: Some(WriteConcern::builder().w(Acknowledgment::Nodes(0)).build()),
+        ..Default::default()
+    };
+    let mut op = Insert::new(
+        Namespace {
+            db: "test_db".to_string(),
+            coll: "test_coll".to_string(),
+        },
+        vec![Document::new(), doc! { "_id": 1234 }],
+        Some(options),
+    );
+    assert!(op.supports_unacknowledged_writes());
+
+    let cmd = op.build(&StreamDescription::new_testing()).unwrap();
+    assert_eq!(cmd.body.get_document("writeConcern"), Ok(&doc! { "w": 0 }));
+
+    // unacknowledged writes only get { ok: 1 } back.
+    let response = CommandResponse::with_document(doc! { "ok": 1.0 });
+    let result = op.handle_response(response, &Default::default()).unwrap();
+    assert!(!result.acknowledged);
+    assert_eq!(result.inserted_ids.len(), 2);
+    assert!(matches!(
+        result.inserted_ids.get(&0),
+        Some(Bson::ObjectId(_))
+    ));
+    assert_eq!(result.inserted_ids.get(&1), Some(&Bson::Int32(1234)));
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_invalid_response() {
@@ -237,6 +270,7 @@ async fn handle_write_failure() {
                     "wtimeout": 0,
                     "provenance": "clientSupplied"
                 } }),
+                labels: vec![],
             };
             assert_eq!(write_concern_error, expected_wc_err);
 
@@ -71,14 +71,17 @@ impl Operation for ListCollections {
         let source_address = response.source_address().clone();
         let body: CursorBody = response.body()?;
 
-        Ok(CursorSpecification::new(
+        let mut spec = CursorSpecification::new(
             body.cursor.ns,
             source_address,
             body.cursor.id,
             self.options.as_ref().and_then(|opts| opts.batch_size),
             None,
+            None,
             body.cursor.first_batch,
-        ))
+        );
+        spec.info.operation_name = Self::NAME;
+        Ok(spec)
     }
 
     fn selection_criteria(&self) -> Option<&SelectionCriteria> {
@@ -88,4 +91,11 @@ impl Operation for ListCollections {
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
+
+    fn cursor_specification<'a>(
+        &self,
+        output: &'a mut Self::O,
+    ) -> Option<&'a mut CursorSpecification> {
+        Some(output)
+    }
 }
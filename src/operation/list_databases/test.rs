@@ -5,6 +5,7 @@ use crate::{
     error::ErrorKind,
     operation::{ListDatabases, Operation},
     options::ListDatabasesOptions,
+    results::DatabaseSpecification,
     selection_criteria::ReadPreference,
 };
 
@@ -126,6 +127,36 @@ async fn handle_success() {
     assert_eq!(actual_values, expected_values);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success_mixed_numeric_types() {
+    let list_databases_op = ListDatabases::empty();
+
+    // Sizes may be reported as int32, int64, or double depending on the server version and any
+    // proxies in between.
+    let response = CommandResponse::with_document(doc! {
+       "databases": [
+           { "name": "admin", "sizeOnDisk": 40960, "empty": false },
+           { "name": "local", "sizeOnDisk": 5_368_709_120_i64, "empty": false },
+           { "name": "test", "sizeOnDisk": 73728.0, "empty": false },
+       ],
+       "totalSize": 5_368_823_808.0,
+       "ok": 1.0
+    });
+
+    let sizes: Vec<u64> = list_databases_op
+        .handle_response(response, &Default::default())
+        .expect("supposed to succeed")
+        .into_iter()
+        .map(|database| {
+            bson::from_document::<DatabaseSpecification>(database)
+                .unwrap()
+                .size_on_disk
+        })
+        .collect();
+    assert_eq!(sizes, vec![40960, 5_368_709_120, 73728]);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_response_no_databases() {
@@ -4,6 +4,7 @@ mod commit_transaction;
 mod count;
 mod count_documents;
 mod create;
+mod create_indexes;
 mod delete;
 mod distinct;
 mod drop_collection;
@@ -14,16 +15,18 @@ mod get_more;
 mod insert;
 mod list_collections;
 mod list_databases;
+mod profile;
 mod run_command;
 mod update;
 
-use std::{collections::VecDeque, fmt::Debug, ops::Deref};
+use std::{collections::VecDeque, fmt::Debug, ops::Deref, time::Duration};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
     bson::{self, Bson, Document},
-    cmap::{Command, CommandResponse, StreamDescription},
+    cmap::{Command, CommandResponse, Connection, PinnedConnectionHandle, StreamDescription},
+    cursor::CursorSpecification,
     error::{
         BulkWriteError,
         BulkWriteFailure,
@@ -39,11 +42,12 @@ use crate::{
 };
 
 pub(crate) use abort_transaction::AbortTransaction;
-pub(crate) use aggregate::Aggregate;
+pub(crate) use aggregate::{Aggregate, ChangeStreamAggregate};
 pub(crate) use commit_transaction::CommitTransaction;
 pub(crate) use count::Count;
 pub(crate) use count_documents::CountDocuments;
 pub(crate) use create::Create;
+pub(crate) use create_indexes::CreateIndexes;
 pub(crate) use delete::Delete;
 pub(crate) use distinct::Distinct;
 pub(crate) use drop_collection::DropCollection;
@@ -54,6 +58,7 @@ pub(crate) use get_more::GetMore;
 pub(crate) use insert::Insert;
 pub(crate) use list_collections::ListCollections;
 pub(crate) use list_databases::ListDatabases;
+pub(crate) use profile::Profile;
 pub(crate) use run_command::RunCommand;
 pub(crate) use update::Update;
 
@@ -99,16 +104,60 @@ pub(crate) trait Operation {
         None
     }
 
+    /// Whether this operation can be executed with an unacknowledged write concern. Operations
+    /// that return `true` here must not expect anything but `ok` in their response when
+    /// `is_acknowledged` is `false`.
+    fn supports_unacknowledged_writes(&self) -> bool {
+        false
+    }
+
+    /// Whether this operation writes to the database. Snapshot sessions reject these.
+    fn is_write(&self) -> bool {
+        false
+    }
+
     /// Whether this operation supports sessions or not.
     fn supports_sessions(&self) -> bool {
         true
     }
 
+    /// Whether this operation accepts a read concern. Causally consistent and snapshot sessions
+    /// attach their read concern fields to these operations.
+    fn supports_read_concern(&self) -> bool {
+        false
+    }
+
     /// The level of retryability the operation supports.
     fn retryability(&self) -> Retryability {
         Retryability::None
     }
 
+    /// The server-side time limit for this operation, if any. The client also stops waiting for a
+    /// reply once this time (plus a grace period) has elapsed.
+    fn max_time(&self) -> Option<Duration> {
+        None
+    }
+
+    /// The connection that this operation must be executed on, if it continues a cursor that's
+    /// pinned to one.
+    fn pinned_connection(&self) -> Option<&PinnedConnectionHandle> {
+        None
+    }
+
+    /// The cursor created by this operation, if it creates one. When connected through a load
+    /// balancer, the cursor is pinned to the connection that the operation was executed on.
+    fn cursor_specification<'a>(
+        &self,
+        _output: &'a mut Self::O,
+    ) -> Option<&'a mut CursorSpecification> {
+        None
+    }
+
+    /// Hands the connection that the server is still streaming further replies on over to the
+    /// output of this operation. This only happens for operations that allowed exhaust replies;
+    /// any other operation's connection is closed instead.
+    fn take_streaming_connection(&self, _output: &mut Self::O, _connection: Connection) {}
+
     // Updates this operation as needed for a retry.
     fn update_for_retry(&mut self) {}
 
@@ -141,6 +190,58 @@ pub(crate) fn append_options<T: Serialize + Debug>(
     }
 }
 
+/// Encodes `max_time` into the `maxTimeMS` field of `command` as an integer number of
+/// milliseconds, replacing any value that was serialized along with the operation's options.
+///
+/// A `max_time` of zero is omitted rather than sent, and one that doesn't fit into a BSON 64-bit
+/// integer is rejected.
+pub(crate) fn encode_max_time(command: &mut Document, max_time: Option<Duration>) -> Result<()> {
+    command.remove("maxTimeMS");
+
+    let millis = match max_time {
+        Some(max_time) => max_time.as_millis(),
+        None => return Ok(()),
+    };
+    if millis > i64::MAX as u128 {
+        return Err(ErrorKind::InvalidArgument {
+            message: "maxTimeMS value too large".to_string(),
+        }
+        .into());
+    }
+
+    if millis > i32::MAX as u128 {
+        command.insert("maxTimeMS", millis as i64);
+    } else if millis > 0 {
+        command.insert("maxTimeMS", millis as i32);
+    }
+    Ok(())
+}
+
+/// Encodes `write_concern` into the `writeConcern` field of `command`, replacing any write concern
+/// that was serialized along with the operation's options.
+///
+/// The field is omitted entirely if no write concern was specified or if the write concern is the
+/// server default (i.e. has no fields set). Unacknowledged write concerns are always encoded so
+/// that the server doesn't acknowledge the write.
+pub(crate) fn encode_write_concern(
+    command: &mut Document,
+    write_concern: Option<&WriteConcern>,
+) -> Result<()> {
+    command.remove("writeConcern");
+
+    let write_concern = match write_concern {
+        Some(write_concern) => write_concern,
+        None => return Ok(()),
+    };
+    write_concern.validate()?;
+
+    let write_concern = bson::to_document(write_concern)?;
+    if !write_concern.is_empty() {
+        command.insert("writeConcern", write_concern);
+    }
+    Ok(())
+}
+
 #[derive(Deserialize, Debug)]
 struct EmptyBody {}
 
@@ -159,7 +260,7 @@ impl WriteConcernOnlyBody {
         match self.write_concern_error {
             Some(ref wc_error) => Err(Error::new(
                 ErrorKind::Write(WriteFailure::WriteConcernError(wc_error.clone())),
-                self.labels.clone(),
+                Some(error_labels(self.labels.as_ref(), Some(wc_error))),
             )),
             None => Ok(()),
         }
@@ -171,6 +272,7 @@ struct WriteResponseBody<T = EmptyBody> {
     #[serde(flatten)]
     body: T,
 
+    #[serde(deserialize_with = "crate::bson_util::deserialize_u64_from_bson_number")]
     n: u64,
 
     #[serde(rename = "writeErrors")]
@@ -197,11 +299,33 @@ impl<T> WriteResponseBody<T> {
 
         Err(Error::new(
             ErrorKind::BulkWrite(failure),
-            self.labels.clone(),
+            Some(error_labels(
+                self.labels.as_ref(),
+                self.write_concern_error.as_ref(),
+            )),
         ))
     }
 }
 
+/// Collects the labels of a write response's error, treating any labels nested in its
+/// writeConcernError as if they had been returned at the top level. Servers prior to 4.4 may
+/// report labels only within the writeConcernError.
+fn error_labels(
+    labels: Option<&Vec<String>>,
+    write_concern_error: Option<&WriteConcernError>,
+) -> Vec<String> {
+    labels
+        .into_iter()
+        .flatten()
+        .chain(
+            write_concern_error
+                .into_iter()
+                .flat_map(|wc| wc.labels.iter()),
+        )
+        .cloned()
+        .collect()
+}
+
 impl<T> Deref for WriteResponseBody<T> {
     type Target = T;
 
@@ -217,10 +341,15 @@ struct CursorBody {
 
 #[derive(Debug, Deserialize)]
 struct CursorInfo {
+    #[serde(deserialize_with = "crate::bson_util::deserialize_i64_from_bson_number")]
     id: i64,
     ns: Namespace,
     #[serde(rename = "firstBatch")]
     first_batch: VecDeque<Document>,
+    #[serde(rename = "partialResultsReturned", default)]
+    partial_results_returned: bool,
+    #[serde(rename = "postBatchResumeToken")]
+    post_batch_resume_token: Option<Document>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -232,9 +361,20 @@ pub(crate) enum Retryability {
 
 #[cfg(test)]
 mod test {
+    use std::time::Duration;
+
     use crate::{
-        operation::Operation,
-        options::{ReadPreference, SelectionCriteria},
+        bson::{doc, Bson},
+        error::{ErrorKind, WriteFailure},
+        operation::{
+            encode_max_time,
+            encode_write_concern,
+            CursorBody,
+            Operation,
+            WriteConcernOnlyBody,
+            WriteResponseBody,
+        },
+        options::{Acknowledgment, ReadPreference, SelectionCriteria, WriteConcern},
     };
 
     pub(crate) fn op_selection_criteria<F, T>(constructor: F)
@@ -245,12 +385,183 @@ mod test {
         let op = constructor(None);
         assert_eq!(op.selection_criteria(), None);
 
-        let read_pref: SelectionCriteria = ReadPreference::Secondary {
+        let read_pref = SelectionCriteria::ReadPreference(ReadPreference::Secondary {
             options: Default::default(),
-        }
-        .into();
+        });
 
         let op = constructor(Some(read_pref.clone()));
         assert_eq!(op.selection_criteria(), Some(&read_pref));
     }
+
+    #[test]
+    fn encode_max_time_to_command() {
+        let mut command = doc! { "find": "coll", "maxTimeMS": 0 };
+        encode_max_time(&mut command, Some(Duration::from_secs(0))).unwrap();
+        assert_eq!(command, doc! { "find": "coll" });
+
+        encode_max_time(&mut command, Some(Duration::from_millis(10))).unwrap();
+        assert_eq!(command, doc! { "find": "coll", "maxTimeMS": 10 });
+
+        let millis = i32::MAX as u64 + 1;
+        encode_max_time(&mut command, Some(Duration::from_millis(millis))).unwrap();
+        assert_eq!(command, doc! { "find": "coll", "maxTimeMS": millis as i64 });
+
+        let millis = i64::MAX as u64;
+        encode_max_time(&mut command, Some(Duration::from_millis(millis))).unwrap();
+        assert_eq!(command, doc! { "find": "coll", "maxTimeMS": i64::MAX });
+
+        let too_large = Duration::from_millis(i64::MAX as u64) + Duration::from_millis(1);
+        let error = encode_max_time(&mut command, Some(too_large)).unwrap_err();
+        assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+    }
+
+    #[test]
+    fn encode_write_concern_to_command() {
+        let write_concern = WriteConcern::builder()
+            .w(Acknowledgment::Majority)
+            .w_timeout(Duration::from_millis(100))
+            .build();
+
+        // a write concern serialized as part of the options is replaced.
+        let mut command = doc! { "insert": "coll", "writeConcern": { "w": 1 } };
+        encode_write_concern(&mut command, Some(&write_concern)).unwrap();
+        assert_eq!(
+            command,
+            doc! { "insert": "coll", "writeConcern": { "w": "majority", "wtimeout": 100 } }
+        );
+
+        for write_concern in [None, Some(WriteConcern::default())].iter() {
+            let mut command = doc! { "insert": "coll", "writeConcern": { "w": 1 } };
+            encode_write_concern(&mut command, write_concern.as_ref()).unwrap();
+            assert_eq!(command, doc! { "insert": "coll" });
+        }
+
+        let unacknowledged = WriteConcern::builder().w(Acknowledgment::Nodes(0)).build();
+        let mut command = doc! { "insert": "coll" };
+        encode_write_concern(&mut command, Some(&unacknowledged)).unwrap();
+        assert_eq!(
+            command,
+            doc! { "insert": "coll", "writeConcern": { "w": 0 } }
+        );
+
+        let invalid = WriteConcern::builder()
+            .w(Acknowledgment::Nodes(0))
+            .journal(true)
+            .build();
+        let error = encode_write_concern(&mut doc! {}, Some(&invalid)).unwrap_err();
+        assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+    }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn write_concern_error_labels_pre_4_4() {
+        // Synthetic response in the shape returned by pre-4.4 servers, where the error labels are
+        // only reported inside of the writeConcernError.
+        let response = doc! {
+            "ok": 1,
+            "writeConcernError": {
+                "code": 91,
+                "codeName": "ShutdownInProgress",
+                "errmsg": "Replication is being shut down",
+                "errorLabels": ["RetryableWriteError"],
+            },
+        };
+        let body: WriteConcernOnlyBody = bson::from_document(response).unwrap();
+        let error = body.validate().expect_err("validation should fail");
+        assert!(error.contains_label("RetryableWriteError"));
+
+        match *error.kind {
+            ErrorKind::Write(WriteFailure::WriteConcernError(ref wc_error)) => {
+                assert_eq!(wc_error.code, 91);
+                assert_eq!(wc_error.details, None);
+            }
+            ref e => panic!("expected write concern error, got {:?}", e),
+        }
+    }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn write_concern_error_labels_and_details() {
+        // Synthetic response in the shape returned by 5.0+ servers, where the error labels are
+        // reported at the top level and errInfo includes the provenance of the write concern.
+        let response = doc! {
+            "ok": 1,
+            "n": 1,
+            "writeConcernError": {
+                "code": 64,
+                "codeName": "WriteConcernFailed",
+                "errmsg": "waiting for replication timed out",
+                "errInfo": {
+                    "wtimeout": true,
+                    "writeConcern": {
+                        "w": 3,
+                        "wtimeout": 1,
+                        "provenance": "clientSupplied",
+                    },
+                },
+            },
+            "errorLabels": ["RetryableWriteError"],
+        };
+        let body: WriteResponseBody = bson::from_document(response).unwrap();
+        let error = body.validate().expect_err("validation should fail");
+        assert_eq!(error.labels().len(), 1);
+        assert!(error.contains_label("RetryableWriteError"));
+
+        match *error.kind {
+            ErrorKind::BulkWrite(ref failure) => {
+                let wc_error = failure
+                    .write_concern_error
+                    .as_ref()
+                    .expect("write concern error should be present");
+                assert_eq!(wc_error.code, 64);
+                let write_concern = wc_error
+                    .details
+                    .as_ref()
+                    .and_then(|details| details.get_document("writeConcern").ok())
+                    .expect("errInfo should contain the write concern");
+                assert_eq!(write_concern.get_str("provenance"), Ok("clientSupplied"));
+            }
+            ref e => panic!("expected bulk write error, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn numeric_response_fields() {
+        // Synthetic responses modeled on servers that report counts and cursor ids as int32s when
+        // they fit.
+        let body: WriteResponseBody = bson::from_document(doc! { "n": 1, "ok": 1.0 }).unwrap();
+        assert_eq!(body.n, 1);
+        let body: CursorBody = bson::from_document(doc! {
+            "cursor": { "id": 0, "ns": "db.coll", "firstBatch": [] },
+            "ok": 1.0,
+        })
+        .unwrap();
+        assert_eq!(body.cursor.id, 0);
+
+        // Synthetic responses with int64 cursor ids.
+        let body: WriteResponseBody = bson::from_document(doc! { "n": 2, "ok": 1.0 }).unwrap();
+        assert_eq!(body.n, 2);
+        let body: CursorBody = bson::from_document(doc! {
+            "cursor": { "id": 4_856_719_473_624_538_241_i64, "ns": "db.coll", "firstBatch": [] },
+            "ok": 1.0,
+        })
+        .unwrap();
+        assert_eq!(body.cursor.id, 4_856_719_473_624_538_241);
+
+        // Responses from a proxy that rewrites all integers as doubles.
+        let body: WriteResponseBody = bson::from_document(doc! { "n": 3.0, "ok": 1.0 }).unwrap();
+        assert_eq!(body.n, 3);
+        let body: CursorBody = bson::from_document(doc! {
+            "cursor": { "id": 1_048_576.0, "ns": "db.coll", "firstBatch": [] },
+            "ok": 1.0,
+        })
+        .unwrap();
+        assert_eq!(body.cursor.id, 1_048_576);
+
+        // Conversions that would lose information are rejected.
+        for n in [Bson::Double(1.5), Bson::Int32(-1), Bson::String("1".into())].iter() {
+            let response = doc! { "n": n.clone(), "ok": 1.0 };
+            assert!(bson::from_document::<WriteResponseBody>(response).is_err());
+        }
+    }
 }
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod test;
+
+use serde::Deserialize;
+
+use crate::{
+    bson::doc,
+    cmap::{Command, CommandResponse, StreamDescription},
+    error::{ErrorKind, Result},
+    operation::{append_options, Operation},
+    options::{ProfilingLevel, SetProfilingLevelOptions},
+    selection_criteria::{ReadPreference, SelectionCriteria},
+};
+
+/// The value of the `profile` field that reads the current profiling level without changing it.
+const READ_PROFILING_LEVEL: i32 = -1;
+
+#[derive(Debug)]
+pub(crate) struct Profile {
+    target_db: String,
+    level: Option<ProfilingLevel>,
+    options: Option<SetProfilingLevelOptions>,
+}
+
+impl Profile {
+    /// Creates an operation that reads the current profiling level of the database.
+    pub(crate) fn get(target_db: String) -> Self {
+        Self {
+            target_db,
+            level: None,
+            options: None,
+        }
+    }
+
+    /// Creates an operation that sets the profiling level of the database.
+    pub(crate) fn set(
+        target_db: String,
+        level: ProfilingLevel,
+        options: Option<SetProfilingLevelOptions>,
+    ) -> Self {
+        Self {
+            target_db,
+            level: Some(level),
+            options,
+        }
+    }
+}
+
+impl Operation for Profile {
+    /// The profiling level in effect before the operation was executed.
+    type O = ProfilingLevel;
+    const NAME: &'static str = "profile";
+
+    fn build(&mut self, _description: &StreamDescription) -> Result<Command> {
+        let mut body = doc! {
+            Self::NAME: self.level.map(ProfilingLevel::as_i32).unwrap_or(READ_PROFILING_LEVEL),
+        };
+
+        append_options(&mut body, self.options.as_ref())?;
+
+        Ok(Command::new(
+            Self::NAME.to_string(),
+            self.target_db.clone(),
+            body,
+        ))
+    }
+
+    fn handle_response(
+        &self,
+        response: CommandResponse,
+        _description: &StreamDescription,
+    ) -> Result<Self::O> {
+        let body: ResponseBody = response.body()?;
+        ProfilingLevel::from_i32(body.was).ok_or_else(|| {
+            ErrorKind::InvalidResponse {
+                message: format!("unrecognized profiling level: {}", body.was),
+            }
+            .into()
+        })
+    }
+
+    fn selection_criteria(&self) -> Option<&SelectionCriteria> {
+        Some(SelectionCriteria::ReadPreference(ReadPreference::Primary)).as_ref()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseBody {
+    was: i32,
+}
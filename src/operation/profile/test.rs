@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use crate::{
+    bson::doc,
+    cmap::{CommandResponse, StreamDescription},
+    error::ErrorKind,
+    operation::{Operation, Profile},
+    options::{ProfilingLevel, SetProfilingLevelOptions},
+    selection_criteria::ReadPreference,
+};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_get() {
+    let mut op = Profile::get("test_db".to_string());
+    let cmd = op
+        .build(&StreamDescription::new_testing())
+        .expect("build should succeed");
+
+    assert_eq!(cmd.name, "profile");
+    assert_eq!(cmd.target_db, "test_db");
+    assert_eq!(cmd.body, doc! { "profile": -1 });
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_set() {
+    let mut op = Profile::set("test_db".to_string(), ProfilingLevel::All, None);
+    let cmd = op
+        .build(&StreamDescription::new_testing())
+        .expect("build should succeed");
+    assert_eq!(cmd.body, doc! { "profile": 2 });
+
+    let options = SetProfilingLevelOptions::builder()
+        .slow_ms(Duration::from_millis(50))
+        .sample_rate(0.5)
+        .build();
+    let mut op = Profile::set(
+        "test_db".to_string(),
+        ProfilingLevel::SlowOnly,
+        Some(options),
+    );
+    let cmd = op
+        .build(&StreamDescription::new_testing())
+        .expect("build should succeed");
+    assert_eq!(
+        cmd.body,
+        doc! { "profile": 1, "slowms": 50, "sampleRate": 0.5 }
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success() {
+    let op = Profile::get("test_db".to_string());
+
+    let response = CommandResponse::with_document(doc! {
+        "was": 1,
+        "slowms": 100,
+        "sampleRate": 1.0,
+        "ok": 1
+    });
+    let level = op
+        .handle_response(response, &Default::default())
+        .expect("handle should succeed");
+    assert_eq!(level, ProfilingLevel::SlowOnly);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_invalid_response() {
+    let op = Profile::get("test_db".to_string());
+
+    for body in vec![doc! { "ok": 1 }, doc! { "was": 7, "ok": 1 }] {
+        let result = op.handle_response(CommandResponse::with_document(body), &Default::default());
+        match result.map_err(|e| *e.kind) {
+            Err(ErrorKind::InvalidResponse { .. }) => {}
+            other => panic!("expected response error, but got {:?}", other),
+        }
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn op_selection_criteria() {
+    let op = Profile::set("test_db".to_string(), ProfilingLevel::Off, None);
+    assert_eq!(
+        *op.selection_criteria().unwrap().as_read_pref().unwrap(),
+        ReadPreference::Primary
+    );
+    assert!(op.write_concern().is_none());
+}
@@ -5,7 +5,7 @@ use super::Operation;
 use crate::{
     bson::Document,
     client::SESSIONS_UNSUPPORTED_COMMANDS,
-    cmap::{Command, CommandResponse, StreamDescription},
+    cmap::{Command, CommandResponse, PinnedConnectionHandle, StreamDescription},
     error::{ErrorKind, Result},
     options::WriteConcern,
     selection_criteria::SelectionCriteria,
@@ -17,6 +17,7 @@ pub(crate) struct RunCommand {
     command: Document,
     selection_criteria: Option<SelectionCriteria>,
     write_concern: Option<WriteConcern>,
+    pinned_connection: Option<PinnedConnectionHandle>,
 }
 
 impl RunCommand {
@@ -24,6 +25,7 @@ impl RunCommand {
         db: String,
         command: Document,
         selection_criteria: Option<SelectionCriteria>,
+        pinned_connection: Option<&PinnedConnectionHandle>,
     ) -> Result<Self> {
         let write_concern = command
             .get("writeConcern")
@@ -35,6 +37,7 @@ impl RunCommand {
             command,
             selection_criteria,
             write_concern,
+            pinned_connection: pinned_connection.cloned(),
         })
     }
 
@@ -80,6 +83,10 @@ impl Operation for RunCommand {
         self.write_concern.as_ref()
     }
 
+    fn pinned_connection(&self) -> Option<&PinnedConnectionHandle> {
+        self.pinned_connection.as_ref()
+    }
+
     fn supports_sessions(&self) -> bool {
         self.command_name()
             .map(|command_name| {
@@ -8,7 +8,7 @@ use crate::{
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn build() {
-    let mut op = RunCommand::new("foo".into(), doc! { "isMaster": 1 }, None).unwrap();
+    let mut op = RunCommand::new("foo".into(), doc! { "isMaster": 1 }, None, None).unwrap();
     assert!(op.selection_criteria().is_none());
 
     let command = op.build(&StreamDescription::new_testing()).unwrap();
@@ -27,7 +27,7 @@ async fn build() {
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn no_error_ok_0() {
-    let op = RunCommand::new("foo".into(), doc! { "isMaster": 1 }, None).unwrap();
+    let op = RunCommand::new("foo".into(), doc! { "isMaster": 1 }, None, None).unwrap();
     assert!(op.selection_criteria().is_none());
 
     let command_response = CommandResponse::with_document(doc! {
@@ -7,19 +7,23 @@ use crate::{
     bson::{doc, Bson, Document},
     bson_util,
     cmap::{Command, CommandResponse, StreamDescription},
-    error::{convert_bulk_errors, Result},
-    operation::{Operation, Retryability, WriteResponseBody},
-    options::{UpdateModifications, UpdateOptions, WriteConcern},
+    error::{convert_bulk_errors, ErrorKind, Result},
+    operation::{encode_write_concern, Operation, Retryability, WriteResponseBody},
+    options::{ReplaceOptions, UpdateModifications, UpdateOptions, WriteConcern},
     results::UpdateResult,
     Namespace,
 };
 
+/// The first wire version that supports hints on update statements (4.2).
+const HINT_MIN_WIRE_VERSION: i32 = 8;
+
 #[derive(Debug)]
 pub(crate) struct Update {
     ns: Namespace,
     filter: Document,
     update: UpdateModifications,
     multi: Option<bool>,
+    sort: Option<Document>,
     options: Option<UpdateOptions>,
 }
 
@@ -50,16 +54,51 @@ impl Update {
             filter,
             update,
             multi: if multi { Some(true) } else { None },
+            sort: None,
             options,
         }
     }
+
+    pub(crate) fn replace(
+        ns: Namespace,
+        filter: Document,
+        replacement: Document,
+        options: Option<ReplaceOptions>,
+    ) -> Self {
+        let sort = options.as_ref().and_then(|opts| opts.sort.clone());
+        Self {
+            sort,
+            ..Self::new(
+                ns,
+                filter,
+                UpdateModifications::Document(replacement),
+                false,
+                options.map(UpdateOptions::from_replace_options),
+            )
+        }
+    }
 }
 
 impl Operation for Update {
     type O = UpdateResult;
     const NAME: &'static str = "update";
 
-    fn build(&mut self, _description: &StreamDescription) -> Result<Command> {
+    fn build(&mut self, description: &StreamDescription) -> Result<Command> {
+        if self
+            .options
+            .as_ref()
+            .and_then(|opts| opts.hint.as_ref())
+            .is_some()
+            && description.max_wire_version.unwrap_or(0) < HINT_MIN_WIRE_VERSION
+        {
+            return Err(ErrorKind::InvalidArgument {
+                message: "Specifying a hint to an update or replace is not supported on server \
+                          versions < 4.2"
+                    .to_string(),
+            }
+            .into());
+        }
+
         let mut body = doc! {
             Self::NAME: self.ns.coll.clone(),
         };
@@ -89,16 +128,17 @@ impl Operation for Update {
             if let Some(bypass_doc_validation) = options.bypass_document_validation {
                 body.insert("bypassDocumentValidation", bypass_doc_validation);
             }
-
-            if let Some(ref write_concern) = options.write_concern {
-                body.insert("writeConcern", bson::to_bson(write_concern)?);
-            }
         };
+        encode_write_concern(&mut body, self.write_concern())?;
 
         if let Some(multi) = self.multi {
             update.insert("multi", multi);
         }
 
+        if let Some(ref sort) = self.sort {
+            update.insert("sort", sort.clone());
+        }
+
         body.insert("updates", vec![Bson::Document(update)]);
         body.insert("ordered", true); // command monitoring tests expect this (SPEC-1130)
 
@@ -114,6 +154,10 @@ impl Operation for Update {
         response: CommandResponse,
         _description: &StreamDescription,
     ) -> Result<Self::O> {
+        if !self.is_acknowledged() {
+            return Ok(UpdateResult::unacknowledged());
+        }
+
         let body: WriteResponseBody<UpdateBody> = response.body()?;
         body.validate().map_err(convert_bulk_errors)?;
 
@@ -131,6 +175,7 @@ impl Operation for Update {
             matched_count,
             modified_count,
             upserted_id,
+            acknowledged: true,
         })
     }
 
@@ -140,6 +185,14 @@ impl Operation for Update {
             .and_then(|opts| opts.write_concern.as_ref())
     }
 
+    fn is_write(&self) -> bool {
+        true
+    }
+
+    fn supports_unacknowledged_writes(&self) -> bool {
+        true
+    }
+
     fn retryability(&self) -> Retryability {
         if self.multi != Some(true) {
             Retryability::Write
@@ -151,7 +204,10 @@ impl Operation for Update {
 
 #[derive(Deserialize)]
 struct UpdateBody {
-    #[serde(rename = "nModified")]
+    #[serde(
+        rename = "nModified",
+        deserialize_with = "crate::bson_util::deserialize_u64_from_bson_number"
+    )]
     n_modified: u64,
     upserted: Option<Vec<Document>>,
 }
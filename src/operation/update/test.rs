@@ -8,7 +8,7 @@ use crate::{
     concern::{Acknowledgment, WriteConcern},
     error::{ErrorKind, WriteConcernError, WriteError, WriteFailure},
     operation::{Operation, Update},
-    options::{UpdateModifications, UpdateOptions},
+    options::{ReplaceOptions, UpdateModifications, UpdateOptions},
     Namespace,
 };
 
@@ -117,6 +117,72 @@ async fn build_hint() {
     assert_eq!(cmd.body, expected_body);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_hint_unsupported() {
+    let options = UpdateOptions::builder()
+        .hint(Hint::Name("x_1".into()))
+        .build();
+    let mut op = Update::new(
+        Namespace::empty(),
+        doc! {},
+        UpdateModifications::Document(doc! { "$set": { "x": 1 } }),
+        false,
+        Some(options),
+    );
+
+    let mut description = StreamDescription::new_testing();
+    description.max_wire_version = Some(7);
+    let error = op.build(&description).unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+
+    description.max_wire_version = Some(8);
+    assert!(op.build(&description).is_ok());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_replace() {
+    let ns = Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    };
+    let filter = doc! { "x": { "$gt": 1 } };
+    let replacement = doc! { "x": 1, "replaced": true };
+    let options = ReplaceOptions::builder()
+        .hint(Hint::Keys(doc! { "x": 1 }))
+        .sort(doc! { "x": -1 })
+        .upsert(true)
+        .build();
+
+    let mut op = Update::replace(ns, filter.clone(), replacement.clone(), Some(options));
+
+    let description = StreamDescription::new_testing();
+    let mut cmd = op.build(&description).unwrap();
+
+    assert_eq!(cmd.name.as_str(), "update");
+    assert_eq!(cmd.target_db.as_str(), "test_db");
+
+    let mut expected_body = doc! {
+        "update": "test_coll",
+        "updates": [
+            {
+                "q": filter,
+                "u": replacement,
+                "upsert": true,
+                "hint": { "x": 1 },
+                "sort": { "x": -1 },
+            }
+        ],
+        "ordered": true,
+    };
+
+    bson_util::sort_document(&mut cmd.body);
+    bson_util::sort_document(&mut expected_body);
+
+    assert_eq!(cmd.body, expected_body);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn build_many() {
@@ -174,6 +240,52 @@ async fn handle_success() {
     assert_eq!(update_result.matched_count, 0);
     assert_eq!(update_result.modified_count, 1);
     assert_eq!(update_result.upserted_id, Some(Bson::Int32(1)));
+    assert!(update_result.acknowledged);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success_double_counts() {
+    let op = Update::empty();
+
+    // Some proxies rewrite every integer in a response as a double.
+    let response = CommandResponse::with_document(doc! {
+        "ok": 1.0,
+        "n": 3.0,
+        "nModified": 2.0,
+    });
+
+    let update_result = op
+        .handle_response(response, &Default::default())
+        .expect("handle should succeed");
+    assert_eq!(update_result.matched_count, 3);
+    assert_eq!(update_result.modified_count, 2);
+    assert_eq!(update_result.upserted_id, None);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_unacknowledged() {
+    let options = UpdateOptions {
+        write_concern: Some(WriteConcern::builder().w(Acknowledgment::Nodes(0)).build()),
+        ..Default::default()
+    };
+    let mut op = Update::new(
+        Namespace::empty(),
+        doc! {},
+        UpdateModifications::Document(doc! { "$set": { "x": 1 } }),
+        false,
+        Some(options),
+    );
+    assert!(op.supports_unacknowledged_writes());
+
+    let cmd = op.build(&StreamDescription::new_testing()).unwrap();
+    assert_eq!(cmd.body.get_document("writeConcern"), Ok(&doc! { "w": 0 }));
+
+    // the reply to an unacknowledged write has no counts, so it mustn't be parsed as one.
+    let response = CommandResponse::with_document(doc! { "ok": 1.0 });
+    let update_result = op.handle_response(response, &Default::default()).unwrap();
+    assert!(!update_result.acknowledged);
 }
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
@@ -277,6 +389,7 @@ async fn handle_write_concern_failure() {
                     "wtimeout": 0,
                     "provenance": "clientSupplied"
                 } }),
+                labels: vec![],
             };
             assert_eq!(wc_error, &expected_wc_err);
         }
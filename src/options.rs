@@ -16,11 +16,13 @@
 //! ```
 
 pub use crate::{
+    change_stream::options::*,
     client::{auth::*, options::*},
     coll::options::*,
     collation::*,
     concern::*,
     db::options::*,
+    gridfs::options::*,
     selection_criteria::*,
 };
 
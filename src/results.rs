@@ -1,10 +1,15 @@
 //! Contains the types of results returned by CRUD operations.
 
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
 
 use crate::{
     bson::{Bson, Document},
+    cmap::Connection,
     db::options::CreateCollectionOptions,
+    options::ServerAddress,
 };
 
 use bson::Binary;
@@ -16,14 +21,20 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub struct InsertOneResult {
-    /// The `_id` field of the document inserted.
+    /// The `_id` field of the document inserted. This is populated even if the write was
+    /// unacknowledged, since the `_id` is set by the driver before the document is sent.
     pub inserted_id: Bson,
+
+    /// Whether the write was acknowledged by the server. This is `false` if the write concern
+    /// was unacknowledged (i.e. `w: 0`).
+    pub acknowledged: bool,
 }
 
 impl InsertOneResult {
     pub(crate) fn from_insert_many_result(result: InsertManyResult) -> Self {
         Self {
             inserted_id: result.inserted_ids.get(&0).cloned().unwrap_or(Bson::Null),
+            acknowledged: result.acknowledged,
         }
     }
 }
@@ -34,14 +45,20 @@ impl InsertOneResult {
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub struct InsertManyResult {
-    /// The `_id` field of the documents inserted.
+    /// The `_id` field of the documents inserted. If the write was unacknowledged, this contains
+    /// the `_id` of every document that was sent to the server.
     pub inserted_ids: HashMap<usize, Bson>,
+
+    /// Whether the write was acknowledged by the server. This is `false` if the write concern
+    /// was unacknowledged (i.e. `w: 0`).
+    pub acknowledged: bool,
 }
 
 impl InsertManyResult {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(acknowledged: bool) -> Self {
         InsertManyResult {
             inserted_ids: HashMap::new(),
+            acknowledged,
         }
     }
 }
@@ -62,6 +79,21 @@ pub struct UpdateResult {
 
     /// The `_id` field of the upserted document.
     pub upserted_id: Option<Bson>,
+
+    /// Whether the write was acknowledged by the server. If not, the counts are always zero and
+    /// `upserted_id` is always `None`.
+    pub acknowledged: bool,
+}
+
+impl UpdateResult {
+    pub(crate) fn unacknowledged() -> Self {
+        Self {
+            matched_count: 0,
+            modified_count: 0,
+            upserted_id: None,
+            acknowledged: false,
+        }
+    }
 }
 
 /// The result of a [`Collection::delete_one`](../struct.Collection.html#method.delete_one) or
@@ -73,12 +105,119 @@ pub struct DeleteResult {
     /// The number of documents deleted by the operation.
     #[serde(serialize_with = "crate::bson::serde_helpers::serialize_u64_as_i64")]
     pub deleted_count: u64,
+
+    /// Whether the write was acknowledged by the server. If not, `deleted_count` is always zero.
+    pub acknowledged: bool,
+}
+
+impl DeleteResult {
+    pub(crate) fn unacknowledged() -> Self {
+        Self {
+            deleted_count: 0,
+            acknowledged: false,
+        }
+    }
+}
+
+/// The result of a
+/// [`Collection::find_one_and_delete`](../struct.Collection.html#method.find_one_and_delete),
+/// [`Collection::find_one_and_replace`](../struct.Collection.html#method.find_one_and_replace), or
+/// [`Collection::find_one_and_update`](../struct.Collection.html#method.find_one_and_update)
+/// operation.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct FindOneAndModifyResult<T> {
+    /// The document matching the filter. For a replacement or update, this is the document as it
+    /// was either before or after the modification depending on the `return_document` option.
+    /// This is `None` if no document matched the filter, or if a document was upserted and the
+    /// document before the modification was requested.
+    pub value: Option<T>,
+
+    /// The `_id` field of the upserted document, if `upsert` was set and no document matched the
+    /// filter. This is always `None` for a deletion.
+    pub upserted_id: Option<Bson>,
+}
+
+/// The result of a [`Collection::create_index`](../struct.Collection.html#method.create_index)
+/// operation.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CreateIndexResult {
+    /// The name of the index created.
+    pub index_name: String,
 }
 
+/// Details about how an operation was executed, returned alongside the operation's result from the
+/// `_with_outcome` variants of CRUD methods (e.g.
+/// [`Collection::find_with_outcome`](../struct.Collection.html#method.find_with_outcome)).
 #[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct OperationOutcome {
+    /// The address of the server that the operation was ultimately executed on.
+    pub server_address: ServerAddress,
+
+    /// The number of times the operation was attempted. This is greater than 1 if the operation
+    /// was retried.
+    pub attempts: u32,
+
+    /// The total time taken to execute the operation, including server selection, checking out a
+    /// connection, and any retries.
+    pub duration: Duration,
+
+    /// How long the connection used for the final attempt had been open when the operation
+    /// completed.
+    pub connection_age: Duration,
+}
+
+impl OperationOutcome {
+    pub(crate) fn new(connection: &Connection, attempts: u32, start_time: Instant) -> Self {
+        Self {
+            server_address: connection.address().clone(),
+            attempts,
+            duration: start_time.elapsed(),
+            connection_age: connection.age(),
+        }
+    }
+
+    /// Whether the operation was retried.
+    pub fn was_retried(&self) -> bool {
+        self.attempts > 1
+    }
+}
+
+/// The result of a [`Cursor::collect_with_limit`](../struct.Cursor.html#method.collect_with_limit)
+/// operation.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct CollectResult<T> {
+    /// The results collected from the cursor.
+    pub documents: Vec<T>,
+
+    /// The total size in bytes of the BSON documents that were collected.
+    pub bytes: u64,
+
+    /// Whether collection stopped because a limit was reached before the cursor was known to be
+    /// exhausted. If so, the cursor can continue to be iterated to retrieve any remaining results.
+    /// Because the document limit is checked before requesting another batch, this may be `true`
+    /// even if the next batch turns out to be empty.
+    pub truncated: bool,
+}
+
+#[derive(Debug)]
 pub(crate) struct GetMoreResult {
     pub(crate) batch: VecDeque<Document>,
     pub(crate) exhausted: bool,
+
+    /// The lengths of the documents in the batch as they were read from the server, if known.
+    pub(crate) raw_batch_lengths: Option<Vec<usize>>,
+
+    /// The resume token reported for this batch by a change stream cursor.
+    pub(crate) post_batch_resume_token: Option<Document>,
+
+    /// The connection that the server is streaming the remaining batches on, if the getMore was
+    /// sent for an exhaust cursor and the server chose to stream its replies.
+    pub(crate) streaming_connection: Option<Connection>,
 }
 
 /// Describes the type of data store returned when executing
@@ -0,0 +1,48 @@
+use std::{fmt::Debug, time::Instant};
+
+/// A source of monotonic time. All elapsed-time computations in the driver (e.g. idle connection
+/// pruning and server selection timeouts) should go through a `Clock` rather than the wall clock
+/// so that they aren't affected by system clock adjustments and so that tests can control the
+/// passage of time.
+pub(crate) trait Clock: Debug + Send + Sync {
+    /// Returns the current instant according to this clock.
+    fn now(&self) -> Instant;
+}
+
+/// The `Clock` used outside of tests, which is backed by `Instant::now`.
+#[derive(Debug, Default)]
+pub(crate) struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A `Clock` that only moves forward when explicitly advanced.
+#[cfg(test)]
+#[derive(Debug)]
+pub(crate) struct MockClock {
+    now: std::sync::Mutex<Instant>,
+}
+
+#[cfg(test)]
+impl MockClock {
+    pub(crate) fn new() -> Self {
+        Self {
+            now: std::sync::Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Moves this clock forward by `duration`.
+    pub(crate) fn advance(&self, duration: std::time::Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+}
@@ -1,6 +1,7 @@
 mod acknowledged_message;
 mod async_read_ext;
 mod async_write_ext;
+mod clock;
 mod http;
 #[cfg(feature = "async-std-runtime")]
 mod interval;
@@ -14,11 +15,14 @@ pub(crate) use self::{
     acknowledged_message::AcknowledgedMessage,
     async_read_ext::AsyncLittleEndianRead,
     async_write_ext::AsyncLittleEndianWrite,
+    clock::{Clock, SystemClock},
     join_handle::AsyncJoinHandle,
     resolver::AsyncResolver,
-    stream::AsyncStream,
+    stream::{AsyncStream, DEFAULT_CONNECT_TIMEOUT},
 };
 use crate::{error::Result, options::ServerAddress};
+#[cfg(test)]
+pub(crate) use clock::MockClock;
 pub(crate) use http::HttpClient;
 #[cfg(feature = "async-std-runtime")]
 use interval::Interval;
@@ -100,15 +104,18 @@ impl AsyncRuntime {
         }
     }
 
-    /// Run a future in the foreground, blocking on it completing.
-    /// This does not notify the runtime that it will be blocking and should only be used for
-    /// operations that will immediately (or quickly) succeed.
-    pub(crate) fn block_in_place<F, T>(self, fut: F) -> T
+    /// Run a future in the foreground on behalf of the sync API, blocking on it completing.
+    ///
+    /// Blocking a thread that is running an async task can deadlock the runtime, e.g. when a sync
+    /// handle that wraps an async one is used from async code, so this returns an error in that
+    /// case instead.
+    #[cfg(feature = "sync")]
+    pub(crate) fn block_on_sync<F, T>(self, fut: F) -> Result<T>
     where
-        F: Future<Output = T> + Send,
-        T: Send,
+        F: Future<Output = Result<T>>,
     {
-        futures_executor::block_on(fut)
+        check_sync_context()?;
+        self.block_on(fut)
     }
 
     /// Delay for the specified duration.
@@ -178,6 +185,26 @@ impl AsyncRuntime {
     }
 }
 
+/// Returns an error if the current thread is running an async-std task, in which case blocking it
+/// on behalf of the sync API could deadlock the runtime that the sync API itself runs on. Blocking
+/// code that merely has access to a runtime, e.g. a closure passed to `spawn_blocking`, is allowed.
+///
+/// Threads of a tokio runtime aren't rejected: the sync API's futures don't run on them, and
+/// tokio's API can't tell its worker threads apart from its blocking threads, since both have
+/// access to the runtime's handle.
+#[cfg(feature = "sync")]
+fn check_sync_context() -> Result<()> {
+    if async_std::task::try_current().is_some() {
+        return Err(crate::error::ErrorKind::Internal {
+            message: "the sync API cannot be used from within an async task; use the async API \
+                      instead"
+                .to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
 /// Represents the context in which a given runtime method is being called from.
 #[cfg(feature = "tokio-runtime")]
 enum TokioCallingContext {
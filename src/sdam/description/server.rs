@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::{
     bson::{oid::ObjectId, DateTime},
@@ -12,16 +12,37 @@ const DRIVER_MIN_DB_VERSION: &str = "3.6";
 const DRIVER_MIN_WIRE_VERSION: i32 = 6;
 const DRIVER_MAX_WIRE_VERSION: i32 = 13;
 
+/// The type of a server in the cluster, as described by the SDAM spec.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 #[non_exhaustive]
 pub enum ServerType {
+    /// A single, non-replica set mongod.
     Standalone,
+
+    /// A router used in sharded deployments.
     Mongos,
+
+    /// The primary node in a replica set.
     RsPrimary,
+
+    /// A secondary node in a replica set.
     RsSecondary,
+
+    /// A non-data bearing node in a replica set which can participate in elections.
     RsArbiter,
+
+    /// Hidden, starting up, or recovering nodes in a replica set.
     RsOther,
+
+    /// A member of an uninitialized replica set or a member that has been removed from the replica
+    /// set config.
     RsGhost,
+
+    /// A load balancer fronting the deployment, used when the client is configured with
+    /// `load_balanced`.
+    LoadBalancer,
+
+    /// A server that the driver hasn't yet communicated with or can't connect to.
     Unknown,
 }
 
@@ -43,6 +64,7 @@ impl ServerType {
                 | ServerType::RsPrimary
                 | ServerType::RsSecondary
                 | ServerType::Mongos
+                | ServerType::LoadBalancer
         )
     }
 }
@@ -58,8 +80,16 @@ pub(crate) struct ServerDescription {
     pub(crate) address: ServerAddress,
     pub(crate) server_type: ServerType,
     pub(crate) last_update_time: Option<DateTime>,
+
+    // The monotonic counterpart of `last_update_time`. Staleness is computed from this rather than
+    // from the wall clock time so that system clock adjustments between heartbeats don't skew it.
+    pub(crate) last_update_instant: Option<Instant>,
     pub(crate) average_round_trip_time: Option<Duration>,
 
+    // The number of consecutive monitor checks of the server that had failed when this
+    // description was created.
+    pub(crate) consecutive_failures: u32,
+
     // The SDAM spec indicates that a ServerDescription needs to contain an error message if an
     // error occurred when trying to send an isMaster for the server's heartbeat. Additionally,
     // we need to be able to create a server description that doesn't contain either an isMaster
@@ -106,14 +136,19 @@ impl ServerDescription {
             address,
             server_type: Default::default(),
             last_update_time: None,
+            last_update_instant: None,
             reply: is_master_reply.transpose(),
             average_round_trip_time: None,
+            consecutive_failures: 0,
         };
 
         // We want to set last_update_time if we got any sort of response from the server.
         match description.reply {
             Ok(None) => {}
-            _ => description.last_update_time = Some(DateTime::now()),
+            _ => {
+                description.last_update_time = Some(DateTime::now());
+                description.last_update_instant = Some(Instant::now());
+            }
         };
 
         if let Ok(Some(ref mut reply)) = description.reply {
@@ -167,6 +202,21 @@ impl ServerDescription {
         description
     }
 
+    /// Creates a description of a load balancer, optionally including the reply to a handshake
+    /// made through it. Load balancers are never sent heartbeats, so the fields that are only
+    /// populated by monitoring (e.g. the last update time) are left unset.
+    pub(crate) fn new_load_balancer(
+        address: ServerAddress,
+        is_master_reply: Option<IsMasterReply>,
+    ) -> Self {
+        let mut description = Self::new(address, is_master_reply.map(Ok));
+        description.server_type = ServerType::LoadBalancer;
+        description.last_update_time = None;
+        description.last_update_instant = None;
+        description.average_round_trip_time = None;
+        description
+    }
+
     /// Whether this server is "available" as per the definition in the server selection spec.
     pub(crate) fn is_available(&self) -> bool {
         !matches!(self.server_type, ServerType::Unknown)
@@ -262,7 +312,6 @@ impl ServerDescription {
         Ok(me)
     }
 
-    #[cfg(test)]
     pub(crate) fn min_wire_version(&self) -> Result<Option<i32>, String> {
         let me = self
             .reply
@@ -14,19 +14,40 @@ use crate::{
     client::ClusterTime,
     cmap::Command,
     options::{ClientOptions, ServerAddress},
-    sdam::description::server::{ServerDescription, ServerType},
+    sdam::{
+        description::server::{ServerDescription, ServerType},
+        ServerCapabilities,
+    },
     selection_criteria::{ReadPreference, SelectionCriteria},
 };
 
 const DEFAULT_HEARTBEAT_FREQUENCY: Duration = Duration::from_secs(10);
 
-/// The TopologyType type, as described by the SDAM spec.
+/// The logical session timeout assumed for load balanced deployments, matching the server's
+/// default value.
+const LOAD_BALANCED_LOGICAL_SESSION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// The type of the cluster that a client is connected to, as described by the SDAM spec.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
-pub(crate) enum TopologyType {
+#[non_exhaustive]
+pub enum TopologyType {
+    /// A single server, either a standalone or a replica set member connected to directly.
     Single,
+
+    /// A replica set with no known primary.
     ReplicaSetNoPrimary,
+
+    /// A replica set with a known primary.
     ReplicaSetWithPrimary,
+
+    /// A sharded cluster.
     Sharded,
+
+    /// A deployment behind a load balancer, which the driver treats as a single server without
+    /// monitoring it.
+    LoadBalanced,
+
+    /// The type of the cluster has not yet been determined.
     Unknown,
 }
 
@@ -99,7 +120,11 @@ impl TopologyDescription {
                 .and_then(|criteria| criteria.max_staleness()),
         )?;
 
-        let topology_type = if let Some(true) = options.direct_connection {
+        let load_balanced = options.load_balanced == Some(true);
+
+        let topology_type = if load_balanced {
+            TopologyType::LoadBalanced
+        } else if let Some(true) = options.direct_connection {
             TopologyType::Single
         } else if options.repl_set_name.is_some() {
             TopologyType::ReplicaSetNoPrimary
@@ -111,12 +136,34 @@ impl TopologyDescription {
             .hosts
             .into_iter()
             .map(|address| {
-                let description = ServerDescription::new(address.clone(), None);
+                // A load balancer is never monitored, so its type is known up front rather than
+                // being discovered by a heartbeat.
+                let description = if load_balanced {
+                    ServerDescription::new_load_balancer(address.clone(), None)
+                } else {
+                    ServerDescription::new(address.clone(), None)
+                };
 
                 (address, description)
             })
             .collect();
 
+        // Sessions and transactions are always supported through a load balancer, and since no
+        // heartbeats are sent, the logical session timeout can't be learned from the deployment.
+        let (session_support_status, transaction_support_status) = if load_balanced {
+            (
+                SessionSupportStatus::Supported {
+                    logical_session_timeout: LOAD_BALANCED_LOGICAL_SESSION_TIMEOUT,
+                },
+                TransactionSupportStatus::Supported,
+            )
+        } else {
+            (
+                SessionSupportStatus::Undetermined,
+                TransactionSupportStatus::Undetermined,
+            )
+        };
+
         Ok(Self {
             single_seed: servers.len() == 1,
             topology_type,
@@ -124,8 +171,8 @@ impl TopologyDescription {
             max_set_version: None,
             max_election_id: None,
             compatibility_error: None,
-            session_support_status: SessionSupportStatus::Undetermined,
-            transaction_support_status: TransactionSupportStatus::Undetermined,
+            session_support_status,
+            transaction_support_status,
             cluster_time: None,
             local_threshold: options.local_threshold,
             heartbeat_freq: options.heartbeat_freq,
@@ -142,6 +189,10 @@ impl TopologyDescription {
         self.servers.keys()
     }
 
+    pub(crate) fn server_descriptions(&self) -> impl Iterator<Item = &ServerDescription> {
+        self.servers.values()
+    }
+
     pub(crate) fn cluster_time(&self) -> Option<&ClusterTime> {
         self.cluster_time.as_ref()
     }
@@ -161,7 +212,8 @@ impl TopologyDescription {
     ) {
         match (self.topology_type, server_type) {
             (TopologyType::Sharded, ServerType::Mongos)
-            | (TopologyType::Single, ServerType::Mongos) => {
+            | (TopologyType::Single, ServerType::Mongos)
+            | (TopologyType::LoadBalanced, _) => {
                 self.update_command_read_pref_for_mongos(command, criteria);
             }
             (TopologyType::Single, ServerType::Standalone) => {}
@@ -331,8 +383,11 @@ impl TopologyDescription {
         if let Ok(Some(max_wire_version)) = server_description.max_wire_version() {
             match self.topology_type {
                 TopologyType::Sharded => {
-                    // TODO RUST-122: support transactions on sharded clusters
-                    self.transaction_support_status = TransactionSupportStatus::Unsupported;
+                    if max_wire_version < 8 {
+                        self.transaction_support_status = TransactionSupportStatus::Unsupported;
+                    } else {
+                        self.transaction_support_status = TransactionSupportStatus::Supported;
+                    }
                 }
                 _ => {
                     if max_wire_version < 7 {
@@ -387,6 +442,44 @@ impl TopologyDescription {
         self.servers.retain(|host, _| hosts.contains(host));
     }
 
+    /// Gets the features supported by every known data-bearing server in the topology.
+    pub(crate) fn server_capabilities(&self) -> ServerCapabilities {
+        let mut wire_versions: Option<(i32, i32)> = None;
+        let mut replica_set = true;
+        let mut sharded = true;
+
+        for server in self.servers.values() {
+            if !server.server_type.is_data_bearing() {
+                continue;
+            }
+
+            // A load balancer's own type says nothing about the deployment behind it, so the type
+            // reported by the deployment in the handshake is used instead.
+            let server_type = match (server.server_type, &server.reply) {
+                (ServerType::LoadBalancer, Ok(Some(reply))) => reply.command_response.server_type(),
+                (server_type, _) => server_type,
+            };
+
+            let max = match server.max_wire_version() {
+                Ok(Some(max)) => max,
+                _ => continue,
+            };
+            let min = server.min_wire_version().ok().flatten().unwrap_or(0);
+
+            wire_versions = Some(match wire_versions {
+                Some((current_min, current_max)) => (current_min.max(min), current_max.min(max)),
+                None => (min, max),
+            });
+            replica_set &= matches!(server_type, ServerType::RsPrimary | ServerType::RsSecondary);
+            sharded &= server_type == ServerType::Mongos;
+        }
+
+        match wire_versions {
+            Some(_) => ServerCapabilities::new(wire_versions, replica_set, sharded),
+            None => ServerCapabilities::default(),
+        }
+    }
+
     pub(crate) fn session_support_status(&self) -> SessionSupportStatus {
         self.session_support_status
     }
@@ -395,6 +488,24 @@ impl TopologyDescription {
         self.transaction_support_status
     }
 
+    /// Records the description of a load balancer created from the reply to a handshake made
+    /// through it. Since load balancers aren't monitored, this is the only way to learn about
+    /// the deployment behind one.
+    ///
+    /// Unlike `update`, this doesn't affect session or transaction support, which are always
+    /// supported through a load balancer.
+    pub(crate) fn update_load_balancer(&mut self, server_description: ServerDescription) {
+        if self.topology_type != TopologyType::LoadBalanced
+            || !self.servers.contains_key(&server_description.address)
+        {
+            return;
+        }
+
+        self.servers
+            .insert(server_description.address.clone(), server_description);
+        self.check_compatibility();
+    }
+
     /// Update the topology based on the new information about the topology contained by the
     /// ServerDescription.
     pub(crate) fn update(
@@ -429,7 +540,7 @@ impl TopologyDescription {
 
         // Update the topology description based on the current topology type.
         match self.topology_type {
-            TopologyType::Single => {}
+            TopologyType::Single | TopologyType::LoadBalanced => {}
             TopologyType::Unknown => self.update_unknown_topology(server_description)?,
             TopologyType::Sharded => self.update_sharded_topology(server_description),
             TopologyType::ReplicaSetNoPrimary => {
@@ -452,7 +563,7 @@ impl TopologyDescription {
         server_description: ServerDescription,
     ) -> Result<(), String> {
         match server_description.server_type {
-            ServerType::Unknown | ServerType::RsGhost => {}
+            ServerType::Unknown | ServerType::RsGhost | ServerType::LoadBalancer => {}
             ServerType::Standalone => {
                 self.update_unknown_with_standalone_server(server_description)
             }
@@ -487,7 +598,7 @@ impl TopologyDescription {
     ) -> Result<(), String> {
         match server_description.server_type {
             ServerType::Unknown | ServerType::RsGhost => {}
-            ServerType::Standalone | ServerType::Mongos => {
+            ServerType::Standalone | ServerType::Mongos | ServerType::LoadBalancer => {
                 self.servers.remove(&server_description.address);
             }
             ServerType::RsPrimary => {
@@ -511,7 +622,7 @@ impl TopologyDescription {
             ServerType::Unknown | ServerType::RsGhost => {
                 self.record_primary_state();
             }
-            ServerType::Standalone | ServerType::Mongos => {
+            ServerType::Standalone | ServerType::Mongos | ServerType::LoadBalancer => {
                 self.servers.remove(&server_description.address);
                 self.record_primary_state();
             }
@@ -751,8 +862,7 @@ pub(crate) enum TransactionSupportStatus {
     Unsupported,
 
     /// Transactions are supported by this topology. A topology supports transactions if it
-    /// supports sessions and its maxWireVersion >= 7. Transactions are not currently supported
-    /// on sharded clusters (TODO RUST-122).
+    /// supports sessions and its maxWireVersion >= 7, or >= 8 for sharded clusters.
     ///
     /// Note that meeting these conditions does not guarantee that a deployment
     /// supports transactions; any other missing qualification will be reported by the server.
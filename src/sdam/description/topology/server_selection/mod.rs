@@ -114,6 +114,12 @@ impl TopologyDescription {
             return Ok(Vec::new());
         }
 
+        // All operations are routed through the load balancer regardless of the criteria, since it
+        // decides which backend handles them.
+        if let TopologyType::LoadBalanced = self.topology_type {
+            return Ok(self.servers.values().collect());
+        }
+
         let mut suitable_servers = match criteria {
             SelectionCriteria::ReadPreference(ref read_pref) => self.suitable_servers(read_pref)?,
             SelectionCriteria::Predicate(ref filter) => self
@@ -138,7 +144,7 @@ impl TopologyDescription {
     ) -> Result<Vec<&'a ServerDescription>> {
         let servers = match self.topology_type {
             TopologyType::Unknown => Vec::new(),
-            TopologyType::Single => self.servers.values().collect(),
+            TopologyType::Single | TopologyType::LoadBalanced => self.servers.values().collect(),
             TopologyType::Sharded => self.servers_with_type(&[ServerType::Mongos]).collect(),
             TopologyType::ReplicaSetWithPrimary | TopologyType::ReplicaSetNoPrimary => {
                 self.suitable_servers_in_replica_set(read_preference)?
@@ -329,17 +335,23 @@ impl TopologyDescription {
         secondary: &ServerDescription,
         primary: &ServerDescription,
     ) -> Option<i64> {
-        let primary_last_update = primary.last_update_time?.timestamp_millis();
+        let primary_last_update = primary.last_update_instant?;
         let primary_last_write = primary.last_write_date().ok()??.timestamp_millis();
 
-        let secondary_last_update = secondary.last_update_time?.timestamp_millis();
+        let secondary_last_update = secondary.last_update_instant?;
         let secondary_last_write = secondary.last_write_date().ok()??.timestamp_millis();
 
         let heartbeat_frequency = self.heartbeat_frequency().as_millis() as i64;
 
-        let staleness = (secondary_last_update - secondary_last_write)
-            - (primary_last_update - primary_last_write)
-            + heartbeat_frequency;
+        // (S.lastUpdateTime - S.lastWriteDate) - (P.lastUpdateTime - P.lastWriteDate), rearranged
+        // so that the update times are only ever compared against each other.
+        let update_delta = if secondary_last_update >= primary_last_update {
+            (secondary_last_update - primary_last_update).as_millis() as i64
+        } else {
+            -((primary_last_update - secondary_last_update).as_millis() as i64)
+        };
+        let staleness =
+            update_delta - (secondary_last_write - primary_last_write) + heartbeat_frequency;
 
         Some(staleness)
     }
@@ -10,7 +10,7 @@ use crate::{
     options::ServerAddress,
     runtime::AsyncJoinHandle,
     sdam::{description::topology::server_selection, Server},
-    selection_criteria::ReadPreference,
+    selection_criteria::{ReadPreference, SelectionCriteria},
     test::{
         run_spec_test,
         EventClient,
@@ -68,10 +68,9 @@ async fn run_test(test_file: TestFile) {
         .into_topology_description(None)
         .unwrap();
 
-    let read_pref = ReadPreference::Nearest {
+    let read_pref = SelectionCriteria::ReadPreference(ReadPreference::Nearest {
         options: Default::default(),
-    }
-    .into();
+    });
 
     for _ in 0..test_file.iterations {
         let selection =
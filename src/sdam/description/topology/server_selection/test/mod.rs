@@ -1,23 +1,31 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use bson::{doc, DateTime};
+use bson::{doc, DateTime, Document};
+use lazy_static::lazy_static;
 use serde::Deserialize;
 
 use crate::{
+    error::ErrorKind,
     is_master::{IsMasterCommandResponse, IsMasterReply, LastWrite},
-    options::ServerAddress,
+    options::{ClientOptions, ServerAddress},
     sdam::{
-        description::topology::{test::f64_ms_as_duration, TopologyType},
+        description::topology::{test::f64_ms_as_duration, SessionSupportStatus, TopologyType},
+        public::ServerInfo,
         ServerDescription,
         ServerType,
         TopologyDescription,
     },
-    selection_criteria::TagSet,
+    selection_criteria::{ReadPreference, SelectionCriteria, TagSet},
 };
 
 mod in_window;
 mod logic;
 
+lazy_static! {
+    /// The instant that the `lastUpdateTime` values in the spec tests are relative to.
+    static ref LAST_UPDATE_BASE: Instant = Instant::now();
+}
+
 #[derive(Debug, Deserialize)]
 struct TestTopologyDescription {
     #[serde(rename = "type")]
@@ -94,6 +102,7 @@ impl TestServerDescription {
 
         let is_master = IsMasterReply {
             command_response,
+            raw_command_response: Document::new(),
             round_trip_time: self.avg_rtt_ms.map(f64_ms_as_duration),
             cluster_time: None,
         };
@@ -105,6 +114,9 @@ impl TestServerDescription {
         server_desc.last_update_time = self
             .last_update_time
             .map(|i| DateTime::from_millis(i.into()));
+        server_desc.last_update_instant = self
+            .last_update_time
+            .map(|i| *LAST_UPDATE_BASE + Duration::from_millis(i as u64));
 
         Some(server_desc)
     }
@@ -185,10 +197,150 @@ fn is_master_response_from_server_type(server_type: ServerType) -> IsMasterComma
             response.ok = Some(1.0);
             response.is_replica_set = Some(true);
         }
-        ServerType::Standalone => {
+        ServerType::Standalone | ServerType::LoadBalancer => {
             response.ok = Some(1.0);
         }
     };
 
     response
 }
+
+#[test]
+fn staleness_ignores_wall_clock_adjustments() {
+    let topology = TestTopologyDescription {
+        topology_type: TopologyType::ReplicaSetWithPrimary,
+        servers: Vec::new(),
+    }
+    .into_topology_description(Some(Duration::from_secs(10)))
+    .unwrap();
+
+    let server = |address: &str, server_type, last_write_date| {
+        let mut command_response = is_master_response_from_server_type(server_type);
+        command_response.last_write = Some(LastWrite {
+            last_write_date: DateTime::from_millis(last_write_date),
+        });
+        let is_master = IsMasterReply {
+            command_response,
+            raw_command_response: Document::new(),
+            round_trip_time: None,
+            cluster_time: None,
+        };
+        let mut server_desc =
+            ServerDescription::new(ServerAddress::parse(address).unwrap(), Some(Ok(is_master)));
+        server_desc.last_update_instant = Some(*LAST_UPDATE_BASE);
+        server_desc
+    };
+
+    let primary = server("a:27017", ServerType::RsPrimary, 10_000);
+    let mut secondary = server("b:27017", ServerType::RsSecondary, 9_000);
+
+    // Simulate the system clock being stepped back an hour between the two heartbeats.
+    secondary.last_update_time = primary
+        .last_update_time
+        .map(|time| DateTime::from_millis(time.timestamp_millis() - 60 * 60 * 1000));
+
+    assert_eq!(
+        topology.calculate_secondary_staleness_with_primary(&secondary, &primary),
+        Some(11_000)
+    );
+}
+
+#[test]
+fn predicate_helpers() {
+    let server = |address: &str, tags: Option<TagSet>, rtt_ms: Option<u64>| {
+        let mut command_response = is_master_response_from_server_type(ServerType::RsSecondary);
+        command_response.tags = tags;
+        let is_master = IsMasterReply {
+            command_response,
+            raw_command_response: Document::new(),
+            round_trip_time: rtt_ms.map(Duration::from_millis),
+            cluster_time: None,
+        };
+        ServerDescription::new(ServerAddress::parse(address).unwrap(), Some(Ok(is_master)))
+    };
+    let tags = |pairs: &[(&str, &str)]| -> TagSet {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    };
+
+    let east = server(
+        "a:27017",
+        Some(tags(&[("dc", "east"), ("rack", "1")])),
+        Some(5),
+    );
+    let west = server("b:27017", Some(tags(&[("dc", "west")])), Some(50));
+    let untagged = server("c:27017", None, None);
+
+    let selects = |criteria: &SelectionCriteria, server: &ServerDescription| {
+        criteria.as_predicate().unwrap()(&ServerInfo::new(server))
+    };
+
+    let host = SelectionCriteria::host(ServerAddress::parse("b:27017").unwrap());
+    assert!(!selects(&host, &east));
+    assert!(selects(&host, &west));
+
+    let tagged = SelectionCriteria::tagged(vec![tags(&[("dc", "east")]), tags(&[("dc", "north")])]);
+    assert!(selects(&tagged, &east));
+    assert!(!selects(&tagged, &west));
+    assert!(!selects(&tagged, &untagged));
+    let any = SelectionCriteria::tagged(vec![TagSet::new()]);
+    assert!(selects(&any, &untagged));
+
+    let fast = SelectionCriteria::max_latency(Duration::from_millis(10));
+    assert!(selects(&fast, &east));
+    assert!(!selects(&fast, &west));
+    assert!(!selects(&fast, &untagged));
+
+    let west_or_fast = host.clone().or(fast.clone()).unwrap();
+    assert!(selects(&west_or_fast, &east));
+    assert!(selects(&west_or_fast, &west));
+    assert!(!selects(&west_or_fast, &untagged));
+
+    let west_and_fast = host.and(fast).unwrap();
+    assert!(!selects(&west_and_fast, &east));
+    assert!(!selects(&west_and_fast, &west));
+
+    let read_pref = SelectionCriteria::ReadPreference(ReadPreference::Primary);
+    let error = tagged.and(read_pref).unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+}
+
+#[test]
+fn load_balanced_selects_load_balancer() {
+    let address = ServerAddress::parse("lb:27017").unwrap();
+    let mut options = ClientOptions::builder()
+        .hosts(vec![address.clone()])
+        .build();
+    options.load_balanced = Some(true);
+    let topology = TopologyDescription::new(options).unwrap();
+
+    assert_eq!(topology.topology_type(), TopologyType::LoadBalanced);
+    assert_eq!(
+        topology
+            .get_server_description(&address)
+            .map(|server| server.server_type),
+        Some(ServerType::LoadBalancer)
+    );
+    assert!(matches!(
+        topology.session_support_status(),
+        SessionSupportStatus::Supported { .. }
+    ));
+
+    // The load balancer is selected no matter what the criteria are.
+    let criteria = [
+        SelectionCriteria::ReadPreference(ReadPreference::Primary),
+        SelectionCriteria::ReadPreference(ReadPreference::Secondary {
+            options: Default::default(),
+        }),
+        SelectionCriteria::Predicate(std::sync::Arc::new(|_| false)),
+    ];
+    for criteria in criteria.iter() {
+        let suitable = topology
+            .suitable_servers_in_latency_window(criteria)
+            .unwrap();
+        assert_eq!(suitable.len(), 1);
+        assert_eq!(suitable[0].address, address);
+    }
+}
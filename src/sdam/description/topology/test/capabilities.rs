@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use crate::{
+    bson::{doc, Document},
+    is_master::{IsMasterCommandResponse, IsMasterReply},
+    options::{ClientOptions, ServerAddress},
+    sdam::{
+        description::{
+            server::{ServerDescription, ServerType},
+            topology::{TopologyDescription, TopologyType},
+        },
+        ServerCapabilities,
+    },
+};
+
+const MONGODB_3_6: i32 = 6;
+const MONGODB_4_0: i32 = 7;
+const MONGODB_4_2: i32 = 8;
+const MONGODB_5_0: i32 = 13;
+
+fn reply(mut response: Document, max_wire_version: i32) -> IsMasterReply {
+    response.insert("ok", 1);
+    response.insert("minWireVersion", 0);
+    response.insert("maxWireVersion", max_wire_version);
+    response.insert("maxBsonObjectSize", 16_000);
+    response.insert("maxWriteBatchSize", 10_000);
+    let command_response: IsMasterCommandResponse = bson::from_document(response.clone()).unwrap();
+
+    IsMasterReply {
+        command_response,
+        raw_command_response: response,
+        round_trip_time: None,
+        cluster_time: None,
+    }
+}
+
+fn address(port: u16) -> ServerAddress {
+    ServerAddress::Tcp {
+        host: "localhost".to_string(),
+        port: Some(port),
+    }
+}
+
+fn server(port: u16, response: Document, max_wire_version: i32) -> ServerDescription {
+    ServerDescription::new(address(port), Some(Ok(reply(response, max_wire_version))))
+}
+
+fn primary(port: u16, max_wire_version: i32) -> ServerDescription {
+    server(
+        port,
+        doc! { "ismaster": true, "setName": "rs" },
+        max_wire_version,
+    )
+}
+
+fn secondary(port: u16, max_wire_version: i32) -> ServerDescription {
+    server(
+        port,
+        doc! { "ismaster": false, "secondary": true, "setName": "rs" },
+        max_wire_version,
+    )
+}
+
+fn capabilities_of(
+    topology_type: TopologyType,
+    servers: Vec<ServerDescription>,
+) -> ServerCapabilities {
+    let topology = TopologyDescription {
+        single_seed: false,
+        topology_type,
+        set_name: None,
+        max_set_version: None,
+        max_election_id: None,
+        compatibility_error: None,
+        session_support_status: Default::default(),
+        transaction_support_status: Default::default(),
+        cluster_time: None,
+        local_threshold: None,
+        heartbeat_freq: None,
+        servers: servers
+            .into_iter()
+            .map(|server| (server.address.clone(), server))
+            .collect::<HashMap<_, _>>(),
+    };
+
+    topology.server_capabilities()
+}
+
+#[test]
+fn unknown_before_discovery() {
+    let unknown = ServerDescription::new(ServerAddress::default(), None);
+    let capabilities = capabilities_of(TopologyType::Unknown, vec![unknown]);
+
+    assert_eq!(capabilities, ServerCapabilities::default());
+    assert!(!capabilities.is_known());
+    assert_eq!(capabilities.min_wire_version(), None);
+    assert_eq!(capabilities.max_wire_version(), None);
+    assert!(!capabilities.supports_transactions());
+    assert!(!capabilities.supports_retryable_writes());
+    assert!(!capabilities.supports_change_streams());
+    assert!(!capabilities.supports_change_stream_start_after());
+    assert!(!capabilities.supports_wildcard_indexes());
+}
+
+#[test]
+fn replica_set() {
+    // (max wire version, transactions, change stream startAfter, wildcard indexes)
+    let levels = [
+        (MONGODB_3_6, false, false, false),
+        (MONGODB_4_0, true, false, false),
+        (MONGODB_4_2, true, true, true),
+        (MONGODB_5_0, true, true, true),
+    ];
+
+    for (max_wire_version, transactions, start_after, wildcard_indexes) in levels.iter().copied() {
+        let capabilities = capabilities_of(
+            TopologyType::ReplicaSetWithPrimary,
+            vec![
+                primary(27017, max_wire_version),
+                secondary(27018, max_wire_version),
+            ],
+        );
+
+        assert!(capabilities.is_known());
+        assert_eq!(capabilities.min_wire_version(), Some(0));
+        assert_eq!(capabilities.max_wire_version(), Some(max_wire_version));
+        assert_eq!(capabilities.supports_transactions(), transactions);
+        assert!(capabilities.supports_retryable_writes());
+        assert!(capabilities.supports_change_streams());
+        assert_eq!(
+            capabilities.supports_change_stream_start_after(),
+            start_after
+        );
+        assert_eq!(capabilities.supports_wildcard_indexes(), wildcard_indexes);
+    }
+}
+
+#[test]
+fn mixed_versions_use_lowest_max_wire_version() {
+    let arbiter = server(
+        27019,
+        doc! { "arbiterOnly": true, "setName": "rs" },
+        MONGODB_3_6,
+    );
+    let capabilities = capabilities_of(
+        TopologyType::ReplicaSetWithPrimary,
+        vec![
+            primary(27017, MONGODB_4_2),
+            secondary(27018, MONGODB_4_0),
+            arbiter,
+        ],
+    );
+
+    // The arbiter isn't data-bearing, so it doesn't hold back the rest of the deployment.
+    assert_eq!(capabilities.max_wire_version(), Some(MONGODB_4_0));
+    assert!(capabilities.supports_transactions());
+    assert!(!capabilities.supports_change_stream_start_after());
+    assert!(!capabilities.supports_wildcard_indexes());
+
+    // Once the secondary is upgraded, the 4.2 features become available.
+    let capabilities = capabilities_of(
+        TopologyType::ReplicaSetWithPrimary,
+        vec![primary(27017, MONGODB_4_2), secondary(27018, MONGODB_4_2)],
+    );
+    assert_eq!(capabilities.max_wire_version(), Some(MONGODB_4_2));
+    assert!(capabilities.supports_change_stream_start_after());
+    assert!(capabilities.supports_wildcard_indexes());
+}
+
+#[test]
+fn sharded() {
+    let capabilities = capabilities_of(
+        TopologyType::Sharded,
+        vec![
+            server(27017, doc! { "msg": "isdbgrid" }, MONGODB_4_2),
+            server(27018, doc! { "msg": "isdbgrid" }, MONGODB_4_2),
+        ],
+    );
+
+    assert_eq!(capabilities.max_wire_version(), Some(MONGODB_4_2));
+    assert!(capabilities.supports_transactions());
+    assert!(capabilities.supports_retryable_writes());
+    assert!(capabilities.supports_change_stream_start_after());
+    assert!(capabilities.supports_wildcard_indexes());
+
+    // Sharded transactions require 4.2, a version later than replica set transactions.
+    let capabilities = capabilities_of(
+        TopologyType::Sharded,
+        vec![server(27017, doc! { "msg": "isdbgrid" }, MONGODB_4_0)],
+    );
+    assert!(!capabilities.supports_transactions());
+    assert!(capabilities.supports_retryable_writes());
+}
+
+#[test]
+fn standalone() {
+    let capabilities = capabilities_of(
+        TopologyType::Single,
+        vec![server(27017, doc! { "ismaster": true }, MONGODB_5_0)],
+    );
+
+    assert_eq!(capabilities.max_wire_version(), Some(MONGODB_5_0));
+    assert!(!capabilities.supports_transactions());
+    assert!(!capabilities.supports_retryable_writes());
+    assert!(!capabilities.supports_change_streams());
+    assert!(!capabilities.supports_change_stream_start_after());
+    assert!(capabilities.supports_wildcard_indexes());
+}
+
+#[test]
+fn load_balancer_uses_handshake() {
+    let mut options = ClientOptions::builder().hosts(vec![address(27017)]).build();
+    options.load_balanced = Some(true);
+    let mut topology = TopologyDescription::new(options).unwrap();
+
+    // The load balancer is never sent heartbeats, so nothing is known until a connection has been
+    // handshaked through it.
+    assert!(!topology.server_capabilities().is_known());
+
+    topology.update_load_balancer(ServerDescription::new_load_balancer(
+        address(27017),
+        Some(reply(doc! { "msg": "isdbgrid" }, MONGODB_5_0)),
+    ));
+
+    let capabilities = topology.server_capabilities();
+    assert_eq!(capabilities.max_wire_version(), Some(MONGODB_5_0));
+    assert!(capabilities.supports_retryable_writes());
+    assert!(capabilities.supports_change_stream_start_after());
+    assert!(capabilities.supports_wildcard_indexes());
+
+    // The handshake doesn't change how the load balancer itself is treated.
+    assert_eq!(
+        topology
+            .get_server_description(&address(27017))
+            .map(|server| server.server_type),
+        Some(ServerType::LoadBalancer)
+    );
+}
@@ -1,4 +1,11 @@
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 use bson::Document;
 use serde::Deserialize;
@@ -20,6 +27,7 @@ use crate::{
     },
     selection_criteria::TagSet,
     test::{run_spec_test, TestClient, CLIENT_OPTIONS, LOCK},
+    RUNTIME,
 };
 
 #[derive(Debug, Deserialize)]
@@ -98,11 +106,13 @@ impl From<TestIsMasterCommandResponse> for IsMasterCommandResponse {
             max_wire_version: test.max_wire_version,
             tags: test.tags,
             election_id: test.election_id,
+            service_id: None,
             primary: test.primary,
             sasl_supported_mechs: test.sasl_supported_mechs,
             speculative_authenticate: test.speculative_authenticate,
             max_bson_object_size: test.max_bson_object_size.unwrap_or(1234),
             max_write_batch_size: test.max_write_batch_size.unwrap_or(1234),
+            topology_version: None,
         }
     }
 }
@@ -232,6 +242,7 @@ async fn run_test(test_file: TestFile) {
             } else {
                 Ok(IsMasterReply {
                     command_response: command_response.into(),
+                    raw_command_response: Document::new(),
                     round_trip_time: Some(Duration::from_millis(1234)), // Doesn't matter for tests.
                     cluster_time: None,
                 })
@@ -275,6 +286,7 @@ async fn run_test(test_file: TestFile) {
                         HandshakePhase::AfterCompletion {
                             generation: error_generation,
                             max_wire_version: application_error.max_wire_version,
+                            service_id: None,
                         }
                     }
                 };
@@ -529,6 +541,7 @@ async fn pool_cleared_error_does_not_mark_unknown() {
                 address.clone(),
                 Some(Ok(IsMasterReply {
                     command_response: heartbeat_response,
+                    raw_command_response: Document::new(),
                     round_trip_time: Some(Duration::from_secs(1)),
                     cluster_time: None,
                 })),
@@ -566,3 +579,102 @@ async fn pool_cleared_error_does_not_mark_unknown() {
         ServerType::Standalone
     );
 }
+
+/// Measures server selection throughput as the number of concurrently selecting tasks grows, with
+/// a monitor-like task continuously publishing heartbeats in the background. Selection only loads
+/// a snapshot of the topology, so it should neither block on the updates nor fail while they're
+/// happening.
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn concurrent_server_selection() {
+    const SELECTIONS_PER_TASK: usize = 2_000;
+
+    let address = ServerAddress::parse("a:1234").unwrap();
+    let options = ClientOptions::builder()
+        .hosts(vec![address.clone()])
+        .build();
+    let topology = Topology::new_mocked(options);
+    let server = topology
+        .get_servers()
+        .await
+        .remove(&address)
+        .and_then(|s| s.upgrade())
+        .unwrap();
+
+    let heartbeat = move || {
+        let heartbeat_response: IsMasterCommandResponse = bson::from_document(doc! {
+            "ok": 1,
+            "ismaster": true,
+            "minWireVersion": 0,
+            "maxWireVersion": 6,
+            "maxBsonObjectSize": 16_000,
+            "maxWriteBatchSize": 10_000,
+        })
+        .unwrap();
+        ServerDescription::new(
+            address.clone(),
+            Some(Ok(IsMasterReply {
+                command_response: heartbeat_response,
+                raw_command_response: Document::new(),
+                round_trip_time: Some(Duration::from_millis(1)),
+                cluster_time: None,
+            })),
+        )
+    };
+    topology.update(&server, heartbeat()).await;
+
+    let done = Arc::new(AtomicBool::new(false));
+    let monitor = RUNTIME
+        .spawn({
+            let topology = topology.clone();
+            let server = server.clone();
+            let done = done.clone();
+            async move {
+                let mut heartbeats = 0;
+                while !done.load(Ordering::SeqCst) {
+                    topology.update(&server, heartbeat()).await;
+                    heartbeats += 1;
+                    RUNTIME.delay_for(Duration::from_millis(1)).await;
+                }
+                heartbeats
+            }
+        })
+        .unwrap();
+
+    let criteria = SelectionCriteria::ReadPreference(ReadPreference::Nearest {
+        options: Default::default(),
+    });
+    for num_tasks in [1, 4, 16, 64].iter().copied() {
+        let start = Instant::now();
+        let tasks: Vec<_> = (0..num_tasks)
+            .map(|_| {
+                let topology = topology.clone();
+                let criteria = criteria.clone();
+                RUNTIME
+                    .spawn(async move {
+                        for _ in 0..SELECTIONS_PER_TASK {
+                            let selected = topology.attempt_to_select_server(&criteria).await;
+                            assert!(matches!(selected, Ok(Some(_))));
+                        }
+                    })
+                    .unwrap()
+            })
+            .collect();
+        for task in tasks {
+            task.await;
+        }
+
+        let elapsed = start.elapsed();
+        let selections = num_tasks * SELECTIONS_PER_TASK;
+        println!(
+            "{} tasks: {} selections in {:?} ({:.0} selections/sec)",
+            num_tasks,
+            selections,
+            elapsed,
+            selections as f64 / elapsed.as_secs_f64()
+        );
+    }
+
+    done.store(true, Ordering::SeqCst);
+    assert!(monitor.await > 0);
+}
@@ -50,10 +50,15 @@ pub(crate) struct TopologyMessageSubscriber {
 }
 
 impl TopologyMessageSubscriber {
-    fn new(receiver: Receiver<()>) -> Self {
+    pub(super) fn new(receiver: Receiver<()>) -> Self {
         Self { receiver }
     }
 
+    /// Waits until a message is received.
+    pub(crate) async fn wait_for_any_message(&mut self) {
+        let _: Result<_, _> = self.receiver.recv().await;
+    }
+
     /// Waits for either `timeout` to elapse or a message to be received.
     /// Returns true if a message was received, false for a timeout.
     pub(crate) async fn wait_for_message(&mut self, timeout: Duration) -> bool {
@@ -8,7 +8,7 @@ mod state;
 #[cfg(test)]
 mod test;
 
-pub use self::public::{ServerInfo, ServerType};
+pub use self::public::{ServerCapabilities, ServerInfo, ServerType, TopologyType};
 
 #[cfg(test)]
 pub(crate) use self::description::server::ServerDescription;
@@ -1,19 +1,37 @@
 use std::{
-    sync::{Arc, Weak},
-    time::Duration,
+    future::Future,
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
 };
 
+use futures_util::future::{self, Either};
+
 use super::{
     description::server::ServerDescription,
+    message_manager::TopologyMessageSubscriber,
     state::{server::Server, HandshakePhase, Topology, WeakTopology},
     ServerUpdate,
     ServerUpdateReceiver,
 };
 use crate::{
+    bson::Document,
     cmap::{Connection, Handshaker},
-    error::{Error, Result},
-    is_master::{is_master_command, run_is_master, IsMasterReply},
+    error::{Error, ErrorKind, Result},
+    event::sdam::{
+        SdamEventHandler,
+        ServerHeartbeatFailedEvent,
+        ServerHeartbeatStartedEvent,
+        ServerHeartbeatSucceededEvent,
+    },
+    is_master::{
+        awaitable_is_master_command,
+        is_master_command,
+        receive_is_master,
+        run_is_master,
+        IsMasterReply,
+    },
     options::{ClientOptions, ServerAddress},
+    runtime::{Clock, DEFAULT_CONNECT_TIMEOUT},
     RUNTIME,
 };
 
@@ -51,16 +69,20 @@ impl Monitor {
     /// Start the monitor tasks.
     /// A weak reference is used to ensure that the monitor doesn't keep the topology alive after
     /// it's been removed from the topology or the client has been dropped.
+    ///
+    /// A load balancer isn't sent heartbeats, so only the update monitor is started for it.
     pub(super) fn start(self) {
-        let mut heartbeat_monitor = HeartbeatMonitor::new(
-            self.address,
-            Arc::downgrade(&self.server),
-            self.topology.clone(),
-            self.client_options,
-        );
-        RUNTIME.execute(async move {
-            heartbeat_monitor.execute().await;
-        });
+        if self.client_options.load_balanced != Some(true) {
+            let mut heartbeat_monitor = HeartbeatMonitor::new(
+                self.address,
+                Arc::downgrade(&self.server),
+                self.topology.clone(),
+                self.client_options,
+            );
+            RUNTIME.execute(async move {
+                heartbeat_monitor.execute().await;
+            });
+        }
 
         let update_monitor = UpdateMonitor {
             server: Arc::downgrade(&self.server),
@@ -81,6 +103,17 @@ struct HeartbeatMonitor {
     server: Weak<Server>,
     topology: WeakTopology,
     client_options: ClientOptions,
+    clock: Arc<dyn Clock>,
+    backoff: ConnectBackoff,
+
+    /// The `topologyVersion` from the server's most recent reply, if it supports the streaming
+    /// protocol and that isn't disabled. While this is set, checks are awaited: the server only
+    /// replies once its state changes or the heartbeat frequency elapses.
+    topology_version: Option<Document>,
+
+    /// The round trip time most recently measured by the monitor's `RttMonitor`, which is started
+    /// the first time a check is awaited, since the replies to those don't reflect it.
+    round_trip_time: Option<Arc<Mutex<Option<Duration>>>>,
 }
 
 impl HeartbeatMonitor {
@@ -90,14 +123,20 @@ impl HeartbeatMonitor {
         topology: WeakTopology,
         client_options: ClientOptions,
     ) -> Self {
-        let handshaker = Handshaker::new(Some(client_options.clone().into()));
+        let heartbeat_frequency = client_options
+            .heartbeat_freq
+            .unwrap_or(DEFAULT_HEARTBEAT_FREQUENCY);
         Self {
             address,
             server,
+            clock: client_options.clock(),
+            backoff: ConnectBackoff::new(heartbeat_frequency),
+            handshaker: monitoring_handshaker(&client_options),
             client_options,
-            handshaker,
             topology,
             connection: None,
+            topology_version: None,
+            round_trip_time: None,
         }
     }
 
@@ -130,6 +169,13 @@ impl HeartbeatMonitor {
             drop(topology);
             drop(server);
 
+            // Awaited checks are paced by the server, which only replies once its state changes or
+            // the heartbeat frequency elapses, so the next one can start straight away. Requests
+            // for an immediate check interrupt the awaited check instead.
+            if self.topology_version.is_some() {
+                continue;
+            }
+
             #[cfg(test)]
             let min_frequency = self
                 .client_options
@@ -139,9 +185,17 @@ impl HeartbeatMonitor {
             #[cfg(not(test))]
             let min_frequency = MIN_HEARTBEAT_FREQUENCY;
 
-            RUNTIME.delay_for(min_frequency).await;
+            // While the server is unreachable, immediate check requests aren't honored until the
+            // backoff from the previous failures has elapsed.
+            let min_delay = std::cmp::max(min_frequency, self.backoff.remaining(self.clock.now()));
+
+            RUNTIME.delay_for(min_delay).await;
             topology_check_requests_subscriber
-                .wait_for_message(heartbeat_frequency - min_frequency)
+                .wait_for_message(
+                    heartbeat_frequency
+                        .checked_sub(min_delay)
+                        .unwrap_or_default(),
+                )
                 .await;
         }
     }
@@ -152,7 +206,7 @@ impl HeartbeatMonitor {
     /// Returns true if the topology has changed and false otherwise.
     async fn check_server(&mut self, topology: &Topology, server: &Server) -> bool {
         let mut retried = false;
-        let check_result = match self.perform_is_master().await {
+        let check_result = match self.perform_is_master(topology, server).await {
             Ok(reply) => Ok(reply),
             Err(e) => {
                 let previous_description = topology.get_server_description(&server.address).await;
@@ -163,7 +217,7 @@ impl HeartbeatMonitor {
                 {
                     self.handle_error(e, topology, server).await;
                     retried = true;
-                    self.perform_is_master().await
+                    self.perform_is_master(topology, server).await
                 } else {
                     Err(e)
                 }
@@ -172,48 +226,386 @@ impl HeartbeatMonitor {
 
         match check_result {
             Ok(reply) => {
+                self.backoff.record_success();
                 let server_description =
                     ServerDescription::new(server.address.clone(), Some(Ok(reply)));
                 topology.update(server, server_description).await
             }
-            Err(e) => self.handle_error(e, topology, server).await || retried,
+            Err(e) => {
+                self.backoff
+                    .record_failure(self.clock.now(), rand::random::<f64>());
+                self.handle_error(e, topology, server).await || retried
+            }
+        }
+    }
+
+    /// Runs a single check of the server, emitting heartbeat events for it.
+    ///
+    /// An awaited check that's interrupted is abandoned along with its connection, and the server
+    /// is checked again right away over a new one.
+    async fn perform_is_master(
+        &mut self,
+        topology: &Topology,
+        server: &Server,
+    ) -> Result<IsMasterReply> {
+        loop {
+            let awaited = self.connection.is_some() && self.topology_version.is_some();
+            self.emit_event(|handler| {
+                handler.handle_server_heartbeat_started_event(ServerHeartbeatStartedEvent {
+                    server_address: self.address.clone(),
+                    awaited,
+                });
+            });
+
+            let start_time = Instant::now();
+            let result = if awaited {
+                let mut interrupts = CheckInterrupts::new(topology, server);
+                let outcome =
+                    future::select(Box::pin(self.run_check(true)), Box::pin(interrupts.wait()))
+                        .await;
+                match outcome {
+                    Either::Left((result, _)) => Some(result),
+                    Either::Right(_) => None,
+                }
+            } else {
+                Some(self.run_check(false).await)
+            };
+            let duration = start_time.elapsed();
+
+            match result {
+                Some(result) => return self.handle_check_result(result, duration, awaited),
+                None => {
+                    let error: Error = ErrorKind::from(std::io::ErrorKind::Interrupted).into();
+                    let _: Result<_> = self.handle_check_result(Err(error), duration, awaited);
+                }
+            }
         }
     }
 
-    async fn perform_is_master(&mut self) -> Result<IsMasterReply> {
-        let result = match self.connection {
+    /// Records the outcome of a check and emits the heartbeat event for it.
+    fn handle_check_result(
+        &mut self,
+        result: Result<IsMasterReply>,
+        duration: Duration,
+        awaited: bool,
+    ) -> Result<IsMasterReply> {
+        match result {
+            Ok(ref reply) => {
+                self.topology_version =
+                    if self.client_options.disable_streaming_monitoring == Some(true) {
+                        None
+                    } else {
+                        reply.command_response.topology_version.clone()
+                    };
+                self.emit_event(|handler| {
+                    handler.handle_server_heartbeat_succeeded_event(
+                        ServerHeartbeatSucceededEvent {
+                            duration,
+                            reply: reply.raw_command_response.clone(),
+                            server_address: self.address.clone(),
+                            awaited,
+                        },
+                    );
+                });
+            }
+            Err(ref error) => {
+                self.connection.take();
+                self.topology_version = None;
+                self.emit_event(|handler| {
+                    handler.handle_server_heartbeat_failed_event(ServerHeartbeatFailedEvent {
+                        duration,
+                        failure: error.clone(),
+                        server_address: self.address.clone(),
+                        awaited,
+                    });
+                });
+            }
+        }
+
+        result
+    }
+
+    /// Gets the server's current state, either by reading the next reply that it streams, by
+    /// sending an `isMaster` on the existing connection, or by establishing a new connection.
+    async fn run_check(&mut self, awaited: bool) -> Result<IsMasterReply> {
+        let socket_timeout = self
+            .client_options
+            .connect_timeout
+            .unwrap_or(DEFAULT_CONNECT_TIMEOUT);
+        let heartbeat_frequency = self
+            .client_options
+            .heartbeat_freq
+            .unwrap_or(DEFAULT_HEARTBEAT_FREQUENCY);
+        // The server waits for up to the heartbeat frequency before replying to an awaited check.
+        let awaited_timeout = if socket_timeout == Duration::from_secs(0) {
+            socket_timeout
+        } else {
+            socket_timeout + heartbeat_frequency
+        };
+
+        let mut reply = match self.connection {
+            Some(ref mut conn) if conn.is_streaming() => {
+                with_socket_timeout(awaited_timeout, receive_is_master(conn)).await?
+            }
             Some(ref mut conn) => {
-                let command = is_master_command(self.client_options.server_api.as_ref());
-                run_is_master(command, conn).await
+                let api = self.client_options.server_api.as_ref();
+                match self.topology_version {
+                    Some(ref topology_version) => {
+                        let command = awaitable_is_master_command(
+                            api,
+                            topology_version.clone(),
+                            heartbeat_frequency,
+                        );
+                        with_socket_timeout(awaited_timeout, run_is_master(command, conn)).await?
+                    }
+                    None => {
+                        let command = is_master_command(api);
+                        with_socket_timeout(socket_timeout, run_is_master(command, conn)).await?
+                    }
+                }
             }
             None => {
-                let mut connection = Connection::connect_monitoring(
+                let (connection, reply) =
+                    connect_monitoring(&self.address, &self.client_options, &self.handshaker)
+                        .await?;
+                self.connection = Some(connection);
+                reply
+            }
+        };
+
+        if awaited {
+            reply.round_trip_time = self.awaited_round_trip_time();
+        }
+        Ok(reply)
+    }
+
+    /// The round trip time to report for an awaited check, as measured by the `RttMonitor`, which
+    /// is started if it isn't running yet.
+    fn awaited_round_trip_time(&mut self) -> Option<Duration> {
+        let round_trip_time = match self.round_trip_time {
+            Some(ref round_trip_time) => round_trip_time,
+            None => {
+                let rtt_monitor = RttMonitor::new(
                     self.address.clone(),
-                    self.client_options.connect_timeout,
-                    self.client_options.tls_options(),
-                )
-                .await?;
+                    self.server.clone(),
+                    self.topology.clone(),
+                    self.client_options.clone(),
+                );
+                let round_trip_time = rtt_monitor.round_trip_time.clone();
+                RUNTIME.execute(rtt_monitor.execute());
+                self.round_trip_time.get_or_insert(round_trip_time)
+            }
+        };
+        *round_trip_time.lock().unwrap()
+    }
+
+    async fn handle_error(&mut self, error: Error, topology: &Topology, server: &Server) -> bool {
+        topology
+            .handle_monitor_error(error, server, self.backoff.consecutive_failures())
+            .await
+    }
+
+    fn emit_event<F>(&self, emit: F)
+    where
+        F: FnOnce(&Arc<dyn SdamEventHandler>),
+    {
+        if let Some(ref handler) = self.client_options.sdam_event_handler {
+            emit(handler)
+        }
+    }
+}
+
+/// The signals that interrupt an awaited check: requests for an immediate check of the topology,
+/// and cancellations of the server's check once its pool has been cleared due to an application
+/// error. Only the ones sent after it's created are observed.
+struct CheckInterrupts {
+    check_requests: TopologyMessageSubscriber,
+    cancellations: TopologyMessageSubscriber,
+}
 
-                let res = self
-                    .handshaker
-                    .handshake(&mut connection)
-                    .await
-                    .map(|r| r.is_master_reply);
+impl CheckInterrupts {
+    fn new(topology: &Topology, server: &Server) -> Self {
+        Self {
+            check_requests: topology.subscribe_to_topology_check_requests(),
+            cancellations: server.subscribe_to_check_cancellations(),
+        }
+    }
+
+    /// Waits until the check is interrupted.
+    async fn wait(&mut self) {
+        future::select(
+            Box::pin(self.check_requests.wait_for_any_message()),
+            Box::pin(self.cancellations.wait_for_any_message()),
+        )
+        .await;
+    }
+}
+
+/// Measures the round trip time to a server on a dedicated connection while its heartbeat monitor
+/// uses awaited checks, since the server delays its replies to those.
+struct RttMonitor {
+    address: ServerAddress,
+    connection: Option<Connection>,
+    handshaker: Handshaker,
+    server: Weak<Server>,
+    topology: WeakTopology,
+    client_options: ClientOptions,
+    round_trip_time: Arc<Mutex<Option<Duration>>>,
+}
+
+impl RttMonitor {
+    fn new(
+        address: ServerAddress,
+        server: Weak<Server>,
+        topology: WeakTopology,
+        client_options: ClientOptions,
+    ) -> Self {
+        Self {
+            address,
+            connection: None,
+            handshaker: monitoring_handshaker(&client_options),
+            server,
+            topology,
+            client_options,
+            round_trip_time: Default::default(),
+        }
+    }
+
+    async fn execute(mut self) {
+        let heartbeat_frequency = self
+            .client_options
+            .heartbeat_freq
+            .unwrap_or(DEFAULT_HEARTBEAT_FREQUENCY);
+
+        while self.topology.is_alive() && self.server.upgrade().is_some() {
+            match self.measure().await {
+                Ok(round_trip_time) => *self.round_trip_time.lock().unwrap() = round_trip_time,
+                // The heartbeat monitor reports the server's failures, so this only has to
+                // reconnect for the next measurement.
+                Err(_) => self.connection = None,
+            }
+            RUNTIME.delay_for(heartbeat_frequency).await;
+        }
+    }
+
+    async fn measure(&mut self) -> Result<Option<Duration>> {
+        let reply = match self.connection {
+            Some(ref mut conn) => {
+                let socket_timeout = self
+                    .client_options
+                    .connect_timeout
+                    .unwrap_or(DEFAULT_CONNECT_TIMEOUT);
+                let command = is_master_command(self.client_options.server_api.as_ref());
+                with_socket_timeout(socket_timeout, run_is_master(command, conn)).await?
+            }
+            None => {
+                let (connection, reply) =
+                    connect_monitoring(&self.address, &self.client_options, &self.handshaker)
+                        .await?;
                 self.connection = Some(connection);
-                res
+                reply
             }
         };
+        Ok(reply.round_trip_time)
+    }
+}
+
+/// Creates the handshaker for monitoring connections. These never authenticate, so the credential
+/// is omitted from the handshake to avoid sending mechanism negotiation or speculative
+/// authentication.
+fn monitoring_handshaker(client_options: &ClientOptions) -> Handshaker {
+    let mut handshake_options = client_options.clone();
+    handshake_options.credential = None;
+    Handshaker::new(Some(handshake_options.into()))
+}
+
+/// Establishes a new monitoring connection to `address`, returning it along with the reply to its
+/// handshake.
+async fn connect_monitoring(
+    address: &ServerAddress,
+    client_options: &ClientOptions,
+    handshaker: &Handshaker,
+) -> Result<(Connection, IsMasterReply)> {
+    let socket_timeout = client_options
+        .connect_timeout
+        .unwrap_or(DEFAULT_CONNECT_TIMEOUT);
+    let mut connection = Connection::connect_monitoring(
+        address.clone(),
+        client_options.connect_timeout,
+        client_options.tls_options(),
+    )
+    .await?;
+
+    let reply = with_socket_timeout(socket_timeout, handshaker.handshake(&mut connection))
+        .await?
+        .is_master_reply;
+    Ok((connection, reply))
+}
+
+/// Tracks the consecutive failed checks of a server and determines how long its monitor should
+/// wait before trying to establish a new connection to it. The delay grows exponentially with
+/// each failure, starting from `MIN_HEARTBEAT_FREQUENCY` and capped at the heartbeat frequency,
+/// and is jittered so that many clients monitoring the same server don't reconnect in lockstep.
+#[derive(Debug)]
+pub(super) struct ConnectBackoff {
+    consecutive_failures: u32,
+    next_attempt: Option<Instant>,
+    max_delay: Duration,
+}
 
-        if result.is_err() {
-            self.connection.take();
+impl ConnectBackoff {
+    pub(super) fn new(max_delay: Duration) -> Self {
+        Self {
+            consecutive_failures: 0,
+            next_attempt: None,
+            max_delay,
         }
+    }
 
-        result
+    /// The number of checks that have failed since the last successful one.
+    pub(super) fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
     }
 
-    async fn handle_error(&mut self, error: Error, topology: &Topology, server: &Server) -> bool {
-        topology.handle_monitor_error(error, server).await
+    /// Records a failed check at `now`. `jitter` must be in the range [0, 1] and determines where
+    /// in the upper half of the current backoff window the next attempt is scheduled.
+    pub(super) fn record_failure(&mut self, now: Instant, jitter: f64) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+
+        let exponent = std::cmp::min(self.consecutive_failures - 1, 16);
+        let window = std::cmp::min(MIN_HEARTBEAT_FREQUENCY * 2u32.pow(exponent), self.max_delay);
+        // `f64::clamp` isn't available on the minimum supported Rust version.
+        #[allow(clippy::manual_clamp)]
+        let delay = window / 2 + window.mul_f64(jitter.max(0.0).min(1.0) / 2.0);
+
+        self.next_attempt = Some(now + delay);
+    }
+
+    /// Records a successful check, resetting the backoff.
+    pub(super) fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.next_attempt = None;
     }
+
+    /// How much longer after `now` the next connection attempt should be delayed.
+    pub(super) fn remaining(&self, now: Instant) -> Duration {
+        self.next_attempt
+            .map(|next_attempt| next_attempt.saturating_duration_since(now))
+            .unwrap_or_else(|| Duration::from_secs(0))
+    }
+}
+
+/// Runs a monitoring check, returning a network timeout error if it does not complete within
+/// `timeout`. Per the SDAM spec, monitors use `connectTimeoutMS` as their socket timeout, and a
+/// timeout of zero means no timeout is applied.
+async fn with_socket_timeout<T>(
+    timeout: Duration,
+    check: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    if timeout == Duration::from_secs(0) {
+        return check.await;
+    }
+    RUNTIME.timeout(timeout, check).await?
 }
 
 /// Monitor that listens for updates to a given server generated from operation execution.
@@ -252,6 +644,11 @@ impl UpdateMonitor {
                         )
                         .await;
                 }
+                ServerUpdate::LoadBalancerHandshake { reply } => {
+                    topology
+                        .handle_load_balancer_handshake(reply, &server)
+                        .await;
+                }
             }
         }
     }
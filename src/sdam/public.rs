@@ -1,6 +1,6 @@
-use std::{fmt, time::Duration};
+use std::{borrow::Cow, fmt, time::Duration};
 
-pub use crate::sdam::description::server::ServerType;
+pub use crate::sdam::description::{server::ServerType, topology::TopologyType};
 use crate::{
     bson::DateTime,
     is_master::IsMasterCommandResponse,
@@ -10,14 +10,23 @@ use crate::{
 };
 
 /// Provides information about a given server in the cluster that a client is connected to. This is
-/// used when providing a predicate as a `SelectionCriteria`.
+/// used when providing a predicate as a `SelectionCriteria` and is returned from
+/// [`Client::servers`](struct.Client.html#method.servers).
 pub struct ServerInfo<'a> {
-    description: &'a ServerDescription,
+    description: Cow<'a, ServerDescription>,
 }
 
 impl<'a> ServerInfo<'a> {
     pub(crate) fn new(description: &'a ServerDescription) -> Self {
-        Self { description }
+        Self {
+            description: Cow::Borrowed(description),
+        }
+    }
+
+    pub(crate) fn new_owned(description: ServerDescription) -> ServerInfo<'static> {
+        ServerInfo {
+            description: Cow::Owned(description),
+        }
     }
 
     fn command_response_getter<T>(
@@ -51,6 +60,12 @@ impl<'a> ServerInfo<'a> {
         self.description.last_update_time
     }
 
+    /// Gets the number of consecutive times the driver's monitoring thread had failed to check the
+    /// server as of the last update. This is reset to zero once a check succeeds.
+    pub fn consecutive_heartbeat_failures(&self) -> u32 {
+        self.description.consecutive_failures
+    }
+
     /// Gets the maximum wire version that the server supports.
     pub fn max_wire_version(&self) -> Option<i32> {
         self.command_response_getter(|r| r.max_wire_version)
@@ -82,6 +97,82 @@ impl<'a> ServerInfo<'a> {
     }
 }
 
+/// The features supported by the deployment that a `Client` is connected to, as returned from
+/// [`Client::server_capabilities`](struct.Client.html#method.server_capabilities).
+///
+/// These are derived from the wire versions reported by the data-bearing servers that the driver
+/// knows about. To account for mixed-version deployments (e.g. during a rolling upgrade), a feature
+/// is only reported as supported if every known data-bearing server supports it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct ServerCapabilities {
+    wire_versions: Option<(i32, i32)>,
+    replica_set: bool,
+    sharded: bool,
+}
+
+impl ServerCapabilities {
+    pub(crate) fn new(wire_versions: Option<(i32, i32)>, replica_set: bool, sharded: bool) -> Self {
+        Self {
+            wire_versions,
+            replica_set,
+            sharded,
+        }
+    }
+
+    fn max_wire_version_gte(&self, version: i32) -> bool {
+        self.max_wire_version()
+            .map(|max| max >= version)
+            .unwrap_or(false)
+    }
+
+    /// Whether the driver has discovered any data-bearing servers yet. If not, all of the
+    /// `supports_*` methods return `false`.
+    pub fn is_known(&self) -> bool {
+        self.wire_versions.is_some()
+    }
+
+    /// Gets the lowest wire version that every known data-bearing server supports.
+    pub fn min_wire_version(&self) -> Option<i32> {
+        self.wire_versions.map(|(min, _)| min)
+    }
+
+    /// Gets the highest wire version that every known data-bearing server supports.
+    pub fn max_wire_version(&self) -> Option<i32> {
+        self.wire_versions.map(|(_, max)| max)
+    }
+
+    /// Whether the deployment supports multi-document transactions, which requires a replica set
+    /// running MongoDB 4.0 or later or a sharded cluster running MongoDB 4.2 or later.
+    pub fn supports_transactions(&self) -> bool {
+        self.replica_set && self.max_wire_version_gte(7)
+            || self.sharded && self.max_wire_version_gte(8)
+    }
+
+    /// Whether the deployment supports retryable writes, which requires a replica set or sharded
+    /// cluster.
+    pub fn supports_retryable_writes(&self) -> bool {
+        (self.replica_set || self.sharded) && self.max_wire_version_gte(6)
+    }
+
+    /// Whether the deployment supports change streams, which requires a replica set or sharded
+    /// cluster.
+    pub fn supports_change_streams(&self) -> bool {
+        (self.replica_set || self.sharded) && self.max_wire_version_gte(6)
+    }
+
+    /// Whether the deployment supports resuming change streams with `startAfter`, which requires
+    /// MongoDB 4.2 or later.
+    pub fn supports_change_stream_start_after(&self) -> bool {
+        self.supports_change_streams() && self.max_wire_version_gte(8)
+    }
+
+    /// Whether the deployment supports wildcard indexes, which requires MongoDB 4.2 or later.
+    pub fn supports_wildcard_indexes(&self) -> bool {
+        self.max_wire_version_gte(8)
+    }
+}
+
 impl<'a> fmt::Display for ServerInfo<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> std::result::Result<(), fmt::Error> {
         write!(
@@ -71,12 +71,12 @@ impl SrvPollingMonitor {
                 None => break,
             };
 
-            if should_poll(topology.topology_type().await) {
+            if should_poll(topology.topology_type()) {
                 let hosts = self.lookup_hosts().await;
 
                 // verify we should still update before updating in case the topology changed
                 // while the srv lookup was happening.
-                if should_poll(topology.topology_type().await) {
+                if should_poll(topology.topology_type()) {
                     self.update_hosts(hosts, topology.clone()).await;
                 }
             }
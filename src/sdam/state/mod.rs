@@ -2,14 +2,17 @@ pub(super) mod server;
 
 use std::{
     collections::{HashMap, HashSet},
+    ops::{Deref, DerefMut},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
+        Mutex as SyncMutex,
+        RwLock as SyncRwLock,
         Weak,
     },
 };
 
-use tokio::sync::{RwLock, RwLockWriteGuard};
+use tokio::sync::{Mutex, MutexGuard};
 
 use self::server::Server;
 use super::{
@@ -19,9 +22,11 @@ use super::{
     TopologyDescription,
 };
 use crate::{
+    bson::oid::ObjectId,
     client::ClusterTime,
     cmap::{Command, Connection},
     error::{Error, Result},
+    is_master::IsMasterReply,
     options::{ClientOptions, SelectionCriteria, ServerAddress},
     runtime::HttpClient,
     sdam::{
@@ -30,16 +35,16 @@ use crate::{
             topology::{server_selection, TopologyDescriptionDiff, TopologyType},
         },
         srv_polling::SrvPollingMonitor,
+        ServerCapabilities,
         TopologyMessageManager,
     },
-    RUNTIME,
 };
 
 /// A strong reference to the topology, which includes the current state as well as the client
 /// options and the message manager.
 #[derive(Clone, Debug)]
 pub(crate) struct Topology {
-    state: Arc<RwLock<TopologyState>>,
+    state: Arc<SharedTopologyState>,
     common: Common,
 }
 
@@ -47,7 +52,7 @@ pub(crate) struct Topology {
 /// options and the message manager.
 #[derive(Clone, Debug)]
 pub(crate) struct WeakTopology {
-    state: Weak<RwLock<TopologyState>>,
+    state: Weak<SharedTopologyState>,
     common: Common,
 }
 
@@ -60,9 +65,36 @@ struct Common {
     options: ClientOptions,
 }
 
+/// The state of the topology shared between `Topology` and `WeakTopology`.
+///
+/// Readers (e.g. server selection) load the current `TopologyState` snapshot, which only holds
+/// the snapshot's read lock for long enough to clone an `Arc`, so it never waits on an in-progress
+/// update. Writers serialize on `update_lock`, make their changes to a copy of the latest snapshot,
+/// and then publish the copy as the new snapshot, which only holds the write lock for long enough
+/// to swap the `Arc`.
+#[derive(Debug)]
+struct SharedTopologyState {
+    snapshot: SyncRwLock<Arc<TopologyState>>,
+    update_lock: Mutex<()>,
+
+    /// The highest cluster time seen in operation responses. This is tracked separately from the
+    /// topology description so that advancing it on every operation doesn't require publishing a
+    /// new snapshot.
+    cluster_time: SyncMutex<Option<ClusterTime>>,
+}
+
+/// An in-progress update to the topology state. The update holds the shared state's update lock
+/// for as long as it is alive, and its changes only become visible to readers once `publish` is
+/// called.
+struct TopologyStateUpdate<'a> {
+    shared: &'a SharedTopologyState,
+    state: Arc<TopologyState>,
+    _guard: MutexGuard<'a, ()>,
+}
+
 /// The current state of the topology, which includes the topology description and the set of
 /// servers.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct TopologyState {
     http_client: HttpClient,
     description: TopologyDescription,
@@ -95,13 +127,13 @@ impl Topology {
         };
 
         let topology = Self {
-            state: Arc::new(RwLock::new(state)),
+            state: Arc::new(SharedTopologyState::new(state)),
             common,
         };
 
-        // we can block in place here because we're the only ones with access to the lock, so it
-        // should be acquired immediately.
-        let mut topology_state = RUNTIME.block_in_place(topology.state.write());
+        // we're the only ones with access to the state, so the update lock should be acquired
+        // immediately.
+        let mut topology_state = topology.state.try_begin_update().unwrap();
 
         for address in options.hosts {
             topology_state.servers.insert(
@@ -116,6 +148,7 @@ impl Topology {
             );
         }
 
+        topology_state.publish();
         drop(topology_state);
         topology
     }
@@ -149,15 +182,16 @@ impl Topology {
             http_client,
         };
 
-        let state = Arc::new(RwLock::new(topology_state));
+        let state = Arc::new(SharedTopologyState::new(topology_state));
         let topology = Topology { state, common };
 
-        // we can block in place here because we're the only ones with access to the lock, so it
-        // should be acquired immediately.
-        let mut topology_state = RUNTIME.block_in_place(topology.state.write());
+        // we're the only ones with access to the state, so the update lock should be acquired
+        // immediately.
+        let mut topology_state = topology.state.try_begin_update().unwrap();
         for address in hosts {
             topology_state.add_new_server(address, options.clone(), &topology.downgrade());
         }
+        topology_state.publish();
 
         SrvPollingMonitor::start(topology.downgrade());
 
@@ -172,12 +206,12 @@ impl Topology {
     /// Gets the addresses of the servers in the cluster.
     #[cfg(test)]
     pub(crate) async fn servers(&self) -> HashSet<ServerAddress> {
-        self.state.read().await.servers.keys().cloned().collect()
+        self.state.load().servers.keys().cloned().collect()
     }
 
     #[cfg(test)]
     pub(crate) async fn description(&self) -> TopologyDescription {
-        self.state.read().await.description.clone()
+        self.state.load().description.clone()
     }
 
     /// Creates and returns a weak reference to the topology.
@@ -197,7 +231,7 @@ impl Topology {
         &self,
         criteria: &SelectionCriteria,
     ) -> Result<Option<SelectedServer>> {
-        let topology_state = self.state.read().await;
+        let topology_state = self.state.load();
 
         server_selection::attempt_to_select_server(
             criteria,
@@ -212,8 +246,7 @@ impl Topology {
         criteria: &SelectionCriteria,
     ) -> String {
         self.state
-            .read()
-            .await
+            .load()
             .description
             .server_selection_timeout_error_message(criteria)
     }
@@ -246,7 +279,13 @@ impl Topology {
         handshake: HandshakePhase,
         server: &Server,
     ) -> bool {
-        let state_lock = self.state.write().await;
+        if let Some(true) = self.common.options.load_balanced {
+            return self
+                .handle_load_balanced_application_error(error, handshake, server)
+                .await;
+        }
+
+        let state_lock = self.state.begin_update().await;
         if handshake.generation() < server.pool.generation() {
             return false;
         }
@@ -257,7 +296,8 @@ impl Topology {
                 .await;
 
             if updated && (error.is_shutting_down() || handshake.wire_version().unwrap_or(0) < 8) {
-                server.pool.clear(error).await;
+                server.pool.clear(error, None).await;
+                server.cancel_check();
             }
             self.request_topology_check();
 
@@ -268,11 +308,20 @@ impl Topology {
                     || error.is_network_timeout()
                     || error.is_command_error()))
         {
+            let is_auth_error = error.is_auth_error();
             let updated = self
                 .mark_server_as_unknown(error.to_string(), server, state_lock)
                 .await;
             if updated {
-                server.pool.clear(error).await;
+                server.pool.clear(error, None).await;
+                server.cancel_check();
+            }
+
+            // Monitoring connections don't authenticate, so an authentication failure doesn't
+            // indicate that the server is unreachable. Have the monitors check again right away so
+            // that the server doesn't remain Unknown until the next heartbeat.
+            if is_auth_error {
+                self.request_topology_check();
             }
             updated
         } else {
@@ -280,24 +329,82 @@ impl Topology {
         }
     }
 
-    pub(crate) async fn handle_monitor_error(&self, error: Error, server: &Server) -> bool {
-        let state_lock = self.state.write().await;
+    /// Handles an error that occurred during a monitor check, marking the server as Unknown and
+    /// recording how many checks in a row have failed.
+    pub(crate) async fn handle_monitor_error(
+        &self,
+        error: Error,
+        server: &Server,
+        consecutive_failures: u32,
+    ) -> bool {
+        let state_lock = self.state.begin_update().await;
+        let mut description =
+            ServerDescription::new(server.address.clone(), Some(Err(error.to_string())));
+        description.consecutive_failures = consecutive_failures;
         let updated = self
-            .mark_server_as_unknown(error.to_string(), server, state_lock)
+            .update_and_notify(server, description, state_lock)
             .await;
         if updated {
-            server.pool.clear(error).await;
+            server.pool.clear(error, None).await;
         }
         updated
     }
 
+    /// Records the reply to a handshake made through a load balancer in the topology description.
+    pub(crate) async fn handle_load_balancer_handshake(
+        &self,
+        reply: IsMasterReply,
+        server: &Server,
+    ) {
+        let description = ServerDescription::new_load_balancer(server.address.clone(), Some(reply));
+
+        let mut state_lock = self.state.begin_update().await;
+        if state_lock
+            .description
+            .get_server_description(&server.address)
+            == Some(&description)
+        {
+            return;
+        }
+
+        state_lock.description.update_load_balancer(description);
+        state_lock.publish();
+    }
+
+    /// Handles an error that occurred while executing an operation through a load balancer.
+    ///
+    /// The load balancer is never marked Unknown, since that would leave no server to select.
+    /// Instead, only the connections to the backend server that the failed connection was routed
+    /// to are cleared. Errors that occur before the handshake completes can't be attributed to a
+    /// backend, so they're ignored.
+    async fn handle_load_balanced_application_error(
+        &self,
+        error: Error,
+        handshake: HandshakePhase,
+        server: &Server,
+    ) -> bool {
+        let service_id = match handshake.service_id() {
+            Some(service_id) => service_id,
+            None => return false,
+        };
+
+        if handshake.generation() < server.pool.service_generation(&service_id) {
+            return false;
+        }
+
+        if error.is_state_change_error() || error.is_non_timeout_network_error() {
+            server.pool.clear(error, Some(service_id)).await;
+        }
+        false
+    }
+
     /// Marks a server in the cluster as unknown due to the given `error`.
     /// Returns whether the topology changed as a result of the update.
     async fn mark_server_as_unknown(
         &self,
         error: String,
         server: &Server,
-        state_lock: RwLockWriteGuard<'_, TopologyState>,
+        state_lock: TopologyStateUpdate<'_>,
     ) -> bool {
         let description = ServerDescription::new(server.address.clone(), Some(Err(error)));
         self.update_and_notify(server, description, state_lock)
@@ -306,15 +413,17 @@ impl Topology {
 
     /// Update the topology using the given server description.
     ///
-    /// Because this method takes a lock guard as a parameter, it is mainly useful for sychronizing
-    /// updates to the topology with other state management.
+    /// Because this method takes an in-progress update as a parameter, it is mainly useful for
+    /// sychronizing updates to the topology with other state management. The update is published
+    /// before the server's connection pool is marked as ready, but the update lock is held until
+    /// this method returns.
     ///
     /// Returns a boolean indicating whether the topology changed as a result of the update.
     async fn update_and_notify(
         &self,
         server: &Server,
         server_description: ServerDescription,
-        mut state_lock: RwLockWriteGuard<'_, TopologyState>,
+        mut state_lock: TopologyStateUpdate<'_>,
     ) -> bool {
         let server_type = server_description.server_type;
         // TODO RUST-232: Theoretically, `TopologyDescription::update` can return an error. However,
@@ -323,7 +432,10 @@ impl Topology {
         // doesn't check the fields of an Unknown server, and we only return Unknown server
         // descriptions when errors occur. Once we implement SDAM monitoring, we can
         // properly inform users of errors that occur here.
-        match state_lock.update(server_description, &self.common.options, self.downgrade()) {
+        let result = state_lock.update(server_description, &self.common.options, self.downgrade());
+        state_lock.publish();
+
+        match result {
             Ok(Some(_)) => {
                 if server_type.is_data_bearing()
                     || (server_type != ServerType::Unknown
@@ -346,7 +458,7 @@ impl Topology {
         server: &Server,
         server_description: ServerDescription,
     ) -> bool {
-        self.update_and_notify(server, server_description, self.state.write().await)
+        self.update_and_notify(server, server_description, self.state.begin_update().await)
             .await
     }
 
@@ -356,10 +468,9 @@ impl Topology {
         hosts: HashSet<ServerAddress>,
         options: &ClientOptions,
     ) -> bool {
-        self.state
-            .write()
-            .await
-            .update_hosts(&hosts, options, self.downgrade());
+        let mut state_lock = self.state.begin_update().await;
+        state_lock.update_hosts(&hosts, options, self.downgrade());
+        state_lock.publish();
         true
     }
 
@@ -367,21 +478,21 @@ impl Topology {
     /// If the provided cluster time is not higher than the topology's currently highest seen
     /// cluster time, this method has no effect.
     pub(crate) async fn advance_cluster_time(&self, cluster_time: &ClusterTime) {
-        self.state
-            .write()
-            .await
-            .description
-            .advance_cluster_time(cluster_time);
+        let mut current = self.state.cluster_time.lock().unwrap();
+        if current.as_ref() >= Some(cluster_time) {
+            return;
+        }
+        *current = Some(cluster_time.clone());
     }
 
     /// Get the topology's currently highest seen cluster time.
     pub(crate) async fn cluster_time(&self) -> Option<ClusterTime> {
-        self.state
-            .read()
-            .await
-            .description
-            .cluster_time()
-            .map(Clone::clone)
+        // Cluster times gossiped via heartbeats are recorded in the topology description, whereas
+        // those from operation responses are recorded separately, so the highest of the two is
+        // returned.
+        let from_description = self.state.load().description.cluster_time().cloned();
+        let from_operations = self.state.cluster_time.lock().unwrap().clone();
+        std::cmp::max(from_description, from_operations)
     }
 
     /// Updates the given `command` as needed based on the `critiera`.
@@ -392,27 +503,36 @@ impl Topology {
         criteria: Option<&SelectionCriteria>,
     ) {
         self.state
-            .read()
-            .await
+            .load()
             .update_command_with_read_pref(server_address, command, criteria);
     }
 
     /// Gets the latest information on whether sessions are supported or not.
     pub(crate) async fn session_support_status(&self) -> SessionSupportStatus {
-        self.state.read().await.description.session_support_status()
+        self.state.load().description.session_support_status()
     }
 
     /// Gets the latest information on whether transactions are support or not.
     pub(crate) async fn transaction_support_status(&self) -> TransactionSupportStatus {
-        self.state
-            .read()
-            .await
-            .description
-            .transaction_support_status()
+        self.state.load().description.transaction_support_status()
+    }
+
+    pub(crate) fn topology_type(&self) -> TopologyType {
+        self.state.load().description.topology_type()
     }
 
-    pub(super) async fn topology_type(&self) -> TopologyType {
-        self.state.read().await.description.topology_type()
+    pub(crate) fn server_capabilities(&self) -> ServerCapabilities {
+        self.state.load().description.server_capabilities()
+    }
+
+    /// Gets the descriptions of the servers in the cluster.
+    pub(crate) fn server_descriptions(&self) -> Vec<ServerDescription> {
+        self.state
+            .load()
+            .description
+            .server_descriptions()
+            .cloned()
+            .collect()
     }
 
     pub(crate) async fn get_server_description(
@@ -420,8 +540,7 @@ impl Topology {
         address: &ServerAddress,
     ) -> Option<ServerDescription> {
         self.state
-            .read()
-            .await
+            .load()
             .description
             .get_server_description(address)
             .cloned()
@@ -430,8 +549,7 @@ impl Topology {
     #[cfg(test)]
     pub(crate) async fn get_servers(&self) -> HashMap<ServerAddress, Weak<Server>> {
         self.state
-            .read()
-            .await
+            .load()
             .servers
             .iter()
             .map(|(addr, server)| (addr.clone(), Arc::downgrade(server)))
@@ -439,6 +557,70 @@ impl Topology {
     }
 }
 
+impl SharedTopologyState {
+    fn new(state: TopologyState) -> Self {
+        Self {
+            snapshot: SyncRwLock::new(Arc::new(state)),
+            update_lock: Mutex::new(()),
+            cluster_time: SyncMutex::new(None),
+        }
+    }
+
+    /// Gets the latest published snapshot of the topology state.
+    fn load(&self) -> Arc<TopologyState> {
+        self.snapshot.read().unwrap().clone()
+    }
+
+    /// Waits for any other in-progress updates to complete and then begins a new update based on
+    /// the latest snapshot.
+    async fn begin_update(&self) -> TopologyStateUpdate<'_> {
+        let guard = self.update_lock.lock().await;
+        TopologyStateUpdate {
+            shared: self,
+            state: self.load(),
+            _guard: guard,
+        }
+    }
+
+    /// Begins a new update if no other update is in progress.
+    fn try_begin_update(&self) -> Option<TopologyStateUpdate<'_>> {
+        let guard = self.update_lock.try_lock().ok()?;
+        Some(TopologyStateUpdate {
+            shared: self,
+            state: self.load(),
+            _guard: guard,
+        })
+    }
+}
+
+impl<'a> TopologyStateUpdate<'a> {
+    /// Makes the changes from this update visible to readers of the topology state.
+    fn publish(&mut self) {
+        // The previous snapshot is dropped after the write lock is released, since it may be the
+        // last reference to it.
+        let _previous = std::mem::replace(
+            &mut *self.shared.snapshot.write().unwrap(),
+            self.state.clone(),
+        );
+    }
+}
+
+impl<'a> Deref for TopologyStateUpdate<'a> {
+    type Target = TopologyState;
+
+    fn deref(&self) -> &Self::Target {
+        &self.state
+    }
+}
+
+impl<'a> DerefMut for TopologyStateUpdate<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // The state is only copied the first time it's modified after being published, so readers
+        // holding on to an older snapshot never observe a partial update.
+        Arc::make_mut(&mut self.state)
+    }
+}
+
 impl WeakTopology {
     /// Attempts to convert the WeakTopology to a string reference.
     pub(crate) fn upgrade(&self) -> Option<Topology> {
@@ -567,6 +749,7 @@ pub(crate) enum HandshakePhase {
     AfterCompletion {
         generation: u32,
         max_wire_version: i32,
+        service_id: Option<ObjectId>,
     },
 }
 
@@ -581,6 +764,7 @@ impl HandshakePhase {
                 .ok()
                 .and_then(|sd| sd.max_wire_version)
                 .unwrap_or(0),
+            service_id: handshaked_connection.service_id(),
         }
     }
 
@@ -592,6 +776,15 @@ impl HandshakePhase {
         }
     }
 
+    /// The `serviceId` of the backend server behind a load balancer that the connection was routed
+    /// to, if any. This is only known once the handshake has completed.
+    fn service_id(&self) -> Option<ObjectId> {
+        match self {
+            HandshakePhase::AfterCompletion { service_id, .. } => *service_id,
+            HandshakePhase::BeforeCompletion { .. } => None,
+        }
+    }
+
     /// Whether this phase is before the handshake completed or not.
     fn is_before_completion(&self) -> bool {
         matches!(self, HandshakePhase::BeforeCompletion { .. })
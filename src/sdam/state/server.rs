@@ -3,13 +3,16 @@ use std::sync::{
     Arc,
 };
 
+use tokio::sync::broadcast::{self, Sender};
+
 use super::WeakTopology;
 use crate::{
     cmap::{options::ConnectionPoolOptions, ConnectionPool},
     error::Error,
+    is_master::IsMasterReply,
     options::{ClientOptions, ServerAddress},
     runtime::{AcknowledgedMessage, HttpClient},
-    sdam::monitor::Monitor,
+    sdam::{message_manager::TopologyMessageSubscriber, monitor::Monitor},
 };
 
 /// Contains the state for a given server in the topology.
@@ -22,6 +25,9 @@ pub(crate) struct Server {
 
     /// Number of operations currently using this server.
     operation_count: AtomicU32,
+
+    /// Used to cancel the monitor's in-progress check once the pool has been cleared.
+    check_canceller: Sender<()>,
 }
 
 impl Server {
@@ -31,6 +37,7 @@ impl Server {
             address: address.clone(),
             pool: ConnectionPool::new_mocked(address),
             operation_count: AtomicU32::new(operation_count),
+            check_canceller: broadcast::channel(1).0,
         }
     }
 
@@ -52,6 +59,7 @@ impl Server {
             ),
             address: address.clone(),
             operation_count: AtomicU32::new(0),
+            check_canceller: broadcast::channel(1).0,
         });
         let monitor = Monitor::new(address, &server, topology, options.clone(), update_receiver);
         (server, monitor)
@@ -68,6 +76,17 @@ impl Server {
     pub(crate) fn operation_count(&self) -> u32 {
         self.operation_count.load(Ordering::SeqCst)
     }
+
+    /// Cancels the monitor's in-progress check of this server, if any, so that the server is
+    /// checked again right away over a new connection.
+    pub(crate) fn cancel_check(&self) {
+        let _: Result<_, _> = self.check_canceller.send(());
+    }
+
+    /// Subscribe to cancellations of the monitor's in-progress check.
+    pub(crate) fn subscribe_to_check_cancellations(&self) -> TopologyMessageSubscriber {
+        TopologyMessageSubscriber::new(self.check_canceller.subscribe())
+    }
 }
 
 /// An event that could update the topology's view of a server.
@@ -75,6 +94,7 @@ impl Server {
 #[derive(Debug)]
 pub(crate) enum ServerUpdate {
     Error { error: Error, error_generation: u32 },
+    LoadBalancerHandshake { reply: IsMasterReply },
 }
 
 #[derive(Debug)]
@@ -112,7 +132,18 @@ impl ServerUpdateSender {
             error_generation,
         };
 
-        let (message, callback) = AcknowledgedMessage::package(reason);
+        self.send(reason).await;
+    }
+
+    /// Update the load balancer based on the reply to a handshake made through it.
+    /// This will block until the topology has processed the reply.
+    pub(crate) async fn handle_load_balancer_handshake(&mut self, reply: IsMasterReply) {
+        self.send(ServerUpdate::LoadBalancerHandshake { reply })
+            .await;
+    }
+
+    async fn send(&mut self, update: ServerUpdate) {
+        let (message, callback) = AcknowledgedMessage::package(update);
         // These only fails if the other ends hang up, which means the monitor is
         // stopped, so we can just discard this update.
         let _: std::result::Result<_, _> = self.sender.send(message).await;
@@ -4,11 +4,16 @@ use std::{
 };
 
 use bson::{bson, doc};
+use futures::stream::StreamExt;
 use semver::VersionReq;
-use tokio::sync::RwLockWriteGuard;
+use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
+use super::monitor::ConnectBackoff;
 use crate::{
     error::ErrorKind,
+    options::FindOptions,
+    runtime::{Clock, MockClock},
+    sdam::{ServerType, TopologyType},
     test::{
         CmapEvent,
         Event,
@@ -326,3 +331,253 @@ async fn auth_error() {
         &vec![bson!({ "_id": 5 }), bson!({ "_id": 6 })]
     );
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn monitoring_unaffected_by_auth_failure() {
+    let _guard: RwLockWriteGuard<_> = LOCK.run_exclusively().await;
+
+    let setup_client = TestClient::new().await;
+    if !setup_client.auth_enabled() {
+        println!(
+            "skipping monitoring_unaffected_by_auth_failure test due to auth not being enabled"
+        );
+        return;
+    }
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.hosts.drain(1..);
+    options.direct_connection = Some(true);
+    options.server_selection_timeout = Some(Duration::from_secs(5));
+    options.heartbeat_freq = Some(Duration::from_secs(60));
+    if let Some(ref mut credential) = options.credential {
+        credential.password = Some("not the right password".to_string());
+    }
+    let client = Client::with_options(options).expect("client creation should succeed");
+
+    let auth_err = client
+        .database("monitoring_unaffected_by_auth_failure")
+        .collection("monitoring_unaffected_by_auth_failure")
+        .insert_one(doc! { "x": 1 }, None)
+        .await
+        .expect_err("insert should fail");
+    assert!(matches!(*auth_err.kind, ErrorKind::Authentication { .. }));
+
+    // The monitor doesn't authenticate, so it should rediscover the server well before the next
+    // regularly scheduled heartbeat.
+    client
+        .test_select_server(None)
+        .await
+        .expect("server should still be selectable after an authentication failure");
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn load_balanced() {
+    let _guard: RwLockReadGuard<_> = LOCK.run_concurrently().await;
+
+    if CLIENT_OPTIONS.load_balanced != Some(true) {
+        println!("skipping load_balanced test due to not connecting through a load balancer");
+        return;
+    }
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.heartbeat_freq = Some(Duration::from_millis(50));
+    let client = EventClient::with_additional_options(
+        Some(options),
+        Some(Duration::from_millis(50)),
+        None,
+        None,
+    )
+    .await;
+
+    assert_eq!(client.topology_type().await, TopologyType::LoadBalanced);
+
+    let coll = client
+        .init_db_and_coll("load_balanced", "load_balanced")
+        .await;
+    coll.insert_one(doc! { "x": 1 }, None)
+        .await
+        .expect("insert should succeed");
+    let found = coll
+        .find_one(doc! { "x": 1 }, None)
+        .await
+        .expect("find should succeed");
+    assert!(found.is_some());
+
+    let events = client.get_command_started_events(&["insert", "find"]);
+    assert_eq!(events.len(), 2);
+
+    // Give any heartbeats that were (incorrectly) scheduled a chance to run. A heartbeat would
+    // record its reply in the server's description, so the load balancer's description must
+    // remain untouched.
+    RUNTIME.delay_for(Duration::from_millis(500)).await;
+    let servers = client.servers().await;
+    assert_eq!(servers.len(), 1);
+    assert_eq!(servers[0].server_type(), ServerType::LoadBalancer);
+    assert!(servers[0].last_update_time().is_none());
+
+    // The deployment behind the load balancer is still learned from the connection handshakes.
+    assert!(client.server_capabilities().await.is_known());
+}
+
+/// Through a load balancer, every command in a transaction is sent on the connection that the
+/// transaction was started on, while other operations check out different connections.
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn load_balanced_transaction_pins_connection() {
+    let _guard: RwLockReadGuard<_> = LOCK.run_concurrently().await;
+
+    if CLIENT_OPTIONS.load_balanced != Some(true) {
+        println!(
+            "skipping {} due to not connecting through a load balancer",
+            function_name!()
+        );
+        return;
+    }
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_one(doc! { "_id": 0 }, None).await.unwrap();
+
+    let mut session = client.start_session(None).await.unwrap();
+    session.start_transaction(None).await.unwrap();
+    coll.insert_one_with_session(doc! { "_id": 1 }, None, &mut session)
+        .await
+        .unwrap();
+    coll.find_one(doc! { "_id": 0 }, None).await.unwrap();
+    coll.find_one_with_session(doc! { "_id": 1 }, None, &mut session)
+        .await
+        .unwrap();
+    coll.insert_one_with_session(doc! { "_id": 2 }, None, &mut session)
+        .await
+        .unwrap();
+    session.commit_transaction().await.unwrap();
+
+    let events = client.get_command_started_events(&["insert", "find", "commitTransaction"]);
+    let (in_transaction, outside): (Vec<_>, Vec<_>) = events
+        .into_iter()
+        .skip(1)
+        .partition(|event| event.command.contains_key("txnNumber"));
+    assert_eq!(in_transaction.len(), 4);
+    let pinned_id = in_transaction[0].connection.id;
+    for event in in_transaction.iter() {
+        assert_eq!(
+            event.connection.id, pinned_id,
+            "{} was not sent on the pinned connection",
+            event.command_name
+        );
+    }
+    assert_eq!(outside.len(), 1);
+    assert_ne!(outside[0].connection.id, pinned_id);
+}
+
+/// Through a load balancer, the getMore and killCursors commands of a cursor are sent on the
+/// connection that created it.
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn load_balanced_cursor_pins_connection() {
+    let _guard: RwLockReadGuard<_> = LOCK.run_concurrently().await;
+
+    if CLIENT_OPTIONS.load_balanced != Some(true) {
+        println!(
+            "skipping {} due to not connecting through a load balancer",
+            function_name!()
+        );
+        return;
+    }
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0..6).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    let options = FindOptions::builder().batch_size(2).build();
+    let mut cursor = coll.find(None, options).await.unwrap();
+    for _ in 0..3 {
+        cursor.next().await.unwrap().unwrap();
+        coll.find_one(None, None).await.unwrap();
+    }
+    drop(cursor);
+
+    let finds = client.get_command_started_events(&["find"]);
+    let pinned_id = finds[0].connection.id;
+    for find_one in finds.iter().skip(1) {
+        assert_ne!(find_one.connection.id, pinned_id);
+    }
+
+    // The killCursors command is sent in the background once the cursor is dropped.
+    let start = Instant::now();
+    while client
+        .get_command_started_events(&["killCursors"])
+        .is_empty()
+    {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        RUNTIME.delay_for(Duration::from_millis(50)).await;
+    }
+    let cursor_commands = client.get_command_started_events(&["getMore", "killCursors"]);
+    assert_eq!(cursor_commands.len(), 2);
+    for event in cursor_commands {
+        assert_eq!(event.connection.id, pinned_id);
+    }
+}
+
+#[test]
+fn connect_backoff_schedule() {
+    let clock = MockClock::new();
+    let mut backoff = ConnectBackoff::new(Duration::from_secs(10));
+    assert_eq!(backoff.consecutive_failures(), 0);
+    assert_eq!(backoff.remaining(clock.now()), Duration::from_secs(0));
+
+    // With the maximum jitter, the delay doubles after each failure until it reaches the cap.
+    let expected_delays = [500, 1000, 2000, 4000, 8000, 10_000, 10_000];
+    for (i, expected_delay) in expected_delays.iter().enumerate() {
+        backoff.record_failure(clock.now(), 1.0);
+        assert_eq!(backoff.consecutive_failures(), i as u32 + 1);
+        assert_eq!(
+            backoff.remaining(clock.now()),
+            Duration::from_millis(*expected_delay)
+        );
+
+        clock.advance(Duration::from_millis(*expected_delay / 2));
+        assert_eq!(
+            backoff.remaining(clock.now()),
+            Duration::from_millis(*expected_delay / 2)
+        );
+
+        clock.advance(Duration::from_millis(*expected_delay));
+        assert_eq!(backoff.remaining(clock.now()), Duration::from_secs(0));
+    }
+
+    backoff.record_success();
+    assert_eq!(backoff.consecutive_failures(), 0);
+    assert_eq!(backoff.remaining(clock.now()), Duration::from_secs(0));
+
+    backoff.record_failure(clock.now(), 1.0);
+    assert_eq!(backoff.remaining(clock.now()), Duration::from_millis(500));
+}
+
+#[test]
+fn connect_backoff_jitter() {
+    let clock = MockClock::new();
+    let mut backoff = ConnectBackoff::new(Duration::from_secs(10));
+
+    // The jitter picks a delay within the upper half of the current backoff window.
+    backoff.record_failure(clock.now(), 0.0);
+    assert_eq!(backoff.remaining(clock.now()), Duration::from_millis(250));
+
+    backoff.record_failure(clock.now(), 0.5);
+    assert_eq!(backoff.remaining(clock.now()), Duration::from_millis(750));
+
+    for _ in 0..20 {
+        backoff.record_failure(clock.now(), 0.0);
+    }
+    assert_eq!(backoff.remaining(clock.now()), Duration::from_secs(5));
+}
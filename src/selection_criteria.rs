@@ -1,4 +1,4 @@
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::HashMap, convert::TryFrom, sync::Arc, time::Duration};
 
 use derivative::Derivative;
 use serde::{de::Error, Deserialize, Deserializer};
@@ -37,9 +37,13 @@ impl PartialEq for SelectionCriteria {
     }
 }
 
-impl From<ReadPreference> for SelectionCriteria {
-    fn from(read_pref: ReadPreference) -> Self {
-        Self::ReadPreference(read_pref)
+impl TryFrom<ReadPreference> for SelectionCriteria {
+    type Error = crate::error::Error;
+
+    /// Validates `read_pref` as per [`ReadPreference::validate`] before wrapping it.
+    fn try_from(read_pref: ReadPreference) -> Result<Self> {
+        read_pref.validate()?;
+        Ok(Self::ReadPreference(read_pref))
     }
 }
 
@@ -68,10 +72,76 @@ impl SelectionCriteria {
         self.as_read_pref().and_then(|pref| pref.max_staleness())
     }
 
-    pub(crate) fn from_address(address: ServerAddress) -> Self {
+    /// Creates a predicate that only selects the server at `address`. This can be used to run
+    /// an operation against a specific member of a deployment, e.g. to check the health of each
+    /// one.
+    ///
+    /// Since at most one server can match, the server is selected regardless of its latency
+    /// relative to the other servers in the deployment.
+    pub fn host(address: ServerAddress) -> Self {
         SelectionCriteria::Predicate(Arc::new(move |server| server.address() == &address))
     }
 
+    /// Creates a predicate that selects servers whose tags match any of the given tag sets. A
+    /// server's tags match a tag set if the server has every tag in it; an empty tag set matches
+    /// every server.
+    ///
+    /// Unlike the tag sets of a [`ReadPreference`], the tag sets are not tried in order: servers
+    /// matching a later tag set are suitable even if other servers match an earlier one.
+    pub fn tagged(tag_sets: impl IntoIterator<Item = TagSet>) -> Self {
+        let tag_sets: Vec<TagSet> = tag_sets.into_iter().collect();
+        SelectionCriteria::Predicate(Arc::new(move |server| {
+            tag_sets.iter().any(|tag_set| {
+                tag_set
+                    .iter()
+                    .all(|(key, value)| server.tags().and_then(|tags| tags.get(key)) == Some(value))
+            })
+        }))
+    }
+
+    /// Creates a predicate that selects servers whose average round trip time is at most
+    /// `max_latency`. Servers whose round trip time isn't known yet are never selected.
+    pub fn max_latency(max_latency: Duration) -> Self {
+        SelectionCriteria::Predicate(Arc::new(move |server| {
+            server
+                .average_round_trip_time()
+                .map(|rtt| rtt <= max_latency)
+                .unwrap_or(false)
+        }))
+    }
+
+    /// Combines this predicate with `other` into one that selects the servers selected by both.
+    ///
+    /// Returns an error if either criteria is a [`ReadPreference`], since read preferences can't
+    /// be evaluated against a single server in isolation.
+    pub fn and(self, other: SelectionCriteria) -> Result<Self> {
+        let (first, second) = Self::predicates(self, other)?;
+        Ok(SelectionCriteria::Predicate(Arc::new(move |server| {
+            first(server) && second(server)
+        })))
+    }
+
+    /// Combines this predicate with `other` into one that selects the servers selected by either.
+    ///
+    /// Returns an error if either criteria is a [`ReadPreference`], since read preferences can't
+    /// be evaluated against a single server in isolation.
+    pub fn or(self, other: SelectionCriteria) -> Result<Self> {
+        let (first, second) = Self::predicates(self, other)?;
+        Ok(SelectionCriteria::Predicate(Arc::new(move |server| {
+            first(server) || second(server)
+        })))
+    }
+
+    fn predicates(first: Self, second: Self) -> Result<(Predicate, Predicate)> {
+        match (first, second) {
+            (Self::Predicate(first), Self::Predicate(second)) => Ok((first, second)),
+            _ => Err(ErrorKind::InvalidArgument {
+                message: "only predicates can be combined, not read preferences".to_string(),
+            }
+            .into()),
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn serialize_for_client_options<S>(
         selection_criteria: &Option<SelectionCriteria>,
@@ -177,6 +247,10 @@ impl<'de> Deserialize<'de> for ReadPreference {
 pub struct ReadPreferenceOptions {
     /// Specifies which replica set members should be considered for operations. Each tag set will
     /// be checked in order until one or more servers is found with each tag in the set.
+    ///
+    /// The driver does not implicitly add an empty tag set to the end of the list; to fall back to
+    /// any eligible server when none of the tag sets match, specify an empty tag set as the last
+    /// element.
     pub tag_sets: Option<Vec<TagSet>>,
 
     /// Specifies the maximum amount of lag behind the primary that a secondary can be to be
@@ -230,6 +304,46 @@ impl ReadPreference {
         }
     }
 
+    /// Validates the tag sets of this read preference. A tag set is invalid if it contains an
+    /// empty key or a key or value that contains a null byte.
+    ///
+    /// This is checked automatically when converting a read preference into a
+    /// [`SelectionCriteria`] and when a [`Client`](../struct.Client.html) is created with a read
+    /// preference specified in its [`ClientOptions`](struct.ClientOptions.html).
+    pub fn validate(&self) -> Result<()> {
+        let tag_sets = match self {
+            ReadPreference::Primary => return Ok(()),
+            ReadPreference::Secondary { ref options }
+            | ReadPreference::PrimaryPreferred { ref options }
+            | ReadPreference::SecondaryPreferred { ref options }
+            | ReadPreference::Nearest { ref options } => match options.tag_sets {
+                Some(ref tag_sets) => tag_sets,
+                None => return Ok(()),
+            },
+        };
+
+        for (key, value) in tag_sets.iter().flatten() {
+            if key.is_empty() {
+                return Err(ErrorKind::InvalidArgument {
+                    message: "read preference tag set keys must not be empty".to_string(),
+                }
+                .into());
+            }
+
+            if key.contains('\0') || value.contains('\0') {
+                return Err(ErrorKind::InvalidArgument {
+                    message: format!(
+                        "read preference tag {:?}: {:?} must not contain null bytes",
+                        key, value
+                    ),
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn with_tags(mut self, tag_sets: Vec<TagSet>) -> Result<Self> {
         let options = match self {
             ReadPreference::Primary => {
@@ -0,0 +1,78 @@
+use futures_util::stream::StreamExt;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    change_stream::{event::ResumeToken, ChangeStream as AsyncChangeStream},
+    error::Result,
+    RUNTIME,
+};
+
+/// A `ChangeStream` streams the ongoing changes of its associated collection. A `ChangeStream` can
+/// be obtained by calling [`Collection::watch`](struct.Collection.html#method.watch).
+///
+/// `ChangeStream` implements `Iterator`, blocking until the next event arrives. Unlike a cursor,
+/// a change stream doesn't end when there are no more events; it waits for new ones instead, and
+/// only ends once it's invalidated. [`ChangeStream::next_if_any`](#method.next_if_any) can be used
+/// to check for events without blocking until one arrives.
+///
+/// ```rust
+/// # use mongodb::{bson::Document, sync::Client, error::Result};
+/// #
+/// # fn do_stuff() -> Result<()> {
+/// # let client = Client::with_uri_str("mongodb://example.com")?;
+/// # let coll = client.database("foo").collection::<Document>("bar");
+/// #
+/// for event in coll.watch(None, None)? {
+///     println!("operation performed: {:?}", event?.operation_type);
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ChangeStream<T>
+where
+    T: DeserializeOwned,
+{
+    async_stream: AsyncChangeStream<T>,
+}
+
+impl<T> ChangeStream<T>
+where
+    T: DeserializeOwned,
+{
+    pub(crate) fn new(async_stream: AsyncChangeStream<T>) -> Self {
+        Self { async_stream }
+    }
+
+    /// The token that can be used to start a new change stream that picks up after the last event
+    /// returned by this one, or `None` if the server hasn't provided one yet.
+    pub fn resume_token(&self) -> Option<ResumeToken> {
+        self.async_stream.resume_token()
+    }
+
+    /// Retrieves the next event if one is available without waiting for new ones, returning `None`
+    /// otherwise.
+    ///
+    /// If no events are buffered and the server's most recent batch was empty, this returns
+    /// `None` immediately. Otherwise, it makes at most one request to the server, which waits for
+    /// up to
+    /// [`max_await_time`](crate::options::ChangeStreamOptions::max_await_time) for new events.
+    pub fn next_if_any(&mut self) -> Result<Option<T>> {
+        RUNTIME.block_on_sync(self.async_stream.next_if_any())
+    }
+}
+
+impl<T> Iterator for ChangeStream<T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let async_stream = &mut self.async_stream;
+        RUNTIME
+            .block_on_sync(async move { async_stream.next().await.transpose() })
+            .transpose()
+    }
+}
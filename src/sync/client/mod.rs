@@ -11,9 +11,13 @@ use crate::{
         ListDatabasesOptions,
         SelectionCriteria,
         SessionOptions,
+        WarmUpOptions,
     },
     results::DatabaseSpecification,
     Client as AsyncClient,
+    ServerCapabilities,
+    ServerInfo,
+    TopologyType,
     RUNTIME,
 };
 
@@ -74,7 +78,7 @@ impl Client {
     /// [`ClientOptions::parse`](../options/struct.ClientOptions.html#method.parse) for more
     /// details.
     pub fn with_uri_str(uri: impl AsRef<str>) -> Result<Self> {
-        let async_client = RUNTIME.block_on(AsyncClient::with_uri_str(uri.as_ref()))?;
+        let async_client = RUNTIME.block_on_sync(AsyncClient::with_uri_str(uri.as_ref()))?;
         Ok(Self { async_client })
     }
 
@@ -84,6 +88,15 @@ impl Client {
         Ok(Self { async_client })
     }
 
+    /// Creates a `Client` that wraps the given async [`Client`](../struct.Client.html), sharing its
+    /// connection pools and topology monitoring rather than connecting separately.
+    ///
+    /// The returned `Client` must not be used from within an async task, as blocking there could
+    /// deadlock the runtime; operations called that way return an error instead.
+    pub fn from_async(async_client: AsyncClient) -> Self {
+        Self { async_client }
+    }
+
     /// Gets the default selection criteria the `Client` uses for operations..
     pub fn selection_criteria(&self) -> Option<&SelectionCriteria> {
         self.async_client.selection_criteria()
@@ -125,28 +138,86 @@ impl Client {
         filter: impl Into<Option<Document>>,
         options: impl Into<Option<ListDatabasesOptions>>,
     ) -> Result<Vec<DatabaseSpecification>> {
-        RUNTIME.block_on(
+        RUNTIME.block_on_sync(
             self.async_client
                 .list_databases(filter.into(), options.into()),
         )
     }
 
+    /// Gets information about each database present in the cluster the Client is connected to,
+    /// returning the documents reported by the server as-is rather than deserializing them into
+    /// [`DatabaseSpecification`](../results/struct.DatabaseSpecification.html)s.
+    pub fn list_databases_raw(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<ListDatabasesOptions>>,
+    ) -> Result<Vec<Document>> {
+        RUNTIME.block_on_sync(
+            self.async_client
+                .list_databases_raw(filter.into(), options.into()),
+        )
+    }
+
     /// Gets the names of the databases present in the cluster the Client is connected to.
     pub fn list_database_names(
         &self,
         filter: impl Into<Option<Document>>,
         options: impl Into<Option<ListDatabasesOptions>>,
     ) -> Result<Vec<String>> {
-        RUNTIME.block_on(
+        RUNTIME.block_on_sync(
             self.async_client
                 .list_database_names(filter.into(), options.into()),
         )
     }
 
+    /// Gets the type of the cluster the `Client` is connected to, based on the driver's most recent
+    /// knowledge of the cluster.
+    ///
+    /// This method does not send or receive anything across the wire, so the returned value may be
+    /// `TopologyType::Unknown` if the driver has not yet discovered the cluster.
+    pub fn topology_type(&self) -> TopologyType {
+        self.async_client.topology().topology_type()
+    }
+
+    /// Gets information about each of the servers known to be in the cluster the `Client` is
+    /// connected to, based on the driver's most recent knowledge of the cluster.
+    ///
+    /// This method does not send or receive anything across the wire.
+    pub fn servers(&self) -> Vec<ServerInfo<'static>> {
+        self.async_client
+            .topology()
+            .server_descriptions()
+            .into_iter()
+            .map(ServerInfo::new_owned)
+            .collect()
+    }
+
+    /// Gets the features supported by the cluster the `Client` is connected to, based on the
+    /// driver's most recent knowledge of the cluster.
+    ///
+    /// This method does not send or receive anything across the wire, so the returned value may
+    /// not be known yet if the driver has not yet discovered the cluster. See
+    /// [`ServerCapabilities::is_known`](../struct.ServerCapabilities.html#method.is_known).
+    pub fn server_capabilities(&self) -> ServerCapabilities {
+        self.async_client.topology().server_capabilities()
+    }
+
+    /// Establishes `min_pool_size` connections (or a single connection if `min_pool_size` is not
+    /// set) to the server selected by this `Client`'s selection criteria, so that the connection
+    /// pool is ready to serve traffic without incurring the cost of connection establishment on the
+    /// first operations.
+    ///
+    /// This returns once every connection has completed its handshake, or with an error if any of
+    /// them failed or if the `timeout` in the provided options elapsed first. No commands are sent
+    /// on the connections beyond those needed to establish them.
+    pub fn warm_up(&self, options: impl Into<Option<WarmUpOptions>>) -> Result<()> {
+        RUNTIME.block_on_sync(self.async_client.warm_up(options))
+    }
+
     /// Starts a new `ClientSession`.
     pub fn start_session(&self, options: Option<SessionOptions>) -> Result<ClientSession> {
         RUNTIME
-            .block_on(self.async_client.start_session(options))
+            .block_on_sync(self.async_client.start_session(options))
             .map(Into::into)
     }
 }
@@ -37,6 +37,12 @@ impl ClientSession {
         self.async_client_session.id()
     }
 
+    /// Whether this session is currently in a transaction, i.e. a transaction has been started and
+    /// has not yet been committed or aborted.
+    pub fn in_transaction(&self) -> bool {
+        self.async_client_session.in_transaction()
+    }
+
     /// The highest seen cluster time this session has seen so far.
     /// This will be `None` if this session has not been used in an operation yet.
     pub fn cluster_time(&self) -> Option<&ClusterTime> {
@@ -76,7 +82,7 @@ impl ClientSession {
         &mut self,
         options: impl Into<Option<TransactionOptions>>,
     ) -> Result<()> {
-        RUNTIME.block_on(self.async_client_session.start_transaction(options))
+        RUNTIME.block_on_sync(self.async_client_session.start_transaction(options))
     }
 
     /// Commits the transaction that is currently active on this session.
@@ -100,7 +106,7 @@ impl ClientSession {
     /// [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more information on
     /// retryable writes.
     pub fn commit_transaction(&mut self) -> Result<()> {
-        RUNTIME.block_on(self.async_client_session.commit_transaction())
+        RUNTIME.block_on_sync(self.async_client_session.commit_transaction())
     }
 
     /// Aborts the transaction that is currently active on this session. Any open transaction will
@@ -133,6 +139,59 @@ impl ClientSession {
     /// [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more information on
     /// retryable writes.
     pub fn abort_transaction(&mut self) -> Result<()> {
-        RUNTIME.block_on(self.async_client_session.abort_transaction())
+        RUNTIME.block_on_sync(self.async_client_session.abort_transaction())
+    }
+
+    /// Aborts the transaction that is currently active on this session, discarding any error.
+    ///
+    /// This is intended for best-effort cleanup, e.g. after an operation in the transaction has
+    /// failed, where an error from aborting would only obscure the original one. Unlike
+    /// [`ClientSession::abort_transaction`], calling this when there is no active transaction is
+    /// not an error.
+    pub fn abort_transaction_ignoring_errors(&mut self) {
+        let session = &mut self.async_client_session;
+        let _: Result<()> = RUNTIME.block_on_sync(async move {
+            session.abort_transaction_ignoring_errors().await;
+            Ok(())
+        });
+    }
+
+    /// Starts a transaction, runs `callback` within it, and then commits the transaction if
+    /// `callback` succeeded or aborts it if not. The result of `callback` is returned if the
+    /// transaction was committed.
+    ///
+    /// If committing fails with an error labeled "UnknownTransactionCommitResult", e.g. due to a
+    /// network error, the commit is retried once with the same transaction number. If `callback`
+    /// commits or aborts the transaction itself, it is left as is.
+    ///
+    /// ```rust
+    /// # use mongodb::{bson::{doc, Document}, error::Result, sync::{Client, ClientSession}};
+    /// #
+    /// # fn do_stuff() -> Result<()> {
+    /// # let client = Client::with_uri_str("mongodb://example.com")?;
+    /// # let coll = client.database("foo").collection::<Document>("bar");
+    /// # let mut session = client.start_session(None)?;
+    /// session.with_transaction(
+    ///     |session| {
+    ///         coll.insert_one_with_session(doc! { "x": 1 }, None, session)?;
+    ///         coll.delete_one_with_session(doc! { "y": 2 }, None, session)?;
+    ///         Ok(())
+    ///     },
+    ///     None,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_transaction<R, F>(
+        &mut self,
+        callback: F,
+        options: impl Into<Option<TransactionOptions>>,
+    ) -> Result<R>
+    where
+        F: FnOnce(&mut ClientSession) -> Result<R>,
+    {
+        self.start_transaction(options)?;
+        let result = callback(self);
+        RUNTIME.block_on_sync(self.async_client_session.finish_transaction(result))
     }
 }
@@ -2,13 +2,16 @@ use std::{borrow::Borrow, fmt::Debug};
 
 use serde::{de::DeserializeOwned, Serialize};
 
-use super::{ClientSession, Cursor, SessionCursor};
+use super::{ChangeStream, ClientSession, Cursor, SessionCursor};
 use crate::{
     bson::{Bson, Document},
+    change_stream::event::ChangeStreamEvent,
     error::Result,
     options::{
         AggregateOptions,
+        ChangeStreamOptions,
         CountOptions,
+        CreateIndexOptions,
         DeleteOptions,
         DistinctOptions,
         DropCollectionOptions,
@@ -18,8 +21,11 @@ use crate::{
         FindOneAndUpdateOptions,
         FindOneOptions,
         FindOptions,
+        Hint,
+        IndexModel,
         InsertManyOptions,
         InsertOneOptions,
+        MergeOptions,
         ReadConcern,
         ReplaceOptions,
         SelectionCriteria,
@@ -27,7 +33,15 @@ use crate::{
         UpdateOptions,
         WriteConcern,
     },
-    results::{DeleteResult, InsertManyResult, InsertOneResult, UpdateResult},
+    results::{
+        CreateIndexResult,
+        DeleteResult,
+        FindOneAndModifyResult,
+        InsertManyResult,
+        InsertOneResult,
+        OperationOutcome,
+        UpdateResult,
+    },
     Collection as AsyncCollection,
     Namespace,
     RUNTIME,
@@ -79,6 +93,13 @@ impl<T> Collection<T> {
         Self { async_collection }
     }
 
+    /// Creates a `Collection` that wraps the given async
+    /// [`Collection`](../struct.Collection.html). See
+    /// [`Client::from_async`](struct.Client.html#method.from_async) for more details.
+    pub fn from_async(async_collection: AsyncCollection<T>) -> Self {
+        Self::new(async_collection)
+    }
+
     /// Gets a clone of the `Collection` with a different type `U`.
     pub fn clone_with_type<U>(&self) -> Collection<U> {
         Collection::new(self.async_collection.clone_with_type())
@@ -116,7 +137,7 @@ impl<T> Collection<T> {
 
     /// Drops the collection, deleting all data, users, and indexes stored in it.
     pub fn drop(&self, options: impl Into<Option<DropCollectionOptions>>) -> Result<()> {
-        RUNTIME.block_on(self.async_collection.drop(options.into()))
+        RUNTIME.block_on_sync(self.async_collection.drop(options.into()))
     }
 
     /// Drops the collection, deleting all data, users, and indexes stored in it using the provided
@@ -126,12 +147,74 @@ impl<T> Collection<T> {
         options: impl Into<Option<DropCollectionOptions>>,
         session: &mut ClientSession,
     ) -> Result<()> {
-        RUNTIME.block_on(
+        RUNTIME.block_on_sync(
             self.async_collection
                 .drop_with_session(options.into(), &mut session.async_client_session),
         )
     }
 
+    /// Creates the given index on this collection.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/indexes/) for more
+    /// information on indexes.
+    pub fn create_index(
+        &self,
+        index: IndexModel,
+        options: impl Into<Option<CreateIndexOptions>>,
+    ) -> Result<CreateIndexResult> {
+        RUNTIME.block_on_sync(self.async_collection.create_index(index, options.into()))
+    }
+
+    /// Creates the given index on this collection using the provided `ClientSession`.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/indexes/) for more
+    /// information on indexes.
+    pub fn create_index_with_session(
+        &self,
+        index: IndexModel,
+        options: impl Into<Option<CreateIndexOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<CreateIndexResult> {
+        RUNTIME.block_on_sync(self.async_collection.create_index_with_session(
+            index,
+            options.into(),
+            &mut session.async_client_session,
+        ))
+    }
+
+    /// Creates an ascending (`direction` of `1`) or descending (`direction` of `-1`) index on
+    /// `field`, returning the name of the index.
+    ///
+    /// This is shorthand for calling [`create_index`](#method.create_index) with an
+    /// [`IndexModel`](../options/struct.IndexModel.html) whose keys are `{ field: direction }`.
+    pub fn ensure_index(
+        &self,
+        field: &str,
+        direction: i32,
+        options: impl Into<Option<CreateIndexOptions>>,
+    ) -> Result<String> {
+        RUNTIME.block_on_sync(
+            self.async_collection
+                .ensure_index(field, direction, options.into()),
+        )
+    }
+
+    /// Creates an ascending unique index on `field`, returning the name of the index.
+    ///
+    /// This is shorthand for calling [`create_index`](#method.create_index) with an
+    /// [`IndexModel`](../options/struct.IndexModel.html) whose keys are `{ field: 1 }` and whose
+    /// `unique` option is set.
+    pub fn ensure_unique_index(
+        &self,
+        field: &str,
+        options: impl Into<Option<CreateIndexOptions>>,
+    ) -> Result<String> {
+        RUNTIME.block_on_sync(
+            self.async_collection
+                .ensure_unique_index(field, options.into()),
+        )
+    }
+
     /// Runs an aggregation operation.
     ///
     /// See the documentation [here](https://docs.mongodb.com/manual/aggregation/) for more
@@ -143,10 +226,52 @@ impl<T> Collection<T> {
     ) -> Result<Cursor<Document>> {
         let pipeline: Vec<Document> = pipeline.into_iter().collect();
         RUNTIME
-            .block_on(self.async_collection.aggregate(pipeline, options.into()))
+            .block_on_sync(self.async_collection.aggregate(pipeline, options.into()))
+            .map(Cursor::new)
+    }
+
+    /// Runs an aggregation operation, deserializing the resulting documents into `R`.
+    ///
+    /// This is useful when the shape of the pipeline's output differs from the collection's
+    /// documents, e.g. after a `$project` or `$group` stage.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/aggregation/) for more
+    /// information on aggregations.
+    pub fn aggregate_as<R>(
+        &self,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<AggregateOptions>>,
+    ) -> Result<Cursor<R>>
+    where
+        R: DeserializeOwned,
+    {
+        let pipeline: Vec<Document> = pipeline.into_iter().collect();
+        RUNTIME
+            .block_on_sync(self.async_collection.aggregate_as(pipeline, options.into()))
             .map(Cursor::new)
     }
 
+    /// Runs an aggregation operation, also returning an
+    /// [`OperationOutcome`](../results/struct.OperationOutcome.html) describing how the initial
+    /// `aggregate` command was executed, including whether it was retried. The outcome doesn't
+    /// cover the `getMore` commands issued while iterating the returned cursor.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/aggregation/) for more
+    /// information on aggregations.
+    pub fn aggregate_with_outcome(
+        &self,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<AggregateOptions>>,
+    ) -> Result<(Cursor<Document>, OperationOutcome)> {
+        let pipeline: Vec<Document> = pipeline.into_iter().collect();
+        RUNTIME
+            .block_on_sync(
+                self.async_collection
+                    .aggregate_with_outcome(pipeline, options.into()),
+            )
+            .map(|(cursor, outcome)| (Cursor::new(cursor), outcome))
+    }
+
     /// Runs an aggregation operation using the provided `ClientSession`.
     ///
     /// See the documentation [here](https://docs.mongodb.com/manual/aggregation/) for more
@@ -159,7 +284,7 @@ impl<T> Collection<T> {
     ) -> Result<SessionCursor<Document>> {
         let pipeline: Vec<Document> = pipeline.into_iter().collect();
         RUNTIME
-            .block_on(self.async_collection.aggregate_with_session(
+            .block_on_sync(self.async_collection.aggregate_with_session(
                 pipeline,
                 options.into(),
                 &mut session.async_client_session,
@@ -167,12 +292,74 @@ impl<T> Collection<T> {
             .map(SessionCursor::new)
     }
 
+    /// Runs an aggregation operation using the provided `ClientSession`, deserializing the
+    /// resulting documents into `R`. See [`Collection::aggregate_as`](#method.aggregate_as) for
+    /// more details.
+    pub fn aggregate_as_with_session<R>(
+        &self,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<AggregateOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<SessionCursor<R>>
+    where
+        R: DeserializeOwned,
+    {
+        let pipeline: Vec<Document> = pipeline.into_iter().collect();
+        RUNTIME
+            .block_on_sync(self.async_collection.aggregate_as_with_session(
+                pipeline,
+                options.into(),
+                &mut session.async_client_session,
+            ))
+            .map(SessionCursor::new)
+    }
+
+    /// Runs an aggregation operation that writes its results into the collection `target` by
+    /// appending a `$merge` stage built from `options` to `pipeline`. The aggregation is always
+    /// executed on the primary and is never retried.
+    ///
+    /// `pipeline` must not contain its own `$out` or `$merge` stage.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/reference/operator/aggregation/merge/)
+    /// for more information on `$merge`.
+    pub fn aggregate_to(
+        &self,
+        target: Namespace,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<MergeOptions>>,
+    ) -> Result<()> {
+        let pipeline: Vec<Document> = pipeline.into_iter().collect();
+        RUNTIME.block_on_sync(
+            self.async_collection
+                .aggregate_to(target, pipeline, options.into()),
+        )
+    }
+
+    /// Runs an aggregation operation that writes its results into the collection `target` using
+    /// the provided `ClientSession`. See
+    /// [`Collection::aggregate_to`](#method.aggregate_to) for more details.
+    pub fn aggregate_to_with_session(
+        &self,
+        target: Namespace,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<MergeOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<()> {
+        let pipeline: Vec<Document> = pipeline.into_iter().collect();
+        RUNTIME.block_on_sync(self.async_collection.aggregate_to_with_session(
+            target,
+            pipeline,
+            options.into(),
+            &mut session.async_client_session,
+        ))
+    }
+
     /// Estimates the number of documents in the collection using collection metadata.
     pub fn estimated_document_count(
         &self,
         options: impl Into<Option<EstimatedDocumentCountOptions>>,
     ) -> Result<u64> {
-        RUNTIME.block_on(
+        RUNTIME.block_on_sync(
             self.async_collection
                 .estimated_document_count(options.into()),
         )
@@ -187,12 +374,26 @@ impl<T> Collection<T> {
         filter: impl Into<Option<Document>>,
         options: impl Into<Option<CountOptions>>,
     ) -> Result<u64> {
-        RUNTIME.block_on(
+        RUNTIME.block_on_sync(
             self.async_collection
                 .count_documents(filter.into(), options.into()),
         )
     }
 
+    /// Gets the number of documents matching `filter`, also returning an
+    /// [`OperationOutcome`](../results/struct.OperationOutcome.html) describing how the count was
+    /// executed, including whether it was retried.
+    pub fn count_documents_with_outcome(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<CountOptions>>,
+    ) -> Result<(u64, OperationOutcome)> {
+        RUNTIME.block_on_sync(
+            self.async_collection
+                .count_documents_with_outcome(filter.into(), options.into()),
+        )
+    }
+
     /// Gets the number of documents matching `filter` using the provided `ClientSession`.
     ///
     /// Note that using [`Collection::estimated_document_count`](#method.estimated_document_count)
@@ -203,7 +404,7 @@ impl<T> Collection<T> {
         options: impl Into<Option<CountOptions>>,
         session: &mut ClientSession,
     ) -> Result<u64> {
-        RUNTIME.block_on(self.async_collection.count_documents_with_session(
+        RUNTIME.block_on_sync(self.async_collection.count_documents_with_session(
             filter.into(),
             options.into(),
             &mut session.async_client_session,
@@ -216,7 +417,21 @@ impl<T> Collection<T> {
         query: Document,
         options: impl Into<Option<DeleteOptions>>,
     ) -> Result<DeleteResult> {
-        RUNTIME.block_on(self.async_collection.delete_many(query, options.into()))
+        RUNTIME.block_on_sync(self.async_collection.delete_many(query, options.into()))
+    }
+
+    /// Deletes all documents stored in the collection matching `query`, also returning an
+    /// [`OperationOutcome`](../results/struct.OperationOutcome.html) describing how the delete was
+    /// executed.
+    pub fn delete_many_with_outcome(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>>,
+    ) -> Result<(DeleteResult, OperationOutcome)> {
+        RUNTIME.block_on_sync(
+            self.async_collection
+                .delete_many_with_outcome(query, options.into()),
+        )
     }
 
     /// Deletes all documents stored in the collection matching `query` using the provided
@@ -227,7 +442,7 @@ impl<T> Collection<T> {
         options: impl Into<Option<DeleteOptions>>,
         session: &mut ClientSession,
     ) -> Result<DeleteResult> {
-        RUNTIME.block_on(self.async_collection.delete_many_with_session(
+        RUNTIME.block_on_sync(self.async_collection.delete_many_with_session(
             query,
             options.into(),
             &mut session.async_client_session,
@@ -245,7 +460,21 @@ impl<T> Collection<T> {
         query: Document,
         options: impl Into<Option<DeleteOptions>>,
     ) -> Result<DeleteResult> {
-        RUNTIME.block_on(self.async_collection.delete_one(query, options.into()))
+        RUNTIME.block_on_sync(self.async_collection.delete_one(query, options.into()))
+    }
+
+    /// Deletes up to one document found matching `query`, also returning an
+    /// [`OperationOutcome`](../results/struct.OperationOutcome.html) describing how the delete was
+    /// executed, including whether it was retried.
+    pub fn delete_one_with_outcome(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>>,
+    ) -> Result<(DeleteResult, OperationOutcome)> {
+        RUNTIME.block_on_sync(
+            self.async_collection
+                .delete_one_with_outcome(query, options.into()),
+        )
     }
 
     /// Deletes up to one document found matching `query` using the provided `ClientSession`.
@@ -260,7 +489,7 @@ impl<T> Collection<T> {
         options: impl Into<Option<DeleteOptions>>,
         session: &mut ClientSession,
     ) -> Result<DeleteResult> {
-        RUNTIME.block_on(self.async_collection.delete_one_with_session(
+        RUNTIME.block_on_sync(self.async_collection.delete_one_with_session(
             query,
             options.into(),
             &mut session.async_client_session,
@@ -274,7 +503,7 @@ impl<T> Collection<T> {
         filter: impl Into<Option<Document>>,
         options: impl Into<Option<DistinctOptions>>,
     ) -> Result<Vec<Bson>> {
-        RUNTIME.block_on(self.async_collection.distinct(
+        RUNTIME.block_on_sync(self.async_collection.distinct(
             field_name.as_ref(),
             filter.into(),
             options.into(),
@@ -290,7 +519,7 @@ impl<T> Collection<T> {
         options: impl Into<Option<DistinctOptions>>,
         session: &mut ClientSession,
     ) -> Result<Vec<Bson>> {
-        RUNTIME.block_on(self.async_collection.distinct_with_session(
+        RUNTIME.block_on_sync(self.async_collection.distinct_with_session(
             field_name.as_ref(),
             filter.into(),
             options.into(),
@@ -310,10 +539,27 @@ impl<T> Collection<T> {
         update: impl Into<UpdateModifications>,
         options: impl Into<Option<UpdateOptions>>,
     ) -> Result<UpdateResult> {
-        RUNTIME.block_on(
-            self.async_collection
-                .update_many(query, update.into(), options.into()),
-        )
+        RUNTIME.block_on_sync(self.async_collection.update_many(
+            query,
+            update.into(),
+            options.into(),
+        ))
+    }
+
+    /// Updates all documents matching `query` in the collection, also returning an
+    /// [`OperationOutcome`](../results/struct.OperationOutcome.html) describing how the update was
+    /// executed.
+    pub fn update_many_with_outcome(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications>,
+        options: impl Into<Option<UpdateOptions>>,
+    ) -> Result<(UpdateResult, OperationOutcome)> {
+        RUNTIME.block_on_sync(self.async_collection.update_many_with_outcome(
+            query,
+            update.into(),
+            options.into(),
+        ))
     }
 
     /// Updates all documents matching `query` in the collection using the provided `ClientSession`.
@@ -329,7 +575,7 @@ impl<T> Collection<T> {
         options: impl Into<Option<UpdateOptions>>,
         session: &mut ClientSession,
     ) -> Result<UpdateResult> {
-        RUNTIME.block_on(self.async_collection.update_many_with_session(
+        RUNTIME.block_on_sync(self.async_collection.update_many_with_session(
             query,
             update.into(),
             options.into(),
@@ -354,10 +600,27 @@ impl<T> Collection<T> {
         update: impl Into<UpdateModifications>,
         options: impl Into<Option<UpdateOptions>>,
     ) -> Result<UpdateResult> {
-        RUNTIME.block_on(
-            self.async_collection
-                .update_one(query, update.into(), options.into()),
-        )
+        RUNTIME.block_on_sync(self.async_collection.update_one(
+            query,
+            update.into(),
+            options.into(),
+        ))
+    }
+
+    /// Updates up to one document matching `query` in the collection, also returning an
+    /// [`OperationOutcome`](../results/struct.OperationOutcome.html) describing how the update was
+    /// executed, including whether it was retried.
+    pub fn update_one_with_outcome(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications>,
+        options: impl Into<Option<UpdateOptions>>,
+    ) -> Result<(UpdateResult, OperationOutcome)> {
+        RUNTIME.block_on_sync(self.async_collection.update_one_with_outcome(
+            query,
+            update.into(),
+            options.into(),
+        ))
     }
 
     /// Updates up to one document matching `query` in the collection using the provided
@@ -379,7 +642,7 @@ impl<T> Collection<T> {
         options: impl Into<Option<UpdateOptions>>,
         session: &mut ClientSession,
     ) -> Result<UpdateResult> {
-        RUNTIME.block_on(self.async_collection.update_one_with_session(
+        RUNTIME.block_on_sync(self.async_collection.update_one_with_session(
             query,
             update.into(),
             options.into(),
@@ -390,7 +653,7 @@ impl<T> Collection<T> {
 
 impl<T> Collection<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     /// Finds the documents in the collection matching `filter`.
     pub fn find(
@@ -399,10 +662,63 @@ where
         options: impl Into<Option<FindOptions>>,
     ) -> Result<Cursor<T>> {
         RUNTIME
-            .block_on(self.async_collection.find(filter.into(), options.into()))
+            .block_on_sync(self.async_collection.find(filter.into(), options.into()))
+            .map(Cursor::new)
+    }
+
+    /// Finds the documents in the collection matching `filter`, also returning an
+    /// [`OperationOutcome`](../results/struct.OperationOutcome.html) describing how the initial
+    /// `find` command was executed, including whether it was retried. The outcome doesn't cover
+    /// the `getMore` commands issued while iterating the returned cursor.
+    pub fn find_with_outcome(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<FindOptions>>,
+    ) -> Result<(Cursor<T>, OperationOutcome)> {
+        RUNTIME
+            .block_on_sync(
+                self.async_collection
+                    .find_with_outcome(filter.into(), options.into()),
+            )
+            .map(|(cursor, outcome)| (Cursor::new(cursor), outcome))
+    }
+
+    /// Finds the documents in the collection matching `filter`, using the index specified by
+    /// `hint`. Any hint already set in `options` is overridden.
+    pub fn find_with_hint(
+        &self,
+        filter: impl Into<Option<Document>>,
+        hint: impl Into<Hint>,
+        options: impl Into<Option<FindOptions>>,
+    ) -> Result<Cursor<T>> {
+        RUNTIME
+            .block_on_sync(self.async_collection.find_with_hint(
+                filter.into(),
+                hint.into(),
+                options.into(),
+            ))
             .map(Cursor::new)
     }
 
+    /// Starts a new [`ChangeStream`](struct.ChangeStream.html) that receives events for all
+    /// changes in this collection. The events can be filtered or transformed by `pipeline`, which
+    /// is appended to the `$changeStream` stage that opens the stream.
+    ///
+    /// Change streams require either a replica set or a sharded cluster.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/changeStreams/) for more
+    /// information on change streams.
+    pub fn watch(
+        &self,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<ChangeStreamOptions>>,
+    ) -> Result<ChangeStream<ChangeStreamEvent<T>>> {
+        let pipeline: Vec<Document> = pipeline.into_iter().collect();
+        RUNTIME
+            .block_on_sync(self.async_collection.watch(pipeline, options.into()))
+            .map(ChangeStream::new)
+    }
+
     /// Finds the documents in the collection matching `filter` using the provided `ClientSession`.
     pub fn find_with_session(
         &self,
@@ -411,7 +727,7 @@ where
         session: &mut ClientSession,
     ) -> Result<SessionCursor<T>> {
         RUNTIME
-            .block_on(self.async_collection.find_with_session(
+            .block_on_sync(self.async_collection.find_with_session(
                 filter.into(),
                 options.into(),
                 &mut session.async_client_session,
@@ -425,12 +741,58 @@ where
         filter: impl Into<Option<Document>>,
         options: impl Into<Option<FindOneOptions>>,
     ) -> Result<Option<T>> {
-        RUNTIME.block_on(
+        RUNTIME.block_on_sync(
             self.async_collection
                 .find_one(filter.into(), options.into()),
         )
     }
 
+    /// Finds a single document in the collection matching `filter`, using the index specified by
+    /// `hint`. Any hint already set in `options` is overridden.
+    pub fn find_one_with_hint(
+        &self,
+        filter: impl Into<Option<Document>>,
+        hint: impl Into<Hint>,
+        options: impl Into<Option<FindOneOptions>>,
+    ) -> Result<Option<T>> {
+        RUNTIME.block_on_sync(self.async_collection.find_one_with_hint(
+            filter.into(),
+            hint.into(),
+            options.into(),
+        ))
+    }
+
+    /// Finds a single document in the collection matching `filter`, sharing a single server round
+    /// trip with any identical calls that are already in progress on other threads.
+    ///
+    /// Calls are identical if they target the same namespace with the same filter and options,
+    /// including the read concern and selection criteria inherited from the collection. Calls
+    /// with a predicate as their selection criteria are never coalesced.
+    pub fn find_one_coalesced(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<FindOneOptions>>,
+    ) -> Result<Option<T>> {
+        RUNTIME.block_on_sync(
+            self.async_collection
+                .find_one_coalesced(filter.into(), options.into()),
+        )
+    }
+
+    /// Finds a single document in the collection matching `filter`, also returning an
+    /// [`OperationOutcome`](../results/struct.OperationOutcome.html) describing how the find was
+    /// executed, including whether it was retried.
+    pub fn find_one_with_outcome(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<FindOneOptions>>,
+    ) -> Result<(Option<T>, OperationOutcome)> {
+        RUNTIME.block_on_sync(
+            self.async_collection
+                .find_one_with_outcome(filter.into(), options.into()),
+        )
+    }
+
     /// Finds a single document in the collection matching `filter` using the provided
     /// `ClientSession`.
     pub fn find_one_with_session(
@@ -439,12 +801,36 @@ where
         options: impl Into<Option<FindOneOptions>>,
         session: &mut ClientSession,
     ) -> Result<Option<T>> {
-        RUNTIME.block_on(self.async_collection.find_one_with_session(
+        RUNTIME.block_on_sync(self.async_collection.find_one_with_session(
             filter.into(),
             options.into(),
             &mut session.async_client_session,
         ))
     }
+
+    /// Selects `size` documents from the collection at random by running an aggregation with a
+    /// single `$sample` stage.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/reference/operator/aggregation/sample/)
+    /// for more information on `$sample`.
+    pub fn sample(
+        &self,
+        size: u32,
+        options: impl Into<Option<AggregateOptions>>,
+    ) -> Result<Cursor<T>> {
+        RUNTIME
+            .block_on_sync(self.async_collection.sample(size, options.into()))
+            .map(Cursor::new)
+    }
+
+    /// Selects a single document from the collection at random, or returns `None` if the
+    /// collection is empty. See [`Collection::sample`](#method.sample) for more details.
+    pub fn random_document(
+        &self,
+        options: impl Into<Option<AggregateOptions>>,
+    ) -> Result<Option<T>> {
+        RUNTIME.block_on_sync(self.async_collection.random_document(options.into()))
+    }
 }
 
 impl<T> Collection<T>
@@ -461,8 +847,8 @@ where
         &self,
         filter: Document,
         options: impl Into<Option<FindOneAndDeleteOptions>>,
-    ) -> Result<Option<T>> {
-        RUNTIME.block_on(
+    ) -> Result<FindOneAndModifyResult<T>> {
+        RUNTIME.block_on_sync(
             self.async_collection
                 .find_one_and_delete(filter, options.into()),
         )
@@ -480,8 +866,8 @@ where
         filter: Document,
         options: impl Into<Option<FindOneAndDeleteOptions>>,
         session: &mut ClientSession,
-    ) -> Result<Option<T>> {
-        RUNTIME.block_on(self.async_collection.find_one_and_delete_with_session(
+    ) -> Result<FindOneAndModifyResult<T>> {
+        RUNTIME.block_on_sync(self.async_collection.find_one_and_delete_with_session(
             filter,
             options.into(),
             &mut session.async_client_session,
@@ -502,8 +888,8 @@ where
         filter: Document,
         update: impl Into<UpdateModifications>,
         options: impl Into<Option<FindOneAndUpdateOptions>>,
-    ) -> Result<Option<T>> {
-        RUNTIME.block_on(self.async_collection.find_one_and_update(
+    ) -> Result<FindOneAndModifyResult<T>> {
+        RUNTIME.block_on_sync(self.async_collection.find_one_and_update(
             filter,
             update.into(),
             options.into(),
@@ -525,8 +911,8 @@ where
         update: impl Into<UpdateModifications>,
         options: impl Into<Option<FindOneAndUpdateOptions>>,
         session: &mut ClientSession,
-    ) -> Result<Option<T>> {
-        RUNTIME.block_on(self.async_collection.find_one_and_update_with_session(
+    ) -> Result<FindOneAndModifyResult<T>> {
+        RUNTIME.block_on_sync(self.async_collection.find_one_and_update_with_session(
             filter,
             update.into(),
             options.into(),
@@ -551,8 +937,8 @@ where
         filter: Document,
         replacement: T,
         options: impl Into<Option<FindOneAndReplaceOptions>>,
-    ) -> Result<Option<T>> {
-        RUNTIME.block_on(self.async_collection.find_one_and_replace(
+    ) -> Result<FindOneAndModifyResult<T>> {
+        RUNTIME.block_on_sync(self.async_collection.find_one_and_replace(
             filter,
             replacement,
             options.into(),
@@ -572,8 +958,8 @@ where
         replacement: T,
         options: impl Into<Option<FindOneAndReplaceOptions>>,
         session: &mut ClientSession,
-    ) -> Result<Option<T>> {
-        RUNTIME.block_on(self.async_collection.find_one_and_replace_with_session(
+    ) -> Result<FindOneAndModifyResult<T>> {
+        RUNTIME.block_on_sync(self.async_collection.find_one_and_replace_with_session(
             filter,
             replacement,
             options.into(),
@@ -597,7 +983,25 @@ where
         docs: impl IntoIterator<Item = impl Borrow<T>>,
         options: impl Into<Option<InsertManyOptions>>,
     ) -> Result<InsertManyResult> {
-        RUNTIME.block_on(self.async_collection.insert_many(docs, options.into()))
+        RUNTIME.block_on_sync(self.async_collection.insert_many(docs, options.into()))
+    }
+
+    /// Inserts the documents in `docs` into the collection, also returning an
+    /// [`OperationOutcome`](../results/struct.OperationOutcome.html) describing how the insert was
+    /// executed.
+    ///
+    /// If the documents are split into multiple batches, the outcome's `server_address` and
+    /// `connection_age` describe the last batch, `attempts` is the highest number of attempts
+    /// that any batch needed, and `duration` covers all of the batches.
+    pub fn insert_many_with_outcome(
+        &self,
+        docs: impl IntoIterator<Item = impl Borrow<T>>,
+        options: impl Into<Option<InsertManyOptions>>,
+    ) -> Result<(InsertManyResult, OperationOutcome)> {
+        RUNTIME.block_on_sync(
+            self.async_collection
+                .insert_many_with_outcome(docs, options.into()),
+        )
     }
 
     /// Inserts the documents in `docs` into the collection using the provided `ClientSession`.
@@ -612,7 +1016,7 @@ where
         options: impl Into<Option<InsertManyOptions>>,
         session: &mut ClientSession,
     ) -> Result<InsertManyResult> {
-        RUNTIME.block_on(self.async_collection.insert_many_with_session(
+        RUNTIME.block_on_sync(self.async_collection.insert_many_with_session(
             docs,
             options.into(),
             &mut session.async_client_session,
@@ -630,12 +1034,26 @@ where
         doc: impl Borrow<T>,
         options: impl Into<Option<InsertOneOptions>>,
     ) -> Result<InsertOneResult> {
-        RUNTIME.block_on(
+        RUNTIME.block_on_sync(
             self.async_collection
                 .insert_one(doc.borrow(), options.into()),
         )
     }
 
+    /// Inserts `doc` into the collection, also returning an
+    /// [`OperationOutcome`](../results/struct.OperationOutcome.html) describing how the insert was
+    /// executed, including whether it was retried.
+    pub fn insert_one_with_outcome(
+        &self,
+        doc: impl Borrow<T>,
+        options: impl Into<Option<InsertOneOptions>>,
+    ) -> Result<(InsertOneResult, OperationOutcome)> {
+        RUNTIME.block_on_sync(
+            self.async_collection
+                .insert_one_with_outcome(doc.borrow(), options.into()),
+        )
+    }
+
     /// Inserts `doc` into the collection using the provided `ClientSession`.
     ///
     /// This operation will retry once upon failure if the connection and encountered error support
@@ -648,7 +1066,7 @@ where
         options: impl Into<Option<InsertOneOptions>>,
         session: &mut ClientSession,
     ) -> Result<InsertOneResult> {
-        RUNTIME.block_on(self.async_collection.insert_one_with_session(
+        RUNTIME.block_on_sync(self.async_collection.insert_one_with_session(
             doc.borrow(),
             options.into(),
             &mut session.async_client_session,
@@ -667,7 +1085,23 @@ where
         replacement: impl Borrow<T>,
         options: impl Into<Option<ReplaceOptions>>,
     ) -> Result<UpdateResult> {
-        RUNTIME.block_on(self.async_collection.replace_one(
+        RUNTIME.block_on_sync(self.async_collection.replace_one(
+            query,
+            replacement.borrow(),
+            options.into(),
+        ))
+    }
+
+    /// Replaces up to one document matching `query` in the collection with `replacement`, also
+    /// returning an [`OperationOutcome`](../results/struct.OperationOutcome.html) describing how
+    /// the replacement was executed, including whether it was retried.
+    pub fn replace_one_with_outcome(
+        &self,
+        query: Document,
+        replacement: impl Borrow<T>,
+        options: impl Into<Option<ReplaceOptions>>,
+    ) -> Result<(UpdateResult, OperationOutcome)> {
+        RUNTIME.block_on_sync(self.async_collection.replace_one_with_outcome(
             query,
             replacement.borrow(),
             options.into(),
@@ -688,7 +1122,7 @@ where
         options: impl Into<Option<ReplaceOptions>>,
         session: &mut ClientSession,
     ) -> Result<UpdateResult> {
-        RUNTIME.block_on(self.async_collection.replace_one_with_session(
+        RUNTIME.block_on_sync(self.async_collection.replace_one_with_session(
             query,
             replacement.borrow(),
             options.into(),
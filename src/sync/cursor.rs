@@ -5,6 +5,7 @@ use super::ClientSession;
 use crate::{
     bson::Document,
     error::Result,
+    results::CollectResult,
     Cursor as AsyncCursor,
     SessionCursor as AsyncSessionCursor,
     SessionCursorStream,
@@ -71,28 +72,109 @@ use crate::{
 #[derive(Debug)]
 pub struct Cursor<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     async_cursor: AsyncCursor<T>,
 }
 
 impl<T> Cursor<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     pub(crate) fn new(async_cursor: AsyncCursor<T>) -> Self {
         Self { async_cursor }
     }
+
+    /// Whether the server indicated that some shards were unavailable when this cursor was
+    /// created, meaning the results may be incomplete. This can only be `true` if
+    /// [`FindOptions::allow_partial_results`](crate::options::FindOptions::allow_partial_results)
+    /// was set.
+    pub fn partial_results_returned(&self) -> bool {
+        self.async_cursor.partial_results_returned()
+    }
+
+    /// The number of results this cursor has yielded so far, whether individually via the
+    /// `Iterator` implementation or in bulk via methods such as
+    /// [`Cursor::next_batch`](#method.next_batch). Results that could not be deserialized into `T`
+    /// are not counted.
+    ///
+    /// Note that this only reflects progress through the results; it cannot be used to determine
+    /// how many results remain, since the total number of matching documents isn't known to the
+    /// cursor.
+    pub fn count_consumed(&self) -> u64 {
+        self.async_cursor.count_consumed()
+    }
+
+    /// Retrieves all of the results this cursor currently has buffered in memory as a single batch,
+    /// or returns `None` once the cursor is exhausted.
+    ///
+    /// If the buffer is empty, this issues a single `getMore` to fetch the next batch from the
+    /// server first. If that batch is empty but the cursor isn't exhausted, as can happen with a
+    /// tailable cursor, an empty batch is returned. This can be used to process the results in
+    /// bulk rather than one at a time.
+    pub fn next_batch(&mut self) -> Result<Option<Vec<T>>> {
+        RUNTIME.block_on_sync(self.async_cursor.next_batch())
+    }
+
+    /// Collects the results of this cursor into a `Vec`, stopping once either `max_docs` documents
+    /// have been collected or collecting the next document would cause the total size of the
+    /// collected documents to exceed `max_bytes`.
+    ///
+    /// If a limit is reached while the cursor still has results remaining, the returned
+    /// [`CollectResult`](../results/struct.CollectResult.html) will have `truncated` set to `true`,
+    /// and the remaining results can be retrieved by continuing to iterate this cursor.
+    ///
+    /// No results are lost if an error occurs; see the async
+    /// [`Cursor::collect_with_limit`](../struct.Cursor.html#method.collect_with_limit) for how
+    /// errors are reported.
+    pub fn collect_with_limit(
+        &mut self,
+        max_docs: impl Into<Option<usize>>,
+        max_bytes: impl Into<Option<u64>>,
+    ) -> Result<CollectResult<T>> {
+        RUNTIME.block_on_sync(
+            self.async_cursor
+                .collect_with_limit(max_docs.into(), max_bytes.into()),
+        )
+    }
+
+    /// Transforms each successfully retrieved result from this cursor using `f`, passing any
+    /// errors through unchanged.
+    ///
+    /// ```rust
+    /// # use mongodb::{bson::Document, sync::Client, error::Result};
+    /// #
+    /// # fn do_stuff() -> Result<()> {
+    /// # let client = Client::with_uri_str("mongodb://example.com")?;
+    /// # let coll = client.database("foo").collection::<Document>("bar");
+    /// #
+    /// let cursor = coll.find(None, None)?;
+    /// let names: Vec<Option<String>> = cursor
+    ///     .map_ok(|doc| doc.get_str("name").ok().map(String::from))
+    ///     .collect::<Result<_>>()?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn map_ok<U, F>(self, mut f: F) -> impl Iterator<Item = Result<U>>
+    where
+        F: FnMut(T) -> U,
+    {
+        self.map(move |result| result.map(&mut f))
+    }
 }
 
 impl<T> Iterator for Cursor<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     type Item = Result<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        RUNTIME.block_on(self.async_cursor.next())
+        let async_cursor = &mut self.async_cursor;
+        RUNTIME
+            .block_on_sync(async move { async_cursor.next().await.transpose() })
+            .transpose()
     }
 }
 
@@ -118,19 +200,27 @@ where
 #[derive(Debug)]
 pub struct SessionCursor<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     async_cursor: AsyncSessionCursor<T>,
 }
 
 impl<T> SessionCursor<T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     pub(crate) fn new(async_cursor: AsyncSessionCursor<T>) -> Self {
         Self { async_cursor }
     }
 
+    /// Whether the server indicated that some shards were unavailable when this cursor was
+    /// created, meaning the results may be incomplete. This can only be `true` if
+    /// [`FindOptions::allow_partial_results`](crate::options::FindOptions::allow_partial_results)
+    /// was set.
+    pub fn partial_results_returned(&self) -> bool {
+        self.async_cursor.partial_results_returned()
+    }
+
     /// Retrieves a [`SessionCursorIter`] to iterate this cursor. The session provided must be
     /// the same session used to create the cursor.
     pub fn iter<'session>(
@@ -166,6 +256,26 @@ where
     pub fn next(&mut self, session: &mut ClientSession) -> Option<Result<T>> {
         self.iter(session).next()
     }
+
+    /// Closes this cursor, killing the server-side cursor if it hasn't been exhausted yet. The
+    /// session provided must be the same session used to create the cursor.
+    ///
+    /// ```
+    /// # use bson::{doc, Document};
+    /// # use mongodb::sync::Client;
+    /// # fn foo() -> mongodb::error::Result<()> {
+    /// # let client = Client::with_uri_str("foo")?;
+    /// # let coll = client.database("foo").collection::<Document>("bar");
+    /// # let mut session = client.start_session(None)?;
+    /// let mut cursor = coll.find_with_session(doc! { "x": 1 }, None, &mut session)?;
+    /// let first = cursor.next(&mut session).transpose()?;
+    /// cursor.close(&mut session)?;
+    /// # Ok::<(), mongodb::error::Error>(())
+    /// # }
+    /// ```
+    pub fn close(self, session: &mut ClientSession) -> Result<()> {
+        RUNTIME.block_on_sync(self.async_cursor.close(&mut session.async_client_session))
+    }
 }
 
 /// A handle that borrows a `ClientSession` temporarily for executing getMores or iterating through
@@ -174,18 +284,21 @@ where
 /// This updates the buffer of the parent `SessionCursor` when dropped.
 pub struct SessionCursorIter<'cursor, 'session, T = Document>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     async_stream: SessionCursorStream<'cursor, 'session, T>,
 }
 
 impl<T> Iterator for SessionCursorIter<'_, '_, T>
 where
-    T: DeserializeOwned + Unpin,
+    T: DeserializeOwned,
 {
     type Item = Result<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        RUNTIME.block_on(self.async_stream.next())
+        let async_stream = &mut self.async_stream;
+        RUNTIME
+            .block_on_sync(async move { async_stream.next().await.transpose() })
+            .transpose()
     }
 }
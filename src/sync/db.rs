@@ -10,8 +10,10 @@ use crate::{
         CreateCollectionOptions,
         DropDatabaseOptions,
         ListCollectionsOptions,
+        ProfilingLevel,
         ReadConcern,
         SelectionCriteria,
+        SetProfilingLevelOptions,
         WriteConcern,
     },
     results::CollectionSpecification,
@@ -61,6 +63,12 @@ impl Database {
         Self { async_database }
     }
 
+    /// Creates a `Database` that wraps the given async [`Database`](../struct.Database.html). See
+    /// [`Client::from_async`](struct.Client.html#method.from_async) for more details.
+    pub fn from_async(async_database: AsyncDatabase) -> Self {
+        Self::new(async_database)
+    }
+
     /// Gets the name of the `Database`.
     pub fn name(&self) -> &str {
         self.async_database.name()
@@ -107,7 +115,7 @@ impl Database {
 
     /// Drops the database, deleting all data, collections, users, and indexes stored in it.
     pub fn drop(&self, options: impl Into<Option<DropDatabaseOptions>>) -> Result<()> {
-        RUNTIME.block_on(self.async_database.drop(options.into()))
+        RUNTIME.block_on_sync(self.async_database.drop(options.into()))
     }
 
     /// Drops the database, deleting all data, collections, users, and indexes stored in it using
@@ -117,7 +125,7 @@ impl Database {
         options: impl Into<Option<DropDatabaseOptions>>,
         session: &mut ClientSession,
     ) -> Result<()> {
-        RUNTIME.block_on(
+        RUNTIME.block_on_sync(
             self.async_database
                 .drop_with_session(options.into(), &mut session.async_client_session),
         )
@@ -131,7 +139,7 @@ impl Database {
         options: impl Into<Option<ListCollectionsOptions>>,
     ) -> Result<Cursor<CollectionSpecification>> {
         RUNTIME
-            .block_on(
+            .block_on_sync(
                 self.async_database
                     .list_collections(filter.into(), options.into()),
             )
@@ -148,7 +156,7 @@ impl Database {
         session: &mut ClientSession,
     ) -> Result<SessionCursor<CollectionSpecification>> {
         RUNTIME
-            .block_on(self.async_database.list_collections_with_session(
+            .block_on_sync(self.async_database.list_collections_with_session(
                 filter.into(),
                 options.into(),
                 &mut session.async_client_session,
@@ -161,7 +169,7 @@ impl Database {
         &self,
         filter: impl Into<Option<Document>>,
     ) -> Result<Vec<String>> {
-        RUNTIME.block_on(self.async_database.list_collection_names(filter.into()))
+        RUNTIME.block_on_sync(self.async_database.list_collection_names(filter.into()))
     }
 
     /// Gets the names of the collections in the database using the provided `ClientSession`.
@@ -170,7 +178,7 @@ impl Database {
         filter: impl Into<Option<Document>>,
         session: &mut ClientSession,
     ) -> Result<Vec<String>> {
-        RUNTIME.block_on(
+        RUNTIME.block_on_sync(
             self.async_database.list_collection_names_with_session(
                 filter.into(),
                 &mut session.async_client_session,
@@ -187,7 +195,7 @@ impl Database {
         name: impl AsRef<str>,
         options: impl Into<Option<CreateCollectionOptions>>,
     ) -> Result<()> {
-        RUNTIME.block_on(
+        RUNTIME.block_on_sync(
             self.async_database
                 .create_collection(name.as_ref(), options.into()),
         )
@@ -204,7 +212,7 @@ impl Database {
         options: impl Into<Option<CreateCollectionOptions>>,
         session: &mut ClientSession,
     ) -> Result<()> {
-        RUNTIME.block_on(self.async_database.create_collection_with_session(
+        RUNTIME.block_on_sync(self.async_database.create_collection_with_session(
             name.as_ref(),
             options.into(),
             &mut session.async_client_session,
@@ -221,7 +229,7 @@ impl Database {
         command: Document,
         selection_criteria: impl Into<Option<SelectionCriteria>>,
     ) -> Result<Document> {
-        RUNTIME.block_on(
+        RUNTIME.block_on_sync(
             self.async_database
                 .run_command(command, selection_criteria.into()),
         )
@@ -238,7 +246,7 @@ impl Database {
         selection_criteria: impl Into<Option<SelectionCriteria>>,
         session: &mut ClientSession,
     ) -> Result<Document> {
-        RUNTIME.block_on(self.async_database.run_command_with_session(
+        RUNTIME.block_on_sync(self.async_database.run_command_with_session(
             command,
             selection_criteria.into(),
             &mut session.async_client_session,
@@ -256,7 +264,7 @@ impl Database {
     ) -> Result<Cursor<Document>> {
         let pipeline: Vec<Document> = pipeline.into_iter().collect();
         RUNTIME
-            .block_on(self.async_database.aggregate(pipeline, options.into()))
+            .block_on_sync(self.async_database.aggregate(pipeline, options.into()))
             .map(Cursor::new)
     }
 
@@ -272,11 +280,44 @@ impl Database {
     ) -> Result<SessionCursor<Document>> {
         let pipeline: Vec<Document> = pipeline.into_iter().collect();
         RUNTIME
-            .block_on(self.async_database.aggregate_with_session(
+            .block_on_sync(self.async_database.aggregate_with_session(
                 pipeline,
                 options.into(),
                 &mut session.async_client_session,
             ))
             .map(SessionCursor::new)
     }
+
+    /// Gets the level at which the database profiler is currently collecting data.
+    ///
+    /// See the MongoDB [manual](https://docs.mongodb.com/manual/reference/command/profile/) for
+    /// more information on the profiler.
+    pub fn profiling_level(&self) -> Result<ProfilingLevel> {
+        RUNTIME.block_on_sync(self.async_database.profiling_level())
+    }
+
+    /// Sets the level at which the database profiler collects data, along with the threshold and
+    /// sample rate for slow operations.
+    ///
+    /// This command is always sent to the primary and does not use the database's default write
+    /// concern.
+    pub fn set_profiling_level(
+        &self,
+        level: ProfilingLevel,
+        options: impl Into<Option<SetProfilingLevelOptions>>,
+    ) -> Result<()> {
+        RUNTIME.block_on_sync(
+            self.async_database
+                .set_profiling_level(level, options.into()),
+        )
+    }
+
+    /// Queries the `system.profile` collection of the database, which contains the data collected
+    /// by the profiler. The cursor will yield a document for each profiled operation matching
+    /// `filter`.
+    pub fn profiling_info(&self, filter: impl Into<Option<Document>>) -> Result<Cursor<Document>> {
+        RUNTIME
+            .block_on_sync(self.async_database.profiling_info(filter.into()))
+            .map(Cursor::new)
+    }
 }
@@ -4,8 +4,10 @@ use pretty_assertions::assert_eq;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tokio::sync::RwLockReadGuard;
 
+use futures::stream::TryStreamExt;
+
 use crate::{
-    bson::{doc, Document},
+    bson::{doc, Bson, Document},
     error::Result,
     options::{
         Acknowledgment,
@@ -18,6 +20,7 @@ use crate::{
     },
     sync::{Client, Collection},
     test::{TestClient as AsyncTestClient, CLIENT_OPTIONS, LOCK},
+    Client as AsyncClient,
     RUNTIME,
 };
 
@@ -29,7 +32,7 @@ fn init_db_and_coll(client: &Client, db_name: &str, coll_name: &str) -> Collecti
 
 fn init_db_and_typed_coll<T>(client: &Client, db_name: &str, coll_name: &str) -> Collection<T>
 where
-    T: Serialize + DeserializeOwned + Unpin + Debug,
+    T: Serialize + DeserializeOwned + Debug,
 {
     let coll = client.database(db_name).collection(coll_name);
     coll.drop(None).unwrap();
@@ -173,6 +176,169 @@ fn collection() {
     assert_eq!(coll.write_concern(), Some(&wc));
 }
 
+#[test]
+#[function_name::named]
+fn cursor_collect_with_limit() {
+    let _guard: RwLockReadGuard<()> = RUNTIME.block_on(async { LOCK.run_concurrently().await });
+
+    let options = CLIENT_OPTIONS.clone();
+    let client = Client::with_options(options).expect("client creation should succeed");
+    let coll = init_db_and_coll(&client, function_name!(), function_name!());
+
+    coll.insert_many((0..5).map(|i| doc! { "_id": i }), None)
+        .expect("insert should succeed");
+
+    let mut cursor = coll.find(None, None).expect("find should succeed");
+    let result = cursor
+        .collect_with_limit(3, None)
+        .expect("collect_with_limit should succeed");
+    assert!(result.truncated);
+    assert_eq!(
+        result.documents,
+        vec![doc! { "_id": 0 }, doc! { "_id": 1 }, doc! { "_id": 2 }]
+    );
+
+    let results = cursor
+        .collect::<Result<Vec<Document>>>()
+        .expect("cursor iteration should succeed");
+    assert_eq!(results, vec![doc! { "_id": 3 }, doc! { "_id": 4 }]);
+}
+
+#[test]
+#[function_name::named]
+fn change_stream() {
+    let _guard: RwLockReadGuard<()> = RUNTIME.block_on(async { LOCK.run_concurrently().await });
+
+    let should_skip = RUNTIME.block_on(async { AsyncTestClient::new().await.is_standalone() });
+    if should_skip {
+        println!("skipping {} due to standalone topology", function_name!());
+        return;
+    }
+
+    let options = CLIENT_OPTIONS.clone();
+    let client = Client::with_options(options).expect("client creation should succeed");
+    let coll = init_db_and_coll(&client, function_name!(), function_name!());
+
+    let mut stream = coll.watch(None, None).expect("watch should succeed");
+    assert!(stream
+        .next_if_any()
+        .expect("next_if_any should succeed")
+        .is_none());
+
+    coll.insert_one(doc! { "_id": 1 }, None)
+        .expect("insert should succeed");
+    let event = stream
+        .next()
+        .expect("stream should not end")
+        .expect("event should be returned");
+    assert_eq!(event.document_key, Some(doc! { "_id": 1 }));
+    assert!(stream.resume_token().is_some());
+}
+
+/// Runs the same sequence of operations through the sync and async APIs and checks that they
+/// produce the same results.
+#[test]
+#[function_name::named]
+fn async_parity() {
+    let _guard: RwLockReadGuard<()> = RUNTIME.block_on(async { LOCK.run_concurrently().await });
+
+    let docs: Vec<Document> = (0..6).map(|i| doc! { "_id": i, "x": i % 3 }).collect();
+    let db_name = |api: &str| format!("{}_{}", function_name!(), api);
+
+    let sync_results = {
+        let client = Client::with_options(CLIENT_OPTIONS.clone()).unwrap();
+        client.warm_up(None).expect("warm up should succeed");
+
+        let db = client.database(&db_name("sync"));
+        let coll = init_db_and_coll(&client, db.name(), function_name!());
+        coll.insert_many(docs.clone(), None).unwrap();
+
+        let results = vec![
+            Bson::Int64(coll.estimated_document_count(None).unwrap() as i64),
+            Bson::Int64(coll.count_documents(doc! { "x": 0 }, None).unwrap() as i64),
+            Bson::Array(coll.distinct("x", None, None).unwrap()),
+            coll.find_one_and_update(doc! { "_id": 0 }, doc! { "$set": { "x": 10 } }, None)
+                .unwrap()
+                .value
+                .into(),
+            coll.find_one_and_replace(doc! { "_id": 1 }, doc! { "x": 20 }, None)
+                .unwrap()
+                .value
+                .into(),
+            coll.find_one_and_delete(doc! { "_id": 2 }, None)
+                .unwrap()
+                .value
+                .into(),
+            Bson::Array(
+                coll.find(None, None)
+                    .unwrap()
+                    .map(|doc| doc.map(Bson::Document))
+                    .collect::<Result<Vec<_>>>()
+                    .unwrap(),
+            ),
+        ];
+
+        db.drop(None).unwrap();
+        assert!(!client
+            .list_database_names(None, None)
+            .unwrap()
+            .contains(&db.name().to_string()));
+
+        results
+    };
+
+    let async_results = RUNTIME.block_on(async {
+        let client = AsyncClient::with_options(CLIENT_OPTIONS.clone()).unwrap();
+        client.warm_up(None).await.expect("warm up should succeed");
+
+        let db = client.database(&db_name("async"));
+        let coll = db.collection::<Document>(function_name!());
+        coll.drop(None).await.unwrap();
+        coll.insert_many(docs.clone(), None).await.unwrap();
+
+        let results = vec![
+            Bson::Int64(coll.estimated_document_count(None).await.unwrap() as i64),
+            Bson::Int64(coll.count_documents(doc! { "x": 0 }, None).await.unwrap() as i64),
+            Bson::Array(coll.distinct("x", None, None).await.unwrap()),
+            coll.find_one_and_update(doc! { "_id": 0 }, doc! { "$set": { "x": 10 } }, None)
+                .await
+                .unwrap()
+                .value
+                .into(),
+            coll.find_one_and_replace(doc! { "_id": 1 }, doc! { "x": 20 }, None)
+                .await
+                .unwrap()
+                .value
+                .into(),
+            coll.find_one_and_delete(doc! { "_id": 2 }, None)
+                .await
+                .unwrap()
+                .value
+                .into(),
+            Bson::Array(
+                coll.find(None, None)
+                    .await
+                    .unwrap()
+                    .map_ok(Bson::Document)
+                    .try_collect()
+                    .await
+                    .unwrap(),
+            ),
+        ];
+
+        db.drop(None).await.unwrap();
+        assert!(!client
+            .list_database_names(None, None)
+            .await
+            .unwrap()
+            .contains(&db.name().to_string()));
+
+        results
+    });
+
+    assert_eq!(sync_results, async_results);
+}
+
 #[test]
 #[function_name::named]
 fn typed_collection() {
@@ -202,8 +368,8 @@ fn transactions() {
 
     let should_skip = RUNTIME.block_on(async {
         let test_client = AsyncTestClient::new().await;
-        // TODO RUST-122: Unskip this test on sharded clusters
-        !test_client.is_replica_set() || test_client.server_version_lt(4, 0)
+        !(test_client.is_replica_set() && test_client.server_version_gte(4, 0)
+            || test_client.is_sharded() && test_client.server_version_gte(4, 2))
     });
     if should_skip {
         return;
@@ -266,3 +432,78 @@ fn collection_generic_bounds() {
         .collection(function_name!());
     let _result = coll.insert_one(Bar {}, None);
 }
+
+#[test]
+#[function_name::named]
+fn from_async_client() {
+    let _guard: RwLockReadGuard<()> = RUNTIME.block_on(async { LOCK.run_concurrently().await });
+
+    let async_client = AsyncClient::with_options(CLIENT_OPTIONS.clone()).unwrap();
+    let async_coll = async_client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+    RUNTIME.block_on(async_coll.drop(None)).unwrap();
+
+    let client = Client::from_async(async_client.clone());
+    let coll = client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+
+    // use the same client from a thread through the sync API and from tasks through the async one.
+    let thread = std::thread::spawn(move || {
+        for i in 0..10 {
+            coll.insert_one(doc! { "sync": i }, None).unwrap();
+        }
+        coll
+    });
+    let tasks: Vec<_> = (0..10)
+        .map(|i| {
+            let async_coll = async_coll.clone();
+            RUNTIME
+                .spawn(async move { async_coll.insert_one(doc! { "async": i }, None).await })
+                .unwrap()
+        })
+        .collect();
+    for task in tasks {
+        RUNTIME.block_on(task).unwrap();
+    }
+    let coll = thread.join().unwrap();
+
+    assert_eq!(coll.count_documents(None, None).unwrap(), 20);
+    assert_eq!(
+        Collection::from_async(async_coll.clone())
+            .count_documents(doc! { "async": { "$exists": true } }, None)
+            .unwrap(),
+        10
+    );
+
+    // blocking inside a task could deadlock the runtime, so it is rejected instead.
+    let result = RUNTIME.block_on(async move { coll.count_documents(None, None) });
+    assert!(result.is_err());
+}
+
+#[test]
+fn sync_api_in_async_contexts() {
+    let client = Client::with_options(CLIENT_OPTIONS.clone()).unwrap();
+    let coll = client
+        .database("sync_api_in_async_contexts")
+        .collection::<Document>("sync_api_in_async_contexts");
+
+    RUNTIME.block_on(async move {
+        // these only read the driver's current view of the cluster, so they don't block.
+        let _ = client.topology_type();
+        let _ = client.servers();
+        let _ = client.server_capabilities();
+
+        // none of these reach the server, since the guard rejects them before any I/O happens.
+        assert!(ClientOptions::parse("mongodb://localhost:27017").is_err());
+        assert!(coll.count_documents(None, None).is_err());
+        assert!(coll.find(None, None).is_err());
+
+        // blocking threads aren't running a task, so they can use the sync API.
+        let options =
+            async_std::task::spawn_blocking(|| ClientOptions::parse("mongodb://localhost:27017"))
+                .await;
+        assert!(options.is_ok());
+    });
+}
@@ -0,0 +1,333 @@
+use std::time::{Duration, Instant};
+
+use futures::stream::StreamExt;
+use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{
+    bson::{doc, Document},
+    change_stream::event::OperationType,
+    error::ErrorKind,
+    options::{ChangeStreamOptions, FullDocumentBeforeChangeType},
+    test::{
+        util::{EventClient, TestClient},
+        FailCommandOptions,
+        FailPoint,
+        FailPointMode,
+        LOCK,
+    },
+};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn resume_after_get_more_error() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = EventClient::new().await;
+    if client.is_standalone()
+        || client.server_version_lt(4, 2)
+        || !client.supports_fail_command().await
+    {
+        println!(
+            "skipping {} due to unsupported topology or server version",
+            function_name!()
+        );
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let mut stream = coll.watch(None, None).await.unwrap();
+
+    coll.insert_one(doc! { "_id": 1 }, None).await.unwrap();
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(event.document_key, Some(doc! { "_id": 1 }));
+    let token = stream.resume_token().unwrap();
+
+    let options = FailCommandOptions::builder().error_code(43).build();
+    let failpoint = FailPoint::fail_command(&["getMore"], FailPointMode::Times(1), options);
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    coll.insert_one(doc! { "_id": 2 }, None).await.unwrap();
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(event.document_key, Some(doc! { "_id": 2 }));
+
+    let aggregates = client.get_command_started_events(&["aggregate"]);
+    assert_eq!(aggregates.len(), 2);
+    let stage = aggregates[1].command.get_array("pipeline").unwrap()[0]
+        .as_document()
+        .unwrap()
+        .get_document("$changeStream")
+        .unwrap();
+    assert_eq!(stage.get("resumeAfter"), Some(&token.0));
+    assert!(!stage.contains_key("startAfter"));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn reopen_with_resume_after() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.is_standalone() {
+        println!("skipping {} due to standalone topology", function_name!());
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let mut stream = coll.watch(None, None).await.unwrap();
+    coll.insert_many((1..=5).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    for i in 1..=2 {
+        let event = stream.next().await.unwrap().unwrap();
+        assert_eq!(event.document_key, Some(doc! { "_id": i }));
+    }
+    let token = stream.resume_token().unwrap();
+    drop(stream);
+
+    let options = ChangeStreamOptions::builder().resume_after(token).build();
+    let mut stream = coll.watch(None, options).await.unwrap();
+    for i in 3..=5 {
+        let event = stream.next().await.unwrap().unwrap();
+        assert_eq!(event.document_key, Some(doc! { "_id": i }));
+    }
+
+    let options = ChangeStreamOptions::builder()
+        .resume_after(stream.resume_token().unwrap())
+        .start_after(stream.resume_token().unwrap())
+        .build();
+    let error = coll.watch(None, options).await.unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+}
+
+/// A stream interrupted before the server has provided a resume token resumes from the time it
+/// was opened at. Servers 4.0.7 and newer report a token with every batch, in which case the
+/// stream resumes from that instead.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn resume_before_first_event_uses_operation_time() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = EventClient::new().await;
+    if client.is_standalone() || !client.supports_fail_command().await {
+        println!(
+            "skipping {} due to unsupported topology or server version",
+            function_name!()
+        );
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let mut stream = coll.watch(None, None).await.unwrap();
+    let (_, opened) = client.get_successful_command_execution("aggregate");
+
+    let options = FailCommandOptions::builder().error_code(43).build();
+    let failpoint = FailPoint::fail_command(&["getMore"], FailPointMode::Times(1), options);
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    coll.insert_one(doc! { "_id": 1 }, None).await.unwrap();
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(event.document_key, Some(doc! { "_id": 1 }));
+
+    let resumed = client
+        .get_command_started_events(&["aggregate"])
+        .pop()
+        .unwrap();
+    let stage = resumed.command.get_array("pipeline").unwrap()[0]
+        .as_document()
+        .unwrap()
+        .get_document("$changeStream")
+        .unwrap();
+    match opened
+        .reply
+        .get_document("cursor")
+        .unwrap()
+        .get("postBatchResumeToken")
+    {
+        Some(token) => {
+            assert_eq!(stage.get("resumeAfter"), Some(token));
+            assert!(!stage.contains_key("startAtOperationTime"));
+        }
+        None => {
+            assert_eq!(
+                stage.get("startAtOperationTime"),
+                opened.reply.get("operationTime")
+            );
+            assert!(!stage.contains_key("resumeAfter"));
+        }
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn start_after_invalidate() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.is_standalone() || client.server_version_lt(6, 0) {
+        println!(
+            "skipping {} due to unsupported topology or server version",
+            function_name!()
+        );
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_one(doc! { "_id": 0 }, None).await.unwrap();
+
+    let options = ChangeStreamOptions::builder()
+        .show_expanded_events(true)
+        .build();
+    let mut stream = coll.watch(None, options).await.unwrap();
+    coll.drop(None).await.unwrap();
+
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(event.operation_type, OperationType::Drop);
+    let invalidate = stream.next().await.unwrap().unwrap();
+    assert_eq!(invalidate.operation_type, OperationType::Invalidate);
+    assert_eq!(stream.resume_token(), Some(invalidate.id.clone()));
+    assert!(stream.next().await.is_none());
+
+    let options = ChangeStreamOptions::builder()
+        .start_after(invalidate.id)
+        .show_expanded_events(true)
+        .build();
+    let mut stream = coll.watch(None, options).await.unwrap();
+
+    let db = client.database(function_name!());
+    db.create_collection(function_name!(), None).await.unwrap();
+    db.collection::<Document>(function_name!())
+        .insert_one(doc! { "_id": 1 }, None)
+        .await
+        .unwrap();
+
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(
+        event.operation_type,
+        OperationType::Other("create".to_string())
+    );
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(event.operation_type, OperationType::Insert);
+    assert_eq!(event.document_key, Some(doc! { "_id": 1 }));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn update_description_and_pre_image() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.is_standalone() {
+        println!("skipping {} due to standalone topology", function_name!());
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_one(doc! { "_id": 1, "x": 1, "y": 1 }, None)
+        .await
+        .unwrap();
+
+    let mut stream = coll.watch(None, None).await.unwrap();
+    coll.update_one(
+        doc! { "_id": 1 },
+        doc! { "$set": { "x": 2 }, "$unset": { "y": "" } },
+        None,
+    )
+    .await
+    .unwrap();
+
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(event.operation_type, OperationType::Update);
+    let description = event.update_description.unwrap();
+    assert_eq!(description.updated_fields, doc! { "x": 2 });
+    assert_eq!(description.removed_fields, vec!["y".to_string()]);
+    assert_eq!(event.full_document_before_change, None);
+
+    if client.server_version_lt(6, 0) {
+        return;
+    }
+
+    // Pre-images are only recorded for collections that opt into them.
+    client
+        .database(function_name!())
+        .run_command(
+            doc! {
+                "collMod": function_name!(),
+                "changeStreamPreAndPostImages": { "enabled": true },
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let options = ChangeStreamOptions::builder()
+        .full_document_before_change(FullDocumentBeforeChangeType::Required)
+        .build();
+    let mut stream = coll.watch(None, options).await.unwrap();
+    coll.update_one(doc! { "_id": 1 }, doc! { "$set": { "x": 3 } }, None)
+        .await
+        .unwrap();
+
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(
+        event.full_document_before_change,
+        Some(doc! { "_id": 1, "x": 2 })
+    );
+    assert_eq!(
+        event.update_description.unwrap().updated_fields,
+        doc! { "x": 3 }
+    );
+}
+
+/// `next_if_any` returns without waiting out `max_await_time` once the server has reported that
+/// there are no new events, and picks up events that arrive afterwards.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn next_if_any_on_idle_stream() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.is_standalone() {
+        println!("skipping {} due to standalone topology", function_name!());
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let options = ChangeStreamOptions::builder()
+        .max_await_time(Duration::from_secs(5))
+        .build();
+    let mut stream = coll.watch(None, options).await.unwrap();
+
+    // The initial batch of a new stream is empty, so this doesn't contact the server.
+    let start = Instant::now();
+    assert!(stream.next_if_any().await.unwrap().is_none());
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    coll.insert_one(doc! { "_id": 1 }, None).await.unwrap();
+    let mut event = None;
+    for _ in 0..3 {
+        event = stream.next_if_any().await.unwrap();
+        if event.is_some() {
+            break;
+        }
+    }
+    assert_eq!(event.unwrap().document_key, Some(doc! { "_id": 1 }));
+}
@@ -1,4 +1,9 @@
-use std::{borrow::Cow, collections::HashMap, time::Duration};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use bson::Document;
 use serde::Deserialize;
@@ -6,11 +11,19 @@ use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 use crate::{
     bson::{doc, Bson},
+    bson_util,
     error::{CommandError, Error, ErrorKind},
+    event::sdam::{SdamEventHandler, ServerHeartbeatStartedEvent},
     options::{AuthMechanism, ClientOptions, Credential, ListDatabasesOptions, ServerAddress},
     selection_criteria::{ReadPreference, ReadPreferenceOptions, SelectionCriteria},
-    test::{util::TestClient, CLIENT_OPTIONS, LOCK},
+    test::{
+        util::{EventClient, FailCommandOptions, FailPoint, FailPointMode, TestClient},
+        CLIENT_OPTIONS,
+        LOCK,
+    },
     Client,
+    ServerType,
+    TopologyType,
     RUNTIME,
 };
 
@@ -103,6 +116,76 @@ async fn connection_drop_during_read() {
         .is_some());
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn topology_type_after_discovery() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let test_client = TestClient::new().await;
+    if !test_client.is_replica_set() {
+        return;
+    }
+
+    let client = Client::with_options(CLIENT_OPTIONS.clone()).unwrap();
+
+    // Selecting a server for the command requires the primary to have been discovered.
+    client
+        .database("admin")
+        .run_command(doc! { "ping": 1 }, None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        client.topology_type().await,
+        TopologyType::ReplicaSetWithPrimary
+    );
+    let servers = client.servers().await;
+    assert!(servers
+        .iter()
+        .any(|server| server.server_type() == ServerType::RsPrimary));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn select_each_member_by_host() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set() {
+        println!(
+            "skipping {} due to not running on a replica set",
+            function_name!()
+        );
+        return;
+    }
+
+    let members: Vec<ServerAddress> = client
+        .servers()
+        .await
+        .into_iter()
+        .filter(|server| {
+            matches!(
+                server.server_type(),
+                ServerType::RsPrimary | ServerType::RsSecondary
+            )
+        })
+        .map(|server| server.address().clone())
+        .collect();
+    assert!(!members.is_empty());
+
+    for address in members {
+        client
+            .database("admin")
+            .run_command(doc! { "ping": 1 }, SelectionCriteria::host(address.clone()))
+            .await
+            .unwrap();
+
+        let events = client.get_command_started_events(&["ping"]);
+        assert_eq!(events.last().unwrap().connection.address, address);
+    }
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn server_selection_timeout_message() {
@@ -188,6 +271,43 @@ async fn list_databases() {
     }
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn list_databases_typed_matches_raw() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    db.collection("foo")
+        .insert_one(doc! { "x": 1 }, None)
+        .await
+        .unwrap();
+
+    let filter = doc! { "name": function_name!() };
+    let typed = client.list_databases(filter.clone(), None).await.unwrap();
+    let raw = client.list_databases_raw(filter, None).await.unwrap();
+    assert_eq!(typed.len(), 1);
+    assert_eq!(raw.len(), 1);
+
+    let spec = &typed[0];
+    assert_eq!(spec.name, function_name!());
+    assert!(spec.size_on_disk > 0);
+    assert!(!spec.empty);
+    assert_eq!(spec.shards.is_some(), client.is_sharded());
+
+    let raw = &raw[0];
+    assert_eq!(raw.get_str("name"), Ok(function_name!()));
+    assert_eq!(
+        bson_util::get_u64(raw.get("sizeOnDisk").unwrap()),
+        Some(spec.size_on_disk)
+    );
+    assert_eq!(raw.get_bool("empty"), Ok(spec.empty));
+    assert_eq!(raw.get_document("shards").ok(), spec.shards.as_ref());
+
+    db.drop(None).await.unwrap();
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -651,3 +771,172 @@ async fn plain_auth() {
         }
     );
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn app_name_scoped_fail_point() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let fail_point = FailPoint::fail_command(
+        &["find"],
+        FailPointMode::AlwaysOn,
+        FailCommandOptions::builder()
+            .app_name(function_name!().to_string())
+            .error_code(2)
+            .build(),
+    );
+
+    // use a single mongos so that both clients talk to the server the fail point is set on.
+    let mut targeted_options = CLIENT_OPTIONS.clone();
+    targeted_options.app_name = Some(function_name!().to_string());
+    targeted_options.hosts.drain(1..);
+    let mut other_options = CLIENT_OPTIONS.clone();
+    other_options.hosts.drain(1..);
+
+    let targeted_client = TestClient::with_options(Some(targeted_options)).await;
+    if !targeted_client.supports_fail_command().await {
+        println!("skipping app_name_scoped_fail_point due to failCommand not being supported");
+        return;
+    }
+    let other_client = TestClient::with_options(Some(other_options)).await;
+
+    let _fp_guard = targeted_client
+        .enable_failpoint(fail_point, None)
+        .await
+        .unwrap();
+
+    other_client
+        .database(function_name!())
+        .collection::<Document>(function_name!())
+        .find_one(None, None)
+        .await
+        .expect("client with a different app name should not hit the fail point");
+
+    let error = targeted_client
+        .database(function_name!())
+        .collection::<Document>(function_name!())
+        .find_one(None, None)
+        .await
+        .expect_err("client with the fail point's app name should hit it");
+    assert_eq!(error.code(), Some(2));
+}
+
+/// Records whether each heartbeat that was started was awaited.
+#[derive(Clone, Default)]
+struct HeartbeatRecorder {
+    awaited: Arc<Mutex<Vec<bool>>>,
+}
+
+impl SdamEventHandler for HeartbeatRecorder {
+    fn handle_server_heartbeat_started_event(&self, event: ServerHeartbeatStartedEvent) {
+        self.awaited.lock().unwrap().push(event.awaited);
+    }
+}
+
+async fn record_heartbeats(disable_streaming_monitoring: bool) -> Vec<bool> {
+    let recorder = HeartbeatRecorder::default();
+    let mut options = CLIENT_OPTIONS.clone();
+    options.heartbeat_freq = Some(Duration::from_millis(500));
+    options.disable_streaming_monitoring = Some(disable_streaming_monitoring);
+    options.sdam_event_handler = Some(Arc::new(recorder.clone()));
+
+    let client = Client::with_options(options).unwrap();
+    client
+        .database("admin")
+        .run_command(doc! { "ping": 1 }, None)
+        .await
+        .unwrap();
+    RUNTIME.delay_for(Duration::from_secs(2)).await;
+    drop(client);
+
+    let awaited = recorder.awaited.lock().unwrap().clone();
+    awaited
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn disable_streaming_monitoring() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let awaited = record_heartbeats(true).await;
+    assert!(awaited.len() > 1, "expected repeated heartbeats");
+    assert!(awaited.iter().all(|awaited| !awaited));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn streaming_monitoring() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.server_version_lt(4, 4) {
+        println!("skipping streaming_monitoring due to server version");
+        return;
+    }
+
+    let awaited = record_heartbeats(false).await;
+    assert!(awaited.iter().any(|awaited| *awaited));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn application_error_interrupts_streaming_check() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let setup_client = TestClient::new().await;
+    if setup_client.server_version_lt(4, 4) {
+        println!("skipping application_error_interrupts_streaming_check due to server version");
+        return;
+    }
+
+    let recorder = HeartbeatRecorder::default();
+    let mut options = CLIENT_OPTIONS.clone();
+    options.hosts.drain(1..);
+    options.app_name = Some(function_name!().to_string());
+    options.heartbeat_freq = Some(Duration::from_secs(10));
+    options.retry_reads = Some(false);
+    options.sdam_event_handler = Some(Arc::new(recorder.clone()));
+    let client = Client::with_options(options).unwrap();
+    let coll = client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+    coll.find_one(None, None).await.unwrap();
+
+    let start = std::time::Instant::now();
+    while !recorder.awaited.lock().unwrap().contains(&true) {
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "timed out waiting for an awaited heartbeat"
+        );
+        RUNTIME.delay_for(Duration::from_millis(50)).await;
+    }
+
+    let fail_point = FailPoint::fail_command(
+        &["find"],
+        FailPointMode::Times(1),
+        FailCommandOptions::builder()
+            .app_name(function_name!().to_string())
+            .close_connection(true)
+            .build(),
+    );
+    let _fp_guard = setup_client
+        .enable_failpoint(fail_point, None)
+        .await
+        .unwrap();
+
+    let heartbeats_before_error = recorder.awaited.lock().unwrap().len();
+    coll.find_one(None, None)
+        .await
+        .expect_err("find should hit the fail point");
+
+    // The awaited check wouldn't otherwise complete until the heartbeat frequency elapses.
+    RUNTIME.delay_for(Duration::from_secs(1)).await;
+    let awaited = recorder.awaited.lock().unwrap().clone();
+    assert!(
+        awaited[heartbeats_before_error..].contains(&false),
+        "expected the server to be checked again over a new connection, got {:?}",
+        awaited
+    );
+}
@@ -12,26 +12,45 @@ use crate::{
     options::{
         Acknowledgment,
         AggregateOptions,
+        ClientOptions,
+        Collation,
+        CollationStrength,
         CollectionOptions,
+        CountOptions,
+        CreateCollectionOptions,
         DeleteOptions,
+        DistinctOptions,
         DropCollectionOptions,
+        EstimatedDocumentCountOptions,
         FindOneAndDeleteOptions,
+        FindOneAndUpdateOptions,
         FindOneOptions,
         FindOptions,
         Hint,
         InsertManyOptions,
+        InsertOneOptions,
+        MergeOptions,
+        MergeWhenMatched,
+        MergeWhenNotMatched,
         ReadConcern,
         ReadPreference,
+        ReplaceOptions,
+        ReturnDocument,
         SelectionCriteria,
         UpdateOptions,
         WriteConcern,
     },
-    results::DeleteResult,
+    results::{DeleteResult, FindOneAndModifyResult},
     test::{
-        util::{drop_collection, EventClient, TestClient},
+        util::{decimal128_one_and_a_half_and_one_tenth, drop_collection, EventClient, TestClient},
+        CommandEvent,
+        FailCommandOptions,
+        FailPoint,
+        FailPointMode,
         CLIENT_OPTIONS,
         LOCK,
     },
+    Client,
     Collection,
     RUNTIME,
 };
@@ -191,6 +210,53 @@ async fn update() {
     assert!(upsert_results.upserted_id.is_some());
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn operation_outcome() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let (result, outcome) = coll
+        .insert_one_with_outcome(doc! { "x": 1 }, None)
+        .await
+        .unwrap();
+    assert_eq!(outcome.attempts, 1);
+    assert!(!outcome.was_retried());
+
+    let (doc, outcome) = coll
+        .find_one_with_outcome(doc! { "_id": result.inserted_id }, None)
+        .await
+        .unwrap();
+    assert_eq!(doc.unwrap().get("x"), Some(&Bson::Int32(1)));
+    assert_eq!(outcome.attempts, 1);
+    assert!(!outcome.was_retried());
+
+    let (result, outcome) = coll
+        .insert_many_with_outcome(vec![doc! { "x": 2 }, doc! { "x": 3 }], None)
+        .await
+        .unwrap();
+    assert_eq!(result.inserted_ids.len(), 2);
+    assert!(!outcome.was_retried());
+
+    let (count, outcome) = coll.count_documents_with_outcome(None, None).await.unwrap();
+    assert_eq!(count, 3);
+    assert!(!outcome.was_retried());
+
+    let (cursor, outcome) = coll.find_with_outcome(None, None).await.unwrap();
+    assert_eq!(cursor.try_collect::<Vec<_>>().await.unwrap().len(), 3);
+    assert!(!outcome.was_retried());
+
+    let pipeline = vec![doc! { "$match": { "x": { "$gt": 1 } } }];
+    let (cursor, outcome) = coll.aggregate_with_outcome(pipeline, None).await.unwrap();
+    assert_eq!(cursor.try_collect::<Vec<_>>().await.unwrap().len(), 2);
+    assert!(!outcome.was_retried());
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -273,6 +339,112 @@ fn kill_cursors_sent(client: &EventClient) -> bool {
         .is_empty()
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn aggregate_to() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.server_version_lt(4, 2) {
+        println!("skipping {} due to server version < 4.2", function_name!());
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0i32..5).map(|n| doc! { "_id": n, "x": n }), None)
+        .await
+        .unwrap();
+
+    let target = client
+        .init_db_and_coll(function_name!(), &format!("{}_target", function_name!()))
+        .await;
+    target
+        .insert_one(doc! { "_id": 4, "x": 100 }, None)
+        .await
+        .unwrap();
+
+    let pipeline = vec![doc! { "$match": { "x": { "$gt": 1 } } }];
+    let options = MergeOptions::builder()
+        .when_matched(MergeWhenMatched::KeepExisting)
+        .when_not_matched(MergeWhenNotMatched::Insert)
+        .build();
+    coll.aggregate_to(target.namespace(), pipeline, options)
+        .await
+        .unwrap();
+
+    let docs: Vec<Document> = target
+        .find(None, FindOptions::builder().sort(doc! { "_id": 1 }).build())
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(
+        docs,
+        vec![
+            doc! { "_id": 2, "x": 2 },
+            doc! { "_id": 3, "x": 3 },
+            doc! { "_id": 4, "x": 100 },
+        ]
+    );
+
+    let pipeline = vec![doc! { "$out": "foo" }, doc! { "$match": {} }];
+    let error = coll
+        .aggregate_to(target.namespace(), pipeline, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn aggregate_batch_size_zero() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0i32..3).map(|n| doc! { "_id": n }), None)
+        .await
+        .unwrap();
+
+    let event_client = EventClient::new().await;
+    let coll = event_client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+
+    let options = AggregateOptions::builder().batch_size(0).build();
+    let mut cursor = coll
+        .aggregate(vec![doc! { "$sort": { "_id": 1 } }], options)
+        .await
+        .unwrap();
+
+    let (started, succeeded) = event_client.get_successful_command_execution("aggregate");
+    assert_eq!(
+        started.command.get_document("cursor"),
+        Ok(&doc! { "batchSize": 0 })
+    );
+    let first_batch = succeeded
+        .reply
+        .get_document("cursor")
+        .and_then(|cursor| cursor.get_array("firstBatch"))
+        .unwrap();
+    assert!(first_batch.is_empty());
+
+    // the first document has to come from a getMore, which shouldn't carry the batch size of 0.
+    assert_eq!(cursor.try_next().await.unwrap(), Some(doc! { "_id": 0 }));
+    let (get_more, _) = event_client.get_successful_command_execution("getMore");
+    assert!(!get_more.command.contains_key("batchSize"));
+
+    let rest: Vec<Document> = cursor.try_collect().await.unwrap();
+    assert_eq!(rest, vec![doc! { "_id": 1 }, doc! { "_id": 2 }]);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -311,6 +483,58 @@ async fn kill_cursors_on_drop() {
     assert!(kill_cursors_sent(&event_client));
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn find_comment_propagated_to_get_more_and_kill_cursors() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.server_version_lt(4, 4) {
+        println!(
+            "skipping find_comment_propagated_to_get_more_and_kill_cursors due to server version \
+             being less than 4.4"
+        );
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0..3).map(|i| doc! { "x": i }), None)
+        .await
+        .unwrap();
+
+    let event_client = EventClient::new().await;
+    let coll = event_client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+
+    let options = FindOptions::builder()
+        .batch_size(1)
+        .comment("cursor lifecycle".to_string())
+        .build();
+    let mut cursor = coll.find(None, options).await.unwrap();
+    cursor.next().await.unwrap().unwrap();
+    cursor.next().await.unwrap().unwrap();
+    std::mem::drop(cursor);
+
+    // wait for the killCursors spawned from `Cursor`'s `Drop` to be sent.
+    RUNTIME.delay_for(Duration::from_millis(250)).await;
+
+    let events = event_client.get_command_started_events(&["find", "getMore", "killCursors"]);
+    let names: Vec<_> = events.iter().map(|e| e.command_name.as_str()).collect();
+    assert_eq!(names, vec!["find", "getMore", "killCursors"]);
+    for event in events {
+        assert_eq!(
+            event.command.get_str("comment"),
+            Ok("cursor lifecycle"),
+            "{} should carry the comment",
+            event.command_name
+        );
+    }
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -530,6 +754,59 @@ async fn large_insert_ordered_with_errors() {
     }
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn insert_many_inserted_ids_with_error_in_second_batch() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    // Each document's _id is its index, except for a duplicate key that falls in the second batch
+    // once the input is split according to maxWriteBatchSize.
+    let max_write_batch_size = 100_000;
+    let duplicate_index = max_write_batch_size + 2;
+    let docs: Vec<Document> = (0..(max_write_batch_size + 5))
+        .map(|i| {
+            let id = if i == duplicate_index { 5 } else { i as i32 };
+            doc! { "_id": id }
+        })
+        .collect();
+
+    for ordered in [false, true].iter() {
+        coll.drop(None).await.unwrap();
+        let options = InsertManyOptions::builder().ordered(*ordered).build();
+
+        let failure = match *coll
+            .insert_many(&docs, options)
+            .await
+            .expect_err("should get error")
+            .kind
+        {
+            ErrorKind::BulkWrite(failure) => failure,
+            e => panic!("expected bulk write error, got {:?} instead", e),
+        };
+
+        let write_errors = failure.write_errors.expect("should have write errors");
+        assert_eq!(write_errors.len(), 1);
+        assert_eq!(write_errors[0].index, duplicate_index);
+
+        let expected_len = if *ordered {
+            duplicate_index
+        } else {
+            docs.len() - 1
+        };
+        assert_eq!(failure.inserted_ids.len(), expected_len);
+        assert!(!failure.inserted_ids.contains_key(&duplicate_index));
+        for (index, id) in failure.inserted_ids {
+            assert_eq!(id, Bson::Int32(index as i32));
+        }
+    }
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -595,88 +872,359 @@ async fn allow_disk_use_test(options: FindOptions, expected_value: Option<bool>)
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
-async fn ns_not_found_suppression() {
+async fn hint_serialization() {
     let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
 
-    let client = TestClient::new().await;
-    let coll = client.get_coll(function_name!(), function_name!());
-    coll.drop(None).await.expect("drop should not fail");
-    coll.drop(None).await.expect("drop should not fail");
-}
+    let event_client = EventClient::new().await;
+    if event_client.server_version_lt(4, 2) {
+        println!(
+            "skipping {} due to update hints not being supported",
+            function_name!()
+        );
+        return;
+    }
 
-async fn delete_hint_test(options: Option<DeleteOptions>, name: &str) {
-    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+    let coll = event_client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    event_client
+        .database(function_name!())
+        .run_command(
+            doc! {
+                "createIndexes": function_name!(),
+                "indexes": [{ "key": { "x": 1 }, "name": "x_1" }],
+            },
+            None,
+        )
+        .await
+        .expect("index creation should succeed");
 
-    let client = EventClient::new().await;
-    let coll = client.database(name).collection::<Document>(name);
-    let _: Result<DeleteResult> = coll.delete_many(doc! {}, options.clone()).await;
+    let hints = [Hint::from("x_1"), Hint::from(doc! { "x": 1 })];
+    for hint in hints.iter() {
+        let options = FindOptions::builder().hint(hint.clone()).build();
+        coll.find(None, options).await.expect("find should succeed");
 
-    let events = client.get_command_started_events(&["delete"]);
-    assert_eq!(events.len(), 1);
+        let options = UpdateOptions::builder().hint(hint.clone()).build();
+        coll.update_one(doc! {}, doc! { "$set": { "x": 1 } }, options)
+            .await
+            .expect("update should succeed");
+    }
 
-    let event_hint = events[0].command.get_array("deletes").unwrap()[0]
-        .as_document()
-        .unwrap()
-        .get("hint");
-    let expected_hint = match options {
-        Some(options) => options.hint.map(|hint| hint.to_bson()),
-        None => None,
-    };
-    assert_eq!(event_hint, expected_hint.as_ref());
-}
+    let expected: Vec<Bson> = hints.iter().map(Hint::to_bson).collect();
 
-#[cfg_attr(feature = "tokio-runtime", tokio::test)]
-#[cfg_attr(feature = "async-std-runtime", async_std::test)]
-#[function_name::named]
-async fn delete_hint_keys_specified() {
-    let options = DeleteOptions::builder().hint(Hint::Keys(doc! {})).build();
-    delete_hint_test(Some(options), function_name!()).await;
+    let find_hints: Vec<Bson> = event_client
+        .get_command_started_events(&["find"])
+        .into_iter()
+        .map(|event| event.command.get("hint").cloned().unwrap_or(Bson::Null))
+        .collect();
+    assert_eq!(find_hints, expected);
+
+    let update_hints: Vec<Bson> = event_client
+        .get_command_started_events(&["update"])
+        .into_iter()
+        .map(|event| {
+            event
+                .command
+                .get_array("updates")
+                .ok()
+                .and_then(|updates| updates.first())
+                .and_then(Bson::as_document)
+                .and_then(|update| update.get("hint"))
+                .cloned()
+                .unwrap_or(Bson::Null)
+        })
+        .collect();
+    assert_eq!(update_hints, expected);
+    assert_eq!(
+        expected,
+        vec![
+            Bson::String("x_1".to_string()),
+            Bson::Document(doc! { "x": 1 })
+        ]
+    );
 }
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
-async fn delete_hint_string_specified() {
-    let options = DeleteOptions::builder()
-        .hint(Hint::Name(String::new()))
+async fn find_with_hint() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let event_client = EventClient::new().await;
+    let coll = event_client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0..3).map(|x| doc! { "x": x }), None)
+        .await
+        .unwrap();
+    event_client
+        .database(function_name!())
+        .run_command(
+            doc! {
+                "createIndexes": function_name!(),
+                "indexes": [{ "key": { "x": 1 }, "name": "x_1" }],
+            },
+            None,
+        )
+        .await
+        .expect("index creation should succeed");
+
+    // A hint already present in the options is replaced, but the other options are kept.
+    let options = FindOptions::builder()
+        .hint(Hint::Keys(doc! { "_id": 1 }))
+        .batch_size(2)
         .build();
-    delete_hint_test(Some(options), function_name!()).await;
+    let docs: Vec<Document> = coll
+        .find_with_hint(doc! { "x": { "$gte": 1 } }, "x_1", options)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(docs.len(), 2);
+
+    let doc = coll
+        .find_one_with_hint(None, doc! { "x": 1 }, None)
+        .await
+        .unwrap();
+    assert!(doc.is_some());
+
+    let commands: Vec<Document> = event_client
+        .get_command_started_events(&["find"])
+        .into_iter()
+        .map(|event| event.command)
+        .collect();
+    assert_eq!(commands.len(), 2);
+    assert_eq!(commands[0].get_str("hint"), Ok("x_1"));
+    assert_eq!(commands[0].get_i32("batchSize"), Ok(2));
+    assert_eq!(commands[1].get_document("hint"), Ok(&doc! { "x": 1 }));
 }
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
-async fn delete_hint_not_specified() {
-    delete_hint_test(None, function_name!()).await;
-}
-
-async fn find_one_and_delete_hint_test(options: Option<FindOneAndDeleteOptions>, name: &str) {
+async fn replace_one_hint() {
     let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
     let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let db = client.database(function_name!());
+    db.run_command(
+        doc! {
+            "createIndexes": function_name!(),
+            "indexes": [
+                { "key": { "x": 1 }, "name": "x_1" },
+                { "key": { "x": 1, "y": 1 }, "name": "x_1_y_1" },
+            ],
+        },
+        None,
+    )
+    .await
+    .expect("index creation should succeed");
+    coll.insert_many(vec![doc! { "x": 1, "y": 1 }, doc! { "x": 2, "y": 2 }], None)
+        .await
+        .unwrap();
 
-    let req = VersionReq::parse(">= 4.2").unwrap();
-    if options.is_some() && !req.matches(&client.server_version) {
+    let options = ReplaceOptions::builder()
+        .hint(Hint::Name("x_1_y_1".to_string()))
+        .build();
+    let result = coll
+        .replace_one(doc! { "x": 1 }, doc! { "x": 1, "y": 3 }, options)
+        .await;
+
+    if client.server_version_lt(4, 2) {
+        let error = result.expect_err("replace with a hint should fail");
+        assert!(matches!(*error.kind, ErrorKind::InvalidArgument { .. }));
+        assert!(client.get_command_started_events(&["update"]).is_empty());
         return;
     }
+    assert_eq!(result.unwrap().modified_count, 1);
 
-    let coll = client.database(name).collection(name);
-    let _: Result<Option<Document>> = coll.find_one_and_delete(doc! {}, options.clone()).await;
+    let update = client
+        .get_command_started_events(&["update"])
+        .pop()
+        .unwrap()
+        .command
+        .get_array("updates")
+        .unwrap()[0]
+        .as_document()
+        .unwrap()
+        .clone();
+    assert_eq!(update.get_str("hint"), Ok("x_1_y_1"));
 
-    let events = client.get_command_started_events(&["findAndModify"]);
-    assert_eq!(events.len(), 1);
+    // The hinted index is used even though the filter only covers a prefix of it.
+    let explained = db
+        .run_command(
+            doc! {
+                "explain": {
+                    "update": function_name!(),
+                    "updates": [update],
+                },
+                "verbosity": "queryPlanner",
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let winning_plan = explained
+        .get_document("queryPlanner")
+        .and_then(|planner| planner.get_document("winningPlan"))
+        .unwrap();
+    assert_eq!(index_names(winning_plan), vec!["x_1_y_1".to_string()]);
+}
 
-    let event_hint = events[0].command.get("hint").cloned();
-    let expected_hint = options.and_then(|options| options.hint.map(|hint| hint.to_bson()));
-    assert_eq!(event_hint, expected_hint);
+/// Collects the names of the indexes scanned by the stages of an explained query plan.
+fn index_names(plan: &Document) -> Vec<String> {
+    let mut names: Vec<String> = plan
+        .get_str("indexName")
+        .ok()
+        .map(String::from)
+        .into_iter()
+        .collect();
+    for (key, value) in plan {
+        match value {
+            Bson::Document(stage) if key != "keyPattern" => names.extend(index_names(stage)),
+            Bson::Array(stages) => {
+                for stage in stages.iter().filter_map(Bson::as_document) {
+                    names.extend(index_names(stage));
+                }
+            }
+            _ => {}
+        }
+    }
+    names
 }
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
-async fn find_one_and_delete_hint_keys_specified() {
-    let options = FindOneAndDeleteOptions::builder()
-        .hint(Hint::Keys(doc! {}))
-        .build();
+async fn ns_not_found_suppression() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client.get_coll(function_name!(), function_name!());
+    coll.drop(None).await.expect("drop should not fail");
+    coll.drop(None).await.expect("drop should not fail");
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn drop_encrypted_fields() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    let state_collections = [
+        "custom_esc".to_string(),
+        format!("enxcol_.{}.ecc", function_name!()),
+        format!("enxcol_.{}.ecoc", function_name!()),
+    ];
+    for name in state_collections
+        .iter()
+        .map(String::as_str)
+        .chain(Some(function_name!()))
+    {
+        db.collection::<Document>(name)
+            .insert_one(doc! {}, None)
+            .await
+            .unwrap();
+    }
+
+    let options = DropCollectionOptions::builder()
+        .encrypted_fields(doc! { "escCollection": "custom_esc", "fields": [] })
+        .build();
+    db.collection::<Document>(function_name!())
+        .drop(options)
+        .await
+        .unwrap();
+
+    let names = db.list_collection_names(None).await.unwrap();
+    for name in state_collections
+        .iter()
+        .map(String::as_str)
+        .chain(Some(function_name!()))
+    {
+        assert!(!names.iter().any(|n| n == name), "{} wasn't dropped", name);
+    }
+}
+
+async fn delete_hint_test(options: Option<DeleteOptions>, name: &str) {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client.database(name).collection::<Document>(name);
+    let _: Result<DeleteResult> = coll.delete_many(doc! {}, options.clone()).await;
+
+    let events = client.get_command_started_events(&["delete"]);
+    assert_eq!(events.len(), 1);
+
+    let event_hint = events[0].command.get_array("deletes").unwrap()[0]
+        .as_document()
+        .unwrap()
+        .get("hint");
+    let expected_hint = match options {
+        Some(options) => options.hint.map(|hint| hint.to_bson()),
+        None => None,
+    };
+    assert_eq!(event_hint, expected_hint.as_ref());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn delete_hint_keys_specified() {
+    let options = DeleteOptions::builder().hint(Hint::Keys(doc! {})).build();
+    delete_hint_test(Some(options), function_name!()).await;
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn delete_hint_string_specified() {
+    let options = DeleteOptions::builder()
+        .hint(Hint::Name(String::new()))
+        .build();
+    delete_hint_test(Some(options), function_name!()).await;
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn delete_hint_not_specified() {
+    delete_hint_test(None, function_name!()).await;
+}
+
+async fn find_one_and_delete_hint_test(options: Option<FindOneAndDeleteOptions>, name: &str) {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+    let client = EventClient::new().await;
+
+    let req = VersionReq::parse(">= 4.2").unwrap();
+    if options.is_some() && !req.matches(&client.server_version) {
+        return;
+    }
+
+    let coll = client.database(name).collection(name);
+    let _: Result<FindOneAndModifyResult<Document>> =
+        coll.find_one_and_delete(doc! {}, options.clone()).await;
+
+    let events = client.get_command_started_events(&["findAndModify"]);
+    assert_eq!(events.len(), 1);
+
+    let event_hint = events[0].command.get("hint").cloned();
+    let expected_hint = options.and_then(|options| options.hint.map(|hint| hint.to_bson()));
+    assert_eq!(event_hint, expected_hint);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn find_one_and_delete_hint_keys_specified() {
+    let options = FindOneAndDeleteOptions::builder()
+        .hint(Hint::Keys(doc! {}))
+        .build();
     find_one_and_delete_hint_test(Some(options), function_name!()).await;
 }
 
@@ -870,6 +1418,7 @@ async fn typed_find_one_and_replace() {
         .find_one_and_replace(doc! { "x": 1 }, replacement.clone(), None)
         .await
         .unwrap()
+        .value
         .unwrap();
     assert_eq!(result, insert_data);
 
@@ -926,6 +1475,7 @@ async fn typed_returns() {
         .find_one_and_update(doc! { "x": 1 }, doc! { "$inc": { "x": 1 } }, None)
         .await
         .unwrap()
+        .value
         .unwrap();
     assert_eq!(result, insert_data);
 
@@ -933,6 +1483,7 @@ async fn typed_returns() {
         .find_one_and_delete(doc! { "x": 2 }, None)
         .await
         .unwrap()
+        .value
         .unwrap();
     assert_eq!(
         result,
@@ -943,6 +1494,130 @@ async fn typed_returns() {
     );
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn find_one_and_update_return_document() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_one(doc! { "_id": 1, "x": 1 }, None)
+        .await
+        .unwrap();
+
+    let options = FindOneAndUpdateOptions::builder()
+        .return_document(ReturnDocument::After)
+        .build();
+    let result = coll
+        .find_one_and_update(doc! { "_id": 1 }, doc! { "$inc": { "x": 1 } }, options)
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(result, Some(doc! { "_id": 1, "x": 2 }));
+
+    let options = FindOneAndUpdateOptions::builder()
+        .return_document(ReturnDocument::Before)
+        .build();
+    let result = coll
+        .find_one_and_update(doc! { "_id": 1 }, doc! { "$inc": { "x": 1 } }, options)
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(result, Some(doc! { "_id": 1, "x": 2 }));
+
+    // the document before modification is returned by default.
+    let result = coll
+        .find_one_and_update(doc! { "_id": 1 }, doc! { "$inc": { "x": 1 } }, None)
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(result, Some(doc! { "_id": 1, "x": 3 }));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn find_one_and_update_upsert() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let options = FindOneAndUpdateOptions::builder()
+        .upsert(true)
+        .return_document(ReturnDocument::After)
+        .build();
+    let result = coll
+        .find_one_and_update(doc! { "_id": 1 }, doc! { "$set": { "x": 1 } }, options)
+        .await
+        .unwrap();
+    assert_eq!(result.upserted_id, Some(Bson::Int32(1)));
+    assert_eq!(result.value, Some(doc! { "_id": 1, "x": 1 }));
+
+    // no document is upserted if one matches the filter.
+    let options = FindOneAndUpdateOptions::builder().upsert(true).build();
+    let result = coll
+        .find_one_and_update(doc! { "_id": 1 }, doc! { "$set": { "x": 2 } }, options)
+        .await
+        .unwrap();
+    assert_eq!(result.upserted_id, None);
+    assert_eq!(result.value, Some(doc! { "_id": 1, "x": 1 }));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn find_one_and_update_array_filters() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if client.server_version_lt(3, 6) {
+        println!("skipping {} due to server version < 3.6", function_name!());
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_one(doc! { "_id": 1, "grades": [95, 85, 92] }, None)
+        .await
+        .unwrap();
+
+    let options = FindOneAndUpdateOptions::builder()
+        .array_filters(vec![doc! { "g": { "$gte": 90 } }])
+        .bypass_document_validation(true)
+        .return_document(ReturnDocument::After)
+        .build();
+    let result = coll
+        .find_one_and_update(
+            doc! { "_id": 1 },
+            doc! { "$set": { "grades.$[g]": 100 } },
+            options,
+        )
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(result, Some(doc! { "_id": 1, "grades": [100, 85, 100] }));
+
+    let events = client.get_command_started_events(&["findAndModify"]);
+    assert_eq!(events.len(), 1);
+    let command = &events[0].command;
+    assert_eq!(
+        command.get_array("arrayFilters"),
+        Ok(&vec![Bson::Document(doc! { "g": { "$gte": 90 } })])
+    );
+    assert_eq!(command.get_bool("bypassDocumentValidation"), Ok(true));
+    assert!(command
+        .get_document("update")
+        .map(|update| !update.contains_key("arrayFilters"))
+        .unwrap());
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -999,6 +1674,96 @@ async fn collection_options_inherited() {
     assert_options_inherited(&client, "aggregate").await;
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn counts_honor_read_preference_and_session() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set() {
+        println!(
+            "skipping {} due to test topology not being a replica set",
+            function_name!()
+        );
+        return;
+    }
+
+    let primary = client
+        .database("admin")
+        .run_command(doc! { "isMaster": 1 }, None)
+        .await
+        .unwrap()
+        .get_str("primary")
+        .unwrap()
+        .to_string();
+    let is_primary = |client: &EventClient, command_names: &[&str]| {
+        let events = client.get_command_started_events(command_names);
+        let event = events.iter().last().unwrap();
+        event.connection.address.to_string() == primary
+    };
+
+    client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await
+        .insert_one(doc! { "x": 1 }, None)
+        .await
+        .unwrap();
+
+    // the collection's read preference is inherited by both counts.
+    let options = CollectionOptions::builder()
+        .selection_criteria(SelectionCriteria::ReadPreference(
+            ReadPreference::Secondary {
+                options: Default::default(),
+            },
+        ))
+        .build();
+    let coll = client
+        .database(function_name!())
+        .collection_with_options::<Document>(function_name!(), options);
+
+    coll.estimated_document_count(None).await.unwrap();
+    assert!(!is_primary(&client, &["count", "aggregate"]));
+
+    coll.count_documents(None, None).await.unwrap();
+    assert!(!is_primary(&client, &["aggregate"]));
+
+    // a read preference specified for the operation takes precedence.
+    let options = EstimatedDocumentCountOptions::builder()
+        .selection_criteria(SelectionCriteria::ReadPreference(ReadPreference::Primary))
+        .build();
+    coll.estimated_document_count(options).await.unwrap();
+    assert!(is_primary(&client, &["count", "aggregate"]));
+
+    let options = CountOptions::builder()
+        .selection_criteria(SelectionCriteria::ReadPreference(ReadPreference::Primary))
+        .build();
+    coll.count_documents(None, options).await.unwrap();
+    assert!(is_primary(&client, &["aggregate"]));
+
+    // causally consistent sessions read after the session's last operation.
+    let mut session = client.start_session(None).await.unwrap();
+    let coll = client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+    coll.insert_one_with_session(doc! { "x": 2 }, None, &mut session)
+        .await
+        .unwrap();
+    let count = coll
+        .count_documents_with_session(None, None, &mut session)
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+
+    let events = client.get_command_started_events(&["aggregate"]);
+    let command = &events.iter().last().unwrap().command;
+    assert_eq!(command.get_document("lsid"), Ok(session.id()));
+    assert!(command
+        .get_document("readConcern")
+        .unwrap()
+        .contains_key("afterClusterTime"));
+}
+
 async fn assert_options_inherited(client: &EventClient, command_name: &str) {
     let events = client.get_command_started_events(&[command_name]);
     let event = events.iter().last().unwrap();
@@ -1009,6 +1774,50 @@ async fn assert_options_inherited(client: &EventClient, command_name: &str) {
     );
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn aggregate_merge_inherits_collection_write_concern() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if client.server_version_lt(4, 2) {
+        println!(
+            "skipping {} due to $merge not being supported",
+            function_name!()
+        );
+        return;
+    }
+
+    let options = CollectionOptions::builder()
+        .read_concern(ReadConcern::local())
+        .write_concern(WriteConcern::builder().w(Acknowledgment::Majority).build())
+        .build();
+    client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let coll = client
+        .database(function_name!())
+        .collection_with_options::<Document>(function_name!(), options);
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+
+    let out_coll = format!("{}_out", function_name!());
+    coll.aggregate(vec![doc! { "$merge": { "into": out_coll } }], None)
+        .await
+        .unwrap();
+    coll.aggregate(vec![doc! { "$match": { "x": 1 } }], None)
+        .await
+        .unwrap();
+
+    let events = client.get_command_started_events(&["aggregate"]);
+    assert_eq!(events.len(), 2);
+    assert_eq!(
+        events[0].command.get_document("writeConcern"),
+        Ok(&doc! { "w": "majority" })
+    );
+    assert!(!events[1].command.contains_key("writeConcern"));
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -1049,3 +1858,704 @@ async fn collection_generic_bounds() {
         .collection(function_name!());
     let _result = coll.insert_one(Bar {}, None).await;
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn find_one_coalesced() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_one(doc! { "_id": 1, "x": 1 }, None)
+        .await
+        .unwrap();
+
+    let finds = (0..100).map(|_| coll.find_one_coalesced(doc! { "x": 1 }, None));
+    for result in futures::future::join_all(finds).await {
+        assert_eq!(result.unwrap(), Some(doc! { "_id": 1, "x": 1 }));
+    }
+    assert_eq!(client.get_command_started_events(&["find"]).len(), 1);
+
+    // the finished call is no longer shared, so the next one is sent to the server.
+    coll.find_one_coalesced(doc! { "x": 1 }, None)
+        .await
+        .unwrap();
+    assert_eq!(client.get_command_started_events(&["find"]).len(), 2);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn unacknowledged_insert_one() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let options = InsertOneOptions::builder()
+        .write_concern(WriteConcern::builder().w(Acknowledgment::Nodes(0)).build())
+        .build();
+
+    let result = coll
+        .insert_one(doc! { "x": 1 }, options.clone())
+        .await
+        .unwrap();
+    assert!(!result.acknowledged);
+    assert!(matches!(result.inserted_id, Bson::ObjectId(_)));
+
+    let result = coll
+        .insert_one(doc! { "_id": 1, "x": 1 }, options)
+        .await
+        .unwrap();
+    assert!(!result.acknowledged);
+    assert_eq!(result.inserted_id, Bson::Int32(1));
+
+    let result = coll.insert_one(doc! { "x": 2 }, None).await.unwrap();
+    assert!(result.acknowledged);
+
+    let events = client.get_command_started_events(&["insert"]);
+    assert_eq!(events.len(), 3);
+    for event in &events[..2] {
+        assert_eq!(
+            event.command.get_document("writeConcern").unwrap(),
+            &doc! { "w": 0 }
+        );
+    }
+    assert_ne!(
+        events[2].command.get_document("writeConcern").ok(),
+        Some(&doc! { "w": 0 })
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn unacknowledged_write_not_awaited() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    // all operations use the same connection, so the acknowledged operation below is executed by
+    // the server after the unacknowledged writes.
+    let options = ClientOptions::builder().max_pool_size(1).build();
+    let client = EventClient::with_additional_options(Some(options), None, None, None).await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let options = InsertOneOptions::builder()
+        .write_concern(WriteConcern::builder().w(Acknowledgment::Nodes(0)).build())
+        .build();
+
+    for i in 0..10 {
+        let result = coll
+            .insert_one(doc! { "_id": i }, options.clone())
+            .await
+            .unwrap();
+        assert!(!result.acknowledged);
+    }
+
+    // no reply is read for unacknowledged writes, but each one is still reported as succeeded
+    // with an `{ ok: 1 }` reply.
+    let events = client.get_command_events(&["insert"]);
+    assert_eq!(events.len(), 20);
+    for pair in events.chunks(2) {
+        assert!(matches!(pair[0], CommandEvent::Started(_)));
+        match pair[1] {
+            CommandEvent::Succeeded(ref event) => assert_eq!(event.reply, doc! { "ok": 1 }),
+            ref other => panic!("expected insert to succeed, got {:?}", other),
+        }
+    }
+
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 10);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn find_allow_partial_results() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+
+    let options = FindOptions::builder().allow_partial_results(true).build();
+    let mut cursor = coll.find(None, options).await.unwrap();
+    // all shards (if any) are available, so the results are complete.
+    assert!(!cursor.partial_results_returned());
+    assert!(cursor.try_next().await.unwrap().is_some());
+
+    let events = client.get_command_started_events(&["find"]);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].command.get_bool("allowPartialResults"), Ok(true));
+}
+
+/// With one shard made unavailable by a fail point, a find that allows partial results returns the
+/// documents from the other shards and reports that they're incomplete.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn find_allow_partial_results_shard_down() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = TestClient::new().await;
+    if !client.is_sharded() || client.server_version_lt(4, 4) {
+        println!(
+            "skipping {} due to not running against a 4.4+ sharded cluster",
+            function_name!()
+        );
+        return;
+    }
+
+    let admin = client.database("admin");
+    let shards = admin
+        .run_command(doc! { "listShards": 1 }, None)
+        .await
+        .unwrap()
+        .get_array("shards")
+        .unwrap()
+        .clone();
+    if shards.len() < 2 {
+        println!(
+            "skipping {} due to the cluster having fewer than two shards",
+            function_name!()
+        );
+        return;
+    }
+
+    // split the collection into two chunks, one on the database's primary shard and one on
+    // another shard, with a document in each.
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+    admin
+        .run_command(doc! { "enableSharding": function_name!() }, None)
+        .await
+        .unwrap();
+    let ns = format!("{}.{}", function_name!(), function_name!());
+    admin
+        .run_command(doc! { "shardCollection": &ns, "key": { "_id": 1 } }, None)
+        .await
+        .unwrap();
+    admin
+        .run_command(doc! { "split": &ns, "middle": { "_id": 0 } }, None)
+        .await
+        .unwrap();
+
+    let primary_shard = client
+        .database("config")
+        .collection::<Document>("databases")
+        .find_one(doc! { "_id": function_name!() }, None)
+        .await
+        .unwrap()
+        .unwrap()
+        .get_str("primary")
+        .unwrap()
+        .to_string();
+    let other_shard = shards
+        .iter()
+        .filter_map(Bson::as_document)
+        .find(|shard| shard.get_str("_id") != Ok(primary_shard.as_str()))
+        .unwrap();
+    admin
+        .run_command(
+            doc! {
+                "moveChunk": &ns,
+                "find": { "_id": 0 },
+                "to": other_shard.get_str("_id").unwrap(),
+                "_waitForDelete": true,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let coll = db.collection::<Document>(function_name!());
+    coll.insert_many(vec![doc! { "_id": -1 }, doc! { "_id": 1 }], None)
+        .await
+        .unwrap();
+
+    // shard hosts are listed as "<replica set name>/<hosts>", or just "<hosts>" for standalones.
+    let host = other_shard.get_str("host").unwrap();
+    let mut parts = host.rsplitn(2, '/');
+    let hosts = parts.next().unwrap();
+    let uri = match parts.next() {
+        Some(replica_set) => format!("mongodb://{}/?replicaSet={}", hosts, replica_set),
+        None => format!("mongodb://{}", hosts),
+    };
+    let mut shard_options = ClientOptions::parse_uri(&uri, None).await.unwrap();
+    shard_options.credential = CLIENT_OPTIONS.credential.clone();
+    shard_options.tls = CLIENT_OPTIONS.tls.clone();
+    let shard_client = Client::with_options(shard_options).unwrap();
+
+    // the mongos retries reads that fail with a retryable error, so the shard has to keep failing.
+    let options = FailCommandOptions::builder()
+        .error_code(6)
+        .fail_internal_commands(true)
+        .build();
+    let failpoint = FailPoint::fail_command(&["find"], FailPointMode::AlwaysOn, options);
+    let fp_guard = failpoint.enable(&shard_client, None).await.unwrap();
+
+    assert!(coll.find(None, None).await.is_err());
+
+    let options = FindOptions::builder().allow_partial_results(true).build();
+    let cursor = coll.find(None, options).await.unwrap();
+    assert!(cursor.partial_results_returned());
+    let docs: Vec<Document> = cursor.try_collect().await.unwrap();
+    assert_eq!(docs, vec![doc! { "_id": -1 }]);
+
+    drop(fp_guard);
+    db.drop(None).await.unwrap();
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn sample() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    assert_eq!(coll.random_document(None).await.unwrap(), None);
+
+    coll.insert_many((0..100).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    let mut samples = Vec::new();
+    for _ in 0..5 {
+        let mut ids: Vec<Bson> = coll
+            .sample(10, None)
+            .await
+            .unwrap()
+            .map_ok(|doc| doc.get("_id").unwrap().clone())
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(ids.len(), 10);
+        ids.sort_by_key(|id| id.as_i32());
+        samples.push(ids);
+    }
+    assert!(samples.iter().any(|ids| ids != &samples[0]));
+
+    // the first aggregate is from the call to random_document on the empty collection.
+    let events = client.get_command_started_events(&["aggregate"]);
+    assert_eq!(
+        events[1].command.get_array("pipeline").unwrap(),
+        &vec![Bson::Document(doc! { "$sample": { "size": 10_i64 } })]
+    );
+
+    assert!(coll.random_document(None).await.unwrap().is_some());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn distinct_decimal128() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let (one_and_a_half, one_tenth) = decimal128_one_and_a_half_and_one_tenth();
+
+    coll.insert_many(
+        vec![
+            doc! { "x": one_and_a_half.clone() },
+            doc! { "x": one_tenth.clone() },
+            doc! { "x": one_and_a_half.clone() },
+        ],
+        None,
+    )
+    .await
+    .unwrap();
+
+    let values = coll.distinct("x", None, None).await.unwrap();
+    assert_eq!(values.len(), 2);
+    assert!(values.contains(&one_and_a_half));
+    assert!(values.contains(&one_tenth));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn aggregate_let_vars() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.server_version_lt(5, 0) {
+        println!("skipping {} due to server version < 5.0", function_name!());
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((1..=3).map(|i| doc! { "_id": i, "x": i }), None)
+        .await
+        .unwrap();
+
+    let pipeline = vec![
+        doc! { "$project": { "y": { "$multiply": ["$x", "$$multiplier"] } } },
+        doc! { "$sort": { "_id": 1 } },
+    ];
+    let options = AggregateOptions::builder()
+        .let_vars(doc! { "multiplier": 3 })
+        .build();
+    let results: Vec<Document> = coll
+        .aggregate(pipeline, options)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        results,
+        vec![
+            doc! { "_id": 1, "y": 3 },
+            doc! { "_id": 2, "y": 6 },
+            doc! { "_id": 3, "y": 9 },
+        ]
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn aggregate_as_typed() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Summary {
+        name: String,
+        total: i32,
+    }
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await
+        .clone_with_type::<UserType>();
+    coll.insert_many(
+        vec![
+            UserType {
+                x: 1,
+                str: "a".to_string(),
+            },
+            UserType {
+                x: 2,
+                str: "b".to_string(),
+            },
+        ],
+        None,
+    )
+    .await
+    .unwrap();
+
+    let pipeline = vec![
+        doc! { "$sort": { "x": 1 } },
+        doc! { "$project": { "_id": 0, "name": "$str", "total": { "$add": ["$x", 10] } } },
+    ];
+    let results: Vec<Summary> = coll
+        .aggregate_as(pipeline.clone(), None)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(
+        results,
+        vec![
+            Summary {
+                name: "a".to_string(),
+                total: 11,
+            },
+            Summary {
+                name: "b".to_string(),
+                total: 12,
+            },
+        ]
+    );
+
+    // The pipeline output doesn't match the collection's type, so it can't be deserialized into
+    // it.
+    let mut cursor = coll.aggregate_as::<UserType>(pipeline, None).await.unwrap();
+    assert!(cursor.next().await.unwrap().is_err());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn distinct_case_insensitive() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    coll.insert_many(
+        vec![
+            doc! { "name": "apple" },
+            doc! { "name": "Apple" },
+            doc! { "name": "APPLE" },
+            doc! { "name": "banana" },
+        ],
+        None,
+    )
+    .await
+    .unwrap();
+
+    let values = coll.distinct("name", None, None).await.unwrap();
+    assert_eq!(values.len(), 4);
+
+    let collation = Collation::builder()
+        .locale("en")
+        .strength(CollationStrength::Secondary)
+        .build();
+    let options = DistinctOptions::builder().collation(collation).build();
+    let values = coll.distinct("name", None, options).await.unwrap();
+    assert_eq!(values.len(), 2);
+    assert!(values
+        .iter()
+        .any(|value| value.as_str().map(str::to_lowercase).as_deref() == Some("apple")));
+    assert!(values.contains(&Bson::String("banana".to_string())));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn bypass_document_validation() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let options = CreateCollectionOptions::builder()
+        .validator(doc! { "x": { "$gt": 0 } })
+        .build();
+    let coll = client
+        .create_fresh_collection(function_name!(), function_name!(), options)
+        .await;
+
+    let error = coll
+        .insert_one(doc! { "_id": 1, "x": -1 }, None)
+        .await
+        .expect_err("insert should fail validation");
+    assert!(matches!(*error.kind, ErrorKind::Write(_)));
+
+    let insert_one_options = InsertOneOptions::builder()
+        .bypass_document_validation(true)
+        .build();
+    coll.insert_one(doc! { "_id": 1, "x": -1 }, insert_one_options)
+        .await
+        .unwrap();
+
+    let insert_many_options = InsertManyOptions::builder()
+        .bypass_document_validation(true)
+        .build();
+    coll.insert_many(
+        vec![doc! { "_id": 2, "x": -2 }, doc! { "_id": 3, "x": -3 }],
+        insert_many_options,
+    )
+    .await
+    .unwrap();
+
+    let update_options = UpdateOptions::builder()
+        .bypass_document_validation(true)
+        .build();
+    coll.update_one(
+        doc! { "_id": 2 },
+        doc! { "$set": { "x": -20 } },
+        update_options,
+    )
+    .await
+    .unwrap();
+
+    let replace_options = ReplaceOptions::builder()
+        .bypass_document_validation(true)
+        .build();
+    coll.replace_one(doc! { "_id": 3 }, doc! { "x": -30 }, replace_options)
+        .await
+        .unwrap();
+
+    let xs: Vec<i32> = coll
+        .find(None, FindOptions::builder().sort(doc! { "_id": 1 }).build())
+        .await
+        .unwrap()
+        .map_ok(|doc| doc.get_i32("x").unwrap())
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(xs, vec![-1, -20, -30]);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn aggregate_hint() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let event_client = EventClient::new().await;
+    let coll = event_client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0..5).map(|i| doc! { "x": i }), None)
+        .await
+        .unwrap();
+    event_client
+        .database(function_name!())
+        .run_command(
+            doc! {
+                "createIndexes": function_name!(),
+                "indexes": [{ "key": { "x": 1 }, "name": "myIndex" }],
+            },
+            None,
+        )
+        .await
+        .expect("index creation should succeed");
+
+    let pipeline = vec![doc! { "$match": {} }];
+    let options = AggregateOptions::builder()
+        .hint(Hint::Name("myIndex".to_string()))
+        .build();
+    let results: Vec<Document> = coll
+        .aggregate(pipeline.clone(), options)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 5);
+
+    let events = event_client.get_command_started_events(&["aggregate"]);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].command.get_str("hint"), Ok("myIndex"));
+
+    // without the hint an empty $match would scan the collection rather than the index.
+    let explain = event_client
+        .database(function_name!())
+        .run_command(
+            doc! {
+                "explain": {
+                    "aggregate": function_name!(),
+                    "pipeline": pipeline,
+                    "cursor": {},
+                    "hint": "myIndex",
+                },
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(
+        explain.to_string().contains("IXSCAN"),
+        "expected an index scan in {}",
+        explain
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn count_documents_hint() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let event_client = EventClient::new().await;
+    let coll = event_client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0..5).map(|i| doc! { "x": i }), None)
+        .await
+        .unwrap();
+    let index_name = coll.ensure_index("x", 1, None).await.unwrap();
+
+    let hints = [Hint::Name(index_name.clone()), Hint::Keys(doc! { "x": 1 })];
+    for hint in hints.iter() {
+        let options = CountOptions::builder().hint(hint.clone()).build();
+        let count = coll
+            .count_documents(doc! { "x": { "$gte": 2 } }, options.clone())
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+
+        // A hint on a count with an empty filter should still count every document.
+        let count = coll.count_documents(None, options).await.unwrap();
+        assert_eq!(count, 5);
+    }
+
+    let events = event_client.get_command_started_events(&["aggregate"]);
+    assert_eq!(events.len(), 4);
+    for event in &events[..2] {
+        assert_eq!(event.command.get_str("hint"), Ok(index_name.as_str()));
+    }
+    for event in &events[2..] {
+        assert_eq!(event.command.get_document("hint"), Ok(&doc! { "x": 1 }));
+    }
+    for event in events {
+        let pipeline = event.command.get_array("pipeline").unwrap();
+        assert!(pipeline
+            .iter()
+            .filter_map(Bson::as_document)
+            .all(|stage| !stage.contains_key("$hint")));
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn ensure_unique_index() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let name = coll.ensure_unique_index("email", None).await.unwrap();
+    assert_eq!(name, "email_1");
+    let name = coll.ensure_index("age", -1, None).await.unwrap();
+    assert_eq!(name, "age_-1");
+
+    let indexes = client
+        .database(function_name!())
+        .run_command(doc! { "listIndexes": function_name!() }, None)
+        .await
+        .unwrap();
+    let indexes = indexes
+        .get_document("cursor")
+        .and_then(|cursor| cursor.get_array("firstBatch"))
+        .unwrap();
+    let email_index = indexes
+        .iter()
+        .filter_map(Bson::as_document)
+        .find(|index| index.get_str("name") == Ok("email_1"))
+        .expect("email_1 index should exist");
+    assert_eq!(email_index.get_document("key"), Ok(&doc! { "email": 1 }));
+    assert_eq!(email_index.get_bool("unique"), Ok(true));
+
+    coll.insert_one(doc! { "email": "a@example.com", "age": 30 }, None)
+        .await
+        .unwrap();
+    let error = coll
+        .insert_one(doc! { "email": "a@example.com", "age": 31 }, None)
+        .await
+        .expect_err("inserting a duplicate should fail");
+    match *error.kind {
+        ErrorKind::Write(WriteFailure::WriteError(ref write_error)) => {
+            assert_eq!(write_error.code, 11000)
+        }
+        ref e => panic!("expected duplicate key error, got {:?}", e),
+    }
+
+    // a non-unique index permits duplicates.
+    coll.insert_one(doc! { "email": "b@example.com", "age": 30 }, None)
+        .await
+        .unwrap();
+}
@@ -1,12 +1,40 @@
-use std::time::Duration;
+use std::{marker::PhantomPinned, time::Duration};
 
-use futures::{future::Either, StreamExt};
-use tokio::sync::RwLockReadGuard;
+use futures::{future::Either, Stream, StreamExt, TryStreamExt};
+use serde::Deserialize;
+use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 use crate::{
-    bson::doc,
-    options::{CreateCollectionOptions, CursorType, FindOptions},
-    test::{TestClient, LOCK},
+    bson::{doc, Document},
+    bson_util,
+    error::Result,
+    event::command::CursorClosedReason,
+    options::{
+        Acknowledgment,
+        ClientOptions,
+        CreateCollectionOptions,
+        CursorType,
+        FindOptions,
+        InsertManyOptions,
+        ReadPreference,
+        SelectionCriteria,
+        WriteConcern,
+    },
+    test::{
+        CommandEvent,
+        CursorEvent,
+        Event,
+        EventClient,
+        FailCommandOptions,
+        FailPoint,
+        FailPointMode,
+        TestClient,
+        CLIENT_OPTIONS,
+        LOCK,
+    },
+    Cursor,
+    SessionCursor,
+    SessionCursorStream,
     RUNTIME,
 };
 
@@ -109,3 +137,664 @@ async fn session_cursor_next() {
         );
     }
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn session_cursor_close() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    client
+        .create_fresh_collection(function_name!(), function_name!(), None)
+        .await
+        .insert_many((0..5).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    let event_client = EventClient::new().await;
+    let coll = event_client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+    let mut session = event_client.start_session(None).await.unwrap();
+
+    let opts = FindOptions::builder().batch_size(1).build();
+    let mut cursor = coll
+        .find_with_session(None, opts, &mut session)
+        .await
+        .unwrap();
+    assert_eq!(
+        cursor.next(&mut session).await.transpose().unwrap(),
+        Some(doc! { "_id": 0 })
+    );
+    cursor.close(&mut session).await.unwrap();
+
+    let kill_cursors = event_client.get_command_started_events(&["killCursors"]);
+    assert_eq!(kill_cursors.len(), 1);
+    assert_eq!(
+        kill_cursors[0].command.get_document("lsid"),
+        Ok(session.id())
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn collect_with_limit() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .create_fresh_collection(function_name!(), function_name!(), None)
+        .await;
+
+    coll.insert_many((0..10).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    // Use a small batch size to ensure documents are collected across getMores.
+    let find_options = || FindOptions::builder().batch_size(3).build();
+
+    // document count limit
+    let mut cursor = coll.find(None, find_options()).await.unwrap();
+    let result = cursor.collect_with_limit(4, None).await.unwrap();
+    assert!(result.truncated);
+    assert_eq!(
+        result.documents,
+        (0..4).map(|i| doc! { "_id": i }).collect::<Vec<_>>()
+    );
+
+    // the cursor should still be usable to retrieve the remaining documents
+    let rest: Vec<Document> = cursor.try_collect().await.unwrap();
+    assert_eq!(rest, (4..10).map(|i| doc! { "_id": i }).collect::<Vec<_>>());
+
+    // byte size limit
+    let doc_size = bson_util::doc_size_bytes(&doc! { "_id": 0 });
+    let mut cursor = coll.find(None, find_options()).await.unwrap();
+    let result = cursor
+        .collect_with_limit(None, doc_size * 5 + 1)
+        .await
+        .unwrap();
+    assert!(result.truncated);
+    assert_eq!(result.documents.len(), 5);
+    assert_eq!(result.bytes, doc_size * 5);
+    assert_eq!(
+        cursor.next().await.transpose().unwrap(),
+        Some(doc! { "_id": 5 })
+    );
+
+    // neither limit reached
+    let mut cursor = coll.find(None, find_options()).await.unwrap();
+    let result = cursor.collect_with_limit(10, doc_size * 10).await.unwrap();
+    assert!(!result.truncated);
+    assert_eq!(result.documents.len(), 10);
+    assert_eq!(result.bytes, doc_size * 10);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn collect_with_limit_no_extra_get_more() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    client
+        .create_fresh_collection(function_name!(), function_name!(), None)
+        .await
+        .insert_many((0..6).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    let event_client = EventClient::new().await;
+    let coll = event_client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+
+    // Reaching the document limit exactly at the end of a batch shouldn't request the next one.
+    let mut cursor = coll
+        .find(None, FindOptions::builder().batch_size(3).build())
+        .await
+        .unwrap();
+    let result = cursor.collect_with_limit(3, None).await.unwrap();
+    assert!(result.truncated);
+    assert_eq!(result.documents.len(), 3);
+    assert!(event_client
+        .get_command_started_events(&["getMore"])
+        .is_empty());
+
+    let rest: Vec<Document> = cursor.try_collect().await.unwrap();
+    assert_eq!(rest, (3..6).map(|i| doc! { "_id": i }).collect::<Vec<_>>());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn collect_with_limit_deserialization_error() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Doc {
+        x: i32,
+    }
+
+    let client = TestClient::new().await;
+    let coll = client
+        .create_fresh_collection(function_name!(), function_name!(), None)
+        .await;
+    coll.insert_many(
+        vec![
+            doc! { "_id": 0, "x": 0 },
+            doc! { "_id": 1, "x": 1 },
+            doc! { "_id": 2, "x": "two" },
+            doc! { "_id": 3, "x": 3 },
+        ],
+        None,
+    )
+    .await
+    .unwrap();
+
+    let mut cursor = coll
+        .clone_with_type::<Doc>()
+        .find(None, FindOptions::builder().sort(doc! { "_id": 1 }).build())
+        .await
+        .unwrap();
+
+    // The results before the one that can't be deserialized are returned.
+    let result = cursor.collect_with_limit(None, None).await.unwrap();
+    assert!(result.truncated);
+    assert_eq!(result.documents, vec![Doc { x: 0 }, Doc { x: 1 }]);
+    assert_eq!(cursor.count_consumed(), 2);
+
+    // The result that can't be deserialized is left in the cursor.
+    cursor
+        .collect_with_limit(None, None)
+        .await
+        .expect_err("deserializing the third result should fail");
+    assert_eq!(cursor.count_consumed(), 2);
+    cursor
+        .next()
+        .await
+        .unwrap()
+        .expect_err("deserializing the third result should fail");
+
+    let result = cursor.collect_with_limit(None, None).await.unwrap();
+    assert!(!result.truncated);
+    assert_eq!(result.documents, vec![Doc { x: 3 }]);
+    assert_eq!(cursor.count_consumed(), 3);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn collect_with_limit_get_more_error() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.hosts.drain(1..);
+    options.retry_reads = Some(false);
+    options.app_name = Some(function_name!().to_string());
+    let client = TestClient::with_options(Some(options)).await;
+    if !client.supports_fail_command().await {
+        println!(
+            "skipping {} due to failCommand not being supported",
+            function_name!()
+        );
+        return;
+    }
+
+    let coll = client
+        .create_fresh_collection(function_name!(), function_name!(), None)
+        .await;
+    coll.insert_many((0..6).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    let fail_point = FailPoint::fail_command(
+        &["getMore"],
+        FailPointMode::Times(1),
+        FailCommandOptions::builder()
+            .app_name(function_name!().to_string())
+            .error_code(2)
+            .build(),
+    );
+    let _fp_guard = client.enable_failpoint(fail_point, None).await.unwrap();
+
+    let mut cursor = coll
+        .find(None, FindOptions::builder().batch_size(2).build())
+        .await
+        .unwrap();
+
+    // The results collected before the getMore failed are left in the cursor.
+    cursor
+        .collect_with_limit(None, None)
+        .await
+        .expect_err("the getMore should fail");
+    assert_eq!(cursor.count_consumed(), 0);
+
+    let result = cursor.collect_with_limit(None, None).await.unwrap();
+    assert!(!result.truncated);
+    assert_eq!(
+        result.documents,
+        (0..6).map(|i| doc! { "_id": i }).collect::<Vec<_>>()
+    );
+    assert_eq!(cursor.count_consumed(), 6);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn map_ok() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .create_fresh_collection(function_name!(), function_name!(), None)
+        .await;
+
+    coll.insert_many((0..5).map(|i| doc! { "_id": i, "x": i * 2 }), None)
+        .await
+        .unwrap();
+
+    let find_options = FindOptions::builder()
+        .projection(doc! { "_id": 0, "x": 1 })
+        .batch_size(2)
+        .build();
+    let xs: Vec<i32> = coll
+        .find(None, find_options)
+        .await
+        .unwrap()
+        .map_ok(|doc| doc.get_i32("x").unwrap())
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(xs, vec![0, 2, 4, 6, 8]);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn next_batch() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .create_fresh_collection(function_name!(), function_name!(), None)
+        .await;
+
+    coll.insert_many((0..10).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    let find_options = FindOptions::builder().batch_size(3).build();
+    let mut cursor = coll.find(None, find_options).await.unwrap();
+
+    // the first batch is returned from the find response without a getMore.
+    let batch = cursor.next_batch().await.unwrap().unwrap();
+    assert_eq!(batch, (0..3).map(|i| doc! { "_id": i }).collect::<Vec<_>>());
+    assert!(client.get_command_started_events(&["getMore"]).is_empty());
+
+    let mut sizes = Vec::new();
+    while let Some(batch) = cursor.next_batch().await.unwrap() {
+        sizes.push(batch.len());
+    }
+    assert_eq!(sizes, vec![3, 3, 1]);
+    assert_eq!(client.get_command_started_events(&["getMore"]).len(), 3);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn next_batch_empty_get_more() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .create_fresh_collection(
+            function_name!(),
+            function_name!(),
+            CreateCollectionOptions::builder()
+                .capped(true)
+                .size(1_000_000)
+                .build(),
+        )
+        .await;
+
+    coll.insert_many((0..2).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    let find_options = FindOptions::builder()
+        .cursor_type(CursorType::Tailable)
+        .build();
+    let mut cursor = coll.find(None, find_options).await.unwrap();
+
+    let batch = cursor.next_batch().await.unwrap().unwrap();
+    assert_eq!(batch.len(), 2);
+
+    // a getMore on a tailable cursor with no new results returns an empty batch, which should be
+    // handed back rather than retried.
+    let batch = cursor.next_batch().await.unwrap().unwrap();
+    assert!(batch.is_empty());
+    assert_eq!(client.get_command_started_events(&["getMore"]).len(), 1);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn count_consumed() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    #[derive(Debug, Deserialize)]
+    struct Item {
+        x: i32,
+    }
+
+    let client = TestClient::new().await;
+    let coll = client
+        .create_fresh_collection(function_name!(), function_name!(), None)
+        .await;
+
+    coll.insert_many((0..10).map(|i| doc! { "_id": i, "x": i }), None)
+        .await
+        .unwrap();
+    coll.update_one(doc! { "_id": 2 }, doc! { "$set": { "x": "two" } }, None)
+        .await
+        .unwrap();
+
+    let find_options = || {
+        FindOptions::builder()
+            .batch_size(3)
+            .sort(doc! { "_id": 1 })
+            .build()
+    };
+    let typed = coll.clone_with_type::<Item>();
+
+    // results that fail to deserialize aren't counted.
+    let mut cursor = typed.find(None, find_options()).await.unwrap();
+    assert_eq!(cursor.count_consumed(), 0);
+    assert_eq!(cursor.next().await.unwrap().unwrap().x, 0);
+    assert_eq!(cursor.next().await.unwrap().unwrap().x, 1);
+    assert_eq!(cursor.count_consumed(), 2);
+    assert!(cursor.next().await.unwrap().is_err());
+    assert_eq!(cursor.count_consumed(), 2);
+    assert_eq!(cursor.next().await.unwrap().unwrap().x, 3);
+    assert_eq!(cursor.count_consumed(), 3);
+
+    // results retrieved in bulk are counted too.
+    let mut cursor = coll.find(None, find_options()).await.unwrap();
+    let batch = cursor.next_batch().await.unwrap().unwrap();
+    assert_eq!(cursor.count_consumed(), batch.len() as u64);
+    let result = cursor.collect_with_limit(4, None).await.unwrap();
+    assert_eq!(
+        cursor.count_consumed(),
+        (batch.len() + result.documents.len()) as u64
+    );
+    let rest: Vec<Document> = cursor.by_ref().try_collect().await.unwrap();
+    assert_eq!(rest.len(), 3);
+    assert_eq!(cursor.count_consumed(), 10);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn cursor_events() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .create_fresh_collection(function_name!(), function_name!(), None)
+        .await;
+    coll.insert_many((0..5).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+    client.clear_cached_events();
+
+    let find_options = FindOptions::builder().batch_size(2).build();
+
+    // a cursor that is dropped before being exhausted is killed.
+    let mut subscriber = client.subscribe_to_events();
+    let mut cursor = coll.find(None, find_options.clone()).await.unwrap();
+    cursor.next().await.unwrap().unwrap();
+    drop(cursor);
+
+    let kill_cursors = subscriber
+        .wait_for_event(Duration::from_secs(5), |event| {
+            matches!(
+                event,
+                Event::CommandEvent(CommandEvent::Started(started))
+                    if started.command_name == "killCursors"
+            )
+        })
+        .await
+        .expect("killCursors should have been sent");
+    let killed_id = match kill_cursors {
+        Event::CommandEvent(CommandEvent::Started(started)) => started.cursor_id.unwrap(),
+        _ => unreachable!(),
+    };
+    drop(subscriber);
+
+    let events = client.get_cursor_events();
+    assert_eq!(events.len(), 2, "{:?}", events);
+    match (&events[0], &events[1]) {
+        (CursorEvent::Created(created), CursorEvent::Closed(closed)) => {
+            assert_eq!(created.cursor_id, killed_id);
+            assert_eq!(created.namespace.to_string(), coll.namespace().to_string());
+            assert_eq!(created.operation_name, "find");
+            assert_eq!(closed.cursor_id, killed_id);
+            assert_eq!(closed.namespace.to_string(), coll.namespace().to_string());
+            assert_eq!(closed.reason, CursorClosedReason::Killed);
+        }
+        other => panic!("expected created and closed events, got {:?}", other),
+    }
+
+    // a cursor iterated to the end is closed by the server.
+    let cursor = coll.find(None, find_options).await.unwrap();
+    assert_eq!(cursor.count().await, 5);
+
+    let get_mores = client.get_command_started_events(&["getMore"]);
+    let events = client.get_cursor_events();
+    assert_eq!(events.len(), 2, "{:?}", events);
+    match (&events[0], &events[1]) {
+        (CursorEvent::Created(created), CursorEvent::Closed(closed)) => {
+            assert_ne!(created.cursor_id, killed_id);
+            assert_eq!(closed.cursor_id, created.cursor_id);
+            assert_eq!(closed.reason, CursorClosedReason::Exhausted);
+            assert!(!get_mores.is_empty());
+            assert!(get_mores
+                .iter()
+                .all(|get_more| get_more.cursor_id == Some(created.cursor_id)));
+        }
+        other => panic!("expected created and closed events, got {:?}", other),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn get_more_and_kill_cursors_use_cursor_server() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set() {
+        println!(
+            "skipping {} due to test topology not being a replica set",
+            function_name!()
+        );
+        return;
+    }
+
+    // wait for every data-bearing member to have the documents so that the cursor is not cut
+    // short by whichever one is selected.
+    let members = client.server_info.hosts.as_ref().map_or(1, Vec::len) as u32;
+    let write_concern = WriteConcern::builder()
+        .w(Acknowledgment::Nodes(members))
+        .build();
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many(
+        (0..5).map(|i| doc! { "_id": i }),
+        InsertManyOptions::builder()
+            .write_concern(write_concern)
+            .build(),
+    )
+    .await
+    .unwrap();
+
+    for _ in 0..3 {
+        let find_options = FindOptions::builder()
+            .batch_size(1)
+            .selection_criteria(SelectionCriteria::ReadPreference(ReadPreference::Nearest {
+                options: Default::default(),
+            }))
+            .build();
+        let mut cursor = coll.find(None, find_options).await.unwrap();
+        cursor.next().await.unwrap().unwrap();
+        cursor.next().await.unwrap().unwrap();
+
+        let mut subscriber = client.subscribe_to_events();
+        drop(cursor);
+        subscriber
+            .wait_for_event(Duration::from_secs(5), |event| {
+                matches!(
+                    event,
+                    Event::CommandEvent(CommandEvent::Started(started))
+                        if started.command_name == "killCursors"
+                )
+            })
+            .await
+            .expect("killCursors should have been sent");
+        drop(subscriber);
+
+        let events = client.get_command_started_events(&["find", "getMore", "killCursors"]);
+        let names: Vec<_> = events.iter().map(|e| e.command_name.as_str()).collect();
+        assert_eq!(names, vec!["find", "getMore", "killCursors"]);
+        let address = &events[0].connection.address;
+        assert!(events
+            .iter()
+            .all(|event| &event.connection.address == address));
+    }
+}
+
+/// A result type that is not `Unpin`.
+#[derive(Debug, Deserialize)]
+struct NotUnpin {
+    x: i32,
+    #[serde(skip)]
+    _pinned: PhantomPinned,
+}
+
+#[test]
+fn cursors_do_not_require_unpin() {
+    fn assert_stream<S: Stream<Item = Result<NotUnpin>> + Unpin>() {}
+    fn assert_unpin<T: Unpin>() {}
+
+    assert_stream::<Cursor<NotUnpin>>();
+    assert_unpin::<SessionCursor<NotUnpin>>();
+    assert_stream::<SessionCursorStream<NotUnpin>>();
+    #[cfg(feature = "sync")]
+    {
+        fn assert_iter<I: Iterator<Item = Result<NotUnpin>>>() {}
+        assert_iter::<crate::sync::Cursor<NotUnpin>>();
+        assert_iter::<crate::sync::SessionCursorIter<NotUnpin>>();
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn not_unpin_results() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0..3).map(|x| doc! { "x": x }), None)
+        .await
+        .unwrap();
+
+    let options = FindOptions::builder()
+        .batch_size(1)
+        .sort(doc! { "x": 1 })
+        .build();
+    let results: Vec<NotUnpin> = coll
+        .clone_with_type::<NotUnpin>()
+        .find(None, options)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    let xs: Vec<i32> = results.iter().map(|result| result.x).collect();
+    assert_eq!(xs, vec![0, 1, 2]);
+}
+
+/// The server streams the remaining batches of an exhaust cursor in reply to its first getMore.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn exhaust_find() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .create_fresh_collection(function_name!(), function_name!(), None)
+        .await;
+    coll.insert_many((0..10).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    let options = FindOptions::builder()
+        .batch_size(2)
+        .sort(doc! { "_id": 1 })
+        .exhaust(true)
+        .build();
+    let docs: Vec<Document> = coll
+        .find(None, options)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(docs, (0..10).map(|i| doc! { "_id": i }).collect::<Vec<_>>());
+
+    // mongos and servers older than 4.2 reply to each getMore separately instead.
+    if client.is_sharded() || client.server_version_lt(4, 2) {
+        return;
+    }
+    assert_eq!(client.get_command_started_events(&["getMore"]).len(), 1);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn exhaust_find_disabled() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let options = ClientOptions::builder().disable_exhaust(true).build();
+    let client = EventClient::with_options(options).await;
+    let coll = client
+        .create_fresh_collection(function_name!(), function_name!(), None)
+        .await;
+    coll.insert_many((0..10).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    let options = FindOptions::builder()
+        .batch_size(2)
+        .sort(doc! { "_id": 1 })
+        .exhaust(true)
+        .build();
+    let docs: Vec<Document> = coll
+        .find(None, options)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(docs, (0..10).map(|i| doc! { "_id": i }).collect::<Vec<_>>());
+
+    // Each batch is requested with its own getMore when exhaust is disabled for the client.
+    assert!(client.get_command_started_events(&["getMore"]).len() > 1);
+}
@@ -1,24 +1,28 @@
-use std::cmp::Ord;
+use std::{cmp::Ord, time::Duration};
 
 use approx::assert_ulps_eq;
 use futures::stream::TryStreamExt;
 use serde::Deserialize;
-use tokio::sync::RwLockReadGuard;
+use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 use crate::{
     bson::{doc, Bson, Document},
     error::Result,
     options::{
         AggregateOptions,
+        ClusteredIndex,
         Collation,
         CreateCollectionOptions,
         IndexOptionDefaults,
+        ProfilingLevel,
+        SetProfilingLevelOptions,
         ValidationAction,
         ValidationLevel,
     },
     results::{CollectionSpecification, CollectionType},
     test::{
-        util::{EventClient, TestClient},
+        util::{EventClient, FailCommandOptions, FailPoint, FailPointMode, TestClient},
+        CLIENT_OPTIONS,
         LOCK,
     },
     Database,
@@ -302,6 +306,112 @@ async fn db_aggregate() {
         .expect("aggregate should succeed");
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn db_aggregate_get_more() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+
+    if client.server_version_lt(4, 0) {
+        return;
+    }
+
+    let db = client.database("admin");
+
+    // idle connections include this client's own, so there is always more than one result.
+    let pipeline = vec![doc! {
+      "$currentOp": {
+        "allUsers": false,
+        "idleConnections": true
+      }
+    }];
+    let options = AggregateOptions::builder().batch_size(1).build();
+
+    let results: Vec<Document> = db
+        .aggregate(pipeline, options)
+        .await
+        .expect("aggregate should succeed")
+        .try_collect()
+        .await
+        .expect("getMore should succeed");
+    assert!(results.len() > 1);
+
+    let get_mores = client.get_command_started_events(&["getMore"]);
+    assert!(!get_mores.is_empty());
+    for get_more in get_mores {
+        assert_eq!(get_more.db, "admin");
+        assert_eq!(get_more.command.get_str("collection"), Ok("$cmd.aggregate"));
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn db_aggregate_query_stats() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+
+    if client.server_version_lt(7, 1) {
+        println!(
+            "skipping {} due to server version less than 7.1",
+            function_name!()
+        );
+        return;
+    }
+
+    let db = client.database("admin");
+
+    let stats: Vec<Document> = db
+        .aggregate(vec![doc! { "$queryStats": {} }], None)
+        .await
+        .expect("aggregate should succeed")
+        .try_collect()
+        .await
+        .expect("iterating the cursor should succeed");
+
+    // The results depend on the workload previously run against the server, which may be none.
+    assert!(stats.iter().all(|doc| doc.contains_key("key")));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn create_clustered_collection() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+
+    if client.server_version_lt(5, 3) {
+        println!(
+            "skipping {} due to server version less than 5.3",
+            function_name!()
+        );
+        return;
+    }
+
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+
+    let options = CreateCollectionOptions::builder()
+        .clustered_index(ClusteredIndex::default())
+        .build();
+    db.create_collection(function_name!(), options)
+        .await
+        .unwrap();
+
+    let colls = get_coll_info(&db, Some(doc! { "name": function_name!() })).await;
+    assert_eq!(colls.len(), 1);
+    let clustered_index = colls[0]
+        .options
+        .clustered_index
+        .as_ref()
+        .expect("listCollections should report the clustered index");
+    assert_eq!(clustered_index.key, doc! { "_id": 1 });
+    assert!(clustered_index.unique);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn db_aggregate_disk_use() {
@@ -388,3 +498,68 @@ async fn index_option_defaults_test(defaults: Option<IndexOptionDefaults>, name:
     };
     assert_eq!(event_defaults, defaults);
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn profiling() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = TestClient::new().await;
+    if client.is_sharded() || !client.supports_block_connection() {
+        println!("skipping profiling due to unsupported topology or server version");
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    let db = client.database(function_name!());
+
+    let options = SetProfilingLevelOptions::builder()
+        .slow_ms(Duration::from_millis(50))
+        .build();
+    db.set_profiling_level(ProfilingLevel::SlowOnly, options)
+        .await
+        .unwrap();
+    assert_eq!(
+        db.profiling_level().await.unwrap(),
+        ProfilingLevel::SlowOnly
+    );
+
+    let fail_point = FailPoint::fail_command(
+        &["find"],
+        FailPointMode::Times(1),
+        FailCommandOptions::builder()
+            .app_name(function_name!().to_string())
+            .block_connection(Duration::from_millis(100))
+            .build(),
+    );
+    let mut options = CLIENT_OPTIONS.clone();
+    options.app_name = Some(function_name!().to_string());
+    let slow_client = TestClient::with_options(Some(options)).await;
+    let _fp_guard = client.enable_failpoint(fail_point, None).await.unwrap();
+
+    slow_client
+        .database(function_name!())
+        .collection::<Document>(function_name!())
+        .find_one(doc! { "x": 1 }, None)
+        .await
+        .unwrap();
+
+    let ns = format!("{}.{}", function_name!(), function_name!());
+    let profiled: Vec<Document> = db
+        .profiling_info(doc! { "ns": ns, "command.find": function_name!() })
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(profiled.len(), 1, "{:?}", profiled);
+
+    db.set_profiling_level(ProfilingLevel::Off, None)
+        .await
+        .unwrap();
+    assert_eq!(db.profiling_level().await.unwrap(), ProfilingLevel::Off);
+}
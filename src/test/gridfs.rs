@@ -0,0 +1,214 @@
+use futures::io::AsyncReadExt;
+use tokio::sync::RwLockReadGuard;
+
+use crate::{
+    bson::{doc, oid::ObjectId, spec::BinarySubtype, Binary, Bson, DateTime, Document},
+    error::{Error, ErrorKind, GridFsErrorKind, GridFsFileIdentifier},
+    gridfs::{GridFsBucket, GridFsDownloadStream},
+    options::GridFsDownloadByNameOptions,
+    test::{TestClient, LOCK},
+    Database,
+};
+
+/// Stores `data` in the default bucket of `db` the way a GridFS upload would, split into chunks of
+/// `chunk_size` bytes, and returns the new file's `_id`.
+async fn insert_file(
+    db: &Database,
+    filename: &str,
+    data: &[u8],
+    chunk_size: usize,
+    upload_date: i64,
+) -> ObjectId {
+    let id = ObjectId::new();
+    db.collection::<Document>("fs.files")
+        .insert_one(
+            doc! {
+                "_id": id,
+                "length": data.len() as i64,
+                "chunkSize": chunk_size as i32,
+                "uploadDate": DateTime::from_millis(upload_date),
+                "filename": filename,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let chunks: Vec<Document> = data
+        .chunks(chunk_size)
+        .enumerate()
+        .map(|(n, bytes)| {
+            doc! {
+                "files_id": id,
+                "n": n as i32,
+                "data": Binary { subtype: BinarySubtype::Generic, bytes: bytes.to_vec() },
+            }
+        })
+        .collect();
+    if !chunks.is_empty() {
+        db.collection::<Document>("fs.chunks")
+            .insert_many(chunks, None)
+            .await
+            .unwrap();
+    }
+
+    id
+}
+
+async fn read_all(mut stream: GridFsDownloadStream) -> std::io::Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    stream.read_to_end(&mut contents).await?;
+    Ok(contents)
+}
+
+async fn download_by_name(bucket: &GridFsBucket, filename: &str, revision: i32) -> Vec<u8> {
+    let options = GridFsDownloadByNameOptions::builder()
+        .revision(revision)
+        .build();
+    let stream = bucket
+        .open_download_stream_by_name(filename, options)
+        .await
+        .expect("open download stream should succeed");
+    read_all(stream).await.expect("download should succeed")
+}
+
+fn gridfs_error(error: Error) -> GridFsErrorKind {
+    match *error.kind {
+        ErrorKind::GridFs(kind) => kind,
+        other => panic!("expected GridFS error, got {:?}", other),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn download_by_id() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+    let bucket = db.gridfs_bucket(None);
+
+    let data: Vec<u8> = (0..10).collect();
+    let id = insert_file(&db, "numbers", &data, 4, 0).await;
+
+    let stream = bucket.open_download_stream(id.into()).await.unwrap();
+    assert_eq!(stream.file().id, Bson::ObjectId(id));
+    assert_eq!(stream.file().length, 10);
+    assert_eq!(stream.file().chunk_size, 4);
+    assert_eq!(stream.file().filename.as_deref(), Some("numbers"));
+    assert_eq!(read_all(stream).await.unwrap(), data);
+
+    let empty_id = insert_file(&db, "empty", b"", 4, 0).await;
+    let stream = bucket.open_download_stream(empty_id.into()).await.unwrap();
+    assert!(read_all(stream).await.unwrap().is_empty());
+
+    let error = bucket
+        .open_download_stream(ObjectId::new().into())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        gridfs_error(error),
+        GridFsErrorKind::FileNotFound {
+            identifier: GridFsFileIdentifier::Id(_),
+            ..
+        }
+    ));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn download_by_name_revisions() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+    let bucket = db.gridfs_bucket(None);
+
+    insert_file(&db, "file", b"old", 255 * 1024, 1000).await;
+    insert_file(&db, "file", b"new", 255 * 1024, 2000).await;
+
+    assert_eq!(download_by_name(&bucket, "file", -1).await, b"new");
+    assert_eq!(download_by_name(&bucket, "file", -2).await, b"old");
+    assert_eq!(download_by_name(&bucket, "file", 0).await, b"old");
+    assert_eq!(download_by_name(&bucket, "file", 1).await, b"new");
+
+    let stream = bucket
+        .open_download_stream_by_name("file", None)
+        .await
+        .unwrap();
+    assert_eq!(read_all(stream).await.unwrap(), b"new");
+
+    let error = bucket
+        .open_download_stream_by_name(
+            "file",
+            GridFsDownloadByNameOptions::builder().revision(2).build(),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        gridfs_error(error),
+        GridFsErrorKind::RevisionNotFound { revision: 2, .. }
+    ));
+
+    let error = bucket
+        .open_download_stream_by_name("missing", None)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        gridfs_error(error),
+        GridFsErrorKind::FileNotFound {
+            identifier: GridFsFileIdentifier::Filename(ref name),
+            ..
+        } if name == "missing"
+    ));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn download_corrupted_files() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+    let bucket = db.gridfs_bucket(None);
+
+    // Remove the middle chunk so that the download notices the gap.
+    let id = insert_file(&db, "file", b"abcdef", 2, 0).await;
+    db.collection::<Document>("fs.chunks")
+        .delete_one(doc! { "files_id": id, "n": 1 }, None)
+        .await
+        .unwrap();
+    let stream = bucket.open_download_stream(id.into()).await.unwrap();
+    let error = read_all(stream).await.unwrap_err();
+    let error = error
+        .into_inner()
+        .expect("io error should wrap a driver error")
+        .downcast::<Error>()
+        .expect("io error should wrap a driver error");
+    assert!(matches!(
+        gridfs_error(*error),
+        GridFsErrorKind::MissingChunk { n: 1, .. }
+    ));
+
+    // A chunk size of 0 can't describe the file's chunks, so the download is rejected up front.
+    let id = insert_file(&db, "zero", b"abcdef", 2, 0).await;
+    db.collection::<Document>("fs.files")
+        .update_one(
+            doc! { "_id": id },
+            doc! { "$set": { "chunkSize": 0 } },
+            None,
+        )
+        .await
+        .unwrap();
+    let error = bucket.open_download_stream(id.into()).await.unwrap_err();
+    assert!(matches!(
+        gridfs_error(error),
+        GridFsErrorKind::InvalidChunkSize { chunk_size: 0, .. }
+    ));
+}
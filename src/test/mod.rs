@@ -1,6 +1,7 @@
 #[cfg(not(feature = "sync"))]
 mod atlas_connectivity;
 mod auth_aws;
+mod change_stream;
 mod client;
 mod coll;
 mod cursor;
@@ -11,16 +12,19 @@ mod spec;
 mod util;
 
 pub(crate) use self::{
-    spec::{run_spec_test, RunOn, Topology},
+    spec::{run_local_spec_test, run_spec_test, RunOn, Topology},
     util::{
         assert_matches,
+        decimal128_one_and_a_half_and_one_tenth,
         CmapEvent,
         CommandEvent,
+        CursorEvent,
         Event,
         EventClient,
         EventHandler,
         FailCommandOptions,
         FailPoint,
+        FailPointData,
         FailPointMode,
         Matchable,
         TestClient,
@@ -56,6 +60,7 @@ lazy_static! {
             version: ServerApiVersion::from_str(server_api_version.as_str()).unwrap(),
             deprecation_errors: None,
             strict: None,
+            check_pipeline_stages: None,
         }),
         _ => None,
     };
@@ -0,0 +1,14 @@
+use tokio::sync::RwLockWriteGuard;
+
+use crate::test::{run_local_spec_test, spec::run_unified_format_test, LOCK};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn run() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+    run_local_spec_test(
+        &["connection-monitoring-and-pooling", "unified"],
+        run_unified_format_test,
+    )
+    .await;
+}
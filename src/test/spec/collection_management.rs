@@ -1,4 +1,4 @@
-use crate::test::{run_spec_test, LOCK};
+use crate::test::{run_local_spec_test, run_spec_test, LOCK};
 
 use super::run_unified_format_test;
 
@@ -8,3 +8,10 @@ async fn run() {
     let _guard = LOCK.run_exclusively().await;
     run_spec_test(&["collection-management"], run_unified_format_test).await;
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn run_local() {
+    let _guard = LOCK.run_exclusively().await;
+    run_local_spec_test(&["collection-management"], run_unified_format_test).await;
+}
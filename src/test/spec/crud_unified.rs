@@ -1,6 +1,6 @@
 use tokio::sync::RwLockWriteGuard;
 
-use crate::test::{run_spec_test, spec::run_unified_format_test, LOCK};
+use crate::test::{run_local_spec_test, run_spec_test, spec::run_unified_format_test, LOCK};
 
 // The drop implementation on FailPointGuard blocks in the foreground, so we need to use the
 // multi-thread runtime to avoid panicking.
@@ -13,3 +13,10 @@ async fn run() {
     let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
     run_spec_test(&["crud", "unified"], run_unified_format_test).await;
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn run_local() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+    run_local_spec_test(&["crud", "unified"], run_unified_format_test).await;
+}
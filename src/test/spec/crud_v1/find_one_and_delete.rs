@@ -59,7 +59,8 @@ async fn run_find_one_and_delete_test(test_file: TestFile) {
         let result = coll
             .find_one_and_delete(arguments.filter, options)
             .await
-            .expect(&test_case.description);
+            .expect(&test_case.description)
+            .value;
         assert_eq!(result, outcome.result, "{}", test_case.description);
 
         if let Some(c) = outcome.collection {
@@ -73,7 +73,8 @@ async fn run_find_one_and_replace_test(test_file: TestFile) {
         let result = coll
             .find_one_and_replace(arguments.filter, arguments.replacement, options)
             .await
-            .expect(&test_case.description);
+            .expect(&test_case.description)
+            .value;
         assert_eq!(
             result, outcome.result,
             "{}
@@ -76,7 +76,8 @@ async fn run_find_one_and_update_test(test_file: TestFile) {
         let result = coll
             .find_one_and_update(arguments.filter, arguments.update, options)
             .await
-            .expect(&test_case.description);
+            .expect(&test_case.description)
+            .value;
         assert_eq!(
             result, outcome.result,
             "{}
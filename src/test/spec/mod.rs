@@ -1,5 +1,6 @@
 #[cfg(not(feature = "sync"))]
 mod auth;
+mod cmap_unified;
 mod collection_management;
 mod command_monitoring;
 mod connection_stepdown;
@@ -38,13 +39,34 @@ use serde_json::Value;
 
 use crate::bson::Bson;
 
+/// Runs the test files for `spec` that are vendored from the specifications repository.
 pub(crate) async fn run_spec_test<T, F, G>(spec: &[&str], run_test_file: F)
 where
     F: Fn(T) -> G,
     G: Future<Output = ()>,
     T: DeserializeOwned,
 {
-    let base_path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "src", "test", "spec", "json"]
+    run_tests_in_dir("json", spec, run_test_file).await
+}
+
+/// Runs the driver's own test files for `spec`. These use the specification test formats but
+/// aren't part of the specifications repository, so they're kept out of the vendored directory.
+pub(crate) async fn run_local_spec_test<T, F, G>(spec: &[&str], run_test_file: F)
+where
+    F: Fn(T) -> G,
+    G: Future<Output = ()>,
+    T: DeserializeOwned,
+{
+    run_tests_in_dir("local", spec, run_test_file).await
+}
+
+async fn run_tests_in_dir<T, F, G>(dir: &str, spec: &[&str], run_test_file: F)
+where
+    F: Fn(T) -> G,
+    G: Future<Output = ()>,
+    T: DeserializeOwned,
+{
+    let base_path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "src", "test", "spec", dir]
         .iter()
         .chain(spec.iter())
         .collect();
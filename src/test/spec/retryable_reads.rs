@@ -18,6 +18,7 @@ use crate::{
         EventHandler,
         FailCommandOptions,
         FailPoint,
+        FailPointData,
         FailPointMode,
         TestClient,
         CLIENT_OPTIONS,
@@ -72,6 +73,46 @@ async fn retry_releases_connection() {
         .expect("find should succeed");
 }
 
+/// Test ensures that a read is retried after the server closes the connection mid-operation.
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn retry_after_connection_closed() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let mut client_options = CLIENT_OPTIONS.clone();
+    client_options.hosts.drain(1..);
+    client_options.retry_reads = Some(true);
+
+    let client = TestClient::with_options(Some(client_options)).await;
+    if !client.supports_fail_command().await {
+        println!("skipping retry_after_connection_closed due to failCommand not being supported");
+        return;
+    }
+
+    let collection = client
+        .database("retry_after_connection_closed")
+        .collection("retry_after_connection_closed");
+    collection.insert_one(doc! { "x": 1 }, None).await.unwrap();
+
+    let failpoint = FailPointData::close_connection(Some(1)).fail_command(&["find"]);
+    let fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let (doc, outcome) = collection
+        .find_one_with_outcome(doc! {}, None)
+        .await
+        .expect("find should succeed after retrying");
+    assert!(doc.is_some());
+    assert!(outcome.was_retried());
+
+    // Once the failpoint is disabled, reads should succeed on the first attempt.
+    drop(fp_guard);
+    let (_, outcome) = collection
+        .find_one_with_outcome(doc! {}, None)
+        .await
+        .unwrap();
+    assert!(!outcome.was_retried());
+}
+
 /// Prose test from retryable reads spec verifying that PoolClearedErrors are retried.
 #[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
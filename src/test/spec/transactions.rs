@@ -1,25 +1,43 @@
+use futures::{future::FutureExt, stream::TryStreamExt};
 use serde::{Deserialize, Serialize};
 use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 use crate::{
     bson::{doc, serde_helpers::serialize_u64_as_i32, Document},
     client::session::TransactionState,
-    test::{run_spec_test, TestClient, LOCK},
+    test::{
+        run_local_spec_test,
+        run_spec_test,
+        EventClient,
+        FailCommandOptions,
+        FailPoint,
+        FailPointMode,
+        TestClient,
+        LOCK,
+    },
 };
 
-use super::run_v2_test;
+use super::{run_unified_format_test, run_v2_test};
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn run() {
     let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
-    // TODO RUST-122: Unskip tests on sharded clusters
+    // The sharded tests in this format rely on the targetedFailPoint and assertSessionPinned
+    // operations, which only the unified runner supports.
     if TestClient::new().await.is_sharded() {
         return;
     }
     run_spec_test(&["transactions"], run_v2_test).await;
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn run_local() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+    run_local_spec_test(&["transactions"], run_unified_format_test).await;
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -78,3 +96,139 @@ async fn client_errors() {
     assert!(result.is_err());
     assert_eq!(session.transaction.state, TransactionState::InProgress);
 }
+
+/// A write concern error on commit is labeled UnknownTransactionCommitResult but isn't a retryable
+/// write error, so only `with_transaction` retries the commit.
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn with_transaction_retries_unknown_commit_result() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set()
+        || client.server_version_lt(4, 0)
+        || !client.supports_fail_command().await
+    {
+        println!(
+            "skipping {} due to not running against a 4.0+ replica set with failCommand",
+            function_name!()
+        );
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    client
+        .database(function_name!())
+        .create_collection(function_name!(), None)
+        .await
+        .unwrap();
+
+    let options = FailCommandOptions::builder()
+        .write_concern_error(doc! { "code": 64, "errmsg": "multiple errors reported" })
+        .build();
+    let failpoint =
+        FailPoint::fail_command(&["commitTransaction"], FailPointMode::Times(1), options);
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let mut session = client.start_session(None).await.unwrap();
+    let callback_coll = coll.clone();
+    session
+        .with_transaction(
+            move |session| {
+                async move {
+                    callback_coll
+                        .insert_one_with_session(doc! { "_id": 1 }, None, session)
+                        .await
+                }
+                .boxed()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(matches!(
+        session.transaction.state,
+        TransactionState::Committed { .. }
+    ));
+
+    let commits = client.get_command_started_events(&["commitTransaction"]);
+    assert_eq!(commits.len(), 2);
+    assert_eq!(
+        commits[0].command.get("txnNumber"),
+        commits[1].command.get("txnNumber")
+    );
+    assert_eq!(
+        commits[1].command.get_document("writeConcern"),
+        Ok(&doc! { "w": "majority", "wtimeout": 10000 })
+    );
+
+    let docs: Vec<Document> = coll
+        .find(None, None)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(docs, vec![doc! { "_id": 1 }]);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn abort_transaction_ignoring_errors() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set()
+        || client.server_version_lt(4, 0)
+        || !client.supports_fail_command().await
+    {
+        println!(
+            "skipping {} due to not running against a 4.0+ replica set with failCommand",
+            function_name!()
+        );
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    client
+        .database(function_name!())
+        .create_collection(function_name!(), None)
+        .await
+        .unwrap();
+
+    let options = FailCommandOptions::builder().error_code(8).build();
+    let failpoint =
+        FailPoint::fail_command(&["abortTransaction"], FailPointMode::Times(2), options);
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let mut session = client.start_session(None).await.unwrap();
+    session.start_transaction(None).await.unwrap();
+    coll.insert_one_with_session(doc! { "_id": 1 }, None, &mut session)
+        .await
+        .unwrap();
+
+    session.abort_transaction_ignoring_errors().await;
+    assert!(matches!(
+        session.transaction.state,
+        TransactionState::Aborted
+    ));
+    assert_eq!(
+        client
+            .get_command_started_events(&["abortTransaction"])
+            .len(),
+        1
+    );
+
+    // aborting again would be an error, but it is discarded as well.
+    session.abort_transaction_ignoring_errors().await;
+    assert!(session.abort_transaction().await.is_err());
+
+    let count = coll.count_documents(None, None).await.unwrap();
+    assert_eq!(count, 0);
+}
@@ -3,10 +3,16 @@ use std::{
     sync::Arc,
 };
 
+use futures::channel::{mpsc::UnboundedSender, oneshot};
+use tokio::sync::Mutex;
+
+use super::{EntityMap, Operation, TestEvent};
 use crate::{
     bson::{Bson, Document},
+    change_stream::{event::ChangeStreamEvent, ChangeStream},
     event::command::CommandStartedEvent,
-    test::{CommandEvent, EventHandler},
+    gridfs::GridFsBucket,
+    test::{util::FailPointGuard, CmapEvent, CommandEvent, EventHandler},
     Client,
     ClientSession,
     Collection,
@@ -19,7 +25,10 @@ pub enum Entity {
     Database(Database),
     Collection(Collection<Document>),
     Session(SessionEntity),
+    GridFsBucket(GridFsBucket),
+    ChangeStream(ChangeStreamEntity),
     Bson(Bson),
+    Thread(ThreadEntity),
     None,
 }
 
@@ -27,10 +36,17 @@ pub enum Entity {
 pub struct ClientEntity {
     client: Client,
     observer: Arc<EventHandler>,
+
+    /// The types of command events to observe on this client. If not specified, all command
+    /// events are observed.
     pub observe_events: Option<Vec<String>>,
     pub ignore_command_names: Option<Vec<String>>,
 }
 
+/// A change stream entity. Change streams can't be cloned, so the stream is shared between the
+/// copies of the entity map held by the test runner and its threads.
+pub type ChangeStreamEntity = Arc<Mutex<ChangeStream<ChangeStreamEvent<Document>>>>;
+
 #[derive(Clone, Debug)]
 pub struct SessionEntity {
     pub lsid: Document,
@@ -44,6 +60,8 @@ impl ClientEntity {
         observe_events: Option<Vec<String>>,
         ignore_command_names: Option<Vec<String>>,
     ) -> Self {
+        // An empty list of event types is treated the same as not specifying one.
+        let observe_events = observe_events.filter(|events| !events.is_empty());
         Self {
             client,
             observer,
@@ -85,6 +103,32 @@ impl ClientEntity {
         })
     }
 
+    /// Gets a list of all of the CMAP events of the types in `observe_events` that occurred on
+    /// this client.
+    pub fn get_filtered_cmap_events(&self) -> Vec<CmapEvent> {
+        self.observer
+            .get_filtered_cmap_events(|event| match self.observe_events {
+                Some(ref observe_events) => {
+                    let name = TestEvent::from(event.clone()).name();
+                    observe_events.iter().any(|observed| observed == name)
+                }
+                None => true,
+            })
+    }
+
+    /// Gets the number of connections currently checked out from this client's connection pools.
+    pub fn connections_checked_out(&self) -> i64 {
+        let mut checked_out = 0;
+        for event in self.observer.get_filtered_cmap_events(|_| true) {
+            match event {
+                CmapEvent::ConnectionCheckedOut(_) => checked_out += 1,
+                CmapEvent::ConnectionCheckedIn(_) => checked_out -= 1,
+                _ => {}
+            }
+        }
+        checked_out
+    }
+
     /// Gets all events of type commandStartedEvent, excluding configureFailPoint events.
     pub fn get_all_command_started_events(&self) -> Vec<CommandStartedEvent> {
         self.observer.get_all_command_started_events()
@@ -103,6 +147,18 @@ impl From<Collection<Document>> for Entity {
     }
 }
 
+impl From<GridFsBucket> for Entity {
+    fn from(bucket: GridFsBucket) -> Self {
+        Self::GridFsBucket(bucket)
+    }
+}
+
+impl From<ChangeStream<ChangeStreamEvent<Document>>> for Entity {
+    fn from(stream: ChangeStream<ChangeStreamEvent<Document>>) -> Self {
+        Self::ChangeStream(Arc::new(Mutex::new(stream)))
+    }
+}
+
 impl From<Bson> for Entity {
     fn from(bson: Bson) -> Self {
         Self::Bson(bson)
@@ -144,6 +200,22 @@ impl DerefMut for SessionEntity {
     }
 }
 
+/// A thread entity. Operations sent to the thread are executed in order by a separate task that
+/// owns its own copy of the entity map.
+#[derive(Clone, Debug)]
+pub struct ThreadEntity {
+    pub sender: UnboundedSender<ThreadMessage>,
+}
+
+#[derive(Debug)]
+pub enum ThreadMessage {
+    /// Run an operation after adding the given entities to the thread's entity map.
+    Run(Operation, EntityMap),
+    /// Hand back the thread's entities and fail point guards once all previously sent operations
+    /// have completed.
+    Wait(oneshot::Sender<(EntityMap, Vec<FailPointGuard>)>),
+}
+
 impl Entity {
     pub fn as_client(&self) -> &ClientEntity {
         match self {
@@ -180,10 +252,31 @@ impl Entity {
         }
     }
 
+    pub fn as_bucket(&self) -> &GridFsBucket {
+        match self {
+            Self::GridFsBucket(bucket) => bucket,
+            _ => panic!("Expected GridFS bucket entity, got {:?}", &self),
+        }
+    }
+
+    pub fn as_change_stream(&self) -> &ChangeStreamEntity {
+        match self {
+            Self::ChangeStream(stream) => stream,
+            _ => panic!("Expected change stream entity, got {:?}", &self),
+        }
+    }
+
     pub fn as_bson(&self) -> &Bson {
         match self {
             Self::Bson(bson) => bson,
             _ => panic!("Expected BSON entity, got {:?}", &self),
         }
     }
+
+    pub fn as_thread(&self) -> &ThreadEntity {
+        match self {
+            Self::Thread(thread) => thread,
+            _ => panic!("Expected thread entity, got {:?}", &self),
+        }
+    }
 }
@@ -89,7 +89,24 @@ pub fn events_match(
             (Some(_), None) => false,
             _ => true,
         },
-        _ => false,
+        (
+            TestEvent::ConnectionClosed {
+                reason: actual_reason,
+            },
+            TestEvent::ConnectionClosed {
+                reason: expected_reason,
+            },
+        ) => expected_reason.is_none() || actual_reason == expected_reason,
+        (
+            TestEvent::ConnectionCheckOutFailed {
+                reason: actual_reason,
+            },
+            TestEvent::ConnectionCheckOutFailed {
+                reason: expected_reason,
+            },
+        ) => expected_reason.is_none() || actual_reason == expected_reason,
+        // The remaining CMAP events have no fields to compare, so only their types need to match.
+        (actual, expected) => std::mem::discriminant(actual) == std::mem::discriminant(expected),
     }
 }
 
@@ -195,7 +212,13 @@ fn special_operator_matches(
             let id = value.as_str().unwrap();
             entity_matches(id, actual, entities.unwrap())
         }
-        "$$matchesHexBytes" => panic!("GridFS not implemented"),
+        "$$matchesHexBytes" => {
+            let expected = hex::decode(value.as_str().unwrap()).unwrap();
+            match actual {
+                Some(Bson::Binary(binary)) => binary.bytes == expected,
+                _ => false,
+            }
+        }
         "$$sessionLsid" => match entities {
             Some(entity_map) => {
                 let session_id = value.as_str().unwrap();
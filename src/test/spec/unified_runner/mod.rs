@@ -14,12 +14,19 @@ use tokio::sync::RwLockWriteGuard;
 use crate::{
     bson::{doc, Document},
     options::{CollectionOptions, FindOptions, ReadConcern, ReadPreference, SelectionCriteria},
-    test::{run_spec_test, LOCK},
+    test::{run_local_spec_test, run_spec_test, LOCK},
     RUNTIME,
 };
 
 pub use self::{
-    entity::{ClientEntity, Entity, SessionEntity},
+    entity::{
+        ChangeStreamEntity,
+        ClientEntity,
+        Entity,
+        SessionEntity,
+        ThreadEntity,
+        ThreadMessage,
+    },
     matcher::{events_match, results_match},
     operation::{Operation, OperationObject},
     test_event::TestEvent,
@@ -27,6 +34,7 @@ pub use self::{
         deserialize_uri_options_to_uri_string,
         CollectionData,
         ExpectError,
+        ExpectedEventType,
         TestFile,
         TestFileEntity,
         Topology,
@@ -34,15 +42,17 @@ pub use self::{
     test_runner::{EntityMap, TestRunner},
 };
 
-static SPEC_VERSIONS: &[Version] = &[Version::new(1, 0, 0), Version::new(1, 1, 0)];
+static SPEC_VERSIONS: &[Version] = &[
+    Version::new(1, 0, 0),
+    Version::new(1, 1, 0),
+    Version::new(1, 2, 0),
+    Version::new(1, 3, 0),
+];
 
 const SKIPPED_OPERATIONS: &[&str] = &[
-    "assertIndexExists",
-    "assertIndexNotExists",
     "bulkWrite",
     "count",
     "createIndex",
-    "download",
     "download_by_name",
     "listCollectionObjects",
     "listDatabaseObjects",
@@ -73,15 +83,6 @@ pub async fn run_unified_format_test(test_file: TestFile) {
     let mut test_runner = TestRunner::new().await;
 
     if let Some(requirements) = test_file.run_on_requirements {
-        // TODO RUST-122: Unskip this test on sharded clusters
-        if test_runner.internal_client.is_sharded() && test_file.description == "poc-transactions" {
-            println!(
-                "Skipping {}: sharded transactions not supported",
-                &test_file.description
-            );
-            return;
-        }
-
         let mut can_run_on = false;
         for requirement in requirements {
             if requirement.can_run_on(&test_runner.internal_client).await {
@@ -138,65 +139,7 @@ pub async fn run_unified_format_test(test_file: TestFile) {
         }
 
         for operation in test_case.operations {
-            match operation.object {
-                OperationObject::TestRunner => {
-                    operation
-                        .execute_test_runner_operation(&mut test_runner)
-                        .await;
-                }
-                OperationObject::Entity(ref id) => {
-                    let result = operation
-                        .execute_entity_operation(id, &mut test_runner)
-                        .await;
-
-                    if let Some(ref id) = operation.save_result_as_entity {
-                        match &result {
-                            Ok(Some(entity)) => {
-                                if test_runner
-                                    .entities
-                                    .insert(id.clone(), entity.clone())
-                                    .is_some()
-                                {
-                                    panic!("Entity with id {} already present in entity map", id);
-                                }
-                            }
-                            Ok(None) => panic!("{} did not return an entity", operation.name),
-                            Err(_) => panic!("{} should succeed", operation.name),
-                        }
-                    }
-
-                    if let Some(ref expect_result) = operation.expect_result {
-                        let result = result
-                            .unwrap_or_else(|e| {
-                                panic!(
-                                    "{} should succeed, but the following error: {}",
-                                    operation.name, e
-                                )
-                            })
-                            .unwrap_or_else(|| {
-                                panic!("{} should return an entity", operation.name)
-                            });
-                        match result {
-                            Entity::Bson(ref result) => {
-                                assert!(results_match(
-                                    Some(result),
-                                    expect_result,
-                                    operation.returns_root_documents(),
-                                    Some(&test_runner.entities),
-                                ));
-                            }
-                            _ => panic!(
-                                "Incorrect entity type returned from {}, expected BSON",
-                                operation.name
-                            ),
-                        }
-                    } else if let Some(expect_error) = operation.expect_error {
-                        let error = result
-                            .expect_err(&format!("{} should return an error", operation.name));
-                        expect_error.verify_result(error);
-                    }
-                }
-            }
+            test_runner.execute_operation(operation).await;
             // This test (in src/test/spec/json/sessions/server-support.json) runs two
             // operations with implicit sessions in sequence and then checks to see if they
             // used the same lsid. We delay for one second to ensure that the
@@ -214,11 +157,18 @@ pub async fn run_unified_format_test(test_file: TestFile) {
                 let entity = test_runner.entities.get(&expected.client).unwrap();
                 let client = entity.as_client();
 
-                let actual_events: Vec<TestEvent> = client
-                    .get_filtered_events(&client.observe_events, &client.ignore_command_names)
-                    .into_iter()
-                    .map(Into::into)
-                    .collect();
+                let actual_events: Vec<TestEvent> = match expected.event_type {
+                    Some(ExpectedEventType::Cmap) => client
+                        .get_filtered_cmap_events()
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
+                    Some(ExpectedEventType::Command) | None => client
+                        .get_filtered_events(&client.observe_events, &client.ignore_command_names)
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
+                };
 
                 let expected_events = &expected.events;
 
@@ -269,3 +219,10 @@ async fn test_examples() {
     let _guard: RwLockWriteGuard<_> = LOCK.run_exclusively().await;
     run_spec_test(&["unified-runner-examples"], run_unified_format_test).await;
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_local_examples() {
+    let _guard: RwLockWriteGuard<_> = LOCK.run_exclusively().await;
+    run_local_spec_test(&["unified-runner-examples"], run_unified_format_test).await;
+}
@@ -1,17 +1,29 @@
 use std::{collections::HashMap, fmt::Debug, ops::Deref, time::Duration};
 
 use async_trait::async_trait;
-use futures::stream::TryStreamExt;
+use futures::{
+    io::AsyncReadExt,
+    stream::{StreamExt, TryStreamExt},
+};
 use serde::{de::Deserializer, Deserialize};
 
 use super::{Entity, ExpectError, TestRunner};
 
 use crate::{
-    bson::{doc, to_bson, Bson, Deserializer as BsonDeserializer, Document},
+    bson::{
+        doc,
+        spec::BinarySubtype,
+        to_bson,
+        Binary,
+        Bson,
+        Deserializer as BsonDeserializer,
+        Document,
+    },
     client::session::{ClientSession, TransactionState},
     error::Result,
     options::{
         AggregateOptions,
+        ChangeStreamOptions,
         CountOptions,
         CreateCollectionOptions,
         DeleteOptions,
@@ -38,7 +50,7 @@ use crate::{
 };
 
 #[async_trait]
-pub trait TestOperation: Debug {
+pub trait TestOperation: Debug + Send + Sync {
     async fn execute_test_runner_operation(&self, test_runner: &mut TestRunner);
 
     async fn execute_entity_operation(
@@ -164,6 +176,10 @@ impl<'de> Deserialize<'de> for Operation {
                 FailPointCommand::deserialize(BsonDeserializer::new(definition.arguments))
                     .map(|op| Box::new(op) as Box<dyn TestOperation>)
             }
+            "targetedFailPoint" => {
+                TargetedFailPoint::deserialize(BsonDeserializer::new(definition.arguments))
+                    .map(|op| Box::new(op) as Box<dyn TestOperation>)
+            }
             "assertCollectionExists" => {
                 AssertCollectionExists::deserialize(BsonDeserializer::new(definition.arguments))
                     .map(|op| Box::new(op) as Box<dyn TestOperation>)
@@ -172,6 +188,22 @@ impl<'de> Deserialize<'de> for Operation {
                 AssertCollectionNotExists::deserialize(BsonDeserializer::new(definition.arguments))
                     .map(|op| Box::new(op) as Box<dyn TestOperation>)
             }
+            "assertIndexExists" => {
+                AssertIndexExists::deserialize(BsonDeserializer::new(definition.arguments))
+                    .map(|op| Box::new(op) as Box<dyn TestOperation>)
+            }
+            "assertIndexNotExists" => {
+                AssertIndexNotExists::deserialize(BsonDeserializer::new(definition.arguments))
+                    .map(|op| Box::new(op) as Box<dyn TestOperation>)
+            }
+            "assertDatabaseExists" => {
+                AssertDatabaseExists::deserialize(BsonDeserializer::new(definition.arguments))
+                    .map(|op| Box::new(op) as Box<dyn TestOperation>)
+            }
+            "assertDatabaseNotExists" => {
+                AssertDatabaseNotExists::deserialize(BsonDeserializer::new(definition.arguments))
+                    .map(|op| Box::new(op) as Box<dyn TestOperation>)
+            }
             "createCollection" => {
                 CreateCollection::deserialize(BsonDeserializer::new(definition.arguments))
                     .map(|op| Box::new(op) as Box<dyn TestOperation>)
@@ -198,6 +230,14 @@ impl<'de> Deserialize<'de> for Operation {
                 BsonDeserializer::new(definition.arguments),
             )
             .map(|op| Box::new(op) as Box<dyn TestOperation>),
+            "assertSessionPinned" => {
+                AssertSessionPinned::deserialize(BsonDeserializer::new(definition.arguments))
+                    .map(|op| Box::new(op) as Box<dyn TestOperation>)
+            }
+            "assertSessionUnpinned" => {
+                AssertSessionUnpinned::deserialize(BsonDeserializer::new(definition.arguments))
+                    .map(|op| Box::new(op) as Box<dyn TestOperation>)
+            }
             "assertSessionDirty" => {
                 AssertSessionDirty::deserialize(BsonDeserializer::new(definition.arguments))
                     .map(|op| Box::new(op) as Box<dyn TestOperation>)
@@ -206,6 +246,10 @@ impl<'de> Deserialize<'de> for Operation {
                 AssertSessionNotDirty::deserialize(BsonDeserializer::new(definition.arguments))
                     .map(|op| Box::new(op) as Box<dyn TestOperation>)
             }
+            "assertNumberConnectionsCheckedOut" => AssertNumberConnectionsCheckedOut::deserialize(
+                BsonDeserializer::new(definition.arguments),
+            )
+            .map(|op| Box::new(op) as Box<dyn TestOperation>),
             "startTransaction" => {
                 StartTransaction::deserialize(BsonDeserializer::new(definition.arguments))
                     .map(|op| Box::new(op) as Box<dyn TestOperation>)
@@ -218,6 +262,26 @@ impl<'de> Deserialize<'de> for Operation {
                 AbortTransaction::deserialize(BsonDeserializer::new(definition.arguments))
                     .map(|op| Box::new(op) as Box<dyn TestOperation>)
             }
+            "withTransaction" => {
+                WithTransaction::deserialize(BsonDeserializer::new(definition.arguments))
+                    .map(|op| Box::new(op) as Box<dyn TestOperation>)
+            }
+            "runOnThread" => RunOnThread::deserialize(BsonDeserializer::new(definition.arguments))
+                .map(|op| Box::new(op) as Box<dyn TestOperation>),
+            "waitForThread" => {
+                WaitForThread::deserialize(BsonDeserializer::new(definition.arguments))
+                    .map(|op| Box::new(op) as Box<dyn TestOperation>)
+            }
+            "createChangeStream" => {
+                CreateChangeStream::deserialize(BsonDeserializer::new(definition.arguments))
+                    .map(|op| Box::new(op) as Box<dyn TestOperation>)
+            }
+            "iterateUntilDocumentOrError" => IterateUntilDocumentOrError::deserialize(
+                BsonDeserializer::new(definition.arguments),
+            )
+            .map(|op| Box::new(op) as Box<dyn TestOperation>),
+            "download" => Download::deserialize(BsonDeserializer::new(definition.arguments))
+                .map(|op| Box::new(op) as Box<dyn TestOperation>),
             _ => Ok(Box::new(UnimplementedOperation) as Box<dyn TestOperation>),
         }
         .map_err(|e| serde::de::Error::custom(format!("{}", e)))?;
@@ -804,7 +868,7 @@ impl TestOperation for FindOneAndUpdate {
                 self.options.clone(),
             )
             .await?;
-        let result = to_bson(&result)?;
+        let result = to_bson(&result.value)?;
         Ok(Some(result.into()))
     }
 
@@ -837,7 +901,7 @@ impl TestOperation for FindOneAndReplace {
                 self.options.clone(),
             )
             .await?;
-        let result = to_bson(&result)?;
+        let result = to_bson(&result.value)?;
         Ok(Some(result.into()))
     }
 
@@ -865,7 +929,7 @@ impl TestOperation for FindOneAndDelete {
         let result = collection
             .find_one_and_delete(self.filter.clone(), self.options.clone())
             .await?;
-        let result = to_bson(&result)?;
+        let result = to_bson(&result.value)?;
         Ok(Some(result.into()))
     }
 
@@ -888,7 +952,44 @@ impl TestOperation for FailPointCommand {
         let guard = self
             .fail_point
             .clone()
-            .enable(client, Some(ReadPreference::Primary.into()))
+            .enable(
+                client,
+                Some(SelectionCriteria::ReadPreference(ReadPreference::Primary)),
+            )
+            .await
+            .unwrap();
+        test_runner.fail_point_guards.push(guard);
+    }
+
+    async fn execute_entity_operation(
+        &self,
+        _id: &str,
+        _test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        unimplemented!()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct TargetedFailPoint {
+    fail_point: FailPoint,
+    session: String,
+}
+
+#[async_trait]
+impl TestOperation for TargetedFailPoint {
+    async fn execute_test_runner_operation(&self, test_runner: &mut TestRunner) {
+        let session: &ClientSession = test_runner.get_session(&self.session);
+        let criteria = session
+            .transaction
+            .pinned_mongos()
+            .cloned()
+            .unwrap_or_else(|| panic!("session {} is not pinned to a mongos", self.session));
+        let guard = self
+            .fail_point
+            .clone()
+            .enable(&session.client(), Some(criteria))
             .await
             .unwrap();
         test_runner.fail_point_guards.push(guard);
@@ -951,6 +1052,142 @@ impl TestOperation for AssertCollectionNotExists {
     }
 }
 
+/// Lists the names of the indexes on the given collection, treating a collection that does not
+/// exist as having no indexes.
+async fn index_names(
+    test_runner: &TestRunner,
+    database_name: &str,
+    collection_name: &str,
+) -> Vec<String> {
+    let db = test_runner.internal_client.database(database_name);
+    let response = match db
+        .run_command(doc! { "listIndexes": collection_name }, None)
+        .await
+    {
+        Ok(response) => response,
+        Err(e) if e.code() == Some(26) => return Vec::new(),
+        Err(e) => panic!("listIndexes failed: {}", e),
+    };
+    response
+        .get_document("cursor")
+        .and_then(|cursor| cursor.get_array("firstBatch"))
+        .unwrap()
+        .iter()
+        .filter_map(|index| index.as_document()?.get_str("name").ok())
+        .map(String::from)
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct AssertIndexExists {
+    collection_name: String,
+    database_name: String,
+    index_name: String,
+}
+
+#[async_trait]
+impl TestOperation for AssertIndexExists {
+    async fn execute_test_runner_operation(&self, test_runner: &mut TestRunner) {
+        let names = index_names(test_runner, &self.database_name, &self.collection_name).await;
+        assert!(
+            names.contains(&self.index_name),
+            "expected index {} to exist, but found {:?}",
+            self.index_name,
+            names
+        );
+    }
+
+    async fn execute_entity_operation(
+        &self,
+        _id: &str,
+        _test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        unimplemented!()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct AssertIndexNotExists {
+    collection_name: String,
+    database_name: String,
+    index_name: String,
+}
+
+#[async_trait]
+impl TestOperation for AssertIndexNotExists {
+    async fn execute_test_runner_operation(&self, test_runner: &mut TestRunner) {
+        let names = index_names(test_runner, &self.database_name, &self.collection_name).await;
+        assert!(
+            !names.contains(&self.index_name),
+            "expected index {} not to exist, but found {:?}",
+            self.index_name,
+            names
+        );
+    }
+
+    async fn execute_entity_operation(
+        &self,
+        _id: &str,
+        _test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        unimplemented!()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct AssertDatabaseExists {
+    database_name: String,
+}
+
+#[async_trait]
+impl TestOperation for AssertDatabaseExists {
+    async fn execute_test_runner_operation(&self, test_runner: &mut TestRunner) {
+        let names = test_runner
+            .internal_client
+            .list_database_names(None, None)
+            .await
+            .unwrap();
+        assert!(names.contains(&self.database_name));
+    }
+
+    async fn execute_entity_operation(
+        &self,
+        _id: &str,
+        _test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        unimplemented!()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct AssertDatabaseNotExists {
+    database_name: String,
+}
+
+#[async_trait]
+impl TestOperation for AssertDatabaseNotExists {
+    async fn execute_test_runner_operation(&self, test_runner: &mut TestRunner) {
+        let names = test_runner
+            .internal_client
+            .list_database_names(None, None)
+            .await
+            .unwrap();
+        assert!(!names.contains(&self.database_name));
+    }
+
+    async fn execute_entity_operation(
+        &self,
+        _id: &str,
+        _test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        unimplemented!()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub(super) struct CreateCollection {
@@ -1170,6 +1407,50 @@ impl TestOperation for AssertSameLsidOnLastTwoCommands {
     }
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct AssertSessionPinned {
+    session: String,
+}
+
+#[async_trait]
+impl TestOperation for AssertSessionPinned {
+    async fn execute_test_runner_operation(&self, test_runner: &mut TestRunner) {
+        let session: &ClientSession = test_runner.get_session(&self.session);
+        assert!(session.transaction.pinned.is_some());
+    }
+
+    async fn execute_entity_operation(
+        &self,
+        _id: &str,
+        _test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        unimplemented!()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct AssertSessionUnpinned {
+    session: String,
+}
+
+#[async_trait]
+impl TestOperation for AssertSessionUnpinned {
+    async fn execute_test_runner_operation(&self, test_runner: &mut TestRunner) {
+        let session: &ClientSession = test_runner.get_session(&self.session);
+        assert!(session.transaction.pinned.is_none());
+    }
+
+    async fn execute_entity_operation(
+        &self,
+        _id: &str,
+        _test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        unimplemented!()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub(super) struct AssertSessionDirty {
@@ -1277,6 +1558,216 @@ impl TestOperation for AbortTransaction {
     }
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct WithTransaction {
+    callback: Vec<Document>,
+}
+
+#[async_trait]
+impl TestOperation for WithTransaction {
+    async fn execute_entity_operation(
+        &self,
+        id: &str,
+        test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        test_runner
+            .get_mut_session(id)
+            .start_transaction(None)
+            .await?;
+
+        // The callback's operations need the session in the entity map, so they're executed here
+        // rather than through ClientSession::with_transaction, which shares the logic for finishing
+        // the transaction.
+        for operation in &self.callback {
+            let operation =
+                Operation::deserialize(BsonDeserializer::new(Bson::Document(operation.clone())))
+                    .unwrap_or_else(|e| panic!("invalid operation in withTransaction: {}", e));
+            test_runner.execute_operation(operation).await;
+        }
+
+        let session: &mut ClientSession = test_runner.get_mut_session(id);
+        session.finish_transaction(Ok(())).await?;
+        Ok(None)
+    }
+
+    async fn execute_test_runner_operation(&self, _test_runner: &mut TestRunner) {
+        unimplemented!()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct AssertNumberConnectionsCheckedOut {
+    client: String,
+    connections: i64,
+}
+
+#[async_trait]
+impl TestOperation for AssertNumberConnectionsCheckedOut {
+    async fn execute_test_runner_operation(&self, test_runner: &mut TestRunner) {
+        let client = test_runner.get_client(&self.client);
+        assert_eq!(client.connections_checked_out(), self.connections);
+    }
+
+    async fn execute_entity_operation(
+        &self,
+        _id: &str,
+        _test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        unimplemented!()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct RunOnThread {
+    thread: String,
+    operation: Document,
+}
+
+#[async_trait]
+impl TestOperation for RunOnThread {
+    async fn execute_test_runner_operation(&self, test_runner: &mut TestRunner) {
+        let operation = Operation::deserialize(BsonDeserializer::new(Bson::Document(
+            self.operation.clone(),
+        )))
+        .unwrap_or_else(|e| panic!("invalid operation for thread {}: {}", self.thread, e));
+
+        // Sessions can't be shared, so any session the operation uses is moved to the thread until
+        // it is waited on.
+        let mut session_ids = vec![self.operation.get_str("object").ok()];
+        if let Ok(arguments) = self.operation.get_document("arguments") {
+            session_ids.push(arguments.get_str("session").ok());
+        }
+        let session_ids: Vec<String> = session_ids
+            .into_iter()
+            .flatten()
+            .map(String::from)
+            .collect();
+
+        test_runner.run_on_thread(&self.thread, operation, &session_ids);
+    }
+
+    async fn execute_entity_operation(
+        &self,
+        _id: &str,
+        _test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        unimplemented!()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct WaitForThread {
+    thread: String,
+}
+
+#[async_trait]
+impl TestOperation for WaitForThread {
+    async fn execute_test_runner_operation(&self, test_runner: &mut TestRunner) {
+        test_runner.wait_for_thread(&self.thread).await;
+    }
+
+    async fn execute_entity_operation(
+        &self,
+        _id: &str,
+        _test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        unimplemented!()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct CreateChangeStream {
+    pipeline: Vec<Document>,
+    #[serde(flatten)]
+    options: Option<ChangeStreamOptions>,
+}
+
+#[async_trait]
+impl TestOperation for CreateChangeStream {
+    async fn execute_entity_operation(
+        &self,
+        id: &str,
+        test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        let collection = test_runner.get_collection(id);
+        let stream = collection
+            .watch(self.pipeline.clone(), self.options.clone())
+            .await?;
+        Ok(Some(stream.into()))
+    }
+
+    async fn execute_test_runner_operation(&self, _test_runner: &mut TestRunner) {
+        unimplemented!()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct IterateUntilDocumentOrError {}
+
+#[async_trait]
+impl TestOperation for IterateUntilDocumentOrError {
+    async fn execute_entity_operation(
+        &self,
+        id: &str,
+        test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        let stream = test_runner.get_change_stream(id).clone();
+        let mut stream = stream.lock().await;
+        let event = stream
+            .next()
+            .await
+            .unwrap_or_else(|| panic!("change stream {} ended without an event", id))?;
+        let result = to_bson(&event)?;
+        Ok(Some(result.into()))
+    }
+
+    fn returns_root_documents(&self) -> bool {
+        true
+    }
+
+    async fn execute_test_runner_operation(&self, _test_runner: &mut TestRunner) {
+        unimplemented!()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct Download {
+    id: Bson,
+}
+
+#[async_trait]
+impl TestOperation for Download {
+    async fn execute_entity_operation(
+        &self,
+        id: &str,
+        test_runner: &mut TestRunner,
+    ) -> Result<Option<Entity>> {
+        let bucket = test_runner.get_bucket(id);
+        let mut contents = Vec::new();
+        bucket
+            .open_download_stream(self.id.clone())
+            .await?
+            .read_to_end(&mut contents)
+            .await?;
+        let result = Bson::Binary(Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: contents,
+        });
+        Ok(Some(result.into()))
+    }
+
+    async fn execute_test_runner_operation(&self, _test_runner: &mut TestRunner) {
+        unimplemented!()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub(super) struct UnimplementedOperation;
 
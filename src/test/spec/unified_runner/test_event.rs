@@ -1,4 +1,8 @@
-use crate::{bson::Document, test::CommandEvent};
+use crate::{
+    bson::Document,
+    event::cmap::{ConnectionCheckoutFailedReason, ConnectionClosedReason},
+    test::{CmapEvent, CommandEvent},
+};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize, PartialEq)]
@@ -16,6 +20,54 @@ pub enum TestEvent {
     },
     #[serde(rename = "commandFailedEvent")]
     Failed { command_name: Option<String> },
+    #[serde(rename = "poolCreatedEvent")]
+    PoolCreated {},
+    #[serde(rename = "poolReadyEvent")]
+    PoolReady {},
+    #[serde(rename = "poolClearedEvent")]
+    PoolCleared {},
+    #[serde(rename = "poolClosedEvent")]
+    PoolClosed {},
+    #[serde(rename = "connectionCreatedEvent")]
+    ConnectionCreated {},
+    #[serde(rename = "connectionReadyEvent")]
+    ConnectionReady {},
+    #[serde(rename = "connectionClosedEvent")]
+    ConnectionClosed {
+        reason: Option<ConnectionClosedReason>,
+    },
+    #[serde(rename = "connectionCheckOutStartedEvent")]
+    ConnectionCheckOutStarted {},
+    #[serde(rename = "connectionCheckOutFailedEvent")]
+    ConnectionCheckOutFailed {
+        reason: Option<ConnectionCheckoutFailedReason>,
+    },
+    #[serde(rename = "connectionCheckedOutEvent")]
+    ConnectionCheckedOut {},
+    #[serde(rename = "connectionCheckedInEvent")]
+    ConnectionCheckedIn {},
+}
+
+impl TestEvent {
+    /// The name used for this type of event in the `observeEvents` field of a client entity.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TestEvent::Started { .. } => "commandStartedEvent",
+            TestEvent::Succeeded { .. } => "commandSucceededEvent",
+            TestEvent::Failed { .. } => "commandFailedEvent",
+            TestEvent::PoolCreated {} => "poolCreatedEvent",
+            TestEvent::PoolReady {} => "poolReadyEvent",
+            TestEvent::PoolCleared {} => "poolClearedEvent",
+            TestEvent::PoolClosed {} => "poolClosedEvent",
+            TestEvent::ConnectionCreated {} => "connectionCreatedEvent",
+            TestEvent::ConnectionReady {} => "connectionReadyEvent",
+            TestEvent::ConnectionClosed { .. } => "connectionClosedEvent",
+            TestEvent::ConnectionCheckOutStarted {} => "connectionCheckOutStartedEvent",
+            TestEvent::ConnectionCheckOutFailed { .. } => "connectionCheckOutFailedEvent",
+            TestEvent::ConnectionCheckedOut {} => "connectionCheckedOutEvent",
+            TestEvent::ConnectionCheckedIn {} => "connectionCheckedInEvent",
+        }
+    }
 }
 
 impl From<CommandEvent> for TestEvent {
@@ -36,3 +88,25 @@ impl From<CommandEvent> for TestEvent {
         }
     }
 }
+
+impl From<CmapEvent> for TestEvent {
+    fn from(event: CmapEvent) -> Self {
+        match event {
+            CmapEvent::PoolCreated(_) => TestEvent::PoolCreated {},
+            CmapEvent::PoolReady(_) => TestEvent::PoolReady {},
+            CmapEvent::PoolCleared(_) => TestEvent::PoolCleared {},
+            CmapEvent::PoolClosed(_) => TestEvent::PoolClosed {},
+            CmapEvent::ConnectionCreated(_) => TestEvent::ConnectionCreated {},
+            CmapEvent::ConnectionReady(_) => TestEvent::ConnectionReady {},
+            CmapEvent::ConnectionClosed(event) => TestEvent::ConnectionClosed {
+                reason: Some(event.reason),
+            },
+            CmapEvent::ConnectionCheckOutStarted(_) => TestEvent::ConnectionCheckOutStarted {},
+            CmapEvent::ConnectionCheckOutFailed(event) => TestEvent::ConnectionCheckOutFailed {
+                reason: Some(event.reason),
+            },
+            CmapEvent::ConnectionCheckedOut(_) => TestEvent::ConnectionCheckedOut {},
+            CmapEvent::ConnectionCheckedIn(_) => TestEvent::ConnectionCheckedIn {},
+        }
+    }
+}
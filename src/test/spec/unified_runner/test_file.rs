@@ -10,13 +10,16 @@ use crate::{
     client::options::{ServerApi, SessionOptions},
     concern::{Acknowledgment, ReadConcernLevel},
     error::Error,
+    event::cmap::ConnectionClosedReason,
     options::{
         ClientOptions,
         CollectionOptions,
         DatabaseOptions,
+        FindOneAndUpdateOptions,
         HedgedReadOptions,
         ReadConcern,
         ReadPreference,
+        ReturnDocument,
         SelectionCriteria,
         WriteConcern,
     },
@@ -114,6 +117,7 @@ pub enum TestFileEntity {
     Collection(Collection),
     Session(Session),
     Bucket(Bucket),
+    Thread(Thread),
 }
 
 #[derive(Debug, Deserialize)]
@@ -208,6 +212,12 @@ pub struct Session {
     pub session_options: Option<SessionOptions>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Thread {
+    pub id: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct Bucket {
@@ -273,9 +283,17 @@ pub struct TestCase {
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct ExpectedEvents {
     pub client: String,
+    pub event_type: Option<ExpectedEventType>,
     pub events: Vec<TestEvent>,
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExpectedEventType {
+    Command,
+    Cmap,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct ExpectError {
@@ -392,3 +410,77 @@ async fn deserialize_read_concern() {
         other => panic!("Expected custom read concern, got {:?}", other),
     };
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn deserialize_return_document() {
+    let arguments = doc! {
+        "returnDocument": "After",
+    };
+    let d = BsonDeserializer::new(arguments.into());
+    let options = FindOneAndUpdateOptions::deserialize(d).unwrap();
+    assert!(matches!(
+        options.return_document,
+        Some(ReturnDocument::After)
+    ));
+
+    let arguments = doc! {
+        "returnDocument": "Before",
+    };
+    let d = BsonDeserializer::new(arguments.into());
+    let options = FindOneAndUpdateOptions::deserialize(d).unwrap();
+    assert!(matches!(
+        options.return_document,
+        Some(ReturnDocument::Before)
+    ));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn deserialize_cmap_expected_events() {
+    let expected = doc! {
+        "client": "client0",
+        "eventType": "cmap",
+        "events": [
+            { "connectionCheckedOutEvent": {} },
+            { "connectionClosedEvent": { "reason": "stale" } },
+            { "connectionCheckOutFailedEvent": {} },
+        ],
+    };
+    let d = BsonDeserializer::new(expected.into());
+    let expected = ExpectedEvents::deserialize(d).unwrap();
+    assert_eq!(expected.event_type, Some(ExpectedEventType::Cmap));
+    assert_eq!(
+        expected.events,
+        vec![
+            TestEvent::ConnectionCheckedOut {},
+            TestEvent::ConnectionClosed {
+                reason: Some(ConnectionClosedReason::Stale),
+            },
+            TestEvent::ConnectionCheckOutFailed { reason: None },
+        ]
+    );
+
+    let expected = doc! {
+        "client": "client0",
+        "events": [],
+    };
+    let d = BsonDeserializer::new(expected.into());
+    let expected = ExpectedEvents::deserialize(d).unwrap();
+    assert_eq!(expected.event_type, None);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn deserialize_thread_entity() {
+    let entity = doc! { "thread": { "id": "thread0" } };
+    let d = BsonDeserializer::new(entity.into());
+    match TestFileEntity::deserialize(d).unwrap() {
+        TestFileEntity::Thread(thread) => assert_eq!(thread.id, "thread0"),
+        other => panic!("expected thread entity, got {:?}", other),
+    }
+
+    let entity = doc! { "thread": { "id": "thread0", "operations": [] } };
+    let d = BsonDeserializer::new(entity.into());
+    assert!(TestFileEntity::deserialize(d).is_err());
+}
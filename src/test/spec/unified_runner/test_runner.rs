@@ -1,17 +1,36 @@
 use std::{collections::HashMap, sync::Arc};
 
+use futures::{
+    channel::{mpsc, oneshot},
+    StreamExt,
+};
+
 use crate::{
-    bson::Document,
+    bson::{from_document, Document},
     client::options::ClientOptions,
     concern::{Acknowledgment, WriteConcern},
-    options::CollectionOptions,
+    gridfs::GridFsBucket,
+    options::{CollectionOptions, GridFsBucketOptions},
     test::{util::FailPointGuard, EventHandler, TestClient, SERVER_API},
     Client,
     Collection,
     Database,
+    RUNTIME,
 };
 
-use super::{ClientEntity, CollectionData, Entity, SessionEntity, TestFileEntity};
+use super::{
+    results_match,
+    ChangeStreamEntity,
+    ClientEntity,
+    CollectionData,
+    Entity,
+    Operation,
+    OperationObject,
+    SessionEntity,
+    TestFileEntity,
+    ThreadEntity,
+    ThreadMessage,
+};
 
 pub type EntityMap = HashMap<String, Entity>;
 
@@ -65,6 +84,7 @@ impl TestRunner {
 
                     let mut options = ClientOptions::parse_uri(&client.uri, None).await.unwrap();
                     options.command_event_handler = Some(observer.clone());
+                    options.cmap_event_handler = Some(observer.clone());
                     options.server_api = server_api;
                     match client.use_multiple_mongoses {
                         Some(true) => {
@@ -124,8 +144,38 @@ impl TestRunner {
                         .unwrap();
                     (id, Entity::Session(SessionEntity::new(client_session)))
                 }
-                TestFileEntity::Bucket(_) => {
-                    panic!("GridFS not implemented");
+                TestFileEntity::Bucket(bucket) => {
+                    let id = bucket.id.clone();
+                    let database = self.get_database(&bucket.database);
+                    let options: Option<GridFsBucketOptions> = bucket
+                        .bucket_options
+                        .clone()
+                        .map(|options| from_document(options).unwrap());
+                    (id, database.gridfs_bucket(options).into())
+                }
+                TestFileEntity::Thread(thread) => {
+                    let (sender, mut receiver) = mpsc::unbounded();
+                    let mut runner = TestRunner {
+                        internal_client: self.internal_client.clone(),
+                        entities: HashMap::new(),
+                        fail_point_guards: Vec::new(),
+                    };
+                    RUNTIME.execute(async move {
+                        while let Some(message) = receiver.next().await {
+                            match message {
+                                ThreadMessage::Run(operation, entities) => {
+                                    runner.entities.extend(entities);
+                                    runner.execute_operation(operation).await;
+                                }
+                                ThreadMessage::Wait(sender) => {
+                                    let entities = std::mem::take(&mut runner.entities);
+                                    let guards = std::mem::take(&mut runner.fail_point_guards);
+                                    let _ = sender.send((entities, guards));
+                                }
+                            }
+                        }
+                    });
+                    (thread.id.clone(), Entity::Thread(ThreadEntity { sender }))
                 }
             };
             if self.entities.insert(id.clone(), entity).is_some() {
@@ -134,6 +184,103 @@ impl TestRunner {
         }
     }
 
+    /// Sends an operation to be executed by the given thread. The sessions with the given IDs are
+    /// moved out of the entity map until the thread is waited on; the thread receives a copy of
+    /// every other entity.
+    pub fn run_on_thread(&mut self, id: &str, operation: Operation, session_ids: &[String]) {
+        let mut entities: EntityMap = self
+            .entities
+            .iter()
+            .filter(|(_, entity)| !matches!(entity, Entity::Session(_)))
+            .map(|(id, entity)| (id.clone(), entity.clone()))
+            .collect();
+        for session_id in session_ids {
+            if let Some(session @ Entity::Session(_)) = self.entities.remove(session_id) {
+                entities.insert(session_id.clone(), session);
+            }
+        }
+
+        self.entities
+            .get(id)
+            .unwrap()
+            .as_thread()
+            .sender
+            .unbounded_send(ThreadMessage::Run(operation, entities))
+            .unwrap_or_else(|_| panic!("thread {} has stopped", id));
+    }
+
+    /// Waits for all operations sent to the given thread to complete, then adds any entities it
+    /// created or was given to the entity map.
+    pub async fn wait_for_thread(&mut self, id: &str) {
+        let (sender, receiver) = oneshot::channel();
+        let thread = self.entities.get(id).unwrap().as_thread();
+        thread
+            .sender
+            .unbounded_send(ThreadMessage::Wait(sender))
+            .unwrap_or_else(|_| panic!("thread {} has stopped", id));
+        let (entities, mut fail_point_guards) = receiver
+            .await
+            .unwrap_or_else(|_| panic!("thread {} failed", id));
+
+        for (id, entity) in entities {
+            self.entities.entry(id).or_insert(entity);
+        }
+        self.fail_point_guards.append(&mut fail_point_guards);
+    }
+
+    /// Executes a single operation, verifying its result against any expectations.
+    pub async fn execute_operation(&mut self, operation: Operation) {
+        match operation.object {
+            OperationObject::TestRunner => {
+                operation.execute_test_runner_operation(self).await;
+            }
+            OperationObject::Entity(ref id) => {
+                let result = operation.execute_entity_operation(id, self).await;
+
+                if let Some(ref id) = operation.save_result_as_entity {
+                    match &result {
+                        Ok(Some(entity)) => {
+                            if self.entities.insert(id.clone(), entity.clone()).is_some() {
+                                panic!("Entity with id {} already present in entity map", id);
+                            }
+                        }
+                        Ok(None) => panic!("{} did not return an entity", operation.name),
+                        Err(_) => panic!("{} should succeed", operation.name),
+                    }
+                }
+
+                if let Some(ref expect_result) = operation.expect_result {
+                    let result = result
+                        .unwrap_or_else(|e| {
+                            panic!(
+                                "{} should succeed, but the following error: {}",
+                                operation.name, e
+                            )
+                        })
+                        .unwrap_or_else(|| panic!("{} should return an entity", operation.name));
+                    match result {
+                        Entity::Bson(ref result) => {
+                            assert!(results_match(
+                                Some(result),
+                                expect_result,
+                                operation.returns_root_documents(),
+                                Some(&self.entities),
+                            ));
+                        }
+                        _ => panic!(
+                            "Incorrect entity type returned from {}, expected BSON",
+                            operation.name
+                        ),
+                    }
+                } else if let Some(expect_error) = operation.expect_error {
+                    let error =
+                        result.expect_err(&format!("{} should return an error", operation.name));
+                    expect_error.verify_result(error);
+                }
+            }
+        }
+    }
+
     pub fn get_client(&self, id: &str) -> &ClientEntity {
         self.entities.get(id).unwrap().as_client()
     }
@@ -146,6 +293,14 @@ impl TestRunner {
         self.entities.get(id).unwrap().as_collection()
     }
 
+    pub fn get_bucket(&self, id: &str) -> &GridFsBucket {
+        self.entities.get(id).unwrap().as_bucket()
+    }
+
+    pub fn get_change_stream(&self, id: &str) -> &ChangeStreamEntity {
+        self.entities.get(id).unwrap().as_change_stream()
+    }
+
     pub fn get_session(&self, id: &str) -> &SessionEntity {
         self.entities.get(id).unwrap().as_session_entity()
     }
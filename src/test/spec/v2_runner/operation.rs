@@ -1088,7 +1088,7 @@ impl TestOperation for FindOneAndUpdate {
                     .await?
             }
         };
-        let result = bson::to_bson(&result)?;
+        let result = bson::to_bson(&result.value)?;
         Ok(Some(result))
     }
 
@@ -1145,7 +1145,7 @@ impl TestOperation for FindOneAndReplace {
                     .await?
             }
         };
-        let result = bson::to_bson(&result)?;
+        let result = bson::to_bson(&result.value)?;
         Ok(Some(result))
     }
 
@@ -1196,7 +1196,7 @@ impl TestOperation for FindOneAndDelete {
                     .await?
             }
         };
-        let result = bson::to_bson(&result)?;
+        let result = bson::to_bson(&result.value)?;
         Ok(Some(result))
     }
 
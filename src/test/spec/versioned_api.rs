@@ -1,7 +1,8 @@
 use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 use crate::{
-    bson::doc,
+    bson::{doc, Document},
+    error::ErrorKind,
     options::{ServerApi, ServerApiVersion},
     test::{run_spec_test, EventClient, TestClient, CLIENT_OPTIONS, LOCK},
 };
@@ -65,3 +66,72 @@ async fn transaction_handling() {
         assert!(event.command.contains_key("apiVersion"));
     }
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn strict_api_version_sent_with_find() {
+    let _guard: RwLockReadGuard<_> = LOCK.run_concurrently().await;
+
+    let version = ServerApi::builder()
+        .version(ServerApiVersion::V1)
+        .strict(true)
+        .build();
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.server_api = Some(version);
+    let client = EventClient::with_options(options).await;
+    if client.server_version_lt(4, 9) {
+        println!(
+            "skipping {} due to server not supporting versioned API",
+            function_name!()
+        );
+        return;
+    }
+
+    client
+        .database(function_name!())
+        .collection::<Document>(function_name!())
+        .find(None, None)
+        .await
+        .unwrap();
+
+    let events = client.get_command_started_events(&["find"]);
+    assert_eq!(events.len(), 1);
+    let command = &events[0].command;
+    assert_eq!(command.get_str("apiVersion"), Ok("1"));
+    assert_eq!(command.get_bool("apiStrict"), Ok(true));
+    assert!(!command.contains_key("apiDeprecationErrors"));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn strict_excluded_stage_rejected_client_side() {
+    let _guard: RwLockReadGuard<_> = LOCK.run_concurrently().await;
+
+    let version = ServerApi::builder()
+        .version(ServerApiVersion::V1)
+        .strict(true)
+        .check_pipeline_stages(true)
+        .build();
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.server_api = Some(version);
+    let client = EventClient::with_options(options).await;
+
+    let error = client
+        .database(function_name!())
+        .collection::<Document>(function_name!())
+        .aggregate(vec![doc! { "$collStats": { "count": {} } }], None)
+        .await
+        .expect_err("aggregate with $collStats should fail in strict mode");
+    assert!(
+        matches!(*error.kind, ErrorKind::InvalidArgument { ref message } if message.contains("$collStats")),
+        "expected invalid argument error, got {:?}",
+        error
+    );
+
+    let events = client.get_command_started_events(&["aggregate"]);
+    assert!(events.is_empty());
+}
@@ -32,6 +32,8 @@ use crate::{
             CommandFailedEvent,
             CommandStartedEvent,
             CommandSucceededEvent,
+            CursorClosedEvent,
+            CursorCreatedEvent,
         },
     },
     options::ClientOptions,
@@ -89,9 +91,17 @@ impl CommandEvent {
     }
 }
 
+#[derive(Clone, Debug)]
+pub enum CursorEvent {
+    Created(CursorCreatedEvent),
+    Closed(CursorClosedEvent),
+}
+
 #[derive(Clone, Debug)]
 pub struct EventHandler {
     command_events: EventQueue<CommandEvent>,
+    cmap_events: EventQueue<CmapEvent>,
+    cursor_events: EventQueue<CursorEvent>,
     pub pool_cleared_events: EventQueue<PoolClearedEvent>,
     event_broadcaster: tokio::sync::broadcast::Sender<Event>,
 }
@@ -101,6 +111,8 @@ impl EventHandler {
         let (event_broadcaster, _) = tokio::sync::broadcast::channel(500);
         Self {
             command_events: Default::default(),
+            cmap_events: Default::default(),
+            cursor_events: Default::default(),
             pool_cleared_events: Default::default(),
             event_broadcaster,
         }
@@ -112,6 +124,11 @@ impl EventHandler {
             self.event_broadcaster.send(event.into());
     }
 
+    fn handle_cmap(&self, event: CmapEvent) {
+        self.handle(event.clone());
+        self.cmap_events.write().unwrap().push_back(event);
+    }
+
     pub fn subscribe(&self) -> EventSubscriber {
         EventSubscriber {
             _handler: self,
@@ -158,52 +175,60 @@ impl EventHandler {
         let events = self.command_events.read().unwrap();
         events.iter().filter(|e| filter(*e)).cloned().collect()
     }
+
+    pub fn get_filtered_cmap_events<F>(&self, filter: F) -> Vec<CmapEvent>
+    where
+        F: Fn(&CmapEvent) -> bool,
+    {
+        let events = self.cmap_events.read().unwrap();
+        events.iter().filter(|e| filter(*e)).cloned().collect()
+    }
 }
 
 impl CmapEventHandler for EventHandler {
     fn handle_connection_checked_out_event(&self, event: ConnectionCheckedOutEvent) {
-        self.handle(CmapEvent::ConnectionCheckedOut(event))
+        self.handle_cmap(CmapEvent::ConnectionCheckedOut(event))
     }
 
     fn handle_connection_checkout_failed_event(&self, event: ConnectionCheckoutFailedEvent) {
-        self.handle(CmapEvent::ConnectionCheckOutFailed(event))
+        self.handle_cmap(CmapEvent::ConnectionCheckOutFailed(event))
     }
 
     fn handle_pool_cleared_event(&self, event: PoolClearedEvent) {
-        self.handle(CmapEvent::PoolCleared(event.clone()));
+        self.handle_cmap(CmapEvent::PoolCleared(event.clone()));
         self.pool_cleared_events.write().unwrap().push_back(event);
     }
 
     fn handle_pool_ready_event(&self, event: PoolReadyEvent) {
-        self.handle(CmapEvent::PoolReady(event))
+        self.handle_cmap(CmapEvent::PoolReady(event))
     }
 
     fn handle_pool_created_event(&self, event: PoolCreatedEvent) {
-        self.handle(CmapEvent::PoolCreated(event));
+        self.handle_cmap(CmapEvent::PoolCreated(event))
     }
 
     fn handle_pool_closed_event(&self, event: PoolClosedEvent) {
-        self.handle(CmapEvent::PoolClosed(event))
+        self.handle_cmap(CmapEvent::PoolClosed(event))
     }
 
     fn handle_connection_created_event(&self, event: ConnectionCreatedEvent) {
-        self.handle(CmapEvent::ConnectionCreated(event))
+        self.handle_cmap(CmapEvent::ConnectionCreated(event))
     }
 
     fn handle_connection_ready_event(&self, event: ConnectionReadyEvent) {
-        self.handle(CmapEvent::ConnectionReady(event))
+        self.handle_cmap(CmapEvent::ConnectionReady(event))
     }
 
     fn handle_connection_closed_event(&self, event: ConnectionClosedEvent) {
-        self.handle(CmapEvent::ConnectionClosed(event))
+        self.handle_cmap(CmapEvent::ConnectionClosed(event))
     }
 
     fn handle_connection_checkout_started_event(&self, event: ConnectionCheckoutStartedEvent) {
-        self.handle(CmapEvent::ConnectionCheckOutStarted(event))
+        self.handle_cmap(CmapEvent::ConnectionCheckOutStarted(event))
     }
 
     fn handle_connection_checked_in_event(&self, event: ConnectionCheckedInEvent) {
-        self.handle(CmapEvent::ConnectionCheckedIn(event))
+        self.handle_cmap(CmapEvent::ConnectionCheckedIn(event))
     }
 }
 
@@ -231,6 +256,20 @@ impl CommandEventHandler for EventHandler {
             .unwrap()
             .push_back(CommandEvent::Succeeded(event))
     }
+
+    fn handle_cursor_created_event(&self, event: CursorCreatedEvent) {
+        self.cursor_events
+            .write()
+            .unwrap()
+            .push_back(CursorEvent::Created(event))
+    }
+
+    fn handle_cursor_closed_event(&self, event: CursorClosedEvent) {
+        self.cursor_events
+            .write()
+            .unwrap()
+            .push_back(CursorEvent::Closed(event))
+    }
 }
 
 #[derive(Debug)]
@@ -387,6 +426,15 @@ impl EventClient {
             .collect()
     }
 
+    pub fn get_cursor_events(&self) -> Vec<CursorEvent> {
+        self.handler
+            .cursor_events
+            .write()
+            .unwrap()
+            .drain(..)
+            .collect()
+    }
+
     pub fn get_pool_cleared_events(&self) -> Vec<PoolClearedEvent> {
         self.handler
             .pool_cleared_events
@@ -403,6 +451,7 @@ impl EventClient {
 
     pub fn clear_cached_events(&self) {
         self.handler.command_events.write().unwrap().clear();
+        self.handler.cursor_events.write().unwrap().clear();
         self.handler.pool_cleared_events.write().unwrap().clear();
     }
 }
@@ -61,6 +61,7 @@ impl FailPoint {
     }
 }
 
+#[derive(Debug)]
 pub struct FailPointGuard {
     client: Client,
     failpoint_name: String,
@@ -88,6 +89,32 @@ impl Drop for FailPointGuard {
     }
 }
 
+/// The mode and data of a failCommand fail point, independent of the commands it applies to.
+pub struct FailPointData {
+    mode: FailPointMode,
+    options: FailCommandOptions,
+}
+
+impl FailPointData {
+    /// Fail point data that causes the server to close the connection upon receiving an affected
+    /// command. If `times` is `None`, the fail point will remain on until it is disabled.
+    pub fn close_connection(times: Option<u32>) -> Self {
+        let mode = match times {
+            Some(n) => FailPointMode::Times(n as i32),
+            None => FailPointMode::AlwaysOn,
+        };
+        Self {
+            mode,
+            options: FailCommandOptions::builder().close_connection(true).build(),
+        }
+    }
+
+    /// Create a failCommand fail point that applies this data to `fail_commands`.
+    pub fn fail_command(self, fail_commands: &[&str]) -> FailPoint {
+        FailPoint::fail_command(fail_commands, self.mode, self.options)
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(unused)]
@@ -128,6 +155,11 @@ pub struct FailCommandOptions {
 
     /// Document to be returned as a write concern error.
     write_concern_error: Option<Document>,
+
+    /// Whether the fail point should also apply to commands sent by other members of the
+    /// cluster, e.g. by a mongos to a shard. By default, only commands from external clients are
+    /// affected.
+    fail_internal_commands: Option<bool>,
 }
 
 fn serialize_block_connection<S: Serializer>(
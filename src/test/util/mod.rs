@@ -4,8 +4,8 @@ mod lock;
 mod matchable;
 
 pub use self::{
-    event::{CmapEvent, CommandEvent, Event, EventClient, EventHandler},
-    failpoint::{FailCommandOptions, FailPoint, FailPointGuard, FailPointMode},
+    event::{CmapEvent, CommandEvent, CursorEvent, Event, EventClient, EventHandler},
+    failpoint::{FailCommandOptions, FailPoint, FailPointData, FailPointGuard, FailPointMode},
     lock::TestLock,
     matchable::{assert_matches, Matchable},
 };
@@ -77,7 +77,8 @@ impl TestClient {
             .await;
         session.mark_dirty();
 
-        let is_master = RunCommand::new("admin".into(), doc! { "isMaster":  1 }, None).unwrap();
+        let is_master =
+            RunCommand::new("admin".into(), doc! { "isMaster":  1 }, None, None).unwrap();
 
         let server_info = bson::from_bson(Bson::Document(
             client
@@ -87,7 +88,8 @@ impl TestClient {
         ))
         .unwrap();
 
-        let build_info = RunCommand::new("test".into(), doc! { "buildInfo":  1 }, None).unwrap();
+        let build_info =
+            RunCommand::new("test".into(), doc! { "buildInfo":  1 }, None, None).unwrap();
 
         let response = client
             .execute_operation(build_info, &mut session)
@@ -99,7 +101,7 @@ impl TestClient {
         let server_version = Version::parse(server_version_str).unwrap();
 
         let get_parameters =
-            RunCommand::new("admin".into(), doc! { "getParameter": "*" }, None).unwrap();
+            RunCommand::new("admin".into(), doc! { "getParameter": "*" }, None, None).unwrap();
         let server_parameters = client
             .execute_operation(get_parameters, &mut session)
             .await
@@ -190,7 +192,7 @@ impl TestClient {
 
     pub async fn init_db_and_typed_coll<T>(&self, db_name: &str, coll_name: &str) -> Collection<T>
     where
-        T: Serialize + DeserializeOwned + Unpin + Debug,
+        T: Serialize + DeserializeOwned + Debug,
     {
         let coll = self.database(db_name).collection(coll_name);
         drop_collection(&coll).await;
@@ -339,7 +341,7 @@ impl TestClient {
 
 pub async fn drop_collection<T>(coll: &Collection<T>)
 where
-    T: Serialize + DeserializeOwned + Unpin + Debug,
+    T: Serialize + DeserializeOwned + Debug,
 {
     match coll.drop(None).await.map_err(|e| *e.kind) {
         Err(ErrorKind::Command(CommandError { code: 26, .. })) | Ok(_) => {}
@@ -381,6 +383,34 @@ pub struct IsMasterCommandResponse {
     pub primary: Option<String>,
 }
 
+/// The `Decimal128` values 1.5 and 0.1.
+pub fn decimal128_one_and_a_half_and_one_tenth() -> (Bson, Bson) {
+    let mut one_and_a_half = [0; 16];
+    one_and_a_half[0] = 15;
+    one_and_a_half[14..].copy_from_slice(&[0x3e, 0x30]);
+    let mut one_tenth = one_and_a_half;
+    one_tenth[0] = 1;
+    (
+        decimal128_from_bytes(one_and_a_half),
+        decimal128_from_bytes(one_tenth),
+    )
+}
+
+/// Builds a BSON `Decimal128` value from its little-endian byte representation. The `bson` crate
+/// only offers constructors behind its unstable `decimal128` feature, so the value is decoded from
+/// a raw document instead.
+fn decimal128_from_bytes(bytes: [u8; 16]) -> Bson {
+    // length (24) + element type (0x13) + key "d" + value + document terminator
+    let mut raw = vec![24, 0, 0, 0, 0x13, b'd', 0];
+    raw.extend_from_slice(&bytes);
+    raw.push(0);
+
+    Document::from_reader(raw.as_slice())
+        .unwrap()
+        .remove("d")
+        .unwrap()
+}
+
 pub fn get_default_name(description: &str) -> String {
     let mut db_name = description
         .replace('$', "%")